@@ -133,12 +133,19 @@ pub async fn get_command_index(
     let items = features::get_all_command_items();
     
     // Apply usage-based ranking (no context boost - frontend handles that)
-    let ranked_items = context::rank_commands(
+    let mut ranked_items = context::rank_commands(
         items,
         |cmd| cmd.id.clone(),
         &metrics,
         None, // No context boost - frontend handles search
     );
+
+    // Pinned clipboard items and the copy stack change at runtime, so they bypass
+    // the cached base list and keep their own order
+    if let Some(history) = _app.try_state::<crate::core::clipboard::ClipboardHistory>() {
+        ranked_items.extend(history.stack().command_items());
+        ranked_items.extend(history.pins().command_items());
+    }
     
     Ok(ranked_items)
 }
@@ -29,5 +29,8 @@ pub async fn save_settings(app_handle: tauri::AppHandle, mut settings: AppSettin
         settings.api_keys.currency_api_key = current_settings.api_keys.currency_api_key;
     }
 
+    // Reject rules that can't compile rather than silently skipping them later
+    crate::core::clipboard::rules::CaptureRules::compile(&settings.preferences.clipboard.capture_rules)?;
+
     settings.save(&app_handle).await.map_err(|e| crate::shared::error::AppError::Io(e))
 }
@@ -2,13 +2,31 @@
 //!
 //! Provides clipboard history tracking and monitoring functionality.
 //!
-//! This module contains two main components:
-//! - `history`: Manages clipboard history with deduplication and capacity limits
+//! This module contains these main components:
+//! - `history`: Manages clipboard history with deduplication and retention limits
 //! - `monitor`: Background thread that monitors clipboard changes
+//! - `source`: Platform change notifications the monitor waits on
+//! - `formats`: Reads and restores text, HTML, RTF, images and file lists
+//! - `search`: Tokenised, encrypted index behind history search
+//! - `pins`: Pinned items and named snippet collections that never expire
+//! - `stack`: Collects consecutive copies to paste together or one by one
+//! - `rules`: User capture rules deciding whether and how copies are stored
+//! - `originals`: Short-lived, memory-only originals of redacted copies
+//! - `expiry`: Clears sensitive copies from the clipboard and history on a timer
+//! - `backup`: Passphrase-encrypted export and import of history and pins
 
+pub mod backup;
+pub mod expiry;
+pub mod formats;
 pub mod history;
 pub mod monitor;
+pub mod originals;
 pub mod filter;
+pub mod pins;
+pub mod rules;
+pub mod search;
+pub mod source;
+pub mod stack;
 pub mod state;
 
 pub use history::ClipboardHistory;
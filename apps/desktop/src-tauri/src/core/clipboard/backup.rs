@@ -0,0 +1,341 @@
+//! Portable, passphrase-encrypted backups of history and pins
+//!
+//! The history database is encrypted with a key kept in the OS keyring, so
+//! copying it to another machine is no use. A backup is one file encrypted
+//! with a key derived from a passphrase the user picks:
+//!
+//! ```text
+//! "PWCLIPBK" | version (u8) | Argon2id m_cost, t_cost, p_cost (u32 LE)
+//!   | salt (16) | nonce (24) | XChaCha20-Poly1305 ciphertext of the CBOR payload
+//! ```
+//!
+//! The header is authenticated together with the payload, so tampering with
+//! the version or key derivation settings fails decryption. Importing merges
+//! by ID and timestamp and can be run dry to preview what would change.
+//! Items marked `no_sync` and sensitive items on a timer are never exported.
+
+use std::path::Path;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use super::history::ClipboardHistory;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::types::{
+    ClipboardCollection, ClipboardExportSummary, ClipboardHistoryItem, ClipboardImportSummary,
+    PinnedClipboardItem,
+};
+
+/// First bytes of every backup file
+const MAGIC: &[u8; 8] = b"PWCLIPBK";
+
+/// Bumped whenever the header or payload layout changes
+const FORMAT_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Where the key derivation settings start in the header
+const KDF_OFFSET: usize = MAGIC.len() + 1;
+
+const HEADER_LEN: usize = KDF_OFFSET + 12 + SALT_LEN + NONCE_LEN;
+
+/// Shortest passphrase accepted for a new backup
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Argon2id cost: memory in KiB, passes and lanes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+/// Cost of new backups (64 MiB, 3 passes)
+const DEFAULT_KDF: KdfParams = KdfParams { m_cost: 64 * 1024, t_cost: 3, p_cost: 1 };
+
+/// Highest cost read from a file, so a crafted header can't exhaust memory
+const MAX_KDF: KdfParams = KdfParams { m_cost: 1024 * 1024, t_cost: 16, p_cost: 16 };
+
+/// Decrypted contents of a backup file
+#[derive(Serialize, Deserialize)]
+struct Backup {
+    exported_at: DateTime<Utc>,
+    items: Vec<BackupItem>,
+    collections: Vec<ClipboardCollection>,
+    pins: Vec<BackupPin>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupItem {
+    item: ClipboardHistoryItem,
+    /// Image data (PNG)
+    #[serde(default, with = "serde_bytes")]
+    png: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupPin {
+    pin: PinnedClipboardItem,
+    /// Image data (PNG)
+    #[serde(default, with = "serde_bytes")]
+    png: Option<Vec<u8>>,
+}
+
+/// Write history and pins to an encrypted backup file at `path`
+pub fn export(history: &ClipboardHistory, path: &Path, passphrase: &str) -> AppResult<ClipboardExportSummary> {
+    export_with(history, path, passphrase, DEFAULT_KDF)
+}
+
+fn export_with(
+    history: &ClipboardHistory,
+    path: &Path,
+    passphrase: &str,
+    kdf: KdfParams,
+) -> AppResult<ClipboardExportSummary> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(AppError::Validation(format!(
+            "Passphrase must be at least {} characters", MIN_PASSPHRASE_CHARS
+        )));
+    }
+
+    let items: Vec<BackupItem> = history.export_items()?
+        .into_iter()
+        .map(|(item, png)| BackupItem { item, png })
+        .collect();
+    let (collections, pins) = history.pins().export()?;
+    let pins: Vec<BackupPin> = pins.into_iter().map(|(pin, png)| BackupPin { pin, png }).collect();
+    let summary = ClipboardExportSummary {
+        items: items.len() as i32,
+        pins: pins.len() as i32,
+        collections: collections.len() as i32,
+    };
+
+    let backup = Backup { exported_at: Utc::now(), items, collections, pins };
+    let mut payload = Vec::new();
+    ciborium::into_writer(&backup, &mut payload)
+        .map_err(|e| AppError::Validation(format!("Serialization error: {}", e)))?;
+    let file = seal(&payload, passphrase, kdf)?;
+    std::fs::write(path, file)
+        .map_err(|e| AppError::Io(format!("Failed to write backup: {}", e)))?;
+
+    println!(
+        "[ClipboardBackup] Exported {} items, {} pins and {} collections",
+        summary.items, summary.pins, summary.collections
+    );
+    Ok(summary)
+}
+
+/// Merge an encrypted backup file into history and pins. A dry run only
+/// reports what would change.
+pub fn import(
+    history: &ClipboardHistory,
+    path: &Path,
+    passphrase: &str,
+    dry_run: bool,
+) -> AppResult<ClipboardImportSummary> {
+    let file = std::fs::read(path)
+        .map_err(|e| AppError::Io(format!("Failed to read backup: {}", e)))?;
+    let payload = open(&file, passphrase)?;
+    let backup: Backup = ciborium::from_reader(payload.as_slice())
+        .map_err(|e| AppError::Validation(format!("Unreadable backup contents: {}", e)))?;
+
+    let items = backup.items.into_iter()
+        .filter(|entry| !entry.item.no_sync)
+        .map(|entry| (entry.item, entry.png))
+        .collect();
+    let pins = backup.pins.into_iter()
+        .filter(|entry| !entry.pin.item.no_sync)
+        .map(|entry| (entry.pin, entry.png))
+        .collect();
+    let (items, items_pruned) = history.merge_items(items, dry_run)?;
+    let (collections_added, pins) = history.pins().merge(backup.collections, pins, dry_run)?;
+
+    if !dry_run {
+        println!(
+            "[ClipboardBackup] Imported {} new and {} updated items ({} pruned), {} new and {} updated pins",
+            items.added, items.updated, items_pruned, pins.added, pins.updated
+        );
+    }
+    Ok(ClipboardImportSummary {
+        dry_run,
+        exported_at: backup.exported_at,
+        items_added: items.added as i32,
+        items_updated: items.updated as i32,
+        items_skipped: items.skipped as i32,
+        items_pruned: items_pruned as i32,
+        pins_added: pins.added as i32,
+        pins_updated: pins.updated as i32,
+        pins_skipped: pins.skipped as i32,
+        collections_added: collections_added as i32,
+    })
+}
+
+/// 256-bit key from the passphrase
+fn derive_key(passphrase: &str, salt: &[u8], kdf: KdfParams) -> AppResult<[u8; 32]> {
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+        .map_err(|e| AppError::Validation(format!("Invalid key derivation settings: {}", e)))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::System(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+fn cipher(key: &[u8; 32]) -> AppResult<XChaCha20Poly1305> {
+    XChaCha20Poly1305::new_from_slice(key)
+        .map_err(|e| AppError::System(format!("Failed to create cipher: {}", e)))
+}
+
+/// Header followed by the encrypted payload
+fn seal(payload: &[u8], passphrase: &str, kdf: KdfParams) -> AppResult<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut file = Vec::with_capacity(HEADER_LEN + payload.len() + 16);
+    file.extend_from_slice(MAGIC);
+    file.push(FORMAT_VERSION);
+    for value in [kdf.m_cost, kdf.t_cost, kdf.p_cost] {
+        file.extend_from_slice(&value.to_le_bytes());
+    }
+    file.extend_from_slice(&salt);
+    file.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt, kdf)?;
+    let ciphertext = cipher(&key)?
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg: payload, aad: &file })
+        .map_err(|e| AppError::Calculation(format!("Encryption failed: {}", e)))?;
+    file.extend(ciphertext);
+    Ok(file)
+}
+
+/// Payload of a backup file, after checking its header
+fn open(file: &[u8], passphrase: &str) -> AppResult<Vec<u8>> {
+    if file.len() < HEADER_LEN || !file.starts_with(MAGIC) {
+        return Err(AppError::Validation("Not a clipboard backup file".to_string()));
+    }
+    let version = file[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(AppError::Validation(format!(
+            "Unsupported backup version {} (expected {})", version, FORMAT_VERSION
+        )));
+    }
+
+    let (header, ciphertext) = file.split_at(HEADER_LEN);
+    let word = |at: usize| {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&header[at..at + 4]);
+        u32::from_le_bytes(bytes)
+    };
+    let kdf = KdfParams {
+        m_cost: word(KDF_OFFSET),
+        t_cost: word(KDF_OFFSET + 4),
+        p_cost: word(KDF_OFFSET + 8),
+    };
+    if kdf.m_cost > MAX_KDF.m_cost || kdf.t_cost > MAX_KDF.t_cost || kdf.p_cost > MAX_KDF.p_cost {
+        return Err(AppError::Validation("Backup key derivation settings are out of range".to_string()));
+    }
+    let salt = &header[KDF_OFFSET + 12..KDF_OFFSET + 12 + SALT_LEN];
+    let nonce = &header[HEADER_LEN - NONCE_LEN..];
+
+    let key = derive_key(passphrase, salt, kdf)?;
+    cipher(&key)?
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| AppError::Validation("Wrong passphrase, or the backup file is damaged".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap settings so tests stay fast
+    const TEST_KDF: KdfParams = KdfParams { m_cost: 64, t_cost: 1, p_cost: 1 };
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("clipboard-backup-{}.bin", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let file = seal(b"payload", "correct horse", TEST_KDF).unwrap();
+        assert_eq!(open(&file, "correct horse").unwrap(), b"payload");
+        assert!(open(&file, "wrong horse").is_err());
+
+        let mut tampered = file.clone();
+        tampered[KDF_OFFSET + 12] ^= 1; // salt
+        assert!(open(&tampered, "correct horse").is_err());
+
+        let mut newer = file.clone();
+        newer[MAGIC.len()] = FORMAT_VERSION + 1;
+        let error = open(&newer, "correct horse").unwrap_err().to_string();
+        assert!(error.contains("Unsupported backup version"), "{}", error);
+
+        let mut costly = file;
+        costly[KDF_OFFSET..KDF_OFFSET + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(open(&costly, "correct horse").is_err());
+        assert!(open(b"not a backup", "correct horse").is_err());
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let source = ClipboardHistory::in_memory();
+        source.add_item(ClipboardHistoryItem::new_text("hello".to_string(), None));
+        let mut private = ClipboardHistoryItem::new_text("device only".to_string(), None);
+        private.no_sync = true;
+        source.add_item(private);
+        let mut expiring = ClipboardHistoryItem::new_text("token ••••".to_string(), None);
+        expiring.expires_at = Some(Utc::now() + chrono::Duration::hours(1));
+        source.add_item(expiring.clone());
+        // Pinning a sensitive copy keeps its timer, so the pin stays home too
+        source.pins().pin(&expiring, None, None, None).unwrap();
+        let snippets = source.pins().save_collection(None, "Snippets").unwrap();
+        let snippet = ClipboardHistoryItem::new_text("SELECT 1".to_string(), None);
+        source.pins().pin(&snippet, None, Some(snippets.id), None).unwrap();
+
+        let path = temp_path();
+        assert!(export_with(&source, &path, "short", TEST_KDF).is_err());
+        let exported = export_with(&source, &path, "correct horse", TEST_KDF).unwrap();
+        assert_eq!((exported.items, exported.pins, exported.collections), (1, 1, 1));
+
+        let target = ClipboardHistory::in_memory();
+        assert!(import(&target, &path, "wrong horse", true).is_err());
+        let preview = import(&target, &path, "correct horse", true).unwrap();
+        assert!(preview.dry_run);
+        assert_eq!((preview.items_added, preview.pins_added, preview.collections_added), (1, 1, 1));
+        assert_eq!(target.count(), 0, "a dry run writes nothing");
+
+        let imported = import(&target, &path, "correct horse", false).unwrap();
+        assert_eq!((imported.items_added, imported.pins_added, imported.collections_added), (1, 1, 1));
+        assert_eq!(target.get_items().unwrap().iter().map(|item| item.content.as_str()).collect::<Vec<_>>(), vec!["hello"]);
+        assert_eq!(target.pins().list().unwrap().items[0].item.content, "SELECT 1");
+
+        let again = import(&target, &path, "correct horse", false).unwrap();
+        assert_eq!((again.items_added, again.items_skipped, again.pins_skipped), (0, 1, 1));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_edited_pin_replaces_older_copy() {
+        let source = ClipboardHistory::in_memory();
+        let pin = source.pins().pin(&ClipboardHistoryItem::new_text("SELECT 1".to_string(), None), None, None, None).unwrap();
+        let path = temp_path();
+        export_with(&source, &path, "correct horse", TEST_KDF).unwrap();
+        let target = ClipboardHistory::in_memory();
+        assert_eq!(import(&target, &path, "correct horse", false).unwrap().pins_added, 1);
+
+        source.pins().update(&pin.item.id, "SELECT 2".to_string(), Some("Two".to_string()), None).unwrap();
+        export_with(&source, &path, "correct horse", TEST_KDF).unwrap();
+        let preview = import(&target, &path, "correct horse", true).unwrap();
+        assert_eq!((preview.pins_updated, preview.pins_skipped), (1, 0));
+        let imported = import(&target, &path, "correct horse", false).unwrap();
+        assert_eq!((imported.pins_updated, imported.pins_skipped), (1, 0));
+        let copy = target.pins().get(&pin.item.id).unwrap().unwrap();
+        assert_eq!((copy.item.content.as_str(), copy.title.as_deref()), ("SELECT 2", Some("Two")));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,125 @@
+//! Expiry of sensitive copies
+//!
+//! When secrets are found in a copy, two optional timers start (both set in
+//! clipboard settings). The system clipboard is cleared after a delay, but
+//! only if it still holds that copy, so something copied since is never
+//! lost. The history item gets a TTL, and a background sweeper deletes it
+//! from the database once the TTL runs out.
+
+use std::sync::RwLock;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+
+use super::formats;
+use super::history::ClipboardHistory;
+use crate::shared::emit::emit_event;
+use crate::shared::events::AppEvent;
+use crate::shared::settings::ClipboardSettings;
+
+/// How often the sweeper looks for expired items
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Expiry timers from settings (None = off)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ExpiryPolicy {
+    clear_after: Option<Duration>,
+    item_ttl: Option<chrono::Duration>,
+}
+
+impl ExpiryPolicy {
+    fn from_settings(settings: &ClipboardSettings) -> Self {
+        Self {
+            clear_after: settings.clear_sensitive_after_secs
+                .filter(|secs| *secs > 0)
+                .map(|secs| Duration::from_secs(secs as u64)),
+            item_ttl: settings.sensitive_item_ttl_mins
+                .filter(|mins| *mins > 0)
+                .map(|mins| chrono::Duration::minutes(mins as i64)),
+        }
+    }
+}
+
+static POLICY: Lazy<RwLock<ExpiryPolicy>> =
+    Lazy::new(|| RwLock::new(ExpiryPolicy::from_settings(&ClipboardSettings::default())));
+
+/// Replace the expiry timers (called when settings are loaded or saved)
+pub fn set_expiry(settings: &ClipboardSettings) {
+    let policy = ExpiryPolicy::from_settings(settings);
+    match POLICY.write() {
+        Ok(mut guard) => *guard = policy,
+        Err(poisoned) => *poisoned.into_inner() = policy,
+    }
+}
+
+fn policy() -> ExpiryPolicy {
+    match POLICY.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// When a sensitive item copied now should leave history (None = keep it)
+pub fn item_expiry() -> Option<DateTime<Utc>> {
+    policy().item_ttl.map(|ttl| Utc::now() + ttl)
+}
+
+/// Clear the system clipboard once the delay is up, if it still holds the
+/// copy with this fingerprint
+pub fn schedule_clear(fingerprint: u64) {
+    let Some(delay) = policy().clear_after else { return };
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-clear".to_string())
+        .spawn(move || {
+            std::thread::sleep(delay);
+            match formats::read() {
+                Ok(current) if current.fingerprint() == fingerprint => match formats::clear() {
+                    Ok(()) => println!("[ClipboardExpiry] Cleared sensitive copy from the clipboard"),
+                    Err(e) => eprintln!("[ClipboardExpiry] Failed to clear clipboard: {}", e),
+                },
+                Ok(_) => {}
+                Err(e) => eprintln!("[ClipboardExpiry] Failed to read clipboard: {}", e),
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("[ClipboardExpiry] Failed to start clear timer: {}", e);
+    }
+}
+
+/// Delete expired items now and then, telling the frontend which went
+pub fn start_sweeper(app: AppHandle, history: ClipboardHistory) {
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-sweeper".to_string())
+        .spawn(move || loop {
+            match history.purge_expired() {
+                Ok(ids) if ids.is_empty() => {}
+                Ok(ids) => {
+                    println!("[ClipboardExpiry] Purged {} expired item(s)", ids.len());
+                    emit_event(&app, AppEvent::ClipboardExpired(ids));
+                }
+                Err(e) => eprintln!("[ClipboardExpiry] Failed to purge expired items: {}", e),
+            }
+            std::thread::sleep(SWEEP_INTERVAL);
+        });
+    if let Err(e) = spawned {
+        eprintln!("[ClipboardExpiry] Failed to start sweeper: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_from_settings() {
+        let mut settings = ClipboardSettings::default();
+        assert_eq!(ExpiryPolicy::from_settings(&settings), ExpiryPolicy::default());
+
+        settings.clear_sensitive_after_secs = Some(30);
+        settings.sensitive_item_ttl_mins = Some(0);
+        let policy = ExpiryPolicy::from_settings(&settings);
+        assert_eq!(policy.clear_after, Some(Duration::from_secs(30)));
+        assert_eq!(policy.item_ttl, None);
+    }
+}
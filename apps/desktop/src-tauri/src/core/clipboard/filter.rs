@@ -1,73 +1,336 @@
-use crate::core::context::detection;
-use std::sync::OnceLock;
-use regex::Regex;
-
-static SECRET_PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
-static APP_BLACKLIST: OnceLock<Vec<String>> = OnceLock::new();
-
-/// Initialize the filter patterns and blacklist
-fn get_secret_patterns() -> &'static Vec<Regex> {
-    SECRET_PATTERNS.get_or_init(|| {
-        vec![
-            // GitHub Personal Access Token
-            Regex::new(r"ghp_[a-zA-Z0-9]{36}").expect("Invalid GitHub token regex"),
-            // Stripe Live Key
-            Regex::new(r"sk_live_[a-zA-Z0-9]{24}").expect("Invalid Stripe key regex"),
-            // Slack Token
-            Regex::new(r"xox[baprs]-[a-zA-Z0-9]{10,48}").expect("Invalid Slack token regex"),
-            // AWS Access Key ID
-            Regex::new(r"AKIA[0-9A-Z]{16}").expect("Invalid AWS ID regex"),
-            // Google API Key (Basic check)
-            Regex::new(r"AIza[0-9A-Za-z-_]{35}").expect("Invalid Google API key regex"),
-            // Generic Private Key Block
-            Regex::new(r"-----BEGIN (RSA|DSA|EC|PGP|OPENSSH) PRIVATE KEY-----").expect("Invalid Private Key regex"),
-        ]
-    })
+//! Secret detection
+//!
+//! A curated set of detectors (`detectors.rs`) runs over copied text through
+//! one `RegexSet`, so each chunk of text is read once no matter how many
+//! detectors there are; only detectors the set reports are re-run to find
+//! their spans. Hits are then confirmed with checksums and scores
+//! (`checks.rs`): Luhn for cards, mod-97 for IBANs, Shannon entropy for
+//! random-looking tokens, and placeholder checks for assignments.
+//!
+//! Findings carry spans, so callers can mask just the secrets (`redact`)
+//! rather than drop the whole text.
+//!
+//! Large inputs are scanned in overlapping chunks, so there is no size limit.
+//! Throughput is measured by an ignored test:
+//! `cargo test --release bench_scan -- --ignored --nocapture`.
+
+mod checks;
+#[cfg(test)]
+mod corpus;
+mod detectors;
+
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexSet};
+
+use super::rules::REDACTION_MASK;
+use detectors::DETECTORS;
+
+/// Bytes scanned at a time
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes shared by neighbouring chunks, so a secret on the boundary is whole in one of them
+const CHUNK_OVERLAP: usize = 8 * 1024;
+
+/// What a detector found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretKind {
+    AwsAccessKey,
+    AwsSecretKey,
+    GitHubToken,
+    StripeKey,
+    SlackToken,
+    SlackWebhook,
+    OpenAiKey,
+    AnthropicKey,
+    GoogleApiKey,
+    GcpServiceAccount,
+    GcpOAuthSecret,
+    AzureStorageKey,
+    AzureClientSecret,
+    AzureSasToken,
+    PrivateKey,
+    Jwt,
+    ConnectionString,
+    EnvSecret,
+    CredentialAssignment,
+    CardNumber,
+    Iban,
+    HighEntropyToken,
+}
+
+impl SecretKind {
+    /// Name that is safe to log
+    pub fn label(self) -> &'static str {
+        match self {
+            SecretKind::AwsAccessKey => "AWS access key",
+            SecretKind::AwsSecretKey => "AWS secret key",
+            SecretKind::GitHubToken => "GitHub token",
+            SecretKind::StripeKey => "Stripe key",
+            SecretKind::SlackToken => "Slack token",
+            SecretKind::SlackWebhook => "Slack webhook",
+            SecretKind::OpenAiKey => "OpenAI key",
+            SecretKind::AnthropicKey => "Anthropic key",
+            SecretKind::GoogleApiKey => "Google API key",
+            SecretKind::GcpServiceAccount => "GCP service account key",
+            SecretKind::GcpOAuthSecret => "GCP OAuth client secret",
+            SecretKind::AzureStorageKey => "Azure storage key",
+            SecretKind::AzureClientSecret => "Azure client secret",
+            SecretKind::AzureSasToken => "Azure SAS token",
+            SecretKind::PrivateKey => "private key",
+            SecretKind::Jwt => "JWT",
+            SecretKind::ConnectionString => "connection string password",
+            SecretKind::EnvSecret => ".env secret",
+            SecretKind::CredentialAssignment => "credential assignment",
+            SecretKind::CardNumber => "card number",
+            SecretKind::Iban => "IBAN",
+            SecretKind::HighEntropyToken => "high-entropy token",
+        }
+    }
 }
 
-fn get_app_blacklist() -> &'static Vec<String> {
-    APP_BLACKLIST.get_or_init(|| {
-        vec![
-            "com.agilebits.onepassword".to_string(), // 1Password 4
-            "com.onepassword.onepassword".to_string(), // 1Password 7
-            "com.1password.1password".to_string(), // 1Password 8
-            "com.bitwarden.desktop".to_string(), // Bitwarden
-            "org.keepassxc.keepassxc".to_string(), // KeePassXC
-            "com.apple.keychainaccess".to_string(), // macOS Keychain Access
-            "co.lastpass.lpmacosx".to_string(), // LastPass
-        ]
+/// A secret in scanned text; the span covers the secret value only
+/// (e.g. the password of a connection string, not the whole URL)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: SecretKind,
+    /// Byte offsets into the scanned text
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Detectors compiled once: the set finds which fire, the regexes find where
+struct Engine {
+    set: RegexSet,
+    regexes: Vec<Regex>,
+}
+
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let patterns = DETECTORS.iter().map(|detector| detector.pattern);
+    Engine {
+        set: RegexSet::new(patterns.clone()).expect("Invalid secret detector set"),
+        regexes: patterns.map(|pattern| Regex::new(pattern).expect("Invalid secret detector")).collect(),
+    }
+});
+
+/// Overlapping windows of at most `CHUNK_SIZE` bytes, with their offsets.
+/// Each window after the first starts at a line start inside the overlap
+/// when there is one, so line-anchored detectors still work.
+fn windows(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = Some(0);
+    std::iter::from_fn(move || {
+        let from = start?;
+        let mut end = (from + CHUNK_SIZE).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        start = if end == text.len() {
+            None
+        } else {
+            let mut next = end - CHUNK_OVERLAP;
+            while !text.is_char_boundary(next) {
+                next += 1;
+            }
+            Some(text[next..end].find('\n').map_or(next, |newline| next + newline + 1))
+        };
+        Some((from, &text[from..end]))
     })
 }
 
-/// Check if text content contains sensitive data or comes from a blacklisted app
-pub fn is_sensitive(content: &str, source_app: Option<&str>) -> bool {
-    // 1. Check Source App Blacklist
-    if let Some(app_id) = source_app {
-        // Simple case-insensitive check. Real Bundle IDs are usually lowercase but user might provide app name.
-        // Ideally we should match against Bundle ID, but `active_app` currently returns the localized Name (e.g., "1Password").
-        // So we should also blacklist known App Names.
-        let lower_app = app_id.to_lowercase();
-        if lower_app.contains("password") || lower_app.contains("keychain") || lower_app.contains("bitwarden") || lower_app.contains("keepass") {
-            println!("[ClipboardFilter] Blocking content from sensitive app: {}", app_id);
-            return true;
+/// Confirmed hits with the index of the detector behind each
+fn find(text: &str) -> Vec<(usize, Finding)> {
+    let engine = &*ENGINE;
+    let mut hits = Vec::new();
+    for (offset, window) in windows(text) {
+        for index in engine.set.matches(window).iter() {
+            let detector = &DETECTORS[index];
+            for captures in engine.regexes[index].captures_iter(window) {
+                let Some(secret) = captures.get(detector.group) else { continue };
+                let span = match detector.narrow {
+                    Some(narrow) => match narrow(secret.as_str()) {
+                        Some(inner) => secret.start() + inner.start..secret.start() + inner.end,
+                        None => continue,
+                    },
+                    None => secret.range(),
+                };
+                if detector.confirm.is_some_and(|confirm| !confirm(&window[span.clone()])) {
+                    continue;
+                }
+                hits.push((index, Finding {
+                    kind: detector.kind,
+                    start: offset + span.start,
+                    end: offset + span.end,
+                }));
+            }
+        }
+    }
+    hits
+}
+
+/// Every secret in `text`, in order. Where findings overlap, the more
+/// specific detector (earlier in the table) wins.
+pub fn scan(text: &str) -> Vec<Finding> {
+    let mut hits = find(text);
+    hits.sort_by_key(|(index, finding)| (*index, finding.start));
+
+    let mut findings: Vec<Finding> = Vec::with_capacity(hits.len());
+    for (_, finding) in hits {
+        let overlaps = findings.iter().any(|kept| finding.start < kept.end && kept.start < finding.end);
+        if !overlaps {
+            findings.push(finding);
         }
     }
+    findings.sort_by_key(|finding| finding.start);
+    findings
+}
+
+/// Known vendor prefixes, kept when a token is masked so it stays recognisable
+static VENDOR_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:sk-ant-[a-z]+\d{2}-|sk-(?:proj|svcacct|admin)-|sk-|(?:sk|rk)_live_|xox[a-z]-|gh[a-z]_|github_pat_|GOCSPX-|AIza|AKIA|ASIA|ABIA|ACCA)")
+        .expect("Invalid vendor prefix regex")
+});
+
+/// Masked form of one secret. Vendor tokens keep their prefix and last four
+/// characters (`sk_live_••••1234`), cards and IBANs their last four; anything
+/// that could be a password is masked whole.
+fn mask(kind: SecretKind, secret: &str) -> String {
+    let show_suffix = match kind {
+        SecretKind::AwsAccessKey
+        | SecretKind::GitHubToken
+        | SecretKind::StripeKey
+        | SecretKind::SlackToken
+        | SecretKind::OpenAiKey
+        | SecretKind::AnthropicKey
+        | SecretKind::GoogleApiKey
+        | SecretKind::GcpOAuthSecret => secret.len() >= 20,
+        SecretKind::CardNumber | SecretKind::Iban => true,
+        _ => false,
+    };
+    if !show_suffix {
+        return REDACTION_MASK.to_string();
+    }
+    let prefix = VENDOR_PREFIX.find(secret).map_or("", |prefix| prefix.as_str());
+    let mut suffix_start = secret.len().saturating_sub(4);
+    while !secret.is_char_boundary(suffix_start) {
+        suffix_start += 1;
+    }
+    format!("{}{}{}", prefix, REDACTION_MASK, &secret[suffix_start..])
+}
 
-    // 2. Check Content Patterns (Secrets)
-    // Avoid running regex on very long content to prevent DoS
-    if content.len() > 10_000 {
-        return false; // Assume long content is a document/code block which might accidentally trigger, or just too expensive. 
-                      // actually, large pastes might contain keys. But we need performance.
+/// `text` with every finding masked; findings must come from `scan(text)`
+pub fn redact(text: &str, findings: &[Finding]) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut copied = 0;
+    for finding in findings {
+        redacted.push_str(&text[copied..finding.start]);
+        redacted.push_str(&mask(finding.kind, &text[finding.start..finding.end]));
+        copied = finding.end;
+    }
+    redacted.push_str(&text[copied..]);
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_true_positives() {
+        for (kind, sample) in corpus::true_positives() {
+            let findings = scan(&sample);
+            assert!(
+                findings.iter().any(|finding| finding.kind == kind),
+                "expected {:?} in {:?}, found {:?}", kind, sample, findings
+            );
+        }
     }
 
-    let patterns = get_secret_patterns();
-    for pattern in patterns {
-        if pattern.is_match(content) {
-            // SECURITY: Never log sensitive content or pattern details
-            eprintln!("[SECURITY] Blocked sensitive content matching pattern: [REDACTED]");
-            return true;
+    #[test]
+    fn test_false_positives() {
+        for sample in corpus::FALSE_POSITIVES {
+            assert_eq!(scan(sample), Vec::new(), "false positive in {:?}", sample);
         }
     }
 
-    false
+    #[test]
+    fn test_spans_cover_the_secret() {
+        let password = "Tr0ub4dor-and-3";
+        let text = format!("DATABASE_URL=postgres://app:{}@db.internal:5432/app", password);
+        let findings = scan(&text);
+        assert_eq!(findings.len(), 1, "{:?}", findings);
+        assert_eq!(findings[0].kind, SecretKind::ConnectionString);
+        assert_eq!(&text[findings[0].start..findings[0].end], password);
+    }
+
+    #[test]
+    fn test_cards_and_ibans_next_to_other_numbers() {
+        let found = |text: &str| -> Vec<(SecretKind, String)> {
+            scan(text).iter().map(|f| (f.kind, text[f.start..f.end].to_string())).collect()
+        };
+        let card = |number: &str| vec![(SecretKind::CardNumber, number.to_string())];
+        assert_eq!(found("4111 1111 1111 1111 123"), card("4111 1111 1111 1111"));
+        assert_eq!(found("card 4111 1111 1111 1111 12 29 cvv 123"), card("4111 1111 1111 1111"));
+        assert_eq!(found("ref 12 3456 4111-1111-1111-1111"), card("4111-1111-1111-1111"));
+        assert_eq!(found("3782 822463 10005 0427 1234"), card("3782 822463 10005"));
+        assert_eq!(found("4111111111111111 123"), card("4111111111111111"));
+        assert!(found("4111 1111 1111 1112 123").is_empty());
+
+        // Belgian IBANs are exactly four groups, so a fifth isn't part of them
+        assert_eq!(found("BE68 5390 0754 7034 1234"), vec![(SecretKind::Iban, "BE68 5390 0754 7034".to_string())]);
+        assert_eq!(found("IBAN BE68539007547034, ref 42"), vec![(SecretKind::Iban, "BE68539007547034".to_string())]);
+    }
+
+    #[test]
+    fn test_large_input_is_scanned_across_chunks() {
+        let token = corpus::github_token();
+        let filler = "lorem ipsum dolor sit amet\n".repeat(10_000);
+        // Straddle the first chunk boundary, then sit at the very end
+        let mut text = filler[..CHUNK_SIZE - 10].to_string();
+        text.push('\n');
+        text.push_str(&token);
+        text.push('\n');
+        text.push_str(&filler);
+        text.push_str(&token);
+
+        let findings = scan(&text);
+        assert_eq!(findings.len(), 2, "{:?}", findings);
+        assert!(findings.iter().all(|finding| &text[finding.start..finding.end] == token));
+        assert!(scan(&filler).is_empty());
+    }
+
+    #[test]
+    fn test_redact() {
+        let stripe = concat!("sk_", "live_", "HGwC9pcJxTSwMvJKWmpY1U67");
+        let text = format!(
+            "key {}\ncard 4111 1111 1111 1111\nDB_PASSWORD=c0rrect-h0rse-battery\nbye",
+            stripe
+        );
+        let redacted = redact(&text, &scan(&text));
+        assert_eq!(
+            redacted,
+            "key sk_live_••••1U67\ncard ••••1111\nDB_PASSWORD=••••\nbye"
+        );
+        assert!(scan(&redacted).is_empty());
+        assert_eq!(redact("nothing here", &[]), "nothing here");
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_scan() {
+        // Mostly prose and code, which is what large copies usually are
+        let mut text = String::new();
+        while text.len() < 8 * 1024 * 1024 {
+            text.push_str("fn main() { let total = items.iter().map(|item| item.price * 1.2).sum::<f64>(); }\n");
+            text.push_str("The quick brown fox jumps over the lazy dog, 42 times on 2024-05-01.\n");
+            text.push_str("commit 3f786850e387550fdab836ed7e6dc881de23001b by dev@example.com\n");
+        }
+        let start = Instant::now();
+        let findings = scan(&text);
+        let elapsed = start.elapsed();
+        let mb = text.len() as f64 / (1024.0 * 1024.0);
+        println!(
+            "scanned {:.1} MB in {:?} ({:.1} MB/s), {} finding(s)",
+            mb, elapsed, mb / elapsed.as_secs_f64(), findings.len()
+        );
+    }
 }
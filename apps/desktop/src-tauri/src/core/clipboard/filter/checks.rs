@@ -0,0 +1,243 @@
+//! Checksums and scores that confirm a regex hit is a real secret
+
+use base64::Engine;
+
+/// Shannon entropy in bits per character
+pub fn shannon_entropy(text: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    let mut total = 0usize;
+    for byte in text.bytes() {
+        counts[byte as usize] += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    counts.iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Share of neighbouring characters that switch between upper case, lower
+/// case, digits and symbols. Random tokens switch about every other
+/// character; words and identifiers (even camelCase ones) rarely do.
+pub fn class_switch_ratio(text: &str) -> f64 {
+    let class = |c: char| match c {
+        'A'..='Z' => 0,
+        'a'..='z' => 1,
+        '0'..='9' => 2,
+        _ => 3,
+    };
+    let classes: Vec<u8> = text.chars().map(class).collect();
+    if classes.len() < 2 {
+        return 0.0;
+    }
+    let switches = classes.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    switches as f64 / (classes.len() - 1) as f64
+}
+
+/// Luhn checksum over a string of ASCII digits
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in digits.bytes().rev().enumerate() {
+        if !c.is_ascii_digit() {
+            return false;
+        }
+        let mut d = (c - b'0') as u32;
+        if i % 2 == 1 {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    !digits.is_empty() && sum % 10 == 0
+}
+
+/// Issuer prefix and length of the major card networks
+pub fn card_network_matches(digits: &str) -> bool {
+    let len = digits.len();
+    let prefix = |n: usize| digits.get(..n).and_then(|p| p.parse::<u32>().ok()).unwrap_or(0);
+    match digits.as_bytes().first() {
+        Some(b'4') => matches!(len, 13 | 16 | 19),                               // Visa
+        Some(b'5') => (51..=55).contains(&prefix(2)) && len == 16,              // Mastercard
+        Some(b'2') => (2221..=2720).contains(&prefix(4)) && len == 16,          // Mastercard 2-series
+        Some(b'3') => match prefix(2) {
+            34 | 37 => len == 15,                                                // Amex
+            35 => (16..=19).contains(&len),                                      // JCB
+            36 | 38 | 30 => len == 14 || len == 16,                              // Diners
+            _ => false,
+        },
+        Some(b'6') => {
+            (prefix(4) == 6011 || prefix(2) == 65 || (644..=649).contains(&prefix(3)))
+                && (16..=19).contains(&len)                                      // Discover
+        }
+        _ => false,
+    }
+}
+
+/// IBAN length for each country using them (ISO 13616 registry)
+pub fn iban_length(country: &str) -> Option<usize> {
+    Some(match country {
+        "NO" => 15,
+        "BE" => 16,
+        "DK" | "FI" | "FO" | "GL" | "NL" => 18,
+        "MK" | "SI" => 19,
+        "AT" | "BA" | "EE" | "KZ" | "LT" | "LU" | "XK" => 20,
+        "CH" | "HR" | "LI" | "LV" => 21,
+        "BG" | "BH" | "CR" | "DE" | "GB" | "GE" | "IE" | "ME" | "RS" => 22,
+        "AE" | "GI" | "IL" => 23,
+        "AD" | "CZ" | "ES" | "MD" | "PK" | "RO" | "SA" | "SE" | "SK" | "TN" | "VG" => 24,
+        "PT" => 25,
+        "IS" | "TR" => 26,
+        "FR" | "GR" | "IT" | "MC" | "MR" | "SM" => 27,
+        "AL" | "AZ" | "CY" | "DO" | "GT" | "HU" | "LB" | "PL" => 28,
+        "BR" | "EG" | "PS" | "QA" | "UA" => 29,
+        "JO" | "KW" | "MU" => 30,
+        "MT" => 31,
+        _ => return None,
+    })
+}
+
+/// Country length and mod-97 check of an IBAN (spaces allowed)
+pub fn iban_valid(iban: &str) -> bool {
+    let compact: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    if !compact.is_ascii() || compact.len() < 4 || iban_length(&compact[..2]) != Some(compact.len()) {
+        return false;
+    }
+    // Move the country code and check digits to the end, letters become 10..35
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder = 0u32;
+    for c in rearranged {
+        let value = match c.to_digit(36) {
+            Some(value) => value,
+            None => return false,
+        };
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// Whether a JWT's header decodes to a JSON object naming an algorithm
+pub fn jwt_header_valid(token: &str) -> bool {
+    let Some(header) = token.split('.').next() else { return false };
+    let Ok(decoded) = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(header.trim_end_matches('=')) else {
+        return false;
+    };
+    serde_json::from_slice::<serde_json::Value>(&decoded)
+        .ok()
+        .is_some_and(|value| value.get("alg").is_some())
+}
+
+/// Words that mark a value as an example rather than a real credential
+const PLACEHOLDER_MARKERS: &[&str] = &[
+    "example", "placeholder", "your", "changeme", "dummy", "sample", "redacted", "here", "fake",
+];
+
+/// Words that make up placeholders along with a marker ("your-api-key-here")
+const PLACEHOLDER_FILLERS: &[&str] = &[
+    "my", "the", "a", "an", "to", "me", "change", "api", "key", "access", "auth", "client", "secret",
+    "password", "passwd", "pass", "pwd", "token", "user", "username", "value", "id", "db", "database",
+];
+
+/// Values too obviously fake to be a credential
+const PLACEHOLDER_VALUES: &[&str] = &[
+    "password", "passwd", "pass", "pwd", "secret", "token", "user", "username", "null", "none", "undefined", "%s",
+];
+
+/// Whether a whole value is a template variable: `${NAME}`, `$NAME`,
+/// `{{ name }}`, `<name>` or `%(name)s`
+fn is_template(value: &str) -> bool {
+    let wrapped = |open: &str, close: &str| {
+        value.len() > open.len() + close.len() && value.starts_with(open) && value.ends_with(close)
+    };
+    let env_name = value.strip_prefix('$').is_some_and(|name| {
+        name.starts_with(|c: char| c.is_ascii_uppercase() || c == '_')
+            && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    });
+    env_name || wrapped("${", "}") || wrapped("{{", "}}") || wrapped("<", ">") || wrapped("%(", ")s")
+}
+
+/// Whether a captured value as a whole looks like an example, a template
+/// variable or a mask. Only whole values count: a real password may well
+/// contain "your" or "<3".
+pub fn is_placeholder(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    let mut chars = lower.chars();
+    let first = chars.next();
+    if is_template(value)
+        || PLACEHOLDER_VALUES.contains(&lower.as_str())
+        || first.is_some_and(|first| chars.all(|c| c == first))
+    {
+        return true;
+    }
+
+    // "your-api-key-here", "EXAMPLE_TOKEN", "xxxx-xxxx": placeholder words only
+    let is_mask = |word: &str| word.len() >= 3 && word.chars().all(|c| matches!(c, 'x' | '*' | '•'));
+    let words: Vec<&str> = lower.split(['-', '_', '.', ' ']).filter(|word| !word.is_empty()).collect();
+    !words.is_empty()
+        && words.iter().any(|word| PLACEHOLDER_MARKERS.contains(word) || is_mask(word))
+        && words.iter().all(|word| {
+            PLACEHOLDER_MARKERS.contains(word) || PLACEHOLDER_FILLERS.contains(word) || is_mask(word)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(shannon_entropy(""), 0.0);
+        assert_eq!(shannon_entropy("aaaa"), 0.0);
+        assert!((shannon_entropy("abcd") - 2.0).abs() < 1e-9);
+        assert!(class_switch_ratio("getUserAccountSettings2") < 0.35);
+        assert!(class_switch_ratio("kN2cZZAETujr8qvAQhn59Q") > 0.5);
+    }
+
+    #[test]
+    fn test_luhn_and_networks() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(!luhn_valid("4111111111111112"));
+        assert!(card_network_matches("4111111111111111"));
+        assert!(card_network_matches("378282246310005"));
+        assert!(!card_network_matches("1111111111111117"));
+        assert!(!card_network_matches("37828224631000")); // Amex is 15 digits
+    }
+
+    #[test]
+    fn test_iban() {
+        assert!(iban_valid("DE89 3704 0044 0532 0130 00"));
+        assert!(iban_valid("GB82WEST12345698765432"));
+        assert!(!iban_valid("DE89 3704 0044 0532 0130 01"));
+        assert!(!iban_valid("XX89370400440532013000"));
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert!(is_placeholder("${DB_PASSWORD}"));
+        assert!(is_placeholder("<your-token>"));
+        assert!(is_placeholder("Password"));
+        assert!(is_placeholder("00000000"));
+        assert!(is_placeholder("$DB_PASSWORD"));
+        assert!(is_placeholder("{{ vault_password }}"));
+        assert!(is_placeholder("YOUR_API_KEY_HERE"));
+        assert!(is_placeholder("example"));
+        assert!(is_placeholder("xxxx-xxxx-xxxx"));
+        assert!(!is_placeholder("s3cr3t-Horse-42"));
+        // Markers inside a real value don't make it fake
+        assert!(!is_placeholder("Yourname!<3"));
+        assert!(!is_placeholder("<3-my-dog>-"));
+        assert!(!is_placeholder("$ecret-Horse-42"));
+        assert!(!is_placeholder("example.com-Tr0ub4dor"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Curated secret detectors
+//!
+//! Each detector is a regex plus an optional check that confirms the hit.
+//! Order matters: when two findings overlap, the earlier (more specific)
+//! detector wins, so the generic entropy detector comes last.
+
+use std::ops::Range;
+
+use super::checks::{self, class_switch_ratio, shannon_entropy};
+use super::SecretKind;
+
+/// Least entropy (bits per character) for the generic random-token detector
+const ENTROPY_THRESHOLD: f64 = 4.2;
+
+/// Least character class switch rate for the generic random-token detector
+const SWITCH_THRESHOLD: f64 = 0.45;
+
+/// Finds the secret inside a capture, or rejects it
+pub type Narrow = fn(&str) -> Option<Range<usize>>;
+
+pub struct Detector {
+    pub kind: SecretKind,
+    pub pattern: &'static str,
+    /// Capture group holding the secret itself (0 = the whole match)
+    pub group: usize,
+    /// Extra check on the captured secret
+    pub confirm: Option<fn(&str) -> bool>,
+    /// Where the secret sits inside a capture that may run on into
+    /// neighbouring text (None from the function rejects the hit)
+    pub narrow: Option<Narrow>,
+}
+
+const fn detector(kind: SecretKind, pattern: &'static str) -> Detector {
+    Detector { kind, pattern, group: 0, confirm: None, narrow: None }
+}
+
+/// Value of a `NAME=value` style assignment: long enough and not a template
+fn real_value(value: &str) -> bool {
+    value.len() >= 8 && !checks::is_placeholder(value)
+}
+
+fn real_password(value: &str) -> bool {
+    !checks::is_placeholder(value)
+}
+
+fn card_number(digits: &str) -> bool {
+    (13..=19).contains(&digits.len())
+        && checks::card_network_matches(digits)
+        && checks::luhn_valid(digits)
+        && !checks::is_placeholder(digits)
+}
+
+/// The card inside a run of digit groups, which may carry a CVV, expiry
+/// or reference number on either side: the first group-aligned stretch of
+/// 13-19 digits that passes the card checks, longest first
+fn card_span(candidate: &str) -> Option<Range<usize>> {
+    let groups: Vec<Range<usize>> = candidate
+        .split([' ', '-'])
+        .scan(0, |offset, group| {
+            let range = *offset..*offset + group.len();
+            *offset = range.end + 1;
+            Some(range)
+        })
+        .filter(|range| !range.is_empty())
+        .collect();
+    (0..groups.len()).find_map(|first| {
+        (first..groups.len()).rev().find_map(|last| {
+            let span = groups[first].start..groups[last].end;
+            let digits: String = candidate[span.clone()].chars().filter(char::is_ascii_digit).collect();
+            card_number(&digits).then_some(span)
+        })
+    })
+}
+
+/// The IBAN at the start of a capture that may have taken in a following
+/// group: exactly as many characters as the country's IBANs have
+fn iban_span(candidate: &str) -> Option<Range<usize>> {
+    let length = checks::iban_length(candidate.get(..2)?)?;
+    let mut seen = 0;
+    let end = candidate.char_indices().find_map(|(i, c)| {
+        if c != ' ' {
+            seen += 1;
+        }
+        (seen == length).then_some(i + c.len_utf8())
+    })?;
+    // The IBAN has to end where a group does
+    let at_boundary = candidate[end..].is_empty() || candidate[end..].starts_with(' ');
+    (at_boundary && checks::iban_valid(&candidate[..end])).then_some(0..end)
+}
+
+/// Random-looking token: mixed case with digits, high entropy, and no
+/// word-like runs (which rules out long identifiers)
+fn random_token(token: &str) -> bool {
+    let has = |f: fn(&char) -> bool| token.chars().any(|c| f(&c));
+    has(char::is_ascii_uppercase)
+        && has(char::is_ascii_lowercase)
+        && has(char::is_ascii_digit)
+        && !checks::is_placeholder(token)
+        && shannon_entropy(token) >= ENTROPY_THRESHOLD
+        && class_switch_ratio(token) >= SWITCH_THRESHOLD
+}
+
+pub const DETECTORS: &[Detector] = &[
+    Detector {
+        kind: SecretKind::GcpServiceAccount,
+        pattern: r#""private_key"\s*:\s*"(-----BEGIN[^"]*PRIVATE KEY-----[^"]*)""#,
+        group: 1,
+        confirm: None,
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::PrivateKey,
+        pattern: r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----(?s:.*?-----END (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----)?",
+        group: 0,
+        confirm: None,
+        narrow: None,
+    },
+    detector(SecretKind::AnthropicKey, r"\bsk-ant-(?:api|admin|oat|sid)\d{2}-[A-Za-z0-9_-]{80,}"),
+    detector(SecretKind::OpenAiKey, r"\bsk-(?:proj|svcacct|admin)-[A-Za-z0-9_-]{40,}"),
+    detector(SecretKind::OpenAiKey, r"\bsk-[A-Za-z0-9]{20}T3BlbkFJ[A-Za-z0-9]{20}\b"),
+    detector(SecretKind::AwsAccessKey, r"\b(?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16}\b"),
+    Detector {
+        kind: SecretKind::AwsSecretKey,
+        pattern: r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})(?:[^A-Za-z0-9/+=]|$)"#,
+        group: 1,
+        confirm: None,
+        narrow: None,
+    },
+    detector(SecretKind::GitHubToken, r"\b(?:ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{36}\b"),
+    detector(SecretKind::GitHubToken, r"\bgithub_pat_[A-Za-z0-9_]{82}\b"),
+    detector(SecretKind::StripeKey, r"\b(?:sk|rk)_live_[A-Za-z0-9]{24,99}\b"),
+    detector(SecretKind::SlackToken, r"\bxox[baprse]-[A-Za-z0-9-]{10,72}"),
+    detector(SecretKind::SlackWebhook, r"https://hooks\.slack\.com/services/T[A-Z0-9]+/B[A-Z0-9]+/[A-Za-z0-9]{16,}"),
+    detector(SecretKind::GoogleApiKey, r"\bAIza[0-9A-Za-z_-]{35}"),
+    detector(SecretKind::GcpOAuthSecret, r"\bGOCSPX-[A-Za-z0-9_-]{28}"),
+    Detector {
+        kind: SecretKind::AzureStorageKey,
+        pattern: r"(?i)\bAccountKey=([A-Za-z0-9+/]{86}==)",
+        group: 1,
+        confirm: None,
+        narrow: None,
+    },
+    detector(SecretKind::AzureClientSecret, r"\b[A-Za-z0-9_~.-]{3}\dQ~[A-Za-z0-9_~.-]{31,34}"),
+    Detector {
+        kind: SecretKind::AzureSasToken,
+        pattern: r"[?&]sv=\d{4}-\d{2}-\d{2}&[^\s]*?\bsig=([A-Za-z0-9%+/=]{40,})",
+        group: 1,
+        confirm: None,
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::Jwt,
+        pattern: r"\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{16,}",
+        group: 0,
+        confirm: Some(checks::jwt_header_valid),
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::ConnectionString,
+        pattern: r"(?i)\b(?:postgres(?:ql)?|mysql|mariadb|mongodb(?:\+srv)?|rediss?|amqps?|mssql|sqlserver|ftp|sftp|smtps?)://[^\s:/@]+:([^\s@/]+)@[^\s/]+",
+        group: 1,
+        confirm: Some(real_password),
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::ConnectionString,
+        pattern: r"(?i)\b(?:Server|Data Source|Host)\s*=[^;\n]+;[^\n]*?\b(?:Password|Pwd)\s*=\s*([^;\s]+)",
+        group: 1,
+        confirm: Some(real_password),
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::EnvSecret,
+        pattern: r#"(?m)^[ \t]*(?:export[ \t]+)?[A-Z][A-Z0-9_]*(?:SECRET|TOKEN|PASSWORD|PASSWD|PWD|API_?KEY|PRIVATE_KEY|ACCESS_KEY|CREDENTIALS?)[ \t]*=[ \t]*["']?([^\s"'#]+)"#,
+        group: 1,
+        confirm: Some(real_value),
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::CredentialAssignment,
+        pattern: r#"(?i)\b(?:password|passwd|secret|api[_-]?key|access[_-]?token|auth[_-]?token|client[_-]?secret)["']?[ \t]*[:=][ \t]*["']([^\s"']+)["']"#,
+        group: 1,
+        confirm: Some(real_value),
+        narrow: None,
+    },
+    Detector {
+        kind: SecretKind::CardNumber,
+        // Up to 31 digits so a CVV or expiry after the card doesn't crowd it out
+        pattern: r"\b(\d(?:[ -]?\d){12,30})\b",
+        group: 1,
+        confirm: None,
+        narrow: Some(card_span),
+    },
+    Detector {
+        kind: SecretKind::Iban,
+        pattern: r"\b([A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?)\b",
+        group: 1,
+        confirm: None,
+        narrow: Some(iban_span),
+    },
+    Detector {
+        kind: SecretKind::HighEntropyToken,
+        pattern: r#"(?:\A\s*|["'=:][ \t]*)([A-Za-z0-9_+-]{32,200}={0,2})(?:\s*\z|["',;)\]}\s])"#,
+        group: 1,
+        confirm: Some(random_token),
+        narrow: None,
+    },
+];
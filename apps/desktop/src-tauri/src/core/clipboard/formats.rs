@@ -0,0 +1,450 @@
+//! Rich clipboard formats
+//!
+//! Reads every representation the system clipboard offers (plain text, HTML,
+//! RTF, an image, a list of files) into a `ClipboardSnapshot`, turns it into a
+//! history item, and writes all of them back on paste so the target app gets
+//! the richest format it understands. Images are kept as PNG blobs stored
+//! beside the item; the item itself only carries a small thumbnail.
+//!
+//! RTF is only reachable on macOS (through `NSPasteboard`); elsewhere it is
+//! simply never captured.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use base64::Engine;
+use image::ImageEncoder;
+
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::types::{ClipboardHistoryItem, ClipboardImageInfo, ClipboardItemType};
+
+/// Largest plain text kept, in bytes
+pub const MAX_TEXT_BYTES: usize = 1024 * 1024;
+
+/// Largest HTML fragment kept, in bytes
+pub const MAX_HTML_BYTES: usize = 2 * 1024 * 1024;
+
+/// Largest RTF document kept, in bytes
+pub const MAX_RTF_BYTES: usize = 2 * 1024 * 1024;
+
+/// Largest image kept, in pixels (about a 6K screenshot)
+pub const MAX_IMAGE_PIXELS: usize = 6144 * 3456;
+
+/// Largest encoded PNG kept, in bytes
+pub const MAX_PNG_BYTES: usize = 16 * 1024 * 1024;
+
+/// Most file references kept from one copy
+pub const MAX_FILES: usize = 500;
+
+/// Longest side of the thumbnail stored with image items
+const THUMBNAIL_SIZE: u32 = 160;
+
+/// Decoded RGBA8 image
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Everything the clipboard held at one moment
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClipboardSnapshot {
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+    pub image: Option<RgbaImage>,
+    /// `file://` URLs
+    pub files: Vec<String>,
+}
+
+// SECURITY: never print clipboard contents
+impl std::fmt::Debug for ClipboardSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClipboardSnapshot")
+            .field("text", &self.text.as_ref().map(|t| t.len()))
+            .field("html", &self.html.as_ref().map(|h| h.len()))
+            .field("rtf", &self.rtf.as_ref().map(|r| r.len()))
+            .field("image", &self.image.as_ref().map(|i| (i.width, i.height)))
+            .field("files", &self.files.len())
+            .finish()
+    }
+}
+
+impl ClipboardSnapshot {
+    pub fn is_empty(&self) -> bool {
+        self.text.as_deref().is_none_or(str::is_empty)
+            && self.html.is_none()
+            && self.rtf.is_none()
+            && self.image.is_none()
+            && self.files.is_empty()
+    }
+
+    /// Cheap change detector: equal snapshots give equal fingerprints
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A history item plus the PNG blob stored beside it (images only)
+pub struct Capture {
+    pub item: ClipboardHistoryItem,
+    pub png: Option<Vec<u8>>,
+}
+
+/// Shared arboard handle; recreated after an error
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+fn with_clipboard<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>) -> AppResult<T> {
+    let mut guard = match CLIPBOARD.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut clipboard = match guard.take() {
+        Some(clipboard) => clipboard,
+        None => arboard::Clipboard::new()
+            .map_err(|e| AppError::Io(format!("Failed to open clipboard: {}", e)))?,
+    };
+    let result = f(&mut clipboard).map_err(|e| AppError::Io(format!("Clipboard access failed: {}", e)))?;
+    *guard = Some(clipboard);
+    Ok(result)
+}
+
+/// `Ok(None)` when the clipboard simply doesn't hold this format
+fn optional<T>(result: Result<T, arboard::Error>) -> Result<Option<T>, arboard::Error> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(arboard::Error::ContentNotAvailable | arboard::Error::ConversionFailure) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Read every supported representation from the system clipboard
+pub fn read() -> AppResult<ClipboardSnapshot> {
+    let mut snapshot = with_clipboard(|clipboard| {
+        let files = optional(clipboard.get().file_list())?.unwrap_or_default();
+        let image = if files.is_empty() {
+            optional(clipboard.get().image())?.and_then(|image| {
+                (image.width * image.height <= MAX_IMAGE_PIXELS).then(|| RgbaImage {
+                    width: image.width as u32,
+                    height: image.height as u32,
+                    bytes: image.bytes.into_owned(),
+                })
+            })
+        } else {
+            None
+        };
+        Ok(ClipboardSnapshot {
+            text: optional(clipboard.get().text())?,
+            html: optional(clipboard.get().html())?,
+            rtf: None,
+            image,
+            files: files.iter().filter_map(|path| url::Url::from_file_path(path).ok()).map(String::from).collect(),
+        })
+    })?;
+    snapshot.rtf = read_rtf();
+    Ok(snapshot)
+}
+
+/// Empty the system clipboard
+pub fn clear() -> AppResult<()> {
+    with_clipboard(|clipboard| clipboard.clear())
+}
+
+/// Put every representation of `snapshot` on the system clipboard
+pub fn write(snapshot: &ClipboardSnapshot) -> AppResult<()> {
+    let text = snapshot.text.clone().unwrap_or_default();
+    with_clipboard(|clipboard| {
+        if !snapshot.files.is_empty() {
+            let paths: Vec<PathBuf> = snapshot.files.iter()
+                .filter_map(|file| url::Url::parse(file).ok()?.to_file_path().ok())
+                .collect();
+            clipboard.set().file_list(&paths)
+        } else if let Some(image) = &snapshot.image {
+            clipboard.set().image(arboard::ImageData {
+                width: image.width as usize,
+                height: image.height as usize,
+                bytes: image.bytes.as_slice().into(),
+            })
+        } else if let Some(html) = &snapshot.html {
+            clipboard.set().html(html.as_str(), Some(text.as_str()))
+        } else {
+            clipboard.set().text(text.as_str())
+        }
+    })?;
+    if let Some(rtf) = &snapshot.rtf {
+        write_rtf(rtf)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn read_rtf() -> Option<String> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pb: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pb == nil {
+            return None;
+        }
+        let rtf_type = NSString::alloc(nil).init_str("public.rtf");
+        let data: id = msg_send![pb, dataForType: rtf_type];
+        if data == nil {
+            return None;
+        }
+        let length: usize = msg_send![data, length];
+        if length == 0 || length > MAX_RTF_BYTES {
+            return None;
+        }
+        let bytes: *const u8 = msg_send![data, bytes];
+        let slice = std::slice::from_raw_parts(bytes, length);
+        Some(String::from_utf8_lossy(slice).into_owned())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_rtf() -> Option<String> {
+    None
+}
+
+/// Add RTF next to whatever was just written
+#[cfg(target_os = "macos")]
+fn write_rtf(rtf: &str) -> AppResult<()> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pb: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pb == nil {
+            return Err(AppError::Io("Failed to get NSPasteboard".to_string()));
+        }
+        let rtf_type = NSString::alloc(nil).init_str("public.rtf");
+        let types: id = msg_send![class!(NSArray), arrayWithObject: rtf_type];
+        let _: isize = msg_send![pb, addTypes: types owner: nil];
+        let data: id = msg_send![class!(NSData), dataWithBytes: rtf.as_ptr() length: rtf.len()];
+        let success: bool = msg_send![pb, setData: data forType: rtf_type];
+        if !success {
+            return Err(AppError::Io("Failed to write RTF to clipboard".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn write_rtf(_rtf: &str) -> AppResult<()> {
+    Ok(())
+}
+
+/// Turn a snapshot into a history item, dropping representations over their
+/// size limit. The richest remaining format decides the item type:
+/// files, then images (when there is no text), then HTML, RTF and plain text.
+pub fn capture(snapshot: &ClipboardSnapshot, source_app: Option<String>) -> AppResult<Option<Capture>> {
+    let text = snapshot.text.clone().filter(|t| !t.is_empty() && t.len() <= MAX_TEXT_BYTES);
+    let html = snapshot.html.clone().filter(|h| h.len() <= MAX_HTML_BYTES);
+    let rtf = snapshot.rtf.clone().filter(|r| r.len() <= MAX_RTF_BYTES);
+    let files: Vec<String> = snapshot.files.iter().take(MAX_FILES).cloned().collect();
+
+    let capture = if !files.is_empty() {
+        let content = files.iter().map(|file| file_name_or_path(file, false)).collect::<Vec<_>>().join("\n");
+        let mut item = ClipboardHistoryItem::new_text(content, source_app);
+        item.item_type = ClipboardItemType::Files;
+        item.preview = files_preview(&files, snapshot.files.len());
+        item.files = files;
+        Capture { item, png: None }
+    } else if let (Some(image), None) = (&snapshot.image, &text) {
+        let png = encode_png(image.width, image.height, &image.bytes)?;
+        if png.len() > MAX_PNG_BYTES {
+            println!("[ClipboardFormats] Image over the size limit, skipped");
+            return Ok(None);
+        }
+        let info = ClipboardImageInfo {
+            width: image.width as i32,
+            height: image.height as i32,
+            byte_size: png.len() as i32,
+            thumbnail: thumbnail(image)?,
+        };
+        Capture { item: ClipboardHistoryItem::new_image(info, source_app), png: Some(png) }
+    } else if let Some(text) = text {
+        let mut item = ClipboardHistoryItem::new_text(text, source_app);
+        if html.is_some() {
+            item.item_type = ClipboardItemType::Html;
+        } else if rtf.is_some() {
+            item.item_type = ClipboardItemType::Rtf;
+        }
+        item.html = html;
+        item.rtf = rtf;
+        Capture { item, png: None }
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(capture))
+}
+
+/// Snapshot that pastes `item` back in every format it was captured with
+pub fn restore(item: &ClipboardHistoryItem, png: Option<&[u8]>) -> AppResult<ClipboardSnapshot> {
+    let image = match (item.item_type == ClipboardItemType::Image, png) {
+        (true, Some(png)) => Some(decode_png(png)?),
+        (true, None) => return Err(AppError::Validation("Image data is missing".to_string())),
+        _ => None,
+    };
+    Ok(ClipboardSnapshot {
+        text: (!item.content.is_empty() && image.is_none()).then(|| item.content.clone()),
+        html: item.html.clone(),
+        rtf: item.rtf.clone(),
+        image,
+        files: item.files.clone(),
+    })
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> AppResult<Vec<u8>> {
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(rgba, width, height, image::ExtendedColorType::Rgba8)
+        .map_err(|e| AppError::Io(format!("Failed to encode image: {}", e)))?;
+    Ok(png)
+}
+
+fn decode_png(png: &[u8]) -> AppResult<RgbaImage> {
+    let decoded = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map_err(|e| AppError::Io(format!("Failed to decode image: {}", e)))?
+        .to_rgba8();
+    Ok(RgbaImage {
+        width: decoded.width(),
+        height: decoded.height(),
+        bytes: decoded.into_raw(),
+    })
+}
+
+/// PNG data URL at most `THUMBNAIL_SIZE` on its longest side
+fn thumbnail(source: &RgbaImage) -> AppResult<String> {
+    let buffer = image::RgbaImage::from_raw(source.width, source.height, source.bytes.clone())
+        .ok_or_else(|| AppError::Validation("Image data doesn't match its size".to_string()))?;
+    let scale = (THUMBNAIL_SIZE as f64 / source.width.max(source.height) as f64).min(1.0);
+    let width = ((source.width as f64 * scale).round() as u32).max(1);
+    let height = ((source.height as f64 * scale).round() as u32).max(1);
+    let small = image::imageops::thumbnail(&buffer, width, height);
+    let png = encode_png(width, height, small.as_raw())?;
+    Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png)))
+}
+
+/// Last path segment of a file URL, or the whole local path
+fn file_name_or_path(file: &str, name_only: bool) -> String {
+    let Some(path) = url::Url::parse(file).ok().and_then(|url| url.to_file_path().ok()) else {
+        return file.to_string();
+    };
+    match path.file_name() {
+        Some(name) if name_only => name.to_string_lossy().into_owned(),
+        _ => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// "report.pdf" or "3 files: a.png, b.png, c.png…"
+fn files_preview(files: &[String], total: usize) -> String {
+    let names: Vec<String> = files.iter().take(3).map(|file| file_name_or_path(file, true)).collect();
+    if total == 1 {
+        return names.join("");
+    }
+    let more = if total > names.len() { "…" } else { "" };
+    format!("{} files: {}{}", total, names.join(", "), more)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32) -> RgbaImage {
+        RgbaImage { width, height, bytes: vec![200; (width * height * 4) as usize] }
+    }
+
+    #[test]
+    fn test_capture_priority() {
+        let snapshot = ClipboardSnapshot {
+            text: Some("Hello".to_string()),
+            html: Some("<b>Hello</b>".to_string()),
+            rtf: Some("{\\rtf1 Hello}".to_string()),
+            ..Default::default()
+        };
+        let item = capture(&snapshot, None).unwrap().unwrap().item;
+        assert_eq!(item.item_type, ClipboardItemType::Html);
+        assert_eq!(item.content, "Hello");
+        assert!(item.rtf.is_some());
+
+        // Oversized HTML is dropped, the text survives
+        let snapshot = ClipboardSnapshot {
+            text: Some("Hello".to_string()),
+            html: Some("x".repeat(MAX_HTML_BYTES + 1)),
+            ..Default::default()
+        };
+        let item = capture(&snapshot, None).unwrap().unwrap().item;
+        assert_eq!(item.item_type, ClipboardItemType::Text);
+        assert!(item.html.is_none());
+
+        let huge = ClipboardSnapshot { text: Some("x".repeat(MAX_TEXT_BYTES + 1)), ..Default::default() };
+        assert!(capture(&huge, None).unwrap().is_none());
+        assert!(capture(&ClipboardSnapshot::default(), None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_image_round_trip() {
+        let snapshot = ClipboardSnapshot { image: Some(image(400, 200)), ..Default::default() };
+        let Capture { item, png } = capture(&snapshot, None).unwrap().unwrap();
+        assert_eq!(item.item_type, ClipboardItemType::Image);
+        assert!(item.content.is_empty());
+        assert_eq!(item.preview, "Image 400×200");
+        let info = item.image.as_ref().unwrap();
+        assert!(info.thumbnail.starts_with("data:image/png;base64,"));
+
+        let restored = restore(&item, png.as_deref()).unwrap();
+        assert!(restored.image == snapshot.image);
+        assert!(restore(&item, None).is_err());
+
+        let thumb = decode_png(&base64::engine::general_purpose::STANDARD
+            .decode(info.thumbnail.trim_start_matches("data:image/png;base64,")).unwrap()).unwrap();
+        assert_eq!((thumb.width, thumb.height), (160, 80));
+    }
+
+    #[test]
+    fn test_image_with_text_is_text() {
+        // Office apps put a rendered picture next to copied text
+        let snapshot = ClipboardSnapshot {
+            text: Some("A1".to_string()),
+            image: Some(image(2, 2)),
+            ..Default::default()
+        };
+        let Capture { item, png } = capture(&snapshot, None).unwrap().unwrap();
+        assert_eq!(item.item_type, ClipboardItemType::Text);
+        assert!(png.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_files() {
+        let files: Vec<String> = ["/tmp/a.txt", "/tmp/b c.png", "/tmp/d.pdf", "/tmp/e.md"].iter()
+            .map(|p| url::Url::from_file_path(p).unwrap().to_string())
+            .collect();
+        let snapshot = ClipboardSnapshot { files: files.clone(), text: Some("a.txt".to_string()), ..Default::default() };
+        let item = capture(&snapshot, None).unwrap().unwrap().item;
+        assert_eq!(item.item_type, ClipboardItemType::Files);
+        assert_eq!(item.preview, "4 files: a.txt, b c.png, d.pdf…");
+        assert!(item.content.starts_with("/tmp/a.txt\n/tmp/b c.png"));
+        assert_eq!(restore(&item, None).unwrap().files, files);
+
+        assert_eq!(files_preview(&files[..1], 1), "a.txt");
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = ClipboardSnapshot { text: Some("a".to_string()), ..Default::default() };
+        let b = ClipboardSnapshot { html: Some("a".to_string()), ..a.clone() };
+        assert_eq!(a.fingerprint(), a.clone().fingerprint());
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+}
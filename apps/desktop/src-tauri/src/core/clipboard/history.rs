@@ -1,159 +1,608 @@
 #![allow(dead_code)]
-use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use chrono::{DateTime, Utc};
-use redb::{Database, ReadableTable, TableDefinition};
-use std::path::PathBuf;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, RwLock};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use redb::{Database, ReadOnlyTable, ReadableTable, ReadableTableMetadata, Table, TableDefinition};
 use directories::ProjectDirs;
 
-use crate::shared::types::{ClipboardHistoryItem, ClipboardItemType};
+use super::pins::ClipboardPins;
+use super::search::{content_hash, IndexEntry, SearchFilter};
+use super::stack::CopyStack;
+use crate::shared::settings::ClipboardSettings;
+use crate::shared::types::ClipboardHistoryItem;
 use crate::shared::error::{AppError, AppResult};
 
-/// Maximum number of clipboard items to store
-const MAX_HISTORY_SIZE: usize = 5;
+/// Number of items returned when the caller doesn't ask for a page size
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a caller may request
+pub const MAX_PAGE_SIZE: usize = 200;
 
 /// Redb table definition for clipboard history (v2 using CBOR)
 /// Key: timestamp (u64), Value: serialized ClipboardHistoryItem (CBOR bytes)
 const CLIPBOARD_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("clipboard_history_v2");
 
+/// Encrypted search index, one entry per item under the same key
+/// Key: timestamp (u64), Value: serialized IndexEntry (CBOR bytes)
+const SEARCH_INDEX_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("clipboard_search_v1");
+
+/// Image data too large to keep inside the item, under the item's key
+/// Key: timestamp (u64), Value: encrypted PNG bytes
+const BLOBS_TABLE: TableDefinition<u64, &[u8]> = TableDefinition::new("clipboard_blobs_v1");
+
+/// When items with a TTL expire, kept apart so the sweeper decrypts nothing
+/// Key: timestamp (u64), Value: expiry in epoch milliseconds (u64)
+const EXPIRY_TABLE: TableDefinition<u64, u64> = TableDefinition::new("clipboard_expiry_v1");
+
+/// Repeat copies looked up without decrypting anything
+/// Key: `Codec::keyed_hash` of the item's `content_hash`, Value: timestamp (u64)
+const HASH_TABLE: TableDefinition<&[u8], u64> = TableDefinition::new("clipboard_hash_v1");
+
 // ClipboardItem and ClipboardItemType definitions moved to shared/types.rs
 
+/// Limits applied to stored history after every new item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionPolicy {
+    pub max_items: usize,
+    pub max_age_ms: Option<u64>,
+    pub max_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn from_settings(settings: &ClipboardSettings) -> Self {
+        Self {
+            max_items: (settings.max_items as usize).max(1),
+            max_age_ms: settings.max_age_days.map(|days| days as u64 * 24 * 60 * 60 * 1000),
+            max_bytes: settings.max_total_mb.map(|mb| mb as u64 * 1024 * 1024),
+        }
+    }
+
+    /// Keys to delete, given `(key, stored size)` pairs newest first.
+    /// The newest item always survives; once the count or byte budget
+    /// runs out, everything older goes too.
+    pub fn evictions(&self, entries: impl IntoIterator<Item = (u64, u64)>, now_ms: u64) -> Vec<u64> {
+        let cutoff = self.max_age_ms.map(|age| now_ms.saturating_sub(age));
+        let mut kept = 0;
+        let mut bytes = 0;
+        let mut full = false;
+        let mut evicted = Vec::new();
+
+        for (index, (key, size)) in entries.into_iter().enumerate() {
+            if index > 0 {
+                full = full
+                    || kept >= self.max_items
+                    || self.max_bytes.is_some_and(|max| bytes + size > max);
+                if full || cutoff.is_some_and(|cutoff| key < cutoff) {
+                    evicted.push(key);
+                    continue;
+                }
+            }
+            kept += 1;
+            bytes += size;
+        }
+        evicted
+    }
+}
+
+/// Retention limits from settings (applied on the next copy)
+static RETENTION: Lazy<RwLock<RetentionPolicy>> =
+    Lazy::new(|| RwLock::new(RetentionPolicy::from_settings(&ClipboardSettings::default())));
+
+/// Replace the retention policy (called when settings are loaded or saved)
+pub fn set_retention(settings: &ClipboardSettings) {
+    println!(
+        "[ClipboardHistory] Retention: {} items, {:?} days, {:?} MB",
+        settings.max_items, settings.max_age_days, settings.max_total_mb
+    );
+    let policy = RetentionPolicy::from_settings(settings);
+    match RETENTION.write() {
+        Ok(mut guard) => *guard = policy,
+        Err(poisoned) => *poisoned.into_inner() = policy,
+    }
+}
+
+fn retention() -> RetentionPolicy {
+    match RETENTION.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// One page of history, newest first
+#[derive(Debug, Clone, Default)]
+pub struct HistoryPage {
+    pub items: Vec<ClipboardHistoryItem>,
+    /// Pass as `before` to fetch the next page (None = no more items)
+    pub next: Option<u64>,
+}
+
+/// Inclusive key range below `before` and within `from..=to`, or None when empty
+fn page_range(from: Option<u64>, to: Option<u64>, before: Option<u64>) -> Option<(u64, u64)> {
+    let upper = match before {
+        Some(0) => return None,
+        Some(before) => (before - 1).min(to.unwrap_or(u64::MAX)),
+        None => to.unwrap_or(u64::MAX),
+    };
+    let lower = from.unwrap_or(0);
+    (lower <= upper).then_some((lower, upper))
+}
+
+/// What merging items from elsewhere did (or would do, in a dry run)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeCounts {
+    pub added: usize,
+    /// Replaced by a newer copy
+    pub updated: usize,
+    /// Already here and at least as new
+    pub skipped: usize,
+}
+
 /// Storage trait for clipboard history persistence
 trait Storage: Send + Sync {
-    fn save_item(&self, item: &ClipboardHistoryItem) -> AppResult<()>;
-    fn load_items(&self, limit: usize) -> AppResult<Vec<ClipboardHistoryItem>>;
+    fn save_item(&self, item: &ClipboardHistoryItem, blob: Option<&[u8]>) -> AppResult<()>;
+    /// Image data saved with an item
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>>;
+    /// Newest-first items with keys below `before`
+    fn load_page(&self, before: Option<u64>, limit: usize) -> AppResult<HistoryPage>;
+    /// Newest-first items matching `filter` with keys below `before`
+    fn search(&self, filter: &SearchFilter, before: Option<u64>, limit: usize) -> AppResult<HistoryPage>;
+    /// Delete items outside the retention policy, returning how many went
+    fn prune(&self, policy: &RetentionPolicy, now_ms: u64) -> AppResult<usize>;
+    fn count(&self) -> AppResult<usize>;
     fn clear_all(&self) -> AppResult<()>;
-    fn get_item_by_id(&self, id: &str) -> AppResult<Option<ClipboardHistoryItem>>;
+    /// Key and item with this ID
+    fn find_by_id(&self, id: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>>;
+    /// Key and item whose content hashes to `hash` (the newest, if copied twice)
+    fn find_by_hash(&self, hash: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>>;
+    /// Delete one item with its index entry and image data
+    fn remove_key(&self, key: u64) -> AppResult<()>;
+    /// Delete items whose TTL ran out, returning their IDs
+    fn purge_expired(&self, now_ms: u64) -> AppResult<Vec<String>>;
+    /// Every item with its image data, oldest first
+    fn export_all(&self) -> AppResult<Vec<(ClipboardHistoryItem, Option<Vec<u8>>)>>;
 }
 
 use crate::core::security::encryption::EncryptionManager;
 
+/// Open (or create) the clipboard database shared by history and pins
+fn open_database() -> AppResult<Arc<Database>> {
+    let proj_dirs = ProjectDirs::from("com", "antigravity", "productivity-widgets")
+        .ok_or_else(|| AppError::System("Failed to get project directories".to_string()))?;
+
+    let data_dir = proj_dirs.data_dir();
+    std::fs::create_dir_all(data_dir)
+        .map_err(|e| AppError::Io(format!("Failed to create data directory: {}", e)))?;
+
+    let db_path = data_dir.join("clipboard_history.redb");
+    let db = Database::create(db_path)
+        .map_err(|e| AppError::Io(format!("Failed to create database: {}", e)))?;
+    Ok(Arc::new(db))
+}
+
+/// Encrypted CBOR encoding shared by the clipboard tables
+#[derive(Clone)]
+pub(super) struct Codec {
+    encryption: Arc<EncryptionManager>,
+}
+
+impl Codec {
+    fn new() -> AppResult<Self> {
+        Ok(Self { encryption: Arc::new(EncryptionManager::new()?) })
+    }
+
+    /// Codec with a throwaway key
+    #[cfg(test)]
+    pub(super) fn ephemeral() -> Self {
+        Self { encryption: Arc::new(EncryptionManager::with_random_key()) }
+    }
+
+    /// Serialize to CBOR and encrypt
+    pub(super) fn encode<T: Serialize>(&self, value: &T) -> AppResult<Vec<u8>> {
+        let mut serialized = Vec::new();
+        ciborium::into_writer(value, &mut serialized)
+            .map_err(|e| AppError::Validation(format!("Serialization error: {}", e)))?;
+        self.encryption.encrypt(&serialized)
+    }
+
+    /// Encrypt raw bytes (image blobs)
+    pub(super) fn seal(&self, bytes: &[u8]) -> AppResult<Vec<u8>> {
+        self.encryption.encrypt(bytes)
+    }
+
+    /// HMAC of a `content_hash` under the database key, safe to store in the clear
+    pub(super) fn keyed_hash(&self, hash: &str) -> Vec<u8> {
+        self.encryption.keyed_hash(hash.as_bytes()).to_vec()
+    }
+
+    /// Decrypt raw bytes written by `seal`
+    pub(super) fn open(&self, bytes: &[u8]) -> AppResult<Vec<u8>> {
+        self.encryption.decrypt(bytes)
+    }
+
+    /// Decrypt and deserialize, falling back to plain CBOR
+    pub(super) fn decode<T: DeserializeOwned>(&self, raw_bytes: &[u8]) -> AppResult<T> {
+        match self.encryption.decrypt(raw_bytes) {
+            Ok(plaintext) => {
+                ciborium::from_reader(plaintext.as_slice())
+                    .map_err(|e| AppError::Validation(format!("Deserialization error (decrypted): {}", e)))
+            },
+            Err(_) => {
+                // Fallback: Try decoding as unencrypted (migration path)
+                // Note: Since we switched table name to "clipboard_history_v2",
+                // this path is only needed if we switch ON encryption for an existing v2 table.
+                ciborium::from_reader(raw_bytes)
+                    .map_err(|e| AppError::Validation(format!("Deserialization error (fallback): {}", e)))
+            }
+        }
+    }
+}
+
 /// Redb-based storage implementation
 struct RedbStorage {
     db: Arc<Database>,
-    encryption: Arc<EncryptionManager>,
+    codec: Codec,
 }
 
 impl RedbStorage {
-    fn new() -> AppResult<Self> {
-        let proj_dirs = ProjectDirs::from("com", "antigravity", "productivity-widgets")
-            .ok_or_else(|| AppError::System("Failed to get project directories".to_string()))?;
-        
-        let data_dir = proj_dirs.data_dir();
-        std::fs::create_dir_all(data_dir)
-            .map_err(|e| AppError::Io(format!("Failed to create data directory: {}", e)))?;
-        
-        let db_path = data_dir.join("clipboard_history.redb");
-        let db = Database::create(db_path)
-            .map_err(|e| AppError::Io(format!("Failed to create database: {}", e)))?;
-        
-        // Initialize table
+    fn new(db: Arc<Database>, codec: Codec) -> AppResult<Self> {
+        // Initialize tables
         {
             let write_txn = db.begin_write()
                 .map_err(|e| AppError::Io(format!("Failed to begin write transaction: {}", e)))?;
             {
                 let _table = write_txn.open_table(CLIPBOARD_TABLE)
                     .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+                let _index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+                let _blobs = write_txn.open_table(BLOBS_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+                let _expiry = write_txn.open_table(EXPIRY_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+                let _hashes = write_txn.open_table(HASH_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
             }
             write_txn.commit()
                 .map_err(|e| AppError::Io(format!("Failed to commit transaction: {}", e)))?;
         }
 
-        let encryption = Arc::new(EncryptionManager::new()?);
-        
-        Ok(Self {
-            db: Arc::new(db),
-            encryption,
-        })
+        let storage = Self { db, codec };
+        storage.backfill_index()?;
+        Ok(storage)
+    }
+
+    /// Index items saved before the search index (or its content hash) existed
+    fn backfill_index(&self) -> AppResult<()> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
+        let mut indexed = 0;
+        {
+            let table = write_txn.open_table(CLIPBOARD_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+
+            // Items saved before the hash table existed: decrypt each index entry once
+            let hashes_missing = hashes.is_empty()
+                .map_err(|e| AppError::Io(format!("Failed to read hash table: {}", e)))?;
+            if hashes_missing {
+                let iter = index.iter()
+                    .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+                let mut keyed = Vec::new();
+                for entry in iter {
+                    let (key, value) = entry
+                        .map_err(|e| AppError::Io(format!("Failed to read index entry: {}", e)))?;
+                    if let Ok(index_entry) = self.codec.decode::<IndexEntry>(value.value()) {
+                        keyed.push((self.codec.keyed_hash(&index_entry.hash), key.value()));
+                    }
+                }
+                // Oldest first, so a duplicate's newest copy wins
+                for (hash, key) in keyed {
+                    hashes.insert(hash.as_slice(), key)
+                        .map_err(|e| AppError::Io(format!("Failed to insert hash: {}", e)))?;
+                }
+            }
+
+            let iter = table.iter()
+                .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+            let mut missing = Vec::new();
+            for entry in iter {
+                let (key, value) = entry
+                    .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+                let has_entry = index.get(key.value())
+                    .map_err(|e| AppError::Io(format!("Failed to read index: {}", e)))?
+                    .and_then(|raw| self.codec.decode::<IndexEntry>(raw.value()).ok())
+                    .is_some_and(|entry| !entry.hash.is_empty());
+                if !has_entry {
+                    missing.push((key.value(), value.value().to_vec()));
+                }
+            }
+
+            for (key, raw_bytes) in missing {
+                let Ok(item) = self.codec.decode::<ClipboardHistoryItem>(&raw_bytes) else { continue };
+                let index_entry = IndexEntry::from_item(&item);
+                let encoded = self.codec.encode(&index_entry)?;
+                index.insert(key, encoded.as_slice())
+                    .map_err(|e| AppError::Io(format!("Failed to insert index entry: {}", e)))?;
+                hashes.insert(self.codec.keyed_hash(&index_entry.hash).as_slice(), key)
+                    .map_err(|e| AppError::Io(format!("Failed to insert hash: {}", e)))?;
+                indexed += 1;
+            }
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+
+        if indexed > 0 {
+            println!("[ClipboardHistory] Indexed {} existing items for search", indexed);
+        }
+        Ok(())
+    }
+
+    /// Key of the item with this ID. The ID is only known after decrypting, so
+    /// this walks the (small) index entries newest first.
+    fn find_key(&self, index: &ReadOnlyTable<u64, &'static [u8]>, id: &str) -> AppResult<Option<u64>> {
+        let iter = index.iter()
+            .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+        for entry in iter.rev() {
+            let (key, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read index entry: {}", e)))?;
+            let Ok(index_entry) = self.codec.decode::<IndexEntry>(value.value()) else { continue };
+            if index_entry.id == id {
+                return Ok(Some(key.value()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Newest-first items in `lo..=hi`, reading only the page it returns
+    fn scan_items(&self, lo: u64, hi: u64, limit: usize) -> AppResult<HistoryPage> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let table = read_txn.open_table(CLIPBOARD_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+
+        // Redb ranges are double-ended, so walking backwards stops at the page boundary
+        let mut range = table.range(lo..=hi)
+            .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?
+            .rev();
+
+        let mut page = HistoryPage::default();
+        let mut last_key = None;
+        for entry in range.by_ref().take(limit) {
+            let (key, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            page.items.push(self.codec.decode(value.value())?);
+            last_key = Some(key.value());
+        }
+        if range.next().is_some() {
+            page.next = last_key;
+        }
+        Ok(page)
+    }
+
+    /// Drop the hash entry of an item whose index entry was just removed,
+    /// unless a newer copy with the same content has taken it over
+    fn unlink_hash(&self, hashes: &mut Table<&'static [u8], u64>, key: u64, index_entry: Option<&[u8]>) -> AppResult<()> {
+        let Some(entry) = index_entry.and_then(|raw| self.codec.decode::<IndexEntry>(raw).ok()) else {
+            return Ok(());
+        };
+        let keyed = self.codec.keyed_hash(&entry.hash);
+        let points_here = hashes.get(keyed.as_slice())
+            .map_err(|e| AppError::Io(format!("Failed to read hash: {}", e)))?
+            .is_some_and(|found| found.value() == key);
+        if points_here {
+            hashes.remove(keyed.as_slice())
+                .map_err(|e| AppError::Io(format!("Failed to remove hash: {}", e)))?;
+        }
+        Ok(())
     }
 }
 
 impl Storage for RedbStorage {
-    fn save_item(&self, item: &ClipboardHistoryItem) -> AppResult<()> {
+    fn save_item(&self, item: &ClipboardHistoryItem, blob: Option<&[u8]>) -> AppResult<()> {
         let write_txn = self.db.begin_write()
             .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
-        
+
         {
             let mut table = write_txn.open_table(CLIPBOARD_TABLE)
                 .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
-            
-            let timestamp = item.timestamp.timestamp_millis() as u64;
-            
-            let mut serialized = Vec::new();
-            ciborium::into_writer(item, &mut serialized)
-                .map_err(|e| AppError::Validation(format!("Serialization error: {}", e)))?;
-            
-            // Encrypt data
-            let encrypted = self.encryption.encrypt(&serialized)?;
-
-            table.insert(timestamp, encrypted.as_slice())
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+
+            // Copies within the same millisecond take the next free key
+            let mut key = item.timestamp.timestamp_millis() as u64;
+            while table.get(key)
+                .map_err(|e| AppError::Io(format!("Failed to read key: {}", e)))?
+                .is_some()
+            {
+                key += 1;
+            }
+
+            let encrypted = self.codec.encode(item)?;
+            table.insert(key, encrypted.as_slice())
                 .map_err(|e| AppError::Io(format!("Failed to insert: {}", e)))?;
+
+            let index_entry = IndexEntry::from_item(item);
+            let encrypted_entry = self.codec.encode(&index_entry)?;
+            index.insert(key, encrypted_entry.as_slice())
+                .map_err(|e| AppError::Io(format!("Failed to insert index entry: {}", e)))?;
+
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+            hashes.insert(self.codec.keyed_hash(&index_entry.hash).as_slice(), key)
+                .map_err(|e| AppError::Io(format!("Failed to insert hash: {}", e)))?;
+
+            if let Some(blob) = blob {
+                let mut blobs = write_txn.open_table(BLOBS_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+                blobs.insert(key, self.codec.seal(blob)?.as_slice())
+                    .map_err(|e| AppError::Io(format!("Failed to insert blob: {}", e)))?;
+            }
+
+            if let Some(expires_at) = item.expires_at {
+                let mut expiry = write_txn.open_table(EXPIRY_TABLE)
+                    .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+                expiry.insert(key, expires_at.timestamp_millis().max(0) as u64)
+                    .map_err(|e| AppError::Io(format!("Failed to insert expiry: {}", e)))?;
+            }
         }
-        
+
         write_txn.commit()
             .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
-        
+
         Ok(())
     }
-    
-    fn load_items(&self, limit: usize) -> AppResult<Vec<ClipboardHistoryItem>> {
+
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let index = read_txn.open_table(SEARCH_INDEX_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+        let blobs = read_txn.open_table(BLOBS_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+
+        let Some(key) = self.find_key(&index, id)? else { return Ok(None) };
+        match blobs.get(key)
+            .map_err(|e| AppError::Io(format!("Failed to read blob: {}", e)))?
+        {
+            Some(raw) => Ok(Some(self.codec.open(raw.value())?)),
+            None => Ok(None),
+        }
+    }
+
+    fn load_page(&self, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        match page_range(None, None, before) {
+            Some((lo, hi)) => self.scan_items(lo, hi, limit),
+            None => Ok(HistoryPage::default()),
+        }
+    }
+
+    fn search(&self, filter: &SearchFilter, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        let Some((lo, hi)) = page_range(filter.from_ms, filter.to_ms, before) else {
+            return Ok(HistoryPage::default());
+        };
+        if !filter.needs_index() {
+            return self.scan_items(lo, hi, limit);
+        }
+
         let read_txn = self.db.begin_read()
             .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
-        
         let table = read_txn.open_table(CLIPBOARD_TABLE)
             .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
-        
-        let mut items = Vec::new();
-        // Since redb iteration is synchronous, we collect identifiers first if we want to reverse, 
-        // or just iterate reversely if supported. Redb iterators are double-ended.
-        // We'll proceed with collecting.
-        let iter = table.iter()
+        let index = read_txn.open_table(SEARCH_INDEX_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+
+        // Index entries are encrypted, so matching is a linear scan that
+        // decrypts each entry in the range; items are only read for hits
+        let range = index.range(lo..=hi)
             .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
-        
-        let mut entries: Vec<_> = iter.collect();
-        entries.reverse(); // Newest first
-        
-        for entry in entries.into_iter().take(limit) {
-            let (_, value) = entry
-                .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
-            
-            let raw_bytes = value.value();
-            
-            // Try decrypting
-            let item: ClipboardHistoryItem = match self.encryption.decrypt(raw_bytes) {
-                Ok(plaintext) => {
-                    ciborium::from_reader(plaintext.as_slice())
-                        .map_err(|e| AppError::Validation(format!("Deserialization error (decrypted): {}", e)))?
-                },
-                Err(_) => {
-                    // Fallback: Try decoding as unencrypted (migration path)
-                    // If it matches valid CBOR/JSON, use it.
-                    // Note: Since we switched table name to "clipboard_history_v2", 
-                    // this path is only needed if we switch ON encryption for an existing v2 table.
-                    // Assuming empty v2 table initially, this isn't strictly necessary, 
-                    // but good for safety if we later change specific encryption params.
-                    ciborium::from_reader(raw_bytes)
-                        .map_err(|e| AppError::Validation(format!("Deserialization error (fallback): {}", e)))?
-                }
+        let mut page = HistoryPage::default();
+        let mut last_key = None;
+        for entry in range.rev() {
+            let (key, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read index entry: {}", e)))?;
+            let Ok(index_entry) = self.codec.decode::<IndexEntry>(value.value()) else { continue };
+            if !filter.matches(&index_entry) {
+                continue;
+            }
+            let Some(raw) = table.get(key.value())
+                .map_err(|e| AppError::Io(format!("Failed to read item: {}", e)))?
+            else {
+                continue;
             };
-            
-            items.push(item);
+            // One match past a full page means there is another page
+            if page.items.len() == limit {
+                page.next = last_key;
+                break;
+            }
+            page.items.push(self.codec.decode(raw.value())?);
+            last_key = Some(key.value());
         }
-        
-        Ok(items)
+        Ok(page)
+    }
+
+    fn prune(&self, policy: &RetentionPolicy, now_ms: u64) -> AppResult<usize> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
+        let removed;
+        {
+            let mut table = write_txn.open_table(CLIPBOARD_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+            let mut blobs = write_txn.open_table(BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+            let mut expiry = write_txn.open_table(EXPIRY_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+
+            // Sizes are the stored (encrypted) lengths, so nothing is decrypted here
+            let iter = table.iter()
+                .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+            let mut sizes = Vec::new();
+            for entry in iter.rev() {
+                let (key, value) = entry
+                    .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+                let blob_size = blobs.get(key.value())
+                    .map_err(|e| AppError::Io(format!("Failed to read blob: {}", e)))?
+                    .map_or(0, |blob| blob.value().len());
+                sizes.push((key.value(), (value.value().len() + blob_size) as u64));
+            }
+
+            let evicted = policy.evictions(sizes, now_ms);
+            for key in &evicted {
+                table.remove(*key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove key: {}", e)))?;
+                let removed_entry = index.remove(*key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove index entry: {}", e)))?;
+                self.unlink_hash(&mut hashes, *key, removed_entry.as_ref().map(|raw| raw.value()))?;
+                blobs.remove(*key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove blob: {}", e)))?;
+                expiry.remove(*key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove expiry: {}", e)))?;
+            }
+            removed = evicted.len();
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+        Ok(removed)
     }
-    
+
+    fn count(&self) -> AppResult<usize> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let table = read_txn.open_table(CLIPBOARD_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+        let len = table.len()
+            .map_err(|e| AppError::Io(format!("Failed to count entries: {}", e)))?;
+        Ok(len as usize)
+    }
+
     fn clear_all(&self) -> AppResult<()> {
         let write_txn = self.db.begin_write()
             .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
-        
+
         {
             let mut table = write_txn.open_table(CLIPBOARD_TABLE)
                 .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
-            
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+            let mut blobs = write_txn.open_table(BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+            let mut expiry = write_txn.open_table(EXPIRY_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+
             let iter = table.iter()
                 .map_err(|e| AppError::Io(format!("Failed to iterate: {}", e)))?;
-            
+
             // Collect keys
             let mut keys = Vec::new();
             for entry_result in iter {
@@ -162,54 +611,280 @@ impl Storage for RedbStorage {
                 let (key, _) = entry;
                 keys.push(key.value());
             }
-            
+
             for key in keys {
                 table.remove(key)
                 .map_err(|e| AppError::Io(format!("Failed to remove key: {}", e)))?;
+                index.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove index entry: {}", e)))?;
+                blobs.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove blob: {}", e)))?;
+                expiry.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove expiry: {}", e)))?;
             }
+            hashes.retain(|_, _| false)
+                .map_err(|e| AppError::Io(format!("Failed to clear hashes: {}", e)))?;
         }
-        
+
         write_txn.commit()
             .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
-        
+
         Ok(())
     }
-    
-    fn get_item_by_id(&self, id: &str) -> AppResult<Option<ClipboardHistoryItem>> {
-        // Optimization: iterate finding match instead of loading all?
-        // Encrypted values means we MUST load and decrypt to check content if ID was inside content.
-        // But ID is part of the struct. We have to decrypt everything to find ID.
-        let items = self.load_items(MAX_HISTORY_SIZE * 20)?; 
-        Ok(items.into_iter().find(|item| item.id == id))
+
+    fn find_by_id(&self, id: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let table = read_txn.open_table(CLIPBOARD_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+        let index = read_txn.open_table(SEARCH_INDEX_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+
+        let Some(key) = self.find_key(&index, id)? else { return Ok(None) };
+        match table.get(key)
+            .map_err(|e| AppError::Io(format!("Failed to read item: {}", e)))?
+        {
+            Some(raw) => Ok(Some((key, self.codec.decode(raw.value())?))),
+            None => Ok(None),
+        }
+    }
+
+    fn find_by_hash(&self, hash: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let table = read_txn.open_table(CLIPBOARD_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+        let hashes = read_txn.open_table(HASH_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+
+        let Some(key) = hashes.get(self.codec.keyed_hash(hash).as_slice())
+            .map_err(|e| AppError::Io(format!("Failed to read hash: {}", e)))?
+            .map(|key| key.value())
+        else {
+            return Ok(None);
+        };
+        // Only the one candidate is decrypted, and checked in case the entry is stale
+        match table.get(key)
+            .map_err(|e| AppError::Io(format!("Failed to read item: {}", e)))?
+        {
+            Some(raw) => {
+                let item: ClipboardHistoryItem = self.codec.decode(raw.value())?;
+                Ok((content_hash(&item) == hash).then_some((key, item)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn remove_key(&self, key: u64) -> AppResult<()> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
+        {
+            let mut table = write_txn.open_table(CLIPBOARD_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+            let mut blobs = write_txn.open_table(BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+            let mut expiry = write_txn.open_table(EXPIRY_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+            table.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove key: {}", e)))?;
+            let removed_entry = index.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove index entry: {}", e)))?;
+            self.unlink_hash(&mut hashes, key, removed_entry.as_ref().map(|raw| raw.value()))?;
+            blobs.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove blob: {}", e)))?;
+            expiry.remove(key)
+                .map_err(|e| AppError::Io(format!("Failed to remove expiry: {}", e)))?;
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+        Ok(())
+    }
+
+    fn purge_expired(&self, now_ms: u64) -> AppResult<Vec<String>> {
+        // The sweeper runs every few seconds, so look before taking the write lock
+        let expired = {
+            let read_txn = self.db.begin_read()
+                .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+            let expiry = read_txn.open_table(EXPIRY_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+            let iter = expiry.iter()
+                .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+            let mut expired = Vec::new();
+            for entry in iter {
+                let (key, expires_ms) = entry
+                    .map_err(|e| AppError::Io(format!("Failed to read expiry: {}", e)))?;
+                if expires_ms.value() <= now_ms {
+                    expired.push(key.value());
+                }
+            }
+            expired
+        };
+        if expired.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let write_txn = self.db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
+        let mut purged = Vec::new();
+        {
+            let mut table = write_txn.open_table(CLIPBOARD_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+            let mut index = write_txn.open_table(SEARCH_INDEX_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open index table: {}", e)))?;
+            let mut blobs = write_txn.open_table(BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+            let mut expiry = write_txn.open_table(EXPIRY_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open expiry table: {}", e)))?;
+            let mut hashes = write_txn.open_table(HASH_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open hash table: {}", e)))?;
+
+            for key in expired {
+                // Skip items removed since the read
+                let still_expired = expiry.get(key)
+                    .map_err(|e| AppError::Io(format!("Failed to read expiry: {}", e)))?
+                    .is_some_and(|expires_ms| expires_ms.value() <= now_ms);
+                if !still_expired {
+                    continue;
+                }
+                // Only the index entry is decrypted, for the ID the frontend needs
+                let removed_entry = index.remove(key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove index entry: {}", e)))?;
+                let raw_entry = removed_entry.as_ref().map(|raw| raw.value());
+                self.unlink_hash(&mut hashes, key, raw_entry)?;
+                let id = raw_entry
+                    .and_then(|raw| self.codec.decode::<IndexEntry>(raw).ok())
+                    .map(|entry| entry.id);
+                table.remove(key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove key: {}", e)))?;
+                blobs.remove(key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove blob: {}", e)))?;
+                expiry.remove(key)
+                    .map_err(|e| AppError::Io(format!("Failed to remove expiry: {}", e)))?;
+                purged.extend(id);
+            }
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+        Ok(purged)
+    }
+
+    fn export_all(&self) -> AppResult<Vec<(ClipboardHistoryItem, Option<Vec<u8>>)>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let table = read_txn.open_table(CLIPBOARD_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open table: {}", e)))?;
+        let blobs = read_txn.open_table(BLOBS_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open blobs table: {}", e)))?;
+
+        let iter = table.iter()
+            .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?;
+        let mut exported = Vec::new();
+        for entry in iter {
+            let (key, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            let item = self.codec.decode(value.value())?;
+            let blob = blobs.get(key.value())
+                .map_err(|e| AppError::Io(format!("Failed to read blob: {}", e)))?
+                .map(|raw| self.codec.open(raw.value()))
+                .transpose()?;
+            exported.push((item, blob));
+        }
+        Ok(exported)
     }
 }
 
 /// Clipboard history manager with embedded database persistence
 pub struct ClipboardHistory {
     storage: Arc<dyn Storage>,
+    pins: Arc<ClipboardPins>,
+    stack: Arc<CopyStack>,
     skip_next_add: Arc<Mutex<bool>>,
+    /// Masked item IDs by a hash of the unmasked text behind them, keyed per
+    /// process and kept in memory only, so masked copies of different secrets
+    /// that look the same once masked stay separate items
+    masked_originals: Arc<Mutex<HashMap<u64, String>>>,
 }
 
+/// Random key for `masked_originals`, new every run
+static ORIGINAL_HASHER: Lazy<RandomState> = Lazy::new(RandomState::new);
+
 impl ClipboardHistory {
     /// Create a new clipboard history manager with embedded database
     pub fn new() -> Self {
-        let storage: Arc<dyn Storage> = match RedbStorage::new() {
-            Ok(s) => Arc::new(s),
+        let opened = open_database().and_then(|db| {
+            let codec = Codec::new()?;
+            let storage = RedbStorage::new(Arc::clone(&db), codec.clone())?;
+            let pins = ClipboardPins::open(db, codec)?;
+            Ok((storage, pins))
+        });
+
+        match opened {
+            Ok((storage, pins)) => Self::with_storage(Arc::new(storage), pins),
             Err(e) => {
                 eprintln!("[ClipboardHistory] Failed to initialize database: {}, using in-memory fallback", e);
                 // Fallback to in-memory storage if DB fails
-                Arc::new(InMemoryStorage::new())
+                Self::with_storage(Arc::new(InMemoryStorage::new()), ClipboardPins::in_memory())
             }
-        };
-        
+        }
+    }
+
+    /// History and pins kept in memory only
+    #[cfg(test)]
+    pub(super) fn in_memory() -> Self {
+        Self::with_storage(Arc::new(InMemoryStorage::new()), ClipboardPins::in_memory())
+    }
+
+    fn with_storage(storage: Arc<dyn Storage>, pins: ClipboardPins) -> Self {
         Self {
             storage,
+            pins: Arc::new(pins),
+            stack: Arc::new(CopyStack::default()),
             skip_next_add: Arc::new(Mutex::new(false)),
+            masked_originals: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Pinned items and collections (kept outside history retention)
+    pub fn pins(&self) -> &ClipboardPins {
+        &self.pins
+    }
+
+    /// Copies collected for pasting together
+    pub fn stack(&self) -> &CopyStack {
+        &self.stack
+    }
+
     /// Add an item to the history (persisted to database)
     pub fn add_item(&self, item: ClipboardHistoryItem) {
+        let _ = self.add_capture(item, None);
+    }
+
+    /// Add an item together with its image data (PNG), if it has any.
+    /// Copying something already in history moves that item to the top
+    /// (keeping its ID) and bumps its use count. Returns the stored item.
+    pub fn add_capture(&self, item: ClipboardHistoryItem, png: Option<&[u8]>) -> Option<ClipboardHistoryItem> {
+        self.insert_capture(item, png, None)
+    }
+
+    /// Add an item whose secrets were masked. It only counts as a repeat of
+    /// an item masked from the same `original` text this run, since two
+    /// different secrets can mask to the same text ("card ••••1111").
+    pub fn add_masked_capture(&self, item: ClipboardHistoryItem, png: Option<&[u8]>, original: &str) -> Option<ClipboardHistoryItem> {
+        self.insert_capture(item, png, Some(ORIGINAL_HASHER.hash_one(original)))
+    }
+
+    fn masked_originals(&self) -> std::sync::MutexGuard<'_, HashMap<u64, String>> {
+        match self.masked_originals.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn insert_capture(&self, mut item: ClipboardHistoryItem, png: Option<&[u8]>, original: Option<u64>) -> Option<ClipboardHistoryItem> {
         // Check skip flag (with mutex recovery)
         let mut skip = match self.skip_next_add.lock() {
             Ok(guard) => guard,
@@ -221,35 +896,82 @@ impl ClipboardHistory {
         if *skip {
             println!("[ClipboardHistory] Skipping add due to skip_next_add flag");
             *skip = false;
-            return;
+            return None;
         }
         drop(skip);
 
-        // Check for duplicates by loading recent items
-        if let Ok(recent) = self.storage.load_items(1) {
-            if let Some(last) = recent.first() {
-                if last.content == item.content && last.item_type == item.item_type {
-                    println!("[ClipboardHistory] Skipping duplicate item");
-                    return;
+        // A masked copy only repeats the item masked from the same text, and
+        // a plain copy never repeats a masked one
+        let found = match original {
+            Some(original) => {
+                let id = self.masked_originals().get(&original).cloned();
+                match id {
+                    Some(id) => self.storage.find_by_id(&id),
+                    None => Ok(None),
                 }
             }
-        }
+            None => self.storage.find_by_hash(&content_hash(&item)).map(|found| {
+                found.filter(|(_, existing)| !self.masked_originals().values().any(|id| *id == existing.id))
+            }),
+        };
+        let replaced = match found {
+            Ok(Some((key, existing))) => {
+                item.id = existing.id;
+                item.use_count = existing.use_count.max(1) + 1;
+                Some(key)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                eprintln!("[ClipboardHistory] Failed to look up duplicates: {}", e);
+                None
+            }
+        };
 
-        // Save to database
-        if let Err(e) = self.storage.save_item(&item) {
+        // Save to database; the old copy goes only once the new one is stored
+        if let Err(e) = self.storage.save_item(&item, png) {
             eprintln!("[ClipboardHistory] Failed to save item to database: {}", e);
-            return;
+            return None;
+        }
+        if let Some(key) = replaced {
+            if let Err(e) = self.storage.remove_key(key) {
+                eprintln!("[ClipboardHistory] Failed to remove previous copy: {}", e);
+            }
+            println!("[ClipboardHistory] Moved repeat copy to the top (used {} times)", item.use_count);
         }
 
-        // Enforce MAX_HISTORY_SIZE by loading all and keeping only recent ones
-        let _ = self.storage.load_items(MAX_HISTORY_SIZE * 2);
+        if let Some(original) = original {
+            self.masked_originals().insert(original, item.id.clone());
+        }
+
+        if self.stack.push(&item) {
+            println!("[ClipboardHistory] Added item to copy stack");
+        }
+
+        // Enforce retention (count, age and total size)
+        match self.storage.prune(&retention(), Utc::now().timestamp_millis() as u64) {
+            Ok(0) => {}
+            Ok(removed) => println!("[ClipboardHistory] Pruned {} items past retention", removed),
+            Err(e) => eprintln!("[ClipboardHistory] Failed to prune history: {}", e),
+        }
 
         println!("[ClipboardHistory] Added item to database: {}", item.id);
+        Some(item)
     }
 
-    /// Get all clipboard items (from database)
+    /// Get the most recent clipboard items (from database)
     pub fn get_items(&self) -> AppResult<Vec<ClipboardHistoryItem>> {
-        self.storage.load_items(MAX_HISTORY_SIZE)
+        Ok(self.storage.load_page(None, DEFAULT_PAGE_SIZE)?.items)
+    }
+
+    /// Get a page of items older than `before` (None = start from the newest)
+    pub fn get_page(&self, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        self.storage.load_page(before, limit.clamp(1, MAX_PAGE_SIZE))
+    }
+
+    /// Search history, newest matches first. Anything beyond a time range
+    /// decrypts every index entry in the range, so cost grows with history size.
+    pub fn search(&self, filter: &SearchFilter, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        self.storage.search(filter, before, limit.clamp(1, MAX_PAGE_SIZE))
     }
 
     /// Get a specific item by index (0 = most recent)
@@ -258,9 +980,102 @@ impl ClipboardHistory {
         Ok(items.get(index).cloned())
     }
 
-    /// Get a specific item by ID
+    /// Get a specific item by ID (history first, then pinned items)
     pub fn get_item_by_id(&self, id: &str) -> AppResult<Option<ClipboardHistoryItem>> {
-        self.storage.get_item_by_id(id)
+        match self.storage.find_by_id(id)? {
+            Some((_, item)) => Ok(Some(item)),
+            None => Ok(self.pins.get(id)?.map(|pin| pin.item)),
+        }
+    }
+
+    /// Image data (PNG) of a history item or pinned item
+    pub fn get_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        match self.storage.load_blob(id)? {
+            Some(blob) => Ok(Some(blob)),
+            None => self.pins.blob(id),
+        }
+    }
+
+    /// Delete items whose TTL has run out, returning their IDs
+    pub fn purge_expired(&self) -> AppResult<Vec<String>> {
+        self.storage.purge_expired(Utc::now().timestamp_millis().max(0) as u64)
+    }
+
+    /// Items that may leave this device, with their image data, oldest first.
+    /// Items marked `no_sync` and sensitive items on a timer are left out.
+    pub fn export_items(&self) -> AppResult<Vec<(ClipboardHistoryItem, Option<Vec<u8>>)>> {
+        let mut items = self.storage.export_all()?;
+        items.retain(|(item, _)| !item.no_sync && item.expires_at.is_none());
+        Ok(items)
+    }
+
+    /// Merge items from elsewhere (e.g. a backup). Unknown items are added;
+    /// an item already here, by ID or by content, is replaced only when the
+    /// incoming copy is newer, keeping the local ID. A dry run only counts.
+    /// Returns the counts and how many merged items the retention limits
+    /// removed again right away (left out of the counts; always 0 when dry).
+    pub fn merge_items(
+        &self,
+        items: Vec<(ClipboardHistoryItem, Option<Vec<u8>>)>,
+        dry_run: bool,
+    ) -> AppResult<(MergeCounts, usize)> {
+        let mut counts = MergeCounts::default();
+        // IDs written, and whether each was new
+        let mut merged = Vec::new();
+        for (mut item, png) in items {
+            let existing = match self.storage.find_by_id(&item.id)? {
+                Some(found) => Some(found),
+                None => self.storage.find_by_hash(&content_hash(&item))?,
+            };
+            let replaced = match existing {
+                Some((_, current)) if item.timestamp <= current.timestamp => {
+                    counts.skipped += 1;
+                    continue;
+                }
+                Some((key, current)) => {
+                    item.id = current.id;
+                    item.use_count = item.use_count.max(current.use_count);
+                    counts.updated += 1;
+                    Some(key)
+                }
+                None => {
+                    counts.added += 1;
+                    None
+                }
+            };
+            if dry_run {
+                continue;
+            }
+            self.storage.save_item(&item, png.as_deref())?;
+            if let Some(key) = replaced {
+                self.storage.remove_key(key)?;
+            }
+            merged.push((item.id, replaced.is_none()));
+        }
+
+        let mut pruned = 0;
+        if !merged.is_empty() {
+            match self.storage.prune(&retention(), Utc::now().timestamp_millis() as u64) {
+                Ok(0) => {}
+                Ok(removed) => {
+                    println!("[ClipboardHistory] Pruned {} items past retention", removed);
+                    // Old items from a backup may not have survived; don't count them
+                    for (id, added) in merged {
+                        if self.storage.find_by_id(&id)?.is_some() {
+                            continue;
+                        }
+                        if added {
+                            counts.added -= 1;
+                        } else {
+                            counts.updated -= 1;
+                        }
+                        pruned += 1;
+                    }
+                }
+                Err(e) => eprintln!("[ClipboardHistory] Failed to prune history: {}", e),
+            }
+        }
+        Ok((counts, pruned))
     }
 
     /// Clear all history (from database)
@@ -270,7 +1085,7 @@ impl ClipboardHistory {
 
     /// Get the count of items
     pub fn count(&self) -> usize {
-        self.get_items().map(|v| v.len()).unwrap_or(0)
+        self.storage.count().unwrap_or(0)
     }
 
     /// Set the skip_next_add flag (used for auto-paste to prevent re-adding)
@@ -290,52 +1105,174 @@ impl ClipboardHistory {
     pub fn clone_arc(&self) -> Self {
         Self {
             storage: Arc::clone(&self.storage),
+            pins: Arc::clone(&self.pins),
+            stack: Arc::clone(&self.stack),
             skip_next_add: Arc::clone(&self.skip_next_add),
+            masked_originals: Arc::clone(&self.masked_originals),
         }
     }
 }
 
 /// In-memory fallback storage (used if database initialization fails)
 struct InMemoryStorage {
-    items: Arc<Mutex<Vec<ClipboardHistoryItem>>>,
+    /// `(key, item)` pairs, newest first
+    items: Arc<Mutex<Vec<(u64, ClipboardHistoryItem)>>>,
+    blobs: Arc<Mutex<HashMap<u64, Vec<u8>>>>,
 }
 
 impl InMemoryStorage {
     fn new() -> Self {
         Self {
             items: Arc::new(Mutex::new(Vec::new())),
+            blobs: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    fn collect_page(
+        &self,
+        range: Option<(u64, u64)>,
+        limit: usize,
+        keep: impl Fn(&ClipboardHistoryItem) -> bool,
+    ) -> AppResult<HistoryPage> {
+        let Some((lo, hi)) = range else { return Ok(HistoryPage::default()) };
+        let items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+
+        let mut matches = items.iter()
+            .filter(|(key, item)| (lo..=hi).contains(key) && keep(item));
+        let mut page = HistoryPage::default();
+        let mut last_key = None;
+        for (key, item) in matches.by_ref().take(limit) {
+            page.items.push(item.clone());
+            last_key = Some(*key);
+        }
+        if matches.next().is_some() {
+            page.next = last_key;
+        }
+        Ok(page)
+    }
 }
 
 impl Storage for InMemoryStorage {
-    fn save_item(&self, item: &ClipboardHistoryItem) -> AppResult<()> {
+    fn save_item(&self, item: &ClipboardHistoryItem, blob: Option<&[u8]>) -> AppResult<()> {
         let mut items = self.items.lock()
             .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
-        items.insert(0, item.clone());
-        if items.len() > MAX_HISTORY_SIZE {
-            items.truncate(MAX_HISTORY_SIZE);
+        let mut key = item.timestamp.timestamp_millis() as u64;
+        while items.iter().any(|(existing, _)| *existing == key) {
+            key += 1;
+        }
+        let position = items.iter().position(|(existing, _)| *existing < key).unwrap_or(items.len());
+        items.insert(position, (key, item.clone()));
+        if let Some(blob) = blob {
+            self.blobs.lock()
+                .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?
+                .insert(key, blob.to_vec());
         }
         Ok(())
     }
-    
-    fn load_items(&self, limit: usize) -> AppResult<Vec<ClipboardHistoryItem>> {
+
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        let items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(items.iter()
+            .find(|(_, item)| item.id == id)
+            .and_then(|(key, _)| blobs.get(key).cloned()))
+    }
+
+    fn load_page(&self, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        self.collect_page(page_range(None, None, before), limit, |_| true)
+    }
+
+    fn search(&self, filter: &SearchFilter, before: Option<u64>, limit: usize) -> AppResult<HistoryPage> {
+        let range = page_range(filter.from_ms, filter.to_ms, before);
+        self.collect_page(range, limit, |item| filter.matches(&IndexEntry::from_item(item)))
+    }
+
+    fn prune(&self, policy: &RetentionPolicy, now_ms: u64) -> AppResult<usize> {
+        let mut items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let mut blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let sizes: Vec<(u64, u64)> = items.iter()
+            .map(|(key, item)| {
+                let mut serialized = Vec::new();
+                let _ = ciborium::into_writer(item, &mut serialized);
+                let blob_size = blobs.get(key).map_or(0, Vec::len);
+                (*key, (serialized.len() + blob_size) as u64)
+            })
+            .collect();
+        let evicted = policy.evictions(sizes, now_ms);
+        items.retain(|(key, _)| !evicted.contains(key));
+        blobs.retain(|key, _| !evicted.contains(key));
+        Ok(evicted.len())
+    }
+
+    fn count(&self) -> AppResult<usize> {
         let items = self.items.lock()
             .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
-        Ok(items.iter().take(limit).cloned().collect())
+        Ok(items.len())
     }
-    
+
     fn clear_all(&self) -> AppResult<()> {
         let mut items = self.items.lock()
             .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
         items.clear();
+        self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?
+            .clear();
+        Ok(())
+    }
+
+    fn find_by_id(&self, id: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>> {
+        let items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(items.iter().find(|(_, item)| item.id == id).cloned())
+    }
+
+    fn find_by_hash(&self, hash: &str) -> AppResult<Option<(u64, ClipboardHistoryItem)>> {
+        let items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(items.iter().find(|(_, item)| content_hash(item) == hash).cloned())
+    }
+
+    fn remove_key(&self, key: u64) -> AppResult<()> {
+        self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?
+            .retain(|(existing, _)| *existing != key);
+        self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?
+            .remove(&key);
         Ok(())
     }
-    
-    fn get_item_by_id(&self, id: &str) -> AppResult<Option<ClipboardHistoryItem>> {
+
+    fn purge_expired(&self, now_ms: u64) -> AppResult<Vec<String>> {
+        let mut items = self.items.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let mut blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let mut purged = Vec::new();
+        items.retain(|(key, item)| {
+            let expired = item.expires_at.is_some_and(|at| at.timestamp_millis().max(0) as u64 <= now_ms);
+            if expired {
+                blobs.remove(key);
+                purged.push(item.id.clone());
+            }
+            !expired
+        });
+        Ok(purged)
+    }
+
+    fn export_all(&self) -> AppResult<Vec<(ClipboardHistoryItem, Option<Vec<u8>>)>> {
         let items = self.items.lock()
             .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
-        Ok(items.iter().find(|item| item.id == id).cloned())
+        let blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(items.iter()
+            .rev()
+            .map(|(key, item)| (item.clone(), blobs.get(key).cloned()))
+            .collect())
     }
 }
 
@@ -348,21 +1285,310 @@ impl Default for ClipboardHistory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::types::ClipboardImageInfo;
+
+    fn memory_history() -> ClipboardHistory {
+        ClipboardHistory::in_memory()
+    }
+
+    fn redb_storage() -> RedbStorage {
+        let db = Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .unwrap();
+        RedbStorage::new(Arc::new(db), Codec::ephemeral()).unwrap()
+    }
+
+    fn text_at(content: &str, ms: i64) -> ClipboardHistoryItem {
+        let mut item = ClipboardHistoryItem::new_text(content.to_string(), None);
+        item.timestamp = chrono::DateTime::from_timestamp_millis(ms).unwrap();
+        item
+    }
 
     #[test]
     fn test_clear() {
         let history = ClipboardHistory::new();
         // Since clear() returns Result, we should unwrap it in tests
         let _ = history.clear();
-        
+
         history.add_item(ClipboardHistoryItem::new_text("Item 1".to_string(), None));
         history.add_item(ClipboardHistoryItem::new_text("Item 2".to_string(), None));
-        
+
         assert_eq!(history.count(), 2);
-        
+
         // Handle the Result from clear()
         history.clear().expect("Failed to clear history");
-        
+
         assert_eq!(history.count(), 0);
     }
+
+    #[test]
+    fn test_evictions() {
+        let policy = RetentionPolicy { max_items: 2, max_age_ms: None, max_bytes: None };
+        assert_eq!(policy.evictions([(30, 1), (20, 1), (10, 1)], 40), vec![10]);
+
+        let policy = RetentionPolicy { max_items: 10, max_age_ms: Some(15), max_bytes: None };
+        assert_eq!(policy.evictions([(30, 1), (20, 1), (10, 1)], 40), vec![20, 10]);
+        // The newest item survives even when it is past the age limit
+        assert_eq!(policy.evictions([(10, 1)], 40), Vec::<u64>::new());
+
+        let policy = RetentionPolicy { max_items: 10, max_age_ms: None, max_bytes: Some(100) };
+        assert_eq!(policy.evictions([(30, 60), (20, 50), (10, 10)], 40), vec![20, 10]);
+    }
+
+    #[test]
+    fn test_page_range() {
+        assert_eq!(page_range(None, None, None), Some((0, u64::MAX)));
+        assert_eq!(page_range(Some(5), Some(50), Some(20)), Some((5, 19)));
+        assert_eq!(page_range(Some(20), None, Some(20)), None);
+        assert_eq!(page_range(None, None, Some(0)), None);
+    }
+
+    #[test]
+    fn test_pagination() {
+        let history = memory_history();
+        for i in 0..5 {
+            history.add_item(ClipboardHistoryItem::new_text(format!("Item {}", i), None));
+        }
+
+        let first = history.get_page(None, 2).unwrap();
+        assert_eq!(first.items.iter().map(|i| i.content.as_str()).collect::<Vec<_>>(), vec!["Item 4", "Item 3"]);
+        let second = history.get_page(first.next, 2).unwrap();
+        assert_eq!(second.items[0].content, "Item 2");
+        let last = history.get_page(second.next, 2).unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.next, None);
+    }
+
+    #[test]
+    fn test_image_blobs() {
+        let history = memory_history();
+        let info = ClipboardImageInfo { width: 2, height: 2, byte_size: 4, thumbnail: String::new() };
+        let image = ClipboardHistoryItem::new_image(info.clone(), None);
+        history.add_capture(image.clone(), Some(&[1, 2, 3, 4]));
+        assert_eq!(history.get_blob(&image.id).unwrap(), Some(vec![1, 2, 3, 4]));
+
+        // A different image is not a duplicate even though neither has text
+        let other = ClipboardHistoryItem::new_image(ClipboardImageInfo { width: 3, ..info }, None);
+        history.add_capture(other.clone(), Some(&[5]));
+        assert_eq!(history.count(), 2);
+
+        history.storage.prune(
+            &RetentionPolicy { max_items: 1, max_age_ms: None, max_bytes: None },
+            Utc::now().timestamp_millis() as u64,
+        ).unwrap();
+        assert_eq!(history.get_blob(&image.id).unwrap(), None);
+        assert_eq!(history.get_blob(&other.id).unwrap(), Some(vec![5]));
+    }
+
+    #[test]
+    fn test_repeat_copy_moves_to_top() {
+        let history = memory_history();
+        let first = history.add_capture(ClipboardHistoryItem::new_text("hello".to_string(), None), None).unwrap();
+        history.add_item(ClipboardHistoryItem::new_text("other".to_string(), None));
+
+        let again = history
+            .add_capture(ClipboardHistoryItem::new_text("hello".to_string(), Some("Notes".to_string())), None)
+            .unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.use_count, 2);
+
+        let items = history.get_items().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "hello");
+        assert_eq!(items[0].source_app.as_deref(), Some("Notes"));
+        assert_eq!(items[1].content, "other");
+    }
+
+    #[test]
+    fn test_masked_copies_of_different_secrets_stay_apart() {
+        let history = memory_history();
+        let masked = || ClipboardHistoryItem::new_text("card ••••1111".to_string(), None);
+        let first = history.add_masked_capture(masked(), None, "card 4111 1111 1111 1111").unwrap();
+        let other = history.add_masked_capture(masked(), None, "card 5500 0000 0000 1111").unwrap();
+        assert_ne!(other.id, first.id);
+
+        let again = history.add_masked_capture(masked(), None, "card 4111 1111 1111 1111").unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.use_count, 2);
+        assert_eq!(history.count(), 2);
+    }
+
+    #[test]
+    fn test_copies_feed_active_stack() {
+        let history = memory_history();
+        history.add_item(ClipboardHistoryItem::new_text("before".to_string(), None));
+        history.stack().set_active(true);
+        history.add_item(ClipboardHistoryItem::new_text("a".to_string(), None));
+        history.add_item(ClipboardHistoryItem::new_text("b".to_string(), None));
+        assert_eq!(history.stack().take_joined().as_deref(), Some("a\nb"));
+    }
+
+    #[test]
+    fn test_search_and_retention() {
+        let history = memory_history();
+        history.add_item(ClipboardHistoryItem::new_text("invoice 2024-001".to_string(), Some("Mail".to_string())));
+        history.add_item(ClipboardHistoryItem::new_text("meeting notes".to_string(), Some("Slack".to_string())));
+        history.add_item(ClipboardHistoryItem::new_text("second invoice".to_string(), Some("Slack".to_string())));
+
+        let hits = history.search(&SearchFilter::new("invoce", None, None, None, None), None, 10).unwrap();
+        assert_eq!(hits.items.len(), 2);
+        assert_eq!(hits.items[0].content, "second invoice");
+
+        let hits = history.search(&SearchFilter::new("invoice", Some("mail"), None, None, None), None, 10).unwrap();
+        assert_eq!(hits.items.len(), 1);
+
+        let hits = history.search(&SearchFilter::new("invoice", None, None, None, None), None, 1).unwrap();
+        let rest = history.search(&SearchFilter::new("invoice", None, None, None, None), hits.next, 1).unwrap();
+        assert_eq!(rest.items[0].content, "invoice 2024-001");
+
+        let pruned = history.storage.prune(
+            &RetentionPolicy { max_items: 1, max_age_ms: None, max_bytes: None },
+            Utc::now().timestamp_millis() as u64,
+        ).unwrap();
+        assert_eq!(pruned, 2);
+        assert_eq!(history.count(), 1);
+    }
+
+    #[test]
+    fn test_redb_search_pages() {
+        let storage = redb_storage();
+        for i in 0..4 {
+            storage.save_item(&text_at(&format!("invoice {}", i), 1_000 + i), None).unwrap();
+        }
+        storage.save_item(&ClipboardHistoryItem::new_text("notes".to_string(), None), None).unwrap();
+
+        // Four matches in pages of two: the second page is the last
+        let filter = SearchFilter::new("invoice", None, None, None, None);
+        let first = storage.search(&filter, None, 2).unwrap();
+        assert_eq!(first.items.iter().map(|i| i.content.as_str()).collect::<Vec<_>>(), vec!["invoice 3", "invoice 2"]);
+        let second = storage.search(&filter, first.next, 2).unwrap();
+        assert_eq!(second.items.iter().map(|i| i.content.as_str()).collect::<Vec<_>>(), vec!["invoice 1", "invoice 0"]);
+        assert_eq!(second.next, None);
+        assert_eq!(storage.search(&filter, None, 4).unwrap().next, None);
+    }
+
+    #[test]
+    fn test_redb_find_by_hash() {
+        let storage = redb_storage();
+        let old = text_at("hello", 1_000);
+        let new = text_at("hello", 2_000);
+        storage.save_item(&old, None).unwrap();
+        storage.save_item(&text_at("other", 1_500), None).unwrap();
+        storage.save_item(&new, None).unwrap();
+
+        // The table holds keyed hashes, never the plain content hash
+        let hash = content_hash(&old);
+        let read_txn = storage.db.begin_read().unwrap();
+        let hashes = read_txn.open_table(HASH_TABLE).unwrap();
+        assert_eq!(hashes.len().unwrap(), 2);
+        assert!(hashes.get(hash.as_bytes()).unwrap().is_none());
+        drop((hashes, read_txn));
+
+        assert_eq!(storage.find_by_hash(&hash).unwrap().map(|(key, item)| (key, item.id)), Some((2_000, new.id)));
+        // Removing the older copy leaves the newer one findable
+        storage.remove_key(1_000).unwrap();
+        assert_eq!(storage.find_by_hash(&hash).unwrap().map(|(key, _)| key), Some(2_000));
+        storage.remove_key(2_000).unwrap();
+        assert!(storage.find_by_hash(&hash).unwrap().is_none());
+
+        // Pruning and clearing drop their hashes too
+        storage.save_item(&text_at("third", 3_000), None).unwrap();
+        storage.prune(&RetentionPolicy { max_items: 1, max_age_ms: None, max_bytes: None }, 10_000).unwrap();
+        let read_txn = storage.db.begin_read().unwrap();
+        assert_eq!(read_txn.open_table(HASH_TABLE).unwrap().len().unwrap(), 1);
+        drop(read_txn);
+        storage.clear_all().unwrap();
+        let read_txn = storage.db.begin_read().unwrap();
+        assert!(read_txn.open_table(HASH_TABLE).unwrap().is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let history = memory_history();
+        let mut secret = ClipboardHistoryItem::new_text("token ••••".to_string(), None);
+        secret.expires_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        let mut later = ClipboardHistoryItem::new_text("password ••••".to_string(), None);
+        later.expires_at = Some(Utc::now() + chrono::Duration::hours(1));
+        history.add_item(secret.clone());
+        history.add_item(later);
+        history.add_item(ClipboardHistoryItem::new_text("plain".to_string(), None));
+
+        assert_eq!(history.purge_expired().unwrap(), vec![secret.id.clone()]);
+        assert_eq!(history.count(), 2);
+        assert!(history.get_item_by_id(&secret.id).unwrap().is_none());
+        assert!(history.purge_expired().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_redb_purge_expired() {
+        let storage = redb_storage();
+        let mut secret = text_at("token ••••", 1_000);
+        secret.expires_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        let mut later = text_at("password ••••", 2_000);
+        later.expires_at = Some(Utc::now() + chrono::Duration::hours(1));
+        storage.save_item(&secret, None).unwrap();
+        storage.save_item(&later, None).unwrap();
+
+        let now_ms = Utc::now().timestamp_millis() as u64;
+        assert_eq!(storage.purge_expired(now_ms).unwrap(), vec![secret.id.clone()]);
+        assert!(storage.find_by_id(&secret.id).unwrap().is_none());
+        assert!(storage.purge_expired(now_ms).unwrap().is_empty());
+        assert_eq!(storage.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_merge_items() {
+        let history = memory_history();
+        let kept = ClipboardHistoryItem::new_text("kept".to_string(), None);
+        let mut stale = ClipboardHistoryItem::new_text("stale".to_string(), None);
+        stale.timestamp = Utc::now() - chrono::Duration::hours(1);
+        history.add_item(kept.clone());
+        history.add_item(stale.clone());
+
+        let mut older = kept.clone();
+        older.timestamp = kept.timestamp - chrono::Duration::minutes(5);
+        older.set_content("older edit".to_string());
+        let mut newer = stale.clone();
+        newer.timestamp = Utc::now();
+        newer.set_content("newer edit".to_string());
+        // Same content under another ID counts as the same item
+        let mut same_content = ClipboardHistoryItem::new_text("kept".to_string(), None);
+        same_content.timestamp = kept.timestamp - chrono::Duration::minutes(1);
+        let added = ClipboardHistoryItem::new_text("added".to_string(), None);
+        let incoming = vec![
+            (older, None),
+            (newer, None),
+            (same_content, None),
+            (added.clone(), None),
+        ];
+
+        let expected = MergeCounts { added: 1, updated: 1, skipped: 2 };
+        assert_eq!(history.merge_items(incoming.clone(), true).unwrap(), (expected, 0));
+        assert_eq!(history.count(), 2, "a dry run writes nothing");
+
+        assert_eq!(history.merge_items(incoming.clone(), false).unwrap(), (expected, 0));
+        assert_eq!(history.count(), 3);
+        assert_eq!(history.get_item_by_id(&kept.id).unwrap().unwrap().content, "kept");
+        assert_eq!(history.get_item_by_id(&stale.id).unwrap().unwrap().content, "newer edit");
+        assert!(history.get_item_by_id(&added.id).unwrap().is_some());
+
+        let again = history.merge_items(incoming, false).unwrap();
+        assert_eq!(again, (MergeCounts { added: 0, updated: 0, skipped: 4 }, 0));
+    }
+
+    #[test]
+    fn test_merged_items_past_retention_are_not_counted() {
+        let history = memory_history();
+        history.add_item(ClipboardHistoryItem::new_text("recent".to_string(), None));
+        // Older than the default 30 days
+        let mut ancient = ClipboardHistoryItem::new_text("ancient".to_string(), None);
+        ancient.timestamp = Utc::now() - chrono::Duration::days(60);
+        let fresh = ClipboardHistoryItem::new_text("fresh".to_string(), None);
+        let incoming = vec![(ancient.clone(), None), (fresh, None)];
+
+        let (counts, pruned) = history.merge_items(incoming, false).unwrap();
+        assert_eq!((counts.added, pruned), (1, 1));
+        assert!(history.get_item_by_id(&ancient.id).unwrap().is_none());
+        assert_eq!(history.count(), 2);
+    }
 }
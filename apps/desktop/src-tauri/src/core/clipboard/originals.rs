@@ -0,0 +1,110 @@
+//! Originals of redacted copies
+//!
+//! When a copy is stored with its secrets masked, the unmasked text can be
+//! kept here for a few seconds (a setting, off by default) so pasting the
+//! item right away still gives what was copied. There is a single slot: it
+//! lives in memory only, is never written to disk, and is wiped when it
+//! expires or the next redacted copy replaces it.
+
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+use crate::shared::settings::ClipboardSettings;
+
+struct Held {
+    item_id: String,
+    text: String,
+    expires: Instant,
+}
+
+/// Seconds an original is kept (None = not kept)
+static HOLD_SECS: Lazy<RwLock<Option<u32>>> = Lazy::new(|| RwLock::new(None));
+
+static SLOT: Lazy<Mutex<Option<Held>>> = Lazy::new(|| Mutex::new(None));
+
+fn slot() -> std::sync::MutexGuard<'static, Option<Held>> {
+    match SLOT.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Apply the hold time from settings (called when settings are loaded or saved)
+pub fn set_hold_time(settings: &ClipboardSettings) {
+    let secs = settings.redacted_original_secs.filter(|secs| *secs > 0);
+    match HOLD_SECS.write() {
+        Ok(mut guard) => *guard = secs,
+        Err(poisoned) => *poisoned.into_inner() = secs,
+    }
+    if secs.is_none() {
+        clear();
+    }
+}
+
+fn hold_time() -> Option<Duration> {
+    let secs = match HOLD_SECS.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    };
+    secs.map(|secs| Duration::from_secs(secs as u64))
+}
+
+/// Keep the original text of a redacted item, if holding is enabled
+pub fn hold(item_id: &str, text: String) {
+    if let Some(ttl) = hold_time() {
+        hold_for(item_id, text, ttl);
+    }
+}
+
+fn hold_for(item_id: &str, text: String, ttl: Duration) {
+    *slot() = Some(Held { item_id: item_id.to_string(), text, expires: Instant::now() + ttl });
+
+    // Wipe it on time even if nothing asks for it again
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-original-expiry".to_string())
+        .spawn(move || {
+            std::thread::sleep(ttl);
+            let mut slot = slot();
+            if slot.as_ref().is_some_and(|held| held.expires <= Instant::now()) {
+                *slot = None;
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("[Originals] Failed to start expiry thread: {}", e);
+    }
+}
+
+/// The original text of a redacted item, while it is still held
+pub fn get(item_id: &str) -> Option<String> {
+    let mut slot = slot();
+    match slot.as_ref() {
+        Some(held) if held.expires <= Instant::now() => {
+            *slot = None;
+            None
+        }
+        Some(held) if held.item_id == item_id => Some(held.text.clone()),
+        _ => None,
+    }
+}
+
+/// Drop whatever is held
+pub fn clear() {
+    *slot() = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_expires() {
+        hold_for("item-1", "secret text".to_string(), Duration::from_millis(100));
+        assert_eq!(get("item-1").as_deref(), Some("secret text"));
+        assert_eq!(get("item-2"), None);
+
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(slot().is_none(), "the expiry thread wipes the slot");
+        assert_eq!(get("item-1"), None);
+    }
+}
@@ -0,0 +1,716 @@
+//! Pinned clipboard items and named collections
+//!
+//! Pins are copies taken out of history: retention never touches them and
+//! editing one leaves history alone. Items and collections live in their own
+//! encrypted tables in the history database and keep an explicit `position`,
+//! so the user's order survives restarts. Each pin is also offered to the
+//! palette as a `CommandItem` that pastes it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use chrono::{DateTime, Utc};
+use redb::{Database, ReadableTable, TableDefinition};
+
+use super::history::{Codec, MergeCounts};
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::types::{
+    ClipboardCollection, ClipboardHistoryItem, ClipboardItemType, ClipboardPinsResponse, CommandItem,
+    PinnedClipboardItem,
+};
+
+/// Key: pin ID, Value: serialized PinnedClipboardItem (encrypted CBOR)
+const PINS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("clipboard_pins_v1");
+
+/// Key: collection ID, Value: serialized ClipboardCollection (encrypted CBOR)
+const COLLECTIONS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("clipboard_collections_v1");
+
+/// Key: pin ID, Value: encrypted PNG of a pinned image
+const PIN_BLOBS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("clipboard_pin_blobs_v1");
+
+/// Palette command ID prefix; the rest of the ID is the pinned item's ID
+pub const PIN_COMMAND_PREFIX: &str = "clipboard_pin:";
+
+/// Longest collection name or pin title, in characters
+const MAX_NAME_CHARS: usize = 80;
+
+/// A pin with its image data (PNG), as exported and merged
+pub type PinWithBlob = (PinnedClipboardItem, Option<Vec<u8>>);
+
+/// Upserts and deletions written in one transaction
+#[derive(Default)]
+struct Changes {
+    collections: Vec<ClipboardCollection>,
+    items: Vec<PinnedClipboardItem>,
+    /// Image data for new pins, by pin ID
+    blobs: Vec<(String, Vec<u8>)>,
+    removed_collections: Vec<String>,
+    /// Removing an item also removes its blob
+    removed_items: Vec<String>,
+}
+
+/// Storage trait for pins and collections
+trait PinStorage: Send + Sync {
+    fn load(&self) -> AppResult<(Vec<ClipboardCollection>, Vec<PinnedClipboardItem>)>;
+    fn apply(&self, changes: &Changes) -> AppResult<()>;
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>>;
+}
+
+/// Redb-based pin storage (shares the history database)
+struct RedbPins {
+    db: Arc<Database>,
+    codec: Codec,
+}
+
+impl RedbPins {
+    fn new(db: Arc<Database>, codec: Codec) -> AppResult<Self> {
+        let write_txn = db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write transaction: {}", e)))?;
+        {
+            let _pins = write_txn.open_table(PINS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open pins table: {}", e)))?;
+            let _collections = write_txn.open_table(COLLECTIONS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open collections table: {}", e)))?;
+            let _blobs = write_txn.open_table(PIN_BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open pin blobs table: {}", e)))?;
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(Self { db, codec })
+    }
+}
+
+impl PinStorage for RedbPins {
+    fn load(&self) -> AppResult<(Vec<ClipboardCollection>, Vec<PinnedClipboardItem>)> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let pins = read_txn.open_table(PINS_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open pins table: {}", e)))?;
+        let collections = read_txn.open_table(COLLECTIONS_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open collections table: {}", e)))?;
+
+        let mut loaded_collections = Vec::new();
+        for entry in collections.iter()
+            .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?
+        {
+            let (_, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            match self.codec.decode::<ClipboardCollection>(value.value()) {
+                Ok(collection) => loaded_collections.push(collection),
+                Err(e) => eprintln!("[ClipboardPins] Skipping unreadable collection: {}", e),
+            }
+        }
+
+        let mut loaded_items = Vec::new();
+        for entry in pins.iter()
+            .map_err(|e| AppError::Io(format!("Failed to create iterator: {}", e)))?
+        {
+            let (_, value) = entry
+                .map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            match self.codec.decode::<PinnedClipboardItem>(value.value()) {
+                Ok(pin) => loaded_items.push(pin),
+                Err(e) => eprintln!("[ClipboardPins] Skipping unreadable pin: {}", e),
+            }
+        }
+
+        Ok((loaded_collections, loaded_items))
+    }
+
+    fn apply(&self, changes: &Changes) -> AppResult<()> {
+        let write_txn = self.db.begin_write()
+            .map_err(|e| AppError::Io(format!("Failed to begin write: {}", e)))?;
+        {
+            let mut pins = write_txn.open_table(PINS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open pins table: {}", e)))?;
+            let mut collections = write_txn.open_table(COLLECTIONS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open collections table: {}", e)))?;
+            let mut blobs = write_txn.open_table(PIN_BLOBS_TABLE)
+                .map_err(|e| AppError::Io(format!("Failed to open pin blobs table: {}", e)))?;
+
+            for collection in &changes.collections {
+                let encrypted = self.codec.encode(collection)?;
+                collections.insert(collection.id.as_str(), encrypted.as_slice())
+                    .map_err(|e| AppError::Io(format!("Failed to save collection: {}", e)))?;
+            }
+            for pin in &changes.items {
+                let encrypted = self.codec.encode(pin)?;
+                pins.insert(pin.item.id.as_str(), encrypted.as_slice())
+                    .map_err(|e| AppError::Io(format!("Failed to save pin: {}", e)))?;
+            }
+            for (id, blob) in &changes.blobs {
+                blobs.insert(id.as_str(), self.codec.seal(blob)?.as_slice())
+                    .map_err(|e| AppError::Io(format!("Failed to save pin blob: {}", e)))?;
+            }
+            for id in &changes.removed_collections {
+                collections.remove(id.as_str())
+                    .map_err(|e| AppError::Io(format!("Failed to remove collection: {}", e)))?;
+            }
+            for id in &changes.removed_items {
+                pins.remove(id.as_str())
+                    .map_err(|e| AppError::Io(format!("Failed to remove pin: {}", e)))?;
+                blobs.remove(id.as_str())
+                    .map_err(|e| AppError::Io(format!("Failed to remove pin blob: {}", e)))?;
+            }
+        }
+        write_txn.commit()
+            .map_err(|e| AppError::Io(format!("Failed to commit: {}", e)))?;
+        Ok(())
+    }
+
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        let read_txn = self.db.begin_read()
+            .map_err(|e| AppError::Io(format!("Failed to begin read: {}", e)))?;
+        let blobs = read_txn.open_table(PIN_BLOBS_TABLE)
+            .map_err(|e| AppError::Io(format!("Failed to open pin blobs table: {}", e)))?;
+        match blobs.get(id)
+            .map_err(|e| AppError::Io(format!("Failed to read pin blob: {}", e)))?
+        {
+            Some(raw) => Ok(Some(self.codec.open(raw.value())?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// In-memory fallback pin storage (used if database initialization fails)
+#[derive(Default)]
+struct InMemoryPins {
+    state: Mutex<(Vec<ClipboardCollection>, Vec<PinnedClipboardItem>)>,
+    blobs: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl PinStorage for InMemoryPins {
+    fn load(&self) -> AppResult<(Vec<ClipboardCollection>, Vec<PinnedClipboardItem>)> {
+        let state = self.state.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(state.clone())
+    }
+
+    fn apply(&self, changes: &Changes) -> AppResult<()> {
+        let mut state = self.state.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        let (collections, items) = &mut *state;
+
+        for collection in &changes.collections {
+            collections.retain(|c| c.id != collection.id);
+            collections.push(collection.clone());
+        }
+        for pin in &changes.items {
+            items.retain(|p| p.item.id != pin.item.id);
+            items.push(pin.clone());
+        }
+        collections.retain(|c| !changes.removed_collections.contains(&c.id));
+        items.retain(|p| !changes.removed_items.contains(&p.item.id));
+
+        let mut blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        blobs.extend(changes.blobs.iter().cloned());
+        blobs.retain(|id, _| !changes.removed_items.contains(id));
+        Ok(())
+    }
+
+    fn load_blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        let blobs = self.blobs.lock()
+            .map_err(|e| AppError::Io(format!("Mutex poisoned: {}", e)))?;
+        Ok(blobs.get(id).cloned())
+    }
+}
+
+/// Pinned items and collections manager
+pub struct ClipboardPins {
+    storage: Box<dyn PinStorage>,
+    /// Serializes read-modify-write updates
+    lock: Mutex<()>,
+}
+
+impl ClipboardPins {
+    pub(super) fn open(db: Arc<Database>, codec: Codec) -> AppResult<Self> {
+        Ok(Self::with_storage(Box::new(RedbPins::new(db, codec)?)))
+    }
+
+    pub(super) fn in_memory() -> Self {
+        Self::with_storage(Box::<InMemoryPins>::default())
+    }
+
+    fn with_storage(storage: Box<dyn PinStorage>) -> Self {
+        Self {
+            storage,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn guard(&self) -> MutexGuard<'_, ()> {
+        match self.lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("[ClipboardPins] Mutex poisoned, recovering...");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Collections by position, then items grouped top-level first and by collection
+    fn load_sorted(&self) -> AppResult<ClipboardPinsResponse> {
+        let (mut collections, mut items) = self.storage.load()?;
+        collections.sort_by_key(|c| c.position);
+
+        let rank: HashMap<&str, usize> = collections.iter()
+            .enumerate()
+            .map(|(index, c)| (c.id.as_str(), index + 1))
+            .collect();
+        items.sort_by_key(|pin| {
+            let group = pin.collection_id.as_deref()
+                .map(|id| rank.get(id).copied().unwrap_or(usize::MAX))
+                .unwrap_or(0);
+            (group, pin.position)
+        });
+
+        Ok(ClipboardPinsResponse { collections, items })
+    }
+
+    /// All collections and pinned items, in display order
+    pub fn list(&self) -> AppResult<ClipboardPinsResponse> {
+        self.load_sorted()
+    }
+
+    /// A pinned item by ID
+    pub fn get(&self, id: &str) -> AppResult<Option<PinnedClipboardItem>> {
+        Ok(self.storage.load()?.1.into_iter().find(|pin| pin.item.id == id))
+    }
+
+    /// Image data (PNG) of a pinned image
+    pub fn blob(&self, id: &str) -> AppResult<Option<Vec<u8>>> {
+        self.storage.load_blob(id)
+    }
+
+    /// Pin a copy of `item` (and its image data) at the end of a collection
+    /// (None = top-level pins)
+    pub fn pin(
+        &self,
+        item: &ClipboardHistoryItem,
+        blob: Option<Vec<u8>>,
+        collection_id: Option<String>,
+        title: Option<String>,
+    ) -> AppResult<PinnedClipboardItem> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        check_collection(&current.collections, collection_id.as_deref())?;
+
+        let mut copy = item.clone();
+        copy.id = uuid::Uuid::new_v4().to_string();
+        let pin = PinnedClipboardItem {
+            position: next_position(&current.items, collection_id.as_deref()),
+            item: copy,
+            title: clean_name(title),
+            collection_id,
+            updated_at: None,
+        };
+        self.storage.apply(&Changes {
+            items: vec![pin.clone()],
+            blobs: blob.map(|blob| (pin.item.id.clone(), blob)).into_iter().collect(),
+            ..Default::default()
+        })?;
+        println!("[ClipboardPins] Pinned item {}", pin.item.id);
+        Ok(pin)
+    }
+
+    /// Replace a pin's content, title and collection
+    pub fn update(
+        &self,
+        id: &str,
+        content: String,
+        title: Option<String>,
+        collection_id: Option<String>,
+    ) -> AppResult<PinnedClipboardItem> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        check_collection(&current.collections, collection_id.as_deref())?;
+
+        let mut pin = current.items.iter()
+            .find(|pin| pin.item.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::Validation("Pinned item not found".to_string()))?;
+
+        if pin.item.content != content {
+            if pin.item.item_type == ClipboardItemType::Image {
+                return Err(AppError::Validation("Pinned images can't be edited".to_string()));
+            }
+            pin.item.set_content(content);
+        }
+        if pin.collection_id != collection_id {
+            pin.position = next_position(&current.items, collection_id.as_deref());
+            pin.collection_id = collection_id;
+        }
+        pin.title = clean_name(title);
+        pin.updated_at = Some(Utc::now());
+
+        self.storage.apply(&Changes { items: vec![pin.clone()], ..Default::default() })?;
+        Ok(pin)
+    }
+
+    /// Remove a pinned item
+    pub fn unpin(&self, id: &str) -> AppResult<()> {
+        let _guard = self.guard();
+        self.storage.apply(&Changes { removed_items: vec![id.to_string()], ..Default::default() })
+    }
+
+    /// Put the listed pins first, in the given order; the rest keep their order after them
+    pub fn reorder_items(&self, ids: &[String]) -> AppResult<()> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        let order: Vec<String> = current.items.iter().map(|pin| pin.item.id.clone()).collect();
+        let positions = reordered(&order, ids);
+
+        let items = current.items.into_iter()
+            .filter_map(|mut pin| {
+                let position = positions[&pin.item.id];
+                (pin.position != position).then(|| {
+                    pin.position = position;
+                    pin
+                })
+            })
+            .collect();
+        self.storage.apply(&Changes { items, ..Default::default() })
+    }
+
+    /// Create a collection (no ID) or rename an existing one
+    pub fn save_collection(&self, id: Option<&str>, name: &str) -> AppResult<ClipboardCollection> {
+        let name = clean_name(Some(name.to_string()))
+            .ok_or_else(|| AppError::Validation("Collection name can't be empty".to_string()))?;
+
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        let collection = match id {
+            Some(id) => {
+                let mut existing = current.collections.into_iter()
+                    .find(|c| c.id == id)
+                    .ok_or_else(|| AppError::Validation("Collection not found".to_string()))?;
+                existing.name = name;
+                existing
+            }
+            None => ClipboardCollection {
+                id: uuid::Uuid::new_v4().to_string(),
+                name,
+                position: current.collections.iter().map(|c| c.position + 1).max().unwrap_or(0),
+            },
+        };
+        self.storage.apply(&Changes { collections: vec![collection.clone()], ..Default::default() })?;
+        Ok(collection)
+    }
+
+    /// Delete a collection; its items move to the end of the top-level pins
+    pub fn delete_collection(&self, id: &str) -> AppResult<()> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        let mut position = next_position(&current.items, None);
+        let items = current.items.into_iter()
+            .filter(|pin| pin.collection_id.as_deref() == Some(id))
+            .map(|mut pin| {
+                pin.collection_id = None;
+                pin.position = position;
+                position += 1;
+                pin
+            })
+            .collect();
+
+        self.storage.apply(&Changes {
+            items,
+            removed_collections: vec![id.to_string()],
+            ..Default::default()
+        })
+    }
+
+    /// Put the listed collections first, in the given order
+    pub fn reorder_collections(&self, ids: &[String]) -> AppResult<()> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        let order: Vec<String> = current.collections.iter().map(|c| c.id.clone()).collect();
+        let positions = reordered(&order, ids);
+
+        let collections = current.collections.into_iter()
+            .map(|mut c| {
+                c.position = positions[&c.id];
+                c
+            })
+            .collect();
+        self.storage.apply(&Changes { collections, ..Default::default() })
+    }
+
+    /// Collections and pins (with image data) that may leave this device,
+    /// in display order; pins marked `no_sync` and pins of sensitive copies
+    /// (which keep their timer from history) are left out
+    pub fn export(&self) -> AppResult<(Vec<ClipboardCollection>, Vec<PinWithBlob>)> {
+        let current = self.load_sorted()?;
+        let mut items = Vec::new();
+        for pin in current.items {
+            if pin.item.no_sync || pin.item.expires_at.is_some() {
+                continue;
+            }
+            let blob = self.storage.load_blob(&pin.item.id)?;
+            items.push((pin, blob));
+        }
+        Ok((current.collections, items))
+    }
+
+    /// Merge collections and pins from elsewhere (e.g. a backup). New
+    /// collections go after the existing ones and new pins at the end of
+    /// their collection (top-level when it is missing); a pin already here
+    /// is replaced only by a more recently edited copy and keeps its place. A dry run only
+    /// counts. Returns how many collections were added, and the pin counts.
+    pub fn merge(
+        &self,
+        collections: Vec<ClipboardCollection>,
+        items: Vec<PinWithBlob>,
+        dry_run: bool,
+    ) -> AppResult<(usize, MergeCounts)> {
+        let _guard = self.guard();
+        let current = self.load_sorted()?;
+        let mut changes = Changes::default();
+
+        let mut known = current.collections;
+        let mut position = known.iter().map(|c| c.position + 1).max().unwrap_or(0);
+        for mut collection in collections {
+            if known.iter().any(|c| c.id == collection.id) {
+                continue;
+            }
+            collection.name = clean_name(Some(collection.name)).unwrap_or_else(|| "Imported".to_string());
+            collection.position = position;
+            position += 1;
+            known.push(collection.clone());
+            changes.collections.push(collection);
+        }
+
+        let mut counts = MergeCounts::default();
+        let mut placed = current.items;
+        for (mut pin, blob) in items {
+            pin.title = clean_name(pin.title);
+            match placed.iter().find(|existing| existing.item.id == pin.item.id) {
+                Some(existing) if changed_at(&pin) <= changed_at(existing) => {
+                    counts.skipped += 1;
+                    continue;
+                }
+                Some(existing) => {
+                    pin.collection_id = existing.collection_id.clone();
+                    pin.position = existing.position;
+                    counts.updated += 1;
+                }
+                None => {
+                    if check_collection(&known, pin.collection_id.as_deref()).is_err() {
+                        pin.collection_id = None;
+                    }
+                    pin.position = next_position(&placed, pin.collection_id.as_deref());
+                    counts.added += 1;
+                }
+            }
+            placed.retain(|existing| existing.item.id != pin.item.id);
+            placed.push(pin.clone());
+            changes.blobs.extend(blob.map(|blob| (pin.item.id.clone(), blob)));
+            changes.items.push(pin);
+        }
+
+        if !dry_run {
+            self.storage.apply(&changes)?;
+        }
+        Ok((changes.collections.len(), counts))
+    }
+
+    /// Palette entries that paste each pin, in display order
+    pub fn command_items(&self) -> Vec<CommandItem> {
+        let pins = match self.load_sorted() {
+            Ok(pins) => pins,
+            Err(e) => {
+                eprintln!("[ClipboardPins] Failed to load pins: {}", e);
+                return Vec::new();
+            }
+        };
+        let names: HashMap<&str, &str> = pins.collections.iter()
+            .map(|c| (c.id.as_str(), c.name.as_str()))
+            .collect();
+
+        pins.items.iter()
+            .map(|pin| {
+                let label = pin.title.clone().unwrap_or_else(|| {
+                    pin.item.preview.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim().to_string()
+                });
+                let collection = pin.collection_id.as_deref()
+                    .and_then(|id| names.get(id).copied())
+                    .unwrap_or("Pinned");
+                CommandItem {
+                    id: format!("{}{}", PIN_COMMAND_PREFIX, pin.item.id),
+                    label,
+                    description: Some(collection.to_string()),
+                    action_type: None,
+                    widget_type: None,
+                    category: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// When a pin was last edited, or copied when it never was
+fn changed_at(pin: &PinnedClipboardItem) -> DateTime<Utc> {
+    pin.updated_at.unwrap_or(pin.item.timestamp)
+}
+
+/// Trimmed, length-capped name (None when blank)
+fn clean_name(name: Option<String>) -> Option<String> {
+    let name = name?;
+    let trimmed: String = name.trim().chars().take(MAX_NAME_CHARS).collect();
+    (!trimmed.is_empty()).then_some(trimmed)
+}
+
+fn check_collection(collections: &[ClipboardCollection], id: Option<&str>) -> AppResult<()> {
+    match id {
+        Some(id) if !collections.iter().any(|c| c.id == id) => {
+            Err(AppError::Validation("Collection not found".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Position after the last item in a collection
+fn next_position(items: &[PinnedClipboardItem], collection_id: Option<&str>) -> i32 {
+    items.iter()
+        .filter(|pin| pin.collection_id.as_deref() == collection_id)
+        .map(|pin| pin.position + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// New positions for `current` (in its present order): listed IDs first, then the rest
+fn reordered(current: &[String], ids: &[String]) -> HashMap<String, i32> {
+    let mut positions = HashMap::new();
+    let listed = ids.iter().filter(|id| current.contains(id));
+    let rest = current.iter().filter(|id| !ids.contains(id));
+    for id in listed.chain(rest) {
+        let next = positions.len() as i32;
+        positions.entry(id.clone()).or_insert(next);
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::types::ClipboardImageInfo;
+
+    fn text(content: &str) -> ClipboardHistoryItem {
+        ClipboardHistoryItem::new_text(content.to_string(), None)
+    }
+
+    fn contents(pins: &ClipboardPins) -> Vec<String> {
+        pins.list().unwrap().items.into_iter().map(|pin| pin.item.content).collect()
+    }
+
+    #[test]
+    fn test_pin_is_a_copy() {
+        let pins = ClipboardPins::in_memory();
+        let item = text("SELECT 1");
+        let pin = pins.pin(&item, None, None, Some("  ping query ".to_string())).unwrap();
+        assert_ne!(pin.item.id, item.id);
+        assert_eq!(pin.title.as_deref(), Some("ping query"));
+        assert!(pins.get(&pin.item.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_collections_and_order() {
+        let pins = ClipboardPins::in_memory();
+        let sql = pins.save_collection(None, "SQL snippets").unwrap();
+        let a = pins.pin(&text("a"), None, Some(sql.id.clone()), None).unwrap();
+        let b = pins.pin(&text("b"), None, Some(sql.id.clone()), None).unwrap();
+        pins.pin(&text("top"), None, None, None).unwrap();
+        assert_eq!(contents(&pins), vec!["top", "a", "b"]);
+
+        pins.reorder_items(&[b.item.id.clone(), a.item.id.clone()]).unwrap();
+        assert_eq!(contents(&pins), vec!["top", "b", "a"]);
+
+        assert!(pins.pin(&text("x"), None, Some("missing".to_string()), None).is_err());
+
+        pins.delete_collection(&sql.id).unwrap();
+        let listed = pins.list().unwrap();
+        assert!(listed.collections.is_empty());
+        assert!(listed.items.iter().all(|pin| pin.collection_id.is_none()));
+        assert_eq!(contents(&pins), vec!["top", "b", "a"]);
+    }
+
+    #[test]
+    fn test_update() {
+        let pins = ClipboardPins::in_memory();
+        let pin = pins.pin(&text("old"), None, None, None).unwrap();
+        let updated = pins.update(&pin.item.id, "new text".to_string(), None, None).unwrap();
+        assert_eq!(updated.item.content, "new text");
+        assert_eq!(updated.item.preview, "new text");
+        assert_eq!(updated.item.timestamp, pin.item.timestamp);
+        assert!(updated.updated_at.is_some_and(|at| at >= pin.item.timestamp));
+
+        let info = ClipboardImageInfo { width: 1, height: 1, byte_size: 3, thumbnail: String::new() };
+        let image = pins.pin(&ClipboardHistoryItem::new_image(info, None), Some(vec![1, 2, 3]), None, None).unwrap();
+        assert!(pins.update(&image.item.id, "x".to_string(), None, None).is_err());
+        assert_eq!(pins.blob(&image.item.id).unwrap(), Some(vec![1, 2, 3]));
+        pins.unpin(&image.item.id).unwrap();
+        assert_eq!(pins.blob(&image.item.id).unwrap(), None);
+        assert!(pins.update("missing", "x".to_string(), None, None).is_err());
+    }
+
+    #[test]
+    fn test_command_items() {
+        let pins = ClipboardPins::in_memory();
+        let addresses = pins.save_collection(None, "Addresses").unwrap();
+        let pin = pins.pin(&text("\n221B Baker Street\nLondon"), None, Some(addresses.id), None).unwrap();
+        let items = pins.command_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, format!("{}{}", PIN_COMMAND_PREFIX, pin.item.id));
+        assert_eq!(items[0].label, "221B Baker Street");
+        assert_eq!(items[0].description.as_deref(), Some("Addresses"));
+    }
+
+    #[test]
+    fn test_reordered() {
+        let current = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let positions = reordered(&current, &["c".to_string(), "x".to_string()]);
+        assert_eq!(positions["c"], 0);
+        assert_eq!(positions["a"], 1);
+        assert_eq!(positions["b"], 2);
+        assert!(!positions.contains_key("x"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let pins = ClipboardPins::in_memory();
+        let local = pins.save_collection(None, "Local").unwrap();
+        let kept = pins.pin(&text("kept"), None, Some(local.id.clone()), None).unwrap();
+        let (collections, mut items) = {
+            let source = ClipboardPins::in_memory();
+            let sql = source.save_collection(None, "SQL").unwrap();
+            source.pin(&text("SELECT 1"), None, Some(sql.id.clone()), None).unwrap();
+            source.export().unwrap()
+        };
+
+        let mut newer = kept.clone();
+        newer.item.set_content("kept, edited".to_string());
+        newer.updated_at = Some(kept.item.timestamp + chrono::Duration::seconds(1));
+        newer.collection_id = None;
+        let mut orphan = pins.pin(&text("orphan"), None, None, None).unwrap();
+        pins.unpin(&orphan.item.id).unwrap();
+        orphan.collection_id = Some("missing".to_string());
+        items.push((newer, None));
+        items.push((orphan.clone(), None));
+
+        assert_eq!(pins.merge(collections.clone(), items.clone(), true).unwrap().0, 1);
+        assert_eq!(contents(&pins), vec!["kept"], "a dry run writes nothing");
+
+        let (added, counts) = pins.merge(collections.clone(), items.clone(), false).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(counts, MergeCounts { added: 2, updated: 1, skipped: 0 });
+        let listed = pins.list().unwrap();
+        assert_eq!(listed.collections.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["Local", "SQL"]);
+        assert_eq!(contents(&pins), vec!["orphan", "kept, edited", "SELECT 1"]);
+        let edited = pins.get(&kept.item.id).unwrap().unwrap();
+        assert_eq!(edited.collection_id.as_deref(), Some(local.id.as_str()), "a replaced pin keeps its place");
+        assert_eq!(pins.get(&orphan.item.id).unwrap().unwrap().collection_id, None);
+
+        let (added, counts) = pins.merge(collections, items, false).unwrap();
+        assert_eq!(added, 0);
+        assert_eq!(counts, MergeCounts { added: 0, updated: 0, skipped: 3 });
+    }
+}
@@ -0,0 +1,328 @@
+//! User capture rules
+//!
+//! Rules from settings are compiled once (when settings load or change) and
+//! checked in order against every new copy before it is stored. The first
+//! enabled rule whose conditions all hold decides what happens to the copy.
+//! Only the rule's name and action are ever logged, never the content.
+
+use std::sync::{Arc, RwLock};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::formats::ClipboardSnapshot;
+use crate::shared::error::{AppError, AppResult};
+use crate::shared::settings::{CaptureAction, CaptureRule, ClipboardSettings};
+
+/// Replaces redacted text
+pub const REDACTION_MASK: &str = "••••";
+
+/// Longest pattern accepted, in bytes
+const MAX_PATTERN_LEN: usize = 1_000;
+
+/// Compiled regex size limit, so a pathological pattern can't stall copies
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// The app a copy came from, as far as it is known
+#[derive(Debug, Clone, Default)]
+pub struct SourceApp {
+    pub name: Option<String>,
+    pub bundle_id: Option<String>,
+}
+
+/// One rule, ready to match
+struct CompiledRule {
+    name: String,
+    /// Lowercased app names and bundle IDs, possibly with `*` wildcards
+    apps: Vec<String>,
+    pattern: Option<Regex>,
+    max_bytes: Option<usize>,
+    action: CaptureAction,
+}
+
+impl CompiledRule {
+    fn compile(rule: &CaptureRule) -> AppResult<Self> {
+        let pattern = match rule.pattern.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            Some(pattern) if pattern.len() > MAX_PATTERN_LEN => {
+                return Err(AppError::Validation(format!(
+                    "Pattern of rule '{}' is longer than {} characters", rule.name, MAX_PATTERN_LEN
+                )));
+            }
+            Some(pattern) => Some(
+                regex::RegexBuilder::new(pattern)
+                    .size_limit(REGEX_SIZE_LIMIT)
+                    .build()
+                    .map_err(|e| AppError::Validation(format!("Invalid pattern in rule '{}': {}", rule.name, e)))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            name: rule.name.clone(),
+            apps: rule.apps.iter()
+                .map(|app| app.trim().to_lowercase())
+                .filter(|app| !app.is_empty())
+                .collect(),
+            pattern,
+            max_bytes: rule.max_size_kb.map(|kb| kb as usize * 1024),
+            action: rule.action,
+        })
+    }
+
+    fn matches(&self, source: &SourceApp, snapshot: &ClipboardSnapshot) -> bool {
+        if !self.apps.is_empty() {
+            let known = [source.name.as_deref(), source.bundle_id.as_deref()];
+            let hit = known.iter().flatten().any(|id| {
+                let id = id.to_lowercase();
+                self.apps.iter().any(|app| app_matches(app, &id))
+            });
+            if !hit {
+                return false;
+            }
+        }
+        if let Some(max) = self.max_bytes {
+            if byte_size(snapshot) <= max {
+                return false;
+            }
+        }
+        match &self.pattern {
+            Some(pattern) => snapshot.text.as_deref().is_some_and(|text| pattern.is_match(text)),
+            None => true,
+        }
+    }
+}
+
+/// Whether an app name or bundle ID matches a rule's entry, where `*` stands
+/// for any run of characters (`*password*` matches "1password 7")
+fn app_matches(pattern: &str, id: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = parts.next().and_then(|first| id.strip_prefix(first)) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Size a copy counts as for `max_size_kb`
+fn byte_size(snapshot: &ClipboardSnapshot) -> usize {
+    snapshot.text.as_ref().map_or(0, String::len)
+        + snapshot.html.as_ref().map_or(0, String::len)
+        + snapshot.rtf.as_ref().map_or(0, String::len)
+        + snapshot.image.as_ref().map_or(0, |image| image.bytes.len())
+        + snapshot.files.iter().map(String::len).sum::<usize>()
+}
+
+/// The rule that fired for a copy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub rule: String,
+    pub action: CaptureAction,
+    /// Position among the compiled rules
+    index: usize,
+}
+
+/// Enabled rules in the user's order
+#[derive(Default)]
+pub struct CaptureRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl CaptureRules {
+    /// Compile enabled rules, failing on the first invalid one
+    pub fn compile(rules: &[CaptureRule]) -> AppResult<Self> {
+        let rules = rules.iter()
+            .filter(|rule| rule.enabled)
+            .map(CompiledRule::compile)
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Compile enabled rules, skipping (and logging) invalid ones
+    fn compile_lenient(rules: &[CaptureRule]) -> Self {
+        let rules = rules.iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match CompiledRule::compile(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(e) => {
+                    eprintln!("[CaptureRules] Skipping rule: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// First rule matching this copy
+    pub fn evaluate(&self, source: &SourceApp, snapshot: &ClipboardSnapshot) -> Option<Verdict> {
+        self.rules.iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(source, snapshot))
+            .map(|(index, rule)| Verdict { rule: rule.name.clone(), action: rule.action, index })
+    }
+
+    /// Mask what the rule behind `verdict` matched. Rich formats still hold
+    /// the original text and file paths can't be masked, so both are
+    /// dropped, as is an image; a copy with no text ends up empty.
+    pub fn redact(&self, verdict: &Verdict, snapshot: &ClipboardSnapshot) -> ClipboardSnapshot {
+        let pattern = self.rules.get(verdict.index).and_then(|rule| rule.pattern.as_ref());
+        let text = snapshot.text.as_deref().map(|text| match pattern {
+            Some(pattern) => pattern.replace_all(text, REDACTION_MASK).into_owned(),
+            None => REDACTION_MASK.to_string(),
+        });
+        ClipboardSnapshot { text, ..Default::default() }
+    }
+}
+
+/// Rules from settings (replaced when settings load or change)
+static RULES: Lazy<RwLock<Arc<CaptureRules>>> = Lazy::new(|| {
+    let defaults = ClipboardSettings::default().capture_rules;
+    RwLock::new(Arc::new(CaptureRules::compile_lenient(&defaults)))
+});
+
+/// Replace the active rules (called when settings are loaded or saved)
+pub fn set_rules(rules: &[CaptureRule]) {
+    let compiled = Arc::new(CaptureRules::compile_lenient(rules));
+    println!("[CaptureRules] Loaded {} capture rule(s)", compiled.rules.len());
+    match RULES.write() {
+        Ok(mut guard) => *guard = compiled,
+        Err(poisoned) => *poisoned.into_inner() = compiled,
+    }
+}
+
+/// The active rules
+pub fn current() -> Arc<CaptureRules> {
+    match RULES.read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, apps: &[&str], pattern: Option<&str>, max_size_kb: Option<u32>, action: CaptureAction) -> CaptureRule {
+        CaptureRule {
+            name: name.to_string(),
+            enabled: true,
+            apps: apps.iter().map(|app| app.to_string()).collect(),
+            pattern: pattern.map(String::from),
+            max_size_kb,
+            action,
+        }
+    }
+
+    fn text(content: &str) -> ClipboardSnapshot {
+        ClipboardSnapshot { text: Some(content.to_string()), ..Default::default() }
+    }
+
+    fn app(name: &str, bundle_id: &str) -> SourceApp {
+        SourceApp { name: Some(name.to_string()), bundle_id: Some(bundle_id.to_string()) }
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = CaptureRules::compile(&[
+            rule("Terminal ok", &["com.apple.Terminal"], None, None, CaptureAction::Allow),
+            rule("Tickets", &[], Some(r"JIRA-\d+"), None, CaptureAction::NoSync),
+            rule("Huge", &[], None, Some(1), CaptureAction::Drop),
+        ]).unwrap();
+
+        let terminal = app("Terminal", "com.apple.terminal");
+        let notes = app("Notes", "com.apple.Notes");
+        assert_eq!(rules.evaluate(&terminal, &text("JIRA-12")).unwrap().action, CaptureAction::Allow);
+        assert_eq!(rules.evaluate(&notes, &text("see JIRA-12")).unwrap().rule, "Tickets");
+        assert_eq!(rules.evaluate(&notes, &text(&"x".repeat(2048))).unwrap().action, CaptureAction::Drop);
+        assert_eq!(rules.evaluate(&notes, &text("hello")), None);
+        // App conditions need a known app
+        assert_eq!(rules.evaluate(&SourceApp::default(), &text("hello")), None);
+    }
+
+    #[test]
+    fn test_app_wildcards() {
+        assert!(app_matches("*password*", "com.apple.passwords"));
+        assert!(app_matches("*password*", "password"));
+        assert!(app_matches("com.*.desktop", "com.bitwarden.desktop"));
+        assert!(app_matches("notes", "notes"));
+        assert!(!app_matches("notes", "notes2"));
+        assert!(!app_matches("*pass*word", "passwords"));
+        assert!(!app_matches("a*b*a", "ab"));
+        assert!(!app_matches("a*a", "a"));
+
+        let rules = CaptureRules::compile(&[rule("Vaults", &["*Vault*"], None, None, CaptureAction::Drop)]).unwrap();
+        assert!(rules.evaluate(&app("My Vault", "com.example.app"), &text("x")).is_some());
+        assert!(rules.evaluate(&app("Notes", "com.example.vaulted"), &text("x")).is_some());
+        assert!(rules.evaluate(&app("Notes", "com.apple.Notes"), &text("x")).is_none());
+    }
+
+    #[test]
+    fn test_default_rule_drops_password_managers() {
+        let rules = CaptureRules::compile(&ClipboardSettings::default().capture_rules).unwrap();
+        let drops = |name: Option<&str>, bundle_id: Option<&str>| {
+            let source = SourceApp { name: name.map(String::from), bundle_id: bundle_id.map(String::from) };
+            rules.evaluate(&source, &text("hunter2")).is_some_and(|v| v.action == CaptureAction::Drop)
+        };
+        for bundle_id in [
+            "com.apple.Passwords",
+            "com.agilebits.onepassword7",
+            "com.1password.1password",
+            "in.sinew.Enpass-Desktop",
+            "com.dashlane.dashlanephonefinal",
+            "org.keepassxc.keepassxc",
+            "com.apple.keychainaccess",
+            "me.proton.pass.electron",
+        ] {
+            assert!(drops(None, Some(bundle_id)), "{}", bundle_id);
+        }
+        // As broad as the old built-in filter: anything named like a password manager
+        for name in ["Passwords", "1Password 7", "KeePassXC", "Bitwarden", "Enpass", "Dashlane", "Strongbox Password Safe"] {
+            assert!(drops(Some(name), None), "{}", name);
+        }
+        assert!(!drops(Some("Notes"), Some("com.apple.Notes")));
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules() {
+        let mut disabled = rule("Off", &[], None, None, CaptureAction::Drop);
+        disabled.enabled = false;
+        let rules = CaptureRules::compile(&[disabled.clone()]).unwrap();
+        assert_eq!(rules.evaluate(&SourceApp::default(), &text("anything")), None);
+
+        let invalid = rule("Broken", &[], Some("(unclosed"), None, CaptureAction::Drop);
+        let err = CaptureRules::compile(std::slice::from_ref(&invalid)).err().unwrap();
+        assert!(err.to_string().contains("Broken"));
+        assert!(CaptureRules::compile_lenient(&[invalid, disabled]).rules.is_empty());
+    }
+
+    #[test]
+    fn test_redact() {
+        let rules = CaptureRules::compile(&[
+            rule("Ids", &[], Some(r"\d{3}-\d{2}-\d{4}"), None, CaptureAction::Redact),
+            rule("All", &["Vault"], None, None, CaptureAction::Redact),
+        ]).unwrap();
+        let mut snapshot = text("ssn 123-45-6789 ok");
+        snapshot.html = Some("<b>ssn 123-45-6789</b>".to_string());
+
+        let verdict = rules.evaluate(&SourceApp::default(), &snapshot).unwrap();
+        let redacted = rules.redact(&verdict, &snapshot);
+        assert_eq!(redacted.text.as_deref(), Some("ssn •••• ok"));
+        assert_eq!(redacted.html, None);
+
+        let vault = SourceApp { name: Some("Vault".to_string()), bundle_id: None };
+        let verdict = rules.evaluate(&vault, &text("anything")).unwrap();
+        assert_eq!(verdict.rule, "All");
+        assert_eq!(rules.redact(&verdict, &snapshot).text.as_deref(), Some(REDACTION_MASK));
+
+        // File paths can't be masked, so they go
+        let files = ClipboardSnapshot { files: vec!["/Users/me/secrets.txt".to_string()], ..Default::default() };
+        let redacted = rules.redact(&verdict, &files);
+        assert!(redacted.files.is_empty() && redacted.text.is_none());
+    }
+}
@@ -0,0 +1,249 @@
+//! Clipboard history search
+//!
+//! Every stored item gets an index entry: its lowercased word tokens plus the
+//! metadata used by filters. Entries are encrypted like the items themselves,
+//! so nothing searchable is ever written in the clear. A query matches when
+//! every query term hits some token exactly, as a prefix, or within a small
+//! edit distance ("pasword" still finds "password").
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::shared::types::{ClipboardHistoryItem, ClipboardItemType};
+
+/// Most distinct tokens indexed per item (large pastes keep their first words)
+const MAX_TOKENS: usize = 2_000;
+
+/// Longest token kept, in characters
+const MAX_TOKEN_CHARS: usize = 64;
+
+/// Searchable view of one history item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub id: String,
+    pub item_type: ClipboardItemType,
+    pub source_app: Option<String>,
+    pub tokens: Vec<String>,
+    /// `content_hash` of the item, to find its entry in the keyed hash table
+    #[serde(default)]
+    pub hash: String,
+}
+
+impl IndexEntry {
+    pub fn from_item(item: &ClipboardHistoryItem) -> Self {
+        // Images have no text; their preview ("Image 800×600") is all there is
+        let text = match item.item_type {
+            ClipboardItemType::Image => &item.preview,
+            _ => &item.content,
+        };
+        Self {
+            id: item.id.clone(),
+            item_type: item.item_type.clone(),
+            source_app: item.source_app.clone(),
+            tokens: tokenize(text),
+            hash: content_hash(item),
+        }
+    }
+}
+
+/// Hex SHA-256 over everything that makes two copies the same (IDs,
+/// timestamps and source app aside). Images are compared by size and
+/// thumbnail, which is a cheap stand-in for the full PNG.
+pub fn content_hash(item: &ClipboardHistoryItem) -> String {
+    let mut hasher = Sha256::new();
+    // Length-prefix each part so different splits can't collide
+    let mut part = |bytes: &[u8]| {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    part(format!("{:?}", item.item_type).as_bytes());
+    part(item.content.as_bytes());
+    part(item.html.as_deref().unwrap_or_default().as_bytes());
+    part(item.rtf.as_deref().unwrap_or_default().as_bytes());
+    for file in &item.files {
+        part(file.as_bytes());
+    }
+    if let Some(image) = &item.image {
+        part(format!("{}x{}:{}", image.width, image.height, image.byte_size).as_bytes());
+        part(image.thumbnail.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Lowercased, de-duplicated words in first-seen order
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    let mut tokens = Vec::new();
+    for word in text.unicode_words() {
+        let token: String = word.to_lowercase().chars().take(MAX_TOKEN_CHARS).collect();
+        if seen.insert(token.clone()) {
+            tokens.push(token);
+            if tokens.len() == MAX_TOKENS {
+                break;
+            }
+        }
+    }
+    tokens
+}
+
+/// Parsed search query and filters
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    terms: Vec<String>,
+    source_app: Option<String>,
+    item_type: Option<ClipboardItemType>,
+    /// Inclusive key range (copy time in epoch milliseconds)
+    pub from_ms: Option<u64>,
+    pub to_ms: Option<u64>,
+}
+
+impl SearchFilter {
+    pub fn new(
+        query: &str,
+        source_app: Option<&str>,
+        item_type: Option<ClipboardItemType>,
+        from_ms: Option<u64>,
+        to_ms: Option<u64>,
+    ) -> Self {
+        Self {
+            terms: tokenize(query),
+            source_app: source_app
+                .map(|app| app.trim().to_lowercase())
+                .filter(|app| !app.is_empty()),
+            item_type,
+            from_ms,
+            to_ms,
+        }
+    }
+
+    /// Whether matching needs index entries (anything beyond a time range)
+    pub fn needs_index(&self) -> bool {
+        !self.terms.is_empty() || self.source_app.is_some() || self.item_type.is_some()
+    }
+
+    /// Whether an index entry satisfies every term and filter
+    pub fn matches(&self, entry: &IndexEntry) -> bool {
+        if let Some(kind) = &self.item_type {
+            if &entry.item_type != kind {
+                return false;
+            }
+        }
+        if let Some(app) = &self.source_app {
+            match &entry.source_app {
+                Some(source) if source.to_lowercase().contains(app.as_str()) => {}
+                _ => return false,
+            }
+        }
+        self.terms
+            .iter()
+            .all(|term| entry.tokens.iter().any(|token| term_matches(term, token)))
+    }
+}
+
+/// Exact, prefix or fuzzy match of one query term against one token
+fn term_matches(term: &str, token: &str) -> bool {
+    if token.starts_with(term) {
+        return true;
+    }
+    let allowed = max_edits(term.chars().count());
+    allowed > 0 && within_edits(term, token, allowed)
+}
+
+/// Typos tolerated for a term: none for short terms, more for long ones
+fn max_edits(term_chars: usize) -> usize {
+    match term_chars {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance check that gives up once `limit` is exceeded
+fn within_edits(a: &str, b: &str, limit: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return false;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        if row_min > limit {
+            return false;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()] <= limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, app: Option<&str>) -> IndexEntry {
+        IndexEntry::from_item(&ClipboardHistoryItem::new_text(text.to_string(), app.map(String::from)))
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Hello, hello WORLD! it's 2024"), vec!["hello", "world", "it's", "2024"]);
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn test_prefix_and_exact() {
+        let e = entry("Quarterly report draft", None);
+        assert!(SearchFilter::new("report", None, None, None, None).matches(&e));
+        assert!(SearchFilter::new("quart dra", None, None, None, None).matches(&e));
+        assert!(!SearchFilter::new("report final", None, None, None, None).matches(&e));
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let e = entry("reset your password here", None);
+        assert!(SearchFilter::new("pasword", None, None, None, None).matches(&e));
+        assert!(SearchFilter::new("passwrod", None, None, None, None).matches(&e));
+        // Short terms must match exactly or as a prefix
+        assert!(!SearchFilter::new("pss", None, None, None, None).matches(&e));
+        assert!(!SearchFilter::new("xyz", None, None, None, None).matches(&e));
+    }
+
+    #[test]
+    fn test_filters() {
+        let e = entry("meeting notes", Some("Slack"));
+        assert!(SearchFilter::new("", Some("slack"), None, None, None).matches(&e));
+        assert!(!SearchFilter::new("", Some("Mail"), None, None, None).matches(&e));
+        assert!(SearchFilter::new("notes", None, Some(ClipboardItemType::Text), None, None).matches(&e));
+        assert!(!SearchFilter::new("notes", None, Some(ClipboardItemType::Html), None, None).matches(&e));
+        assert!(!SearchFilter::new("", None, None, Some(1), None).needs_index());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let a = ClipboardHistoryItem::new_text("same".to_string(), Some("Mail".to_string()));
+        let b = ClipboardHistoryItem::new_text("same".to_string(), None);
+        assert_eq!(content_hash(&a), content_hash(&b));
+        assert_eq!(entry("same", None).hash, content_hash(&a));
+
+        let mut html = b.clone();
+        html.html = Some("<b>same</b>".to_string());
+        assert_ne!(content_hash(&a), content_hash(&html));
+        assert_ne!(content_hash(&a), content_hash(&ClipboardHistoryItem::new_text("Same".to_string(), None)));
+    }
+
+    #[test]
+    fn test_within_edits() {
+        assert!(within_edits("kitten", "sitten", 1));
+        assert!(!within_edits("kitten", "sitting", 1));
+        assert!(within_edits("kitten", "sitting", 3));
+    }
+}
@@ -0,0 +1,142 @@
+//! Clipboard change sources
+//!
+//! The monitor no longer reads the whole clipboard on a timer. It blocks on a
+//! `ClipboardSource` until the platform says the clipboard changed, then reads
+//! one snapshot and compares its hash with the last one:
+//! - macOS: `NSPasteboard.changeCount` (a counter, so checking it is free)
+//! - Wayland: `wlr-data-control` selection events
+//! - X11: XFixes selection-owner events
+//! - anything else: a plain timer that reads every time
+//!
+//! `MockSource` feeds scripted snapshots to the monitor logic in tests.
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(all(unix, not(target_os = "macos")))]
+mod wayland;
+#[cfg(all(unix, not(target_os = "macos")))]
+mod x11;
+
+use std::time::Duration;
+
+use super::formats::{self, ClipboardSnapshot};
+use crate::shared::error::AppResult;
+
+/// A clipboard that can report when it changes
+pub trait ClipboardSource: Send {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    /// Block until the clipboard may have changed (true) or `timeout` passes (false)
+    fn wait_for_change(&mut self, timeout: Duration) -> AppResult<bool>;
+
+    /// Current clipboard contents
+    fn read(&mut self) -> AppResult<ClipboardSnapshot> {
+        formats::read()
+    }
+}
+
+/// Best change-notifying source for this platform, falling back to polling
+pub fn system() -> Box<dyn ClipboardSource> {
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(macos::ChangeCountSource::new())
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            match wayland::DataControlSource::connect() {
+                Ok(source) => return Box::new(source),
+                Err(e) => eprintln!("[ClipboardSource] Wayland data-control unavailable: {}", e),
+            }
+        }
+        match x11::XFixesSource::connect() {
+            Ok(source) => return Box::new(source),
+            Err(e) => eprintln!("[ClipboardSource] X11 XFixes unavailable: {}", e),
+        }
+        Box::new(PollingSource)
+    }
+
+    #[cfg(not(unix))]
+    {
+        Box::new(PollingSource)
+    }
+}
+
+/// Reads on every wake-up; the hash comparison filters out non-changes
+pub struct PollingSource;
+
+impl ClipboardSource for PollingSource {
+    fn name(&self) -> &'static str {
+        "polling"
+    }
+
+    fn wait_for_change(&mut self, timeout: Duration) -> AppResult<bool> {
+        std::thread::sleep(timeout);
+        Ok(true)
+    }
+}
+
+/// Wait on a channel fed by a backend's event thread. Bursts of
+/// notifications collapse into one change.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn wait_on(events: &std::sync::mpsc::Receiver<()>, timeout: Duration, backend: &str) -> AppResult<bool> {
+    use std::sync::mpsc::RecvTimeoutError;
+    use crate::shared::error::AppError;
+
+    match events.recv_timeout(timeout) {
+        Ok(()) => {
+            while events.try_recv().is_ok() {}
+            Ok(true)
+        }
+        Err(RecvTimeoutError::Timeout) => Ok(false),
+        Err(RecvTimeoutError::Disconnected) => Err(AppError::Io(format!("{} event thread stopped", backend))),
+    }
+}
+
+/// Scripted source for tests: each pushed snapshot is one change
+#[cfg(test)]
+pub struct MockSource {
+    changes: std::collections::VecDeque<AppResult<ClipboardSnapshot>>,
+    current: ClipboardSnapshot,
+}
+
+#[cfg(test)]
+impl MockSource {
+    pub fn new() -> Self {
+        Self { changes: Default::default(), current: ClipboardSnapshot::default() }
+    }
+
+    /// Queue a copy of `text`
+    pub fn copy(&mut self, text: &str) {
+        self.changes.push_back(Ok(ClipboardSnapshot { text: Some(text.to_string()), ..Default::default() }));
+    }
+
+    /// Queue a failed read
+    pub fn fail(&mut self) {
+        self.changes.push_back(Err(crate::shared::error::AppError::Io("mock failure".to_string())));
+    }
+}
+
+#[cfg(test)]
+impl ClipboardSource for MockSource {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn wait_for_change(&mut self, _timeout: Duration) -> AppResult<bool> {
+        match self.changes.pop_front() {
+            Some(Ok(snapshot)) => {
+                self.current = snapshot;
+                Ok(true)
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(false),
+        }
+    }
+
+    fn read(&mut self) -> AppResult<ClipboardSnapshot> {
+        Ok(self.current.clone())
+    }
+}
@@ -0,0 +1,58 @@
+//! macOS change detection via `NSPasteboard.changeCount`
+//!
+//! The pasteboard bumps its change count on every write, so checking it is a
+//! single message send; the contents are only read when the count moves.
+
+use std::time::{Duration, Instant};
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSInteger;
+use objc::{class, msg_send, sel, sel_impl};
+
+use super::ClipboardSource;
+use crate::shared::error::{AppError, AppResult};
+
+/// How often the change count is checked while waiting
+const CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct ChangeCountSource {
+    last_count: Option<NSInteger>,
+}
+
+impl ChangeCountSource {
+    pub fn new() -> Self {
+        Self { last_count: None }
+    }
+}
+
+fn change_count() -> AppResult<NSInteger> {
+    unsafe {
+        let pb: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pb == nil {
+            return Err(AppError::Io("Failed to get NSPasteboard".to_string()));
+        }
+        Ok(msg_send![pb, changeCount])
+    }
+}
+
+impl ClipboardSource for ChangeCountSource {
+    fn name(&self) -> &'static str {
+        "macOS changeCount"
+    }
+
+    fn wait_for_change(&mut self, timeout: Duration) -> AppResult<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let count = change_count()?;
+            if self.last_count != Some(count) {
+                self.last_count = Some(count);
+                return Ok(true);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            std::thread::sleep(CHECK_INTERVAL.min(deadline - now));
+        }
+    }
+}
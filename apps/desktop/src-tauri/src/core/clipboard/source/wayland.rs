@@ -0,0 +1,122 @@
+//! Wayland change detection via `wlr-data-control`
+//!
+//! Regular Wayland clients only see the clipboard while focused; the
+//! data-control protocol (wlroots compositors, KDE) lets a background client
+//! follow the selection. The compositor sends `selection` on every copy, and
+//! once on bind for the current contents. A dedicated thread dispatches the
+//! queue and forwards each selection to the monitor.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+use wayland_client::{delegate_noop, event_created_child, Connection, Dispatch, QueueHandle};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+};
+
+use super::{wait_on, ClipboardSource};
+use crate::shared::error::{AppError, AppResult};
+
+pub struct DataControlSource {
+    events: mpsc::Receiver<()>,
+}
+
+/// Event-thread state
+struct Listener {
+    sender: mpsc::Sender<()>,
+    /// Offer for the current selection, destroyed when replaced
+    offer: Option<ZwlrDataControlOfferV1>,
+    running: bool,
+}
+
+impl DataControlSource {
+    pub fn connect() -> AppResult<Self> {
+        let conn = Connection::connect_to_env().map_err(wayland_error)?;
+        let (globals, mut queue) = registry_queue_init::<Listener>(&conn).map_err(wayland_error)?;
+        let qh = queue.handle();
+        let seat: WlSeat = globals.bind(&qh, 1..=1, ()).map_err(wayland_error)?;
+        let manager: ZwlrDataControlManagerV1 = globals.bind(&qh, 1..=2, ()).map_err(wayland_error)?;
+        let device = manager.get_data_device(&seat, &qh, ());
+
+        let (sender, events) = mpsc::channel();
+        let mut listener = Listener { sender, offer: None, running: true };
+        std::thread::Builder::new()
+            .name("clipboard-wayland".to_string())
+            .spawn(move || {
+                while listener.running {
+                    if let Err(e) = queue.blocking_dispatch(&mut listener) {
+                        eprintln!("[ClipboardSource] Wayland connection lost: {}", e);
+                        break;
+                    }
+                }
+                device.destroy();
+                manager.destroy();
+            })
+            .map_err(|e| AppError::System(format!("Failed to start Wayland event thread: {}", e)))?;
+
+        println!("[ClipboardSource] Listening for Wayland selection changes");
+        Ok(Self { events })
+    }
+}
+
+fn wayland_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Io(format!("Wayland: {}", e))
+}
+
+impl ClipboardSource for DataControlSource {
+    fn name(&self) -> &'static str {
+        "Wayland data-control"
+    }
+
+    fn wait_for_change(&mut self, timeout: Duration) -> AppResult<bool> {
+        wait_on(&self.events, timeout, "Wayland")
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for Listener {
+    fn event(
+        state: &mut Self,
+        _device: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_device_v1::Event::Selection { id } => {
+                if let Some(previous) = std::mem::replace(&mut state.offer, id) {
+                    previous.destroy();
+                }
+                // The monitor is gone once the receiver is dropped
+                state.running = state.sender.send(()).is_ok();
+            }
+            zwlr_data_control_device_v1::Event::PrimarySelection { id: Some(offer) } => offer.destroy(),
+            zwlr_data_control_device_v1::Event::Finished => state.running = false,
+            _ => {}
+        }
+    }
+
+    event_created_child!(Listener, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for Listener {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(Listener: ignore WlSeat);
+delegate_noop!(Listener: ZwlrDataControlManagerV1);
+delegate_noop!(Listener: ignore ZwlrDataControlOfferV1);
@@ -0,0 +1,72 @@
+//! X11 change detection via XFixes selection events
+//!
+//! XFixes sends `SelectionNotify` whenever a new client takes ownership of
+//! `CLIPBOARD`, which is exactly what a copy does. A dedicated thread blocks
+//! on the connection and forwards each event to the monitor.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+use x11rb::protocol::xproto::ConnectionExt as _;
+use x11rb::protocol::Event;
+
+use super::{wait_on, ClipboardSource};
+use crate::shared::error::{AppError, AppResult};
+
+pub struct XFixesSource {
+    events: mpsc::Receiver<()>,
+}
+
+impl XFixesSource {
+    pub fn connect() -> AppResult<Self> {
+        let (conn, screen) = x11rb::connect(None).map_err(x11_error)?;
+        conn.xfixes_query_version(5, 0).map_err(x11_error)?
+            .reply().map_err(x11_error)?;
+        let root = conn.setup().roots[screen].root;
+        let clipboard = conn.intern_atom(false, b"CLIPBOARD").map_err(x11_error)?
+            .reply().map_err(x11_error)?
+            .atom;
+        conn.xfixes_select_selection_input(root, clipboard, SelectionEventMask::SET_SELECTION_OWNER)
+            .map_err(x11_error)?;
+        conn.flush().map_err(x11_error)?;
+
+        let (sender, events) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("clipboard-xfixes".to_string())
+            .spawn(move || loop {
+                match conn.wait_for_event() {
+                    Ok(Event::XfixesSelectionNotify(_)) => {
+                        // The monitor is gone once the receiver is dropped
+                        if sender.send(()).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("[ClipboardSource] X11 connection lost: {}", e);
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| AppError::System(format!("Failed to start X11 event thread: {}", e)))?;
+
+        println!("[ClipboardSource] Listening for X11 selection changes");
+        Ok(Self { events })
+    }
+}
+
+fn x11_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Io(format!("X11: {}", e))
+}
+
+impl ClipboardSource for XFixesSource {
+    fn name(&self) -> &'static str {
+        "X11 XFixes"
+    }
+
+    fn wait_for_change(&mut self, timeout: Duration) -> AppResult<bool> {
+        wait_on(&self.events, timeout, "X11")
+    }
+}
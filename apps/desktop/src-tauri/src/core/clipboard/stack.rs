@@ -0,0 +1,190 @@
+//! Copy stacking
+//!
+//! While the stack is on, every copy that reaches history is also appended
+//! to an in-memory buffer. The buffer is then pasted either all at once,
+//! joined with the configured separator, or one entry per paste, oldest
+//! first. Nothing here is persisted: the stack is a short-lived scratchpad.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, RwLock};
+use once_cell::sync::Lazy;
+
+use crate::shared::settings::ClipboardSettings;
+use crate::shared::types::{ClipboardHistoryItem, ClipboardItemType, CommandItem, CopyStackState};
+
+/// Palette command ID prefix; the rest names the stack action
+pub const STACK_COMMAND_PREFIX: &str = "clipboard_stack:";
+
+/// Most entries kept; the oldest are dropped beyond this
+const MAX_ENTRIES: usize = 100;
+
+/// Text placed between entries when the stack is pasted at once
+static SEPARATOR: Lazy<RwLock<String>> =
+    Lazy::new(|| RwLock::new(ClipboardSettings::default().stack_separator));
+
+/// Replace the separator (called when settings are loaded or saved)
+pub fn set_separator(settings: &ClipboardSettings) {
+    let separator = settings.stack_separator.clone();
+    match SEPARATOR.write() {
+        Ok(mut guard) => *guard = separator,
+        Err(poisoned) => *poisoned.into_inner() = separator,
+    }
+}
+
+fn separator() -> String {
+    match SEPARATOR.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+#[derive(Default)]
+struct StackState {
+    active: bool,
+    entries: VecDeque<ClipboardHistoryItem>,
+}
+
+/// The copy stack shared by the monitor and the paste commands
+#[derive(Default)]
+pub struct CopyStack {
+    state: Mutex<StackState>,
+}
+
+impl CopyStack {
+    fn lock(&self) -> MutexGuard<'_, StackState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("[CopyStack] Mutex poisoned, recovering...");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Turn collecting on or off (entries already collected stay)
+    pub fn set_active(&self, active: bool) -> CopyStackState {
+        let mut state = self.lock();
+        state.active = active;
+        println!("[CopyStack] {}", if active { "Collecting copies" } else { "Stopped collecting" });
+        snapshot(&state)
+    }
+
+    pub fn state(&self) -> CopyStackState {
+        snapshot(&self.lock())
+    }
+
+    /// Append a copy while collecting. Images can't be joined as text, so
+    /// they stay in history only.
+    pub fn push(&self, item: &ClipboardHistoryItem) -> bool {
+        let mut state = self.lock();
+        if !state.active || item.item_type == ClipboardItemType::Image {
+            return false;
+        }
+        if state.entries.len() == MAX_ENTRIES {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(item.clone());
+        true
+    }
+
+    /// Every entry joined with the separator; empties the stack and stops collecting
+    pub fn take_joined(&self) -> Option<String> {
+        let mut state = self.lock();
+        if state.entries.is_empty() {
+            return None;
+        }
+        let separator = separator();
+        let joined = state.entries.drain(..)
+            .map(|item| item.content)
+            .collect::<Vec<_>>()
+            .join(&separator);
+        state.active = false;
+        Some(joined)
+    }
+
+    /// The oldest entry, removed from the stack
+    pub fn pop_next(&self) -> Option<ClipboardHistoryItem> {
+        self.lock().entries.pop_front()
+    }
+
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Palette entries for starting/stopping and pasting the stack
+    pub fn command_items(&self) -> Vec<CommandItem> {
+        let state = self.lock();
+        let count = state.entries.len();
+        let command = |action: &str, label: String| CommandItem {
+            id: format!("{}{}", STACK_COMMAND_PREFIX, action),
+            label,
+            description: Some(format!("{} in stack", count)),
+            action_type: None,
+            widget_type: None,
+            category: None,
+        };
+
+        let mut items = vec![if state.active {
+            command("stop", "Stop Copy Stack".to_string())
+        } else {
+            command("start", "Start Copy Stack".to_string())
+        }];
+        if count > 0 {
+            items.push(command("paste_all", "Paste Copy Stack".to_string()));
+            items.push(command("paste_next", "Paste Next from Copy Stack".to_string()));
+        }
+        items
+    }
+}
+
+fn snapshot(state: &StackState) -> CopyStackState {
+    CopyStackState {
+        active: state.active,
+        previews: state.entries.iter().map(|item| item.preview.clone()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str) -> ClipboardHistoryItem {
+        ClipboardHistoryItem::new_text(content.to_string(), None)
+    }
+
+    #[test]
+    fn test_collects_only_while_active() {
+        let stack = CopyStack::default();
+        assert!(!stack.push(&text("ignored")));
+        stack.set_active(true);
+        assert!(stack.push(&text("a")));
+        assert!(stack.push(&text("b")));
+        assert_eq!(stack.state().previews, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_paste_joined_and_fifo() {
+        let stack = CopyStack::default();
+        stack.set_active(true);
+        for entry in ["one", "two", "three"] {
+            stack.push(&text(entry));
+        }
+        assert_eq!(stack.pop_next().unwrap().content, "one");
+
+        // Default separator is a newline
+        assert_eq!(stack.take_joined().as_deref(), Some("two\nthree"));
+        assert!(!stack.state().active);
+        assert!(stack.take_joined().is_none());
+        assert!(stack.pop_next().is_none());
+    }
+
+    #[test]
+    fn test_command_items() {
+        let stack = CopyStack::default();
+        assert_eq!(stack.command_items().len(), 1);
+        stack.set_active(true);
+        stack.push(&text("x"));
+        let ids: Vec<String> = stack.command_items().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["clipboard_stack:stop", "clipboard_stack:paste_all", "clipboard_stack:paste_next"]);
+    }
+}
@@ -22,12 +22,18 @@ pub enum ContextCategory {
     Temperature,
     /// Speed measurements (km/h, mph, m/s, etc.)
     Speed,
+    /// Typography and screen units (px, pt, em, rem, dp, etc.)
+    Typography,
     /// Currency amounts (USD, EUR, GBP, etc.)
     Currency,
     /// Text content (for translation)
     Text,
     /// Time measurements (hours, minutes, seconds) - for future scalability
     Time,
+    /// Unix epochs and ISO 8601 / RFC 2822 / RFC 3339 dates
+    Timestamp,
+    /// Cron expressions (5, 6 or 7 fields, or @daily-style macros) and crontab lines
+    Cron,
     /// General/uncategorized content
     General,
 }
@@ -46,6 +52,29 @@ pub fn detect_content_category(text: &str) -> Option<ContextCategory> {
     };
     let text_lower = truncated.to_lowercase();
     
+    // Timestamps: checked first so "2025-03-14T14:30:00Z" isn't read as minutes
+    if crate::core::features::time_converter::timestamps::is_timestamp_text(truncated) {
+        return Some(ContextCategory::Timestamp);
+    }
+    
+    // Cron: before the unit patterns, which would read "0 9 * * 1-5" as a bare number
+    if crate::core::features::time_converter::cron::is_cron_text(truncated) {
+        return Some(ContextCategory::Cron);
+    }
+    
+    // Typography patterns: before the other unit patterns so "16sp" isn't read as seconds
+    let typography_patterns = vec![
+        r"\d+\.?\d*\s*(px|pt|pc|em|rem|vw|vh|dp|dip|sp|pixels?|points?|picas?)\b",
+        r"\d+\.?\d*\s*ios[\s-]?(pt|points?)\b",
+    ];
+    for pattern in typography_patterns {
+        if let Ok(re) = Regex::new(pattern) {
+            if re.is_match(&text_lower) {
+                return Some(ContextCategory::Typography);
+            }
+        }
+    }
+    
     // Length patterns: numbers followed by length units
     let length_patterns = vec![
         r"\d+\.?\d*\s*(mm|cm|m|km|in|inch|inches|ft|foot|feet|yd|yard|yards|mi|mile|miles|millimeter|millimeters|centimeter|centimeters|meter|meters|kilometer|kilometers)",
@@ -74,7 +103,7 @@ pub fn detect_content_category(text: &str) -> Option<ContextCategory> {
     
     // Volume patterns: numbers followed by volume units
     let volume_patterns = vec![
-        r"\d+\.?\d*\s*(ml|l|liter|liters|litre|litres|fl-oz|floz|fluid\s*ounce|fluid\s*ounces|cup|cups|gal|gallon|gallons|milliliter|milliliters)",
+        r"\d+\.?\d*\s*(ml|l|liter|liters|litre|litres|fl-oz|floz|fluid\s*ounce|fluid\s*ounces|tsp|tbsp|teaspoons?|tablespoons?|cup|cups|gal|gallon|gallons|milliliter|milliliters)",
         r"(ml|l|fl-oz|cup|gal)\s*\d+\.?\d*",
     ];
     for pattern in volume_patterns {
@@ -168,6 +197,15 @@ pub fn get_action_category(action: &ActionType) -> Option<ContextCategory> {
         
         // Time conversion - NEW structured variant only
         ActionType::ConvertTimeAction(_) => Some(ContextCategory::Time),
+
+        // Timestamp reformatting (epoch <-> ISO 8601 / RFC 2822 / RFC 3339)
+        ActionType::FormatTimestamp(_) => Some(ContextCategory::Timestamp),
+
+        // Cron explanation and next runs
+        ActionType::ExplainCron(_) => Some(ContextCategory::Cron),
+
+        // Recipes are mostly volume measures (Volume context also admits Mass text)
+        ActionType::ScaleRecipe(_) => Some(ContextCategory::Volume),
         
 
     }
@@ -198,6 +236,8 @@ fn get_unit_category(unit: &str) -> Option<ContextCategory> {
         "ml" | "milliliter" | "milliliters" |
         "l" | "liter" | "liters" | "litre" | "litres" |
         "fl-oz" | "floz" | "fluid ounce" | "fluid ounces" |
+        "tsp" | "teaspoon" | "teaspoons" |
+        "tbsp" | "tablespoon" | "tablespoons" |
         "cup" | "cups" |
         "gal" | "gallon" | "gallons" => Some(ContextCategory::Volume),
         
@@ -207,6 +247,10 @@ fn get_unit_category(unit: &str) -> Option<ContextCategory> {
         // Speed
         "km/h" | "kmh" | "mph" | "kph" => Some(ContextCategory::Speed),
         
+        // Typography
+        "px" | "pt" | "pc" | "em" | "rem" | "vw" | "vh" |
+        "dp" | "sp" | "ios-pt" => Some(ContextCategory::Typography),
+        
         _ => None,
     }
 }
@@ -255,6 +299,42 @@ mod tests {
         assert_eq!(detect_content_category("72 fahrenheit"), Some(ContextCategory::Temperature));
     }
 
+    #[test]
+    fn test_detect_typography() {
+        assert_eq!(detect_content_category("16px"), Some(ContextCategory::Typography));
+        assert_eq!(detect_content_category("1.5rem"), Some(ContextCategory::Typography));
+        assert_eq!(detect_content_category("14 sp"), Some(ContextCategory::Typography));
+        assert_eq!(detect_content_category("12pt"), Some(ContextCategory::Typography));
+        // Not swallowing unrelated words
+        assert_eq!(detect_content_category("3 emails"), Some(ContextCategory::Text));
+    }
+
+    #[test]
+    fn test_detect_timestamp() {
+        assert_eq!(detect_content_category("1741962600"), Some(ContextCategory::Timestamp));
+        assert_eq!(detect_content_category("1741962600123"), Some(ContextCategory::Timestamp));
+        assert_eq!(detect_content_category("2025-03-14T14:30:00+01:00"), Some(ContextCategory::Timestamp));
+        assert_eq!(detect_content_category("Fri, 14 Mar 2025 14:30:00 GMT"), Some(ContextCategory::Timestamp));
+        // Short numbers stay numbers
+        assert_ne!(detect_content_category("Order 12345"), Some(ContextCategory::Timestamp));
+        // Quantities keep their conversions
+        assert_eq!(detect_content_category("384400000 m"), Some(ContextCategory::Length));
+        assert_ne!(detect_content_category("150000000 mg"), Some(ContextCategory::Timestamp));
+        assert_eq!(detect_content_category("1500000000 JPY"), Some(ContextCategory::Currency));
+        assert_ne!(detect_content_category("3125551234"), Some(ContextCategory::Timestamp));
+    }
+
+    #[test]
+    fn test_detect_cron() {
+        assert_eq!(detect_content_category("*/15 9-17 * * MON-FRI"), Some(ContextCategory::Cron));
+        assert_eq!(detect_content_category("0 0 12 ? * 2#1 *"), Some(ContextCategory::Cron));
+        assert_eq!(detect_content_category("@daily"), Some(ContextCategory::Cron));
+        assert_eq!(detect_content_category("5 4 * * * /usr/bin/backup"), Some(ContextCategory::Cron));
+        // Plain numbers and arithmetic aren't schedules
+        assert_ne!(detect_content_category("1 2 3 4 5"), Some(ContextCategory::Cron));
+        assert_ne!(detect_content_category("5 * 3"), Some(ContextCategory::Cron));
+    }
+
     #[test]
     fn test_get_action_category_currency() {
         use crate::shared::types::CurrencyPayload;
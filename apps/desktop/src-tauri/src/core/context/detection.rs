@@ -1,4 +1,5 @@
 use regex::Regex;
+use rust_decimal::prelude::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
 /// Context information extracted from selected text
@@ -29,7 +30,7 @@ pub fn detect_currency(text: &str) -> Option<CurrencyInfo> {
     // Fuzzy pattern: optional prefix, number, optional whitespace, optional suffix
     static CURRENCY_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
     let re = CURRENCY_REGEX.get_or_init(|| {
-        Regex::new(r"(?i)^\s*([^\d\s\.,]*)[\s]*([\d\.,]+)[\s]*([^\d\s\.,]*)\s*$")
+        Regex::new(r"(?i)^\s*([^\d\s\.,'’]*)[\s]*([\.,]?\d(?:[\d\.,'’\u{00A0}\u{202F}]*\d)?)[\s]*([^\d\s\.,'’]*)\s*$")
             .expect("valid currency regex")
     });
 
@@ -38,8 +39,8 @@ pub fn detect_currency(text: &str) -> Option<CurrencyInfo> {
         let number_raw = caps.get(2).map(|m| m.as_str()).unwrap_or("");
         let suffix = caps.get(3).map(|m| m.as_str()).unwrap_or("").trim();
 
-        let cleaned = number_raw.replace(',', "");
-        if let Ok(amount) = cleaned.parse::<f64>() {
+        // Locale-aware: "1.234,56 €", "CHF 1'234.50" and "$1,234.56" all parse
+        if let Some(amount) = crate::shared::number_locale::parse_number(number_raw).and_then(|n| n.value.to_f64()) {
             // Detect currency from prefix/suffix tokens
             let map_token = |raw: &str| -> Option<&'static str> {
                 let t = raw.trim().to_ascii_lowercase();
@@ -176,6 +177,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_currency_localized_amounts() {
+        let result = detect_currency("1.234,56 €").expect("german amount");
+        assert_eq!(result.currency_code, "EUR");
+        assert!((result.amount - 1234.56).abs() < 0.001);
+
+        let result = detect_currency("CHF 1'234.5").expect("swiss amount");
+        assert_eq!(result.currency_code, "CHF");
+        assert!((result.amount - 1234.5).abs() < 0.001);
+    }
+
     #[test]
     fn test_detect_language_chinese() {
         let result = detect_language("你好世界");
@@ -9,13 +9,16 @@ use crate::core::context::category::{ContextCategory, get_action_category};
 /// Validate that an action is permitted for the given text category
 /// 
 /// Rules:
-/// - Category::Length can only execute Length actions
+/// - Category::Length can execute Length OR Typography actions (via DPI)
 /// - Category::Currency can only execute Currency actions
 /// - Category::Volume can execute Volume OR CrossCategory (Volume <-> Mass) actions
 /// - Category::Mass can execute Mass OR CrossCategory (Mass <-> Volume) actions
 /// - Category::Text can only execute Text actions
 /// - Category::Temperature can only execute Temperature actions
 /// - Category::Speed can only execute Speed actions
+/// - Category::Typography can execute Typography OR Length actions (via DPI)
+/// - Category::Timestamp can execute Timestamp OR Time (timezone) actions
+/// - Category::Cron can only execute Cron actions
 pub fn validate_action(
     text_category: &ContextCategory,
     action: &ActionType,
@@ -26,7 +29,9 @@ pub fn validate_action(
     // Check if action is permitted for this text category
     let is_permitted = match text_category {
         ContextCategory::Length => {
-            action_category == ContextCategory::Length
+            // Physical lengths map to screen units at the configured DPI (10mm -> px)
+            action_category == ContextCategory::Length ||
+            action_category == ContextCategory::Typography
         }
         ContextCategory::Currency => {
             action_category == ContextCategory::Currency
@@ -60,6 +65,19 @@ pub fn validate_action(
         ContextCategory::Time => {
             action_category == ContextCategory::Time
         }
+        ContextCategory::Timestamp => {
+            // A timestamp is an instant, so timezone conversions apply too
+            action_category == ContextCategory::Timestamp ||
+            action_category == ContextCategory::Time
+        }
+        ContextCategory::Cron => {
+            action_category == ContextCategory::Cron
+        }
+        ContextCategory::Typography => {
+            // Screen units convert to each other and to physical lengths (16px -> mm)
+            action_category == ContextCategory::Typography ||
+            action_category == ContextCategory::Length
+        }
         ContextCategory::General => {
             // General category allows all actions (fallback)
             true
@@ -144,12 +162,29 @@ fn get_valid_actions_for_category(category: &ContextCategory) -> String {
             "Convert to Kilometers/Hour",
             "Convert to Miles/Hour",
         ],
+        ContextCategory::Typography => vec![
+            "Convert to Pixels",
+            "Convert to rem",
+            "Convert to Points",
+            "Convert to Android dp",
+            "Convert to iOS Points",
+        ],
         ContextCategory::Time => vec![
             "Convert to Hours",
             "Convert to Minutes",
             "Convert to Seconds",
             "Convert to Milliseconds",
         ],
+        ContextCategory::Timestamp => vec![
+            "Convert timestamp to Local time",
+            "Convert timestamp to ISO 8601",
+            "Convert timestamp to Unix seconds",
+            "Convert timestamp to RFC 2822",
+        ],
+        ContextCategory::Cron => vec![
+            "Explain cron expression",
+            "Show next cron runs",
+        ],
         ContextCategory::General => vec!["Any conversion"],
     };
     
@@ -187,5 +187,17 @@ pub async fn execute_feature_action(
             println!("🔵 [dispatch] Routing Unit conversion -> UnitConverter");
             unit_converter::UnitConverterFeature.execute_action(&request.action_type, &request.params).await
         },
+        ActionType::ScaleRecipe(_) => {
+            println!("🔵 [dispatch] Routing Recipe scaling -> UnitConverter");
+            unit_converter::UnitConverterFeature.execute_action(&request.action_type, &request.params).await
+        },
+        ActionType::FormatTimestamp(_) => {
+            println!("🔵 [dispatch] Routing Timestamp action -> TimeConverter");
+            time_converter::TimeConverterFeature.execute_action(&request.action_type, &request.params).await
+        },
+        ActionType::ExplainCron(_) => {
+            println!("🔵 [dispatch] Routing Cron action -> TimeConverter");
+            time_converter::TimeConverterFeature.execute_action(&request.action_type, &request.params).await
+        },
     }
 }
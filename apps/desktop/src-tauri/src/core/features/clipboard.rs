@@ -4,6 +4,9 @@
 
 use crate::shared::types::*;
 use crate::core::clipboard::{ClipboardHistory, ClipboardMonitor};
+use crate::core::clipboard::history::DEFAULT_PAGE_SIZE;
+use crate::core::clipboard::{backup, formats, originals};
+use crate::core::clipboard::search::SearchFilter;
 
 use crate::core::context;
 use crate::system::automation;
@@ -11,6 +14,7 @@ use super::{FeatureSync, FeatureAsync};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
+use chrono::{Local, TimeZone};
 use tauri::Manager;
 use crate::core::context::category::ContextCategory;
 
@@ -56,6 +60,53 @@ pub fn get_clipboard_history(history: tauri::State<ClipboardHistory>) -> crate::
     history.get_items()
 }
 
+/// Search clipboard history, or page through it with an empty query
+#[tauri::command]
+pub fn search_clipboard_history(
+    history: tauri::State<ClipboardHistory>,
+    request: SearchClipboardRequest,
+) -> crate::shared::error::AppResult<ClipboardHistoryPage> {
+    let before = request.cursor
+        .as_deref()
+        .map(|cursor| cursor.parse::<u64>()
+            .map_err(|_| crate::shared::error::AppError::Validation(format!("Invalid cursor '{}'", cursor))))
+        .transpose()?;
+    let filter = SearchFilter::new(
+        &request.query,
+        request.source_app.as_deref(),
+        request.item_type.clone(),
+        request.from.as_deref().map(|from| parse_time_bound(from, false)).transpose()?,
+        request.to.as_deref().map(|to| parse_time_bound(to, true)).transpose()?,
+    );
+    let limit = request.limit
+        .map(|limit| limit.max(1) as usize)
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let page = history.search(&filter, before, limit)?;
+    Ok(ClipboardHistoryPage {
+        items: page.items,
+        next_cursor: page.next.map(|key| key.to_string()),
+    })
+}
+
+/// Epoch milliseconds for an RFC 3339 time or a local date (its start, or its end when `end_of_day`)
+fn parse_time_bound(value: &str, end_of_day: bool) -> crate::shared::error::AppResult<u64> {
+    let value = value.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp_millis().max(0) as u64);
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| crate::shared::error::AppError::Validation(format!("Invalid date '{}' (use YYYY-MM-DD)", value)))?;
+    let day = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+    let start = day.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let millis = Local.from_local_datetime(&start)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+        .unwrap_or_else(|| start.and_utc().timestamp_millis());
+    let millis = if end_of_day { millis - 1 } else { millis };
+    Ok(millis.max(0) as u64)
+}
+
 /// Paste a clipboard item to the active application
 #[tauri::command]
 pub async fn paste_clipboard_item(
@@ -70,12 +121,76 @@ pub async fn paste_clipboard_item(
 
     println!("[PasteItem] Pasting item: {}", item.id);
 
-    history.set_skip_next_add(true);
+    // Restore every captured format (HTML, RTF, image, files), not just the text
+    let png = match item.image {
+        Some(_) => history.get_blob(&item.id)?,
+        None => None,
+    };
+    let mut snapshot = formats::restore(&item, png.as_deref())?;
+    // Shortly after a redacted copy, its original may still be held in memory
+    if item.redacted {
+        if let Some(original) = originals::get(&item.id) {
+            println!("[PasteItem] Pasting held original of redacted item");
+            snapshot.text = Some(original);
+        }
+    }
+    paste_snapshot(&app, &history, &last_active_app, &snapshot, item.source_app.as_deref())
+}
+
+/// Turn collecting copies into the stack on or off
+#[tauri::command]
+pub fn set_copy_stack_active(history: tauri::State<ClipboardHistory>, active: bool) -> crate::shared::error::AppResult<CopyStackState> {
+    Ok(history.stack().set_active(active))
+}
+
+/// Get the copy stack's state and contents
+#[tauri::command]
+pub fn get_copy_stack(history: tauri::State<ClipboardHistory>) -> crate::shared::error::AppResult<CopyStackState> {
+    Ok(history.stack().state())
+}
 
-    use tauri_plugin_clipboard_manager::ClipboardExt;
-    app.clipboard()
-        .write_text(item.content.clone())
-        .map_err(|e| crate::shared::error::AppError::System(format!("Failed to write to clipboard: {}", e)))?;
+/// Drop everything collected in the copy stack
+#[tauri::command]
+pub fn clear_copy_stack(history: tauri::State<ClipboardHistory>) -> crate::shared::error::AppResult<CopyStackState> {
+    history.stack().clear();
+    Ok(history.stack().state())
+}
+
+/// Paste the whole copy stack joined with the separator, or just its oldest entry
+#[tauri::command]
+pub async fn paste_copy_stack(
+    app: tauri::AppHandle,
+    history: tauri::State<'_, ClipboardHistory>,
+    last_active_app: tauri::State<'_, Arc<Mutex<Option<String>>>>,
+    mode: CopyStackPasteMode,
+) -> crate::shared::error::AppResult<CopyStackState> {
+    let empty = || crate::shared::error::AppError::Validation("Copy stack is empty".to_string());
+    let snapshot = match mode {
+        CopyStackPasteMode::Joined => {
+            let text = history.stack().take_joined().ok_or_else(empty)?;
+            formats::ClipboardSnapshot { text: Some(text), ..Default::default() }
+        }
+        CopyStackPasteMode::Next => {
+            let item = history.stack().pop_next().ok_or_else(empty)?;
+            formats::restore(&item, None)?
+        }
+    };
+
+    println!("[PasteItem] Pasting copy stack ({:?})", mode);
+    paste_snapshot(&app, &history, &last_active_app, &snapshot, None)?;
+    Ok(history.stack().state())
+}
+
+/// Put a snapshot on the clipboard and paste it into the last active app
+fn paste_snapshot(
+    app: &tauri::AppHandle,
+    history: &ClipboardHistory,
+    last_active_app: &Mutex<Option<String>>,
+    snapshot: &formats::ClipboardSnapshot,
+    source_app: Option<&str>,
+) -> crate::shared::error::AppResult<()> {
+    history.set_skip_next_add(true);
+    formats::write(snapshot)?;
 
     let target_app = {
         let last_app_guard = match last_active_app.lock() {
@@ -88,9 +203,9 @@ pub async fn paste_clipboard_item(
         if let Some(app_name) = last_app_guard.as_ref() {
             println!("[PasteItem] Using stored last active app: {}", app_name);
             app_name.clone()
-        } else if let Some(source) = &item.source_app {
+        } else if let Some(source) = source_app {
             println!("[PasteItem] Using item source app: {}", source);
-            source.clone()
+            source.to_string()
         } else {
             let fallback = automation::get_active_app().unwrap_or_else(|_| "Finder".to_string());
             println!("[PasteItem] Using fallback app: {}", fallback);
@@ -124,6 +239,97 @@ pub async fn paste_clipboard_item(
     Ok(())
 }
 
+/// Get pinned items and collections
+#[tauri::command]
+pub fn get_clipboard_pins(history: tauri::State<ClipboardHistory>) -> crate::shared::error::AppResult<ClipboardPinsResponse> {
+    history.pins().list()
+}
+
+/// Pin a copy of a history item so it never expires
+#[tauri::command]
+pub fn pin_clipboard_item(
+    history: tauri::State<ClipboardHistory>,
+    request: PinClipboardItemRequest,
+) -> crate::shared::error::AppResult<PinnedClipboardItem> {
+    let item = history
+        .get_item_by_id(&request.item_id)?
+        .ok_or_else(|| crate::shared::error::AppError::Validation("Clipboard item not found".to_string()))?;
+    let blob = history.get_blob(&item.id)?;
+    history.pins().pin(&item, blob, request.collection_id, request.title)
+}
+
+/// Edit a pinned item's content, title or collection
+#[tauri::command]
+pub fn update_pinned_item(
+    history: tauri::State<ClipboardHistory>,
+    request: UpdatePinnedItemRequest,
+) -> crate::shared::error::AppResult<PinnedClipboardItem> {
+    history.pins().update(&request.id, request.content, request.title, request.collection_id)
+}
+
+/// Remove a pinned item
+#[tauri::command]
+pub fn unpin_clipboard_item(history: tauri::State<ClipboardHistory>, id: String) -> crate::shared::error::AppResult<()> {
+    history.pins().unpin(&id)
+}
+
+/// Reorder pinned items (listed IDs first, in order)
+#[tauri::command]
+pub fn reorder_pinned_items(history: tauri::State<ClipboardHistory>, ids: Vec<String>) -> crate::shared::error::AppResult<()> {
+    history.pins().reorder_items(&ids)
+}
+
+/// Create a collection, or rename one when `id` is given
+#[tauri::command]
+pub fn save_clipboard_collection(
+    history: tauri::State<ClipboardHistory>,
+    id: Option<String>,
+    name: String,
+) -> crate::shared::error::AppResult<ClipboardCollection> {
+    history.pins().save_collection(id.as_deref(), &name)
+}
+
+/// Delete a collection (its items become top-level pins)
+#[tauri::command]
+pub fn delete_clipboard_collection(history: tauri::State<ClipboardHistory>, id: String) -> crate::shared::error::AppResult<()> {
+    history.pins().delete_collection(&id)
+}
+
+/// Reorder collections (listed IDs first, in order)
+#[tauri::command]
+pub fn reorder_clipboard_collections(history: tauri::State<ClipboardHistory>, ids: Vec<String>) -> crate::shared::error::AppResult<()> {
+    history.pins().reorder_collections(&ids)
+}
+
+/// Write history and pins to a passphrase-encrypted backup file
+#[tauri::command]
+pub async fn export_clipboard_history(
+    history: tauri::State<'_, ClipboardHistory>,
+    request: ExportClipboardRequest,
+) -> crate::shared::error::AppResult<ClipboardExportSummary> {
+    // Key derivation is deliberately slow, so keep it off the async runtime
+    let history = history.clone_arc();
+    tauri::async_runtime::spawn_blocking(move || {
+        backup::export(&history, std::path::Path::new(&request.path), &request.passphrase)
+    })
+    .await
+    .map_err(|e| crate::shared::error::AppError::System(format!("Export task failed: {}", e)))?
+}
+
+/// Merge a backup file into history and pins, or preview it with `dry_run`
+#[tauri::command]
+pub async fn import_clipboard_history(
+    history: tauri::State<'_, ClipboardHistory>,
+    request: ImportClipboardRequest,
+) -> crate::shared::error::AppResult<ClipboardImportSummary> {
+    let history = history.clone_arc();
+    tauri::async_runtime::spawn_blocking(move || {
+        backup::import(&history, std::path::Path::new(&request.path), &request.passphrase, request.dry_run)
+    })
+    .await
+    .map_err(|e| crate::shared::error::AppError::System(format!("Import task failed: {}", e)))?
+}
+
 /// Clear all clipboard history
 #[tauri::command]
 pub fn clear_clipboard_history(history: tauri::State<ClipboardHistory>) -> crate::shared::error::AppResult<()> {
@@ -142,3 +348,18 @@ pub fn toggle_clipboard_monitor(monitor: tauri::State<ClipboardMonitor>) -> crat
 pub fn get_clipboard_monitor_status(monitor: tauri::State<ClipboardMonitor>) -> crate::shared::error::AppResult<bool> {
     Ok(monitor.is_enabled())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_bound() {
+        assert_eq!(parse_time_bound("2024-03-01T00:00:00Z", false).unwrap(), 1_709_251_200_000);
+        let start = parse_time_bound("2024-03-01", false).unwrap();
+        let end = parse_time_bound("2024-03-01", true).unwrap();
+        assert!(end > start);
+        assert!(end - start < 25 * 60 * 60 * 1000);
+        assert!(parse_time_bound("March", false).is_err());
+    }
+}
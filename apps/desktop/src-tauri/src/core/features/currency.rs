@@ -8,6 +8,7 @@ pub mod types;
 use self::service::CurrencyService;
 use self::types as currency_types;
 use crate::shared::error::AppError;
+use crate::shared::number_locale;
 use crate::shared::types::*;
 use super::{FeatureAsync, FeatureSync};
 use async_trait::async_trait;
@@ -119,14 +120,11 @@ impl FeatureAsync for CurrencyFeature {
             println!("[CurrencyFeature] DEBUG: parse_natural_input succeeded -> amount={}, from={}", amt, code);
             (amt, code)
         } else {
-            let amount_str = text
-                .chars()
-                .filter(|c| c.is_numeric() || *c == '.')
-                .collect::<String>();
-            println!("[CurrencyFeature] DEBUG: Fallback numeric parse string='{}'", amount_str);
-            let amt = Decimal::from_str(&amount_str)
-                .or_else(|_| Decimal::from_str("100"))
-                .unwrap_or_else(|_| Decimal::from(100u32));
+            let parsed = number_locale::first_number(text);
+            println!("[CurrencyFeature] DEBUG: Fallback numeric parse = {:?}", parsed.as_ref().map(|n| &n.canonical));
+            let amt = parsed
+                .map(|n| n.value)
+                .unwrap_or_else(|| Decimal::from(100u32));
             let from = params
                 .get("from")
                 .and_then(|v| v.as_str())
@@ -158,7 +156,7 @@ impl FeatureAsync for CurrencyFeature {
         println!("[CurrencyFeature] DEBUG: convert response: result={}, rate={}, ts={}", response.result, response.rate, response.timestamp);
         
         Ok(ExecuteActionResponse {
-            result: format!("{} {}", response.result, target_currency),
+            result: format!("{} {}", number_locale::format_canonical(&response.result.to_string()), target_currency),
             metadata: Some(serde_json::json!({
                 "rate": response.rate,
                 "timestamp": response.timestamp,
@@ -10,7 +10,7 @@ use regex::Regex;
 use redb::{Database, ReadableTable, TableDefinition};
 use reqwest::Client;
 use rust_decimal::Decimal;
-use std::str::FromStr;
+use crate::shared::number_locale;
 
 use crate::shared::error::AppError;
 
@@ -306,14 +306,14 @@ impl CurrencyService {
     /// Fuzzy parse inputs like "1euro" or "$10" into amount and currency code.
     pub fn parse_natural_input(input: &str) -> Option<(Decimal, String)> {
         static RE: OnceLock<Regex> = OnceLock::new();
-        let re = RE.get_or_init(|| Regex::new(r"(?i)^\s*(\D*)(\d+(?:\.\d+)?)(\D*)\s*$").expect("Invalid natural input regex"));
+        let re = RE.get_or_init(|| Regex::new(r"(?i)^\s*(\D*)(\d(?:[\d\.,'’\u{00A0}\u{202F}]*\d)?)(\D*)\s*$").expect("Invalid natural input regex"));
 
         let caps = re.captures(input)?;
         let prefix = caps.get(1).map(|m| m.as_str()).unwrap_or("").trim();
         let number = caps.get(2)?.as_str();
         let suffix = caps.get(3).map(|m| m.as_str()).unwrap_or("").trim();
 
-        let amount = Decimal::from_str(number).ok()?;
+        let amount = number_locale::parse_number(number)?.value;
 
         let detect = |raw: &str| -> Option<String> {
             let token = raw.trim().to_ascii_lowercase();
@@ -333,17 +333,17 @@ impl CurrencyService {
         Some((amount, currency))
     }
 
-    /// Fuzzy parse strings with prefix/suffix markers and commas: "$10", "1euro", "€5".
+    /// Fuzzy parse strings with prefix/suffix markers and separators: "$10", "1euro", "€5", "1.234,50€".
     pub fn parse_fuzzy_amount(input: &str) -> Option<(Decimal, String)> {
         static RE: OnceLock<Regex> = OnceLock::new();
-        let re = RE.get_or_init(|| Regex::new(r"(?i)^([^\d\.,]*)([\d\.,]+)([^\d\.,]*)$").expect("Invalid fuzzy amount regex"));
+        let re = RE.get_or_init(|| Regex::new(r"(?i)^([^\d\.,'’]*)([\d\.,'’\u{00A0}\u{202F}]+)([^\d\.,'’]*)$").expect("Invalid fuzzy amount regex"));
 
         let caps = re.captures(input.trim())?;
         let prefix = caps.get(1).map(|m| m.as_str()).unwrap_or("").trim();
-        let number_raw = caps.get(2)?.as_str().replace(',', "");
+        let number_raw = caps.get(2)?.as_str();
         let suffix = caps.get(3).map(|m| m.as_str()).unwrap_or("").trim();
 
-        let amount = Decimal::from_str(&number_raw).ok()?;
+        let amount = number_locale::parse_number(number_raw)?.value;
 
         let map_token = |raw: &str| -> Option<String> {
             let token = raw.trim().to_ascii_lowercase();
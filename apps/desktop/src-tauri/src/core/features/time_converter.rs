@@ -6,15 +6,33 @@ use std::sync::OnceLock;
 use std::collections::HashMap;
 use regex::Regex;
 use async_trait::async_trait;
-use chrono::{Local, TimeZone, Offset};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Offset, Utc};
 use chrono_tz::Tz;
 use chrono_english::{parse_date_string, Dialect};
 use crate::shared::types::{
     ConvertTimeRequest, ConvertTimeResponse, ParsedTimeInput, 
-    CommandItem, ActionType, TimePayload, ExecuteActionResponse, TimezoneInfo
+    CommandItem, ActionType, TimePayload, ExecuteActionResponse, TimezoneInfo, TimezoneCandidate,
+    PlanMeetingRequest, PlanMeetingResponse, TimeMention, ConvertTimesInTextRequest,
+    ConvertTimesInTextResponse, TimeMentionConversion, TimeInterpretation, TimestampPayload, ConvertTimestampRequest,
+    ConvertTimestampResponse, TimestampRendering, TimestampZoneTime, DateCalcRequest, DateCalcResponse,
+    HolidayCalendarInfo, WorldClockSnapshot, ConvertIcsRequest, ConvertIcsResponse, IcsEventInfo, IcsOccurrence,
+    IcsZoneTime, CronPayload, CronOutput, ExplainCronRequest, ExplainCronResponse, CronSyntaxError, CronFieldInfo,
+    CronRun
 };
 use super::{FeatureSync, FeatureAsync};
 
+pub mod cron;
+pub mod date_calc;
+pub mod dst;
+pub mod holidays;
+pub mod ics;
+pub mod planner;
+pub mod ranges;
+pub mod solar;
+pub mod timestamps;
+pub mod world_clock;
+pub mod zones;
+
 // ==================================================================================
 // CONSTANTS
 // ==================================================================================
@@ -389,21 +407,27 @@ static IANA_REGEX: OnceLock<Regex> = OnceLock::new();
 static FORMATTED_DATE_REGEX: OnceLock<Regex> = OnceLock::new();
 static TIME_FORMAT_REGEX: OnceLock<Regex> = OnceLock::new();
 static WHITESPACE_REGEX: OnceLock<Regex> = OnceLock::new();
+static WORD_REGEX: OnceLock<Regex> = OnceLock::new();
 
 fn get_iana_regex() -> &'static Regex {
-    IANA_REGEX.get_or_init(|| Regex::new(r"(?i)\b([A-Za-z]+/[A-Za-z_]+)\b").unwrap())
+    // Multi-segment names ("America/Argentina/Buenos_Aires") and Etc offsets ("Etc/GMT+5")
+    IANA_REGEX.get_or_init(|| Regex::new(r"(?i)\b([A-Za-z]+(?:/[A-Za-z0-9_+\-]+)+)").expect("Invalid IANA ID regex"))
+}
+
+fn get_word_regex() -> &'static Regex {
+    WORD_REGEX.get_or_init(|| Regex::new(r"\b[A-Za-z]{3,5}\b").expect("Invalid word regex"))
 }
 
 fn get_formatted_date_regex() -> &'static Regex {
-    FORMATTED_DATE_REGEX.get_or_init(|| Regex::new(r"(?i)\d{1,2}\s+(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)").unwrap())
+    FORMATTED_DATE_REGEX.get_or_init(|| Regex::new(r"(?i)\d{1,2}\s+(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)").expect("Invalid formatted date regex"))
 }
 
 fn get_time_format_regex() -> &'static Regex {
-    TIME_FORMAT_REGEX.get_or_init(|| Regex::new(r"(?i)\d{1,2}:\d{2}\s*(am|pm)").unwrap())
+    TIME_FORMAT_REGEX.get_or_init(|| Regex::new(r"(?i)\d{1,2}:\d{2}\s*(am|pm)").expect("Invalid time format regex"))
 }
 
 fn get_whitespace_regex() -> &'static Regex {
-    WHITESPACE_REGEX.get_or_init(|| Regex::new(r"\s+").unwrap())
+    WHITESPACE_REGEX.get_or_init(|| Regex::new(r"\s+").expect("Invalid whitespace regex"))
 }
 
 /// Detect if text is a conversion result to avoid re-parsing
@@ -470,51 +494,22 @@ fn detect_timezone_from_text(text: &str) -> Option<(String, Option<String>)> {
         return None;
     }
     
-    // Strategy 1: Check for IANA timezone IDs
-    if let Some(caps) = get_iana_regex().captures(text) {
-        if let Some(iana_match) = caps.get(1) {
-            let iana_id = iana_match.as_str();
-            for (_, id, _) in ALL_TIMEZONES {
-                if id.eq_ignore_ascii_case(iana_id) {
-                    return Some((id.to_string(), None));
-                }
-            }
+    // Strategy 1: Check for IANA timezone IDs (full database, aliases resolved)
+    for caps in get_iana_regex().captures_iter(text) {
+        if let Some(tz) = caps.get(1).and_then(|m| zones::lookup_zone(m.as_str())) {
+            return Some((tz.name().to_string(), None));
         }
     }
     
-    // Strategy 2: Check for timezone abbreviations using static map
-    let preferred_timezones = [
-        "America/New_York", // EST/EDT
-        "America/Los_Angeles", // PST/PDT
-        "America/Chicago", // CST/CDT
-        "America/London", // GMT/BST
-        "Europe/London",
-        "Europe/Paris", // CET
-        "Asia/Tokyo", // JST
-    ];
-
-    let mut candidates = Vec::new();
-
-    for (label, abbr) in TIMEZONE_ABBREVIATIONS_MAP {
-       // Check for whole word match without Regex overhead
-       if has_whole_word(&text_lower, &abbr.to_lowercase()) {
-             // Abbreviation matched! Find IANA ID.
-             if let Some((_, iana_id, _)) = ALL_TIMEZONES.iter().find(|(l, _, _)| l == label) {
-                 candidates.push((iana_id.to_string(), Some(abbr.to_string())));
-             }
-       }
-    }
-    
-    // Sort candidates to prioritize preferred timezones
-    if !candidates.is_empty() {
-        candidates.sort_by(|(id_a, _), (id_b, _)| {
-            let rank_a = preferred_timezones.iter().position(|&p| p == id_a).unwrap_or(999);
-            let rank_b = preferred_timezones.iter().position(|&p| p == id_b).unwrap_or(999);
-            rank_a.cmp(&rank_b)
-        });
-        // Safe: Use get() instead of direct indexing to prevent panic if candidates is empty
-        // Return tuple: (iana_id, matched_keyword)
-        return candidates.get(0).map(|(id, keyword)| (id.clone(), keyword.clone()));
+    // Strategy 2: Abbreviations, ranked when ambiguous (CST: US Central, China, Cuba)
+    for token in get_word_regex().find_iter(text).map(|m| m.as_str()) {
+        if !zones::is_abbreviation_token(token) {
+            continue;
+        }
+        let ranked = zones::resolve_abbreviation(token, &zones::ResolveHints::from_system());
+        if let Some(best) = ranked.first() {
+            return Some((best.iana_id.to_string(), Some(best.matched.clone())));
+        }
     }
     
     // Strategy 3: Check for city/country names
@@ -551,6 +546,16 @@ fn detect_timezone_from_text(text: &str) -> Option<(String, Option<String>)> {
         }
     }
     
+    // Strategy 4: Other major cities ("Winnipeg", "Kathmandu"),
+    // written capitalised so ordinary words don't pick a zone
+    for (city, iana_id) in zones::city_zones() {
+        if has_whole_word(text, city) {
+            if let Some(zone) = zones::lookup_zone(iana_id) {
+                return Some((zone.name().to_string(), Some(city.clone())));
+            }
+        }
+    }
+    
     None
 }
 
@@ -558,17 +563,24 @@ fn extract_time_portion(text: &str, detected_timezone: &Option<String>) -> Strin
     let mut cleaned_text = text.to_string();
     
     if let Some(tz) = detected_timezone {
-        // Remove the IANA ID
-        let tz_regex = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(tz))).ok();
-        if let Some(re) = tz_regex {
-             cleaned_text = re.replace_all(&cleaned_text, "").to_string();
-        }
+        // Remove IANA IDs (including aliases of the detected zone)
+        cleaned_text = get_iana_regex()
+            .replace_all(&cleaned_text, |caps: &regex::Captures| {
+                if zones::lookup_zone(&caps[1]).is_some() { String::new() } else { caps[0].to_string() }
+            })
+            .to_string();
 
         // Remove timezone abbreviations
-        for (abbr, _) in TIMEZONE_ABBREVIATIONS_MAP {
-             if let Ok(re) = Regex::new(&format!(r"(?i)\b{}\b", abbr)) {
-                 cleaned_text = re.replace_all(&cleaned_text, "").to_string();
-             }
+        cleaned_text = get_word_regex()
+            .replace_all(&cleaned_text, |caps: &regex::Captures| {
+                if zones::is_abbreviation_token(&caps[0]) { String::new() } else { caps[0].to_string() }
+            })
+            .to_string();
+
+        // Remove a full-database city name that triggered detection
+        let city = zones::city_name(tz);
+        if let Ok(re) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(&city))) {
+            cleaned_text = re.replace_all(&cleaned_text, "").to_string();
         }
         
         // Remove the detected timezone's label and keywords
@@ -612,21 +624,158 @@ pub fn parse_time_from_text(text: &str) -> Option<ParsedTimeInput> {
         return None;
     }
     
+    // Calendar snippets: start from the first event, in the zone it was written in
+    if ics::looks_like_ics(text) {
+        let calendar = ics::parse_calendar(text, system_zone());
+        if let Some(ics::EventTime::Local(local, tz)) = calendar.events.first().map(|e| e.start) {
+            return Some(ParsedTimeInput {
+                time_input: local.format("%Y-%m-%d %-I:%M%P").to_string(),
+                source_timezone: Some(tz.name().to_string()),
+                matched_keyword: None,
+                mentions: None,
+            });
+        }
+    }
+    
+    // Cron schedules: start from the next run, in the system zone the job runs in
+    if cron::is_cron_text(text) {
+        let zone = system_zone();
+        let next = cron::find_cron(text)
+            .and_then(|found| cron::next_runs(&found.schedule, zone, Utc::now(), 1).into_iter().next());
+        if let Some(run) = next {
+            return Some(ParsedTimeInput {
+                time_input: run.at.format("%Y-%m-%d %-I:%M%P").to_string(),
+                source_timezone: Some(zone.name().to_string()),
+                matched_keyword: None,
+                mentions: None,
+            });
+        }
+    }
+    
+    // Epochs and ISO/RFC dates name an exact instant: hand it over as UTC
+    if timestamps::is_timestamp_text(text) {
+        if let Some(found) = timestamps::find_timestamp(text) {
+            return Some(ParsedTimeInput {
+                time_input: found.instant.format("%Y-%m-%d %-I:%M%P").to_string(),
+                source_timezone: Some("UTC".to_string()),
+                matched_keyword: None,
+                mentions: None,
+            });
+        }
+    }
+    
     let detected_result = detect_timezone_from_text(text);
     let (detected_timezone, matched_keyword) = match detected_result {
         Some((tz, kw)) => (Some(tz), kw),
         None => (None, None),
     };
     
-    let time_input = extract_time_portion(text, &detected_timezone);
+    let mut time_input = extract_time_portion(text, &detected_timezone);
+    
+    // Ranges and multiple times: report each, and start from the first one
+    let spans = ranges::find_time_spans(text);
+    let mentions = if spans.len() > 1 || spans.iter().any(|s| s.end.is_some()) {
+        let first = ranges::format_clock(spans[0].start);
+        time_input = match resolve_date_context(text) {
+            Ok(Some(date)) => format!("{} {}", date.format("%Y-%m-%d"), first),
+            _ => first,
+        };
+        Some(spans.iter().map(|span| TimeMention {
+            original: span.original.clone(),
+            start: ranges::format_clock(span.start),
+            end: span.end.map(ranges::format_clock),
+        }).collect())
+    } else {
+        None
+    };
     
     Some(ParsedTimeInput {
         time_input,
         source_timezone: detected_timezone,
         matched_keyword,
+        mentions,
+    })
+}
+
+/// Date words in the text ("tomorrow", "Mar 15"), resolved against today
+fn resolve_date_context(text: &str) -> crate::shared::error::AppResult<Option<NaiveDate>> {
+    let Some(context) = ranges::find_date_context(text) else {
+        return Ok(None);
+    };
+    // chrono-english has no "this friday" or "tonight"
+    let lower = context.to_lowercase();
+    let normalized = lower.strip_prefix("this ").unwrap_or(&lower).replace("tonight", "today");
+    
+    parse_date_string(&normalized, Local::now(), Dialect::Us)
+        .map(|dt| Some(dt.date_naive()))
+        .map_err(|e| crate::shared::error::AppError::Validation(format!("Failed to parse date '{}': {}", context, e)))
+}
+
+/// Convert every time and range in a selection, keeping each next to the original
+pub fn convert_times_in_text(request: ConvertTimesInTextRequest) -> crate::shared::error::AppResult<ConvertTimesInTextResponse> {
+    if is_conversion_result(&request.text) {
+        return Err(crate::shared::error::AppError::Validation("Text looks like a conversion result.".to_string()));
+    }
+    
+    let (detected_timezone, matched_keyword) = match detect_timezone_from_text(&request.text) {
+        Some((tz, kw)) => (Some(tz), kw),
+        None => (None, None),
+    };
+    let source_timezone = request.source_timezone.clone().or(detected_timezone);
+    
+    let spans = ranges::find_time_spans(&request.text);
+    if spans.is_empty() {
+        return Err(crate::shared::error::AppError::Validation(format!("No times found in '{}'", request.text)));
+    }
+    let date = resolve_date_context(&request.text)?.unwrap_or_else(|| Local::now().date_naive());
+    
+    let mut conversions = Vec::with_capacity(spans.len());
+    for span in &spans {
+        let start_naive = date.and_time(span.start);
+        let start = convert_naive(start_naive, source_timezone.as_deref(), &request.target_timezone, matched_keyword.as_deref())?;
+        
+        let end = match span.end {
+            Some(end_time) => {
+                // "10pm–1am" ends the next day
+                let mut end_naive = date.and_time(end_time);
+                if end_time <= span.start {
+                    end_naive += Duration::days(1);
+                }
+                Some(convert_naive(end_naive, source_timezone.as_deref(), &request.target_timezone, matched_keyword.as_deref())?)
+            }
+            None => None,
+        };
+        
+        conversions.push(TimeMentionConversion {
+            original: span.original.clone(),
+            converted: format_span_result(&start, end.as_ref()),
+            start,
+            end,
+        });
+    }
+    
+    println!("[convert_times_in_text] {} times/ranges from {:?}", conversions.len(), source_timezone);
+    
+    Ok(ConvertTimesInTextResponse {
+        conversions,
+        source_timezone,
+        matched_keyword,
     })
 }
 
+/// "03:30pm – 05:00pm, 15 Mar CET", repeating the date only when it differs
+fn format_span_result(start: &ConvertTimeResponse, end: Option<&ConvertTimeResponse>) -> String {
+    let Some(end) = end else {
+        return format!("{} {}", start.target_time, start.target_zone_abbr);
+    };
+    match (start.target_time.split_once(", "), end.target_time.split_once(", ")) {
+        (Some((start_clock, start_date)), Some((end_clock, end_date))) if start_date == end_date => {
+            format!("{} – {}, {} {}", start_clock, end_clock, start_date, end.target_zone_abbr)
+        }
+        _ => format!("{} – {} {}", start.target_time, end.target_time, end.target_zone_abbr),
+    }
+}
+
 // ==================================================================================
 // HELPERS
 // ==================================================================================
@@ -663,14 +812,30 @@ fn format_timezone_label(iana_id: &str, display_label: &str) -> String {
 }
 
 fn format_timezone_label_with_abbr(iana_id: &str, abbr: &str) -> String {
-    // Find the display label from constants
-    let display_label = ALL_TIMEZONES
+    format!("{} ({})", zones::display_label(iana_id), abbr)
+}
+
+/// Describe a ranked zone for the frontend
+fn to_candidate(ranked: &zones::RankedZone) -> Option<TimezoneCandidate> {
+    let tz: Tz = ranked.iana_id.parse().ok()?;
+    let (abbreviation, utc_offset) = zones::current_offset(tz);
+    Some(TimezoneCandidate {
+        iana_id: ranked.iana_id.to_string(),
+        label: format_timezone_label_with_abbr(ranked.iana_id, &abbreviation),
+        abbreviation,
+        utc_offset,
+        matched: ranked.matched.clone(),
+        historical: ranked.historical,
+    })
+}
+
+/// Search every timezone by IANA name, alias, city or abbreviation
+pub fn search_timezones_by_query(query: &str, limit: usize) -> Vec<TimezoneCandidate> {
+    zones::search_zones(query, &zones::ResolveHints::from_system())
         .iter()
-        .find(|(_, id, _)| *id == iana_id)
-        .map(|(label, _, _)| *label)
-        .unwrap_or("Unknown");
-    
-    format!("{} ({})", display_label, abbr)
+        .take(limit)
+        .filter_map(to_candidate)
+        .collect()
 }
 
 pub fn get_all_timezones() -> Vec<TimezoneInfo> {
@@ -687,6 +852,326 @@ pub fn get_all_timezones() -> Vec<TimezoneInfo> {
         .collect()
 }
 
+/// The instant as wall-clock time in one zone
+fn timestamp_zone_time(instant: DateTime<Utc>, iana_id: &str) -> crate::shared::error::AppResult<TimestampZoneTime> {
+    let tz: Tz = zones::lookup_zone(iana_id)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid timezone '{}'", iana_id)))?;
+    let zoned = instant.with_timezone(&tz);
+    let zone_abbr = zoned.format("%Z").to_string();
+    
+    Ok(TimestampZoneTime {
+        iana_id: tz.name().to_string(),
+        label: format_timezone_label_with_abbr(tz.name(), &zone_abbr),
+        time: zoned.format("%I:%M:%S%P, %a %d %b %Y").to_string(),
+        utc_offset: format_utc_offset(zoned.offset().fix().local_minus_utc()),
+        zone_abbr,
+    })
+}
+
+/// System timezone, falling back to UTC
+fn system_zone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|tz| zones::lookup_zone(&tz))
+        .unwrap_or(Tz::UTC)
+}
+
+/// "Mon 3 Mar, 9:00am – 9:15am", naming the end date only when it differs
+fn format_event_span(start: DateTime<Tz>, end: DateTime<Tz>) -> String {
+    let start_text = start.format("%a %-d %b, %-I:%M%P").to_string();
+    if end <= start {
+        start_text
+    } else if end.date_naive() == start.date_naive() {
+        format!("{} – {}", start_text, end.format("%-I:%M%P"))
+    } else {
+        format!("{} – {}", start_text, end.format("%a %-d %b, %-I:%M%P"))
+    }
+}
+
+/// One occurrence in the event's zone and in each target zone
+fn ics_occurrence(occurrence: &ics::Occurrence, targets: &[Tz]) -> IcsOccurrence {
+    match occurrence {
+        ics::Occurrence::AllDay { start, end } => {
+            let last_day = end.pred_opt().unwrap_or(*end);
+            IcsOccurrence {
+                start: start.format("%Y-%m-%d").to_string(),
+                end: end.format("%Y-%m-%d").to_string(),
+                original: if last_day > *start {
+                    format!("{} – {} (all day)", start.format("%a %-d %b"), last_day.format("%a %-d %b"))
+                } else {
+                    format!("{} (all day)", start.format("%a %-d %b"))
+                },
+                zones: Vec::new(),
+            }
+        }
+        ics::Occurrence::Timed { start, end } => IcsOccurrence {
+            start: start.to_rfc3339(),
+            end: end.to_rfc3339(),
+            original: format!("{} {}", format_event_span(*start, *end), start.format("%Z")),
+            zones: targets
+                .iter()
+                .map(|tz| {
+                    let (zoned_start, zoned_end) = (start.with_timezone(tz), end.with_timezone(tz));
+                    let zone_abbr = zoned_start.format("%Z").to_string();
+                    let date_change_indicator = match zoned_start.date_naive().cmp(&start.date_naive()) {
+                        std::cmp::Ordering::Greater => Some("Next day".to_string()),
+                        std::cmp::Ordering::Less => Some("Previous day".to_string()),
+                        std::cmp::Ordering::Equal => None,
+                    };
+                    IcsZoneTime {
+                        iana_id: tz.name().to_string(),
+                        label: format_timezone_label_with_abbr(tz.name(), &zone_abbr),
+                        zone_abbr,
+                        time: format_event_span(zoned_start, zoned_end),
+                        date_change_indicator,
+                    }
+                })
+                .collect(),
+        },
+    }
+}
+
+/// Read events from iCalendar text, list upcoming occurrences in the target
+/// zones, and optionally write the calendar back out in another zone
+pub fn convert_ics_text(request: ConvertIcsRequest, now: DateTime<Utc>) -> crate::shared::error::AppResult<ConvertIcsResponse> {
+    let local_zone = system_zone();
+    let calendar = ics::parse_calendar(&request.text, local_zone);
+    if calendar.events.is_empty() {
+        let reason = calendar.warnings.first().cloned().unwrap_or_else(|| "No calendar events found".to_string());
+        return Err(crate::shared::error::AppError::Validation(reason));
+    }
+    
+    let lookup = |name: &str| zones::lookup_zone(name)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid timezone '{}'", name)));
+    let targets = match request.target_timezones.as_deref() {
+        Some(names) if !names.is_empty() => names.iter().map(|name| lookup(name)).collect::<crate::shared::error::AppResult<Vec<Tz>>>()?,
+        _ => vec![local_zone],
+    };
+    let limit = request.occurrences
+        .map(|n| n.clamp(1, ics::MAX_OCCURRENCES as i32) as usize)
+        .unwrap_or(ics::DEFAULT_OCCURRENCES);
+    
+    let events = calendar.events
+        .iter()
+        .map(|event| IcsEventInfo {
+            uid: event.uid.clone(),
+            summary: event.summary.clone().unwrap_or_else(|| "Untitled event".to_string()),
+            location: event.location.clone(),
+            source_timezone: match event.start {
+                ics::EventTime::Local(_, tz) => tz.name().to_string(),
+                ics::EventTime::Date(_) => local_zone.name().to_string(),
+            },
+            all_day: matches!(event.start, ics::EventTime::Date(_)),
+            recurrence: event.rule.as_ref().map(ics::describe_rule),
+            occurrences: ics::upcoming_occurrences(event, now, limit)
+                .iter()
+                .map(|occurrence| ics_occurrence(occurrence, &targets))
+                .collect(),
+        })
+        .collect();
+    
+    let mut warnings = calendar.warnings.clone();
+    let ics = match request.retarget_timezone.as_deref() {
+        Some(name) => {
+            let (text, notes) = ics::retarget(&calendar, lookup(name)?, local_zone, now, limit);
+            warnings.extend(notes);
+            Some(text)
+        }
+        None => None,
+    };
+    
+    println!("[convert_ics_text] {} event(s), {} warning(s)", calendar.events.len(), warnings.len());
+    
+    Ok(ConvertIcsResponse { events, ics, warnings })
+}
+
+/// "in 45s", "in 3h 20m", "in 2d 4h"
+fn format_until(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds % 86_400 / 3_600, seconds % 3_600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("in {}s", seconds),
+        (0, 0, m) => format!("in {}m", m),
+        (0, h, 0) => format!("in {}h", h),
+        (0, h, m) => format!("in {}h {}m", h, m),
+        (d, 0, _) => format!("in {}d", d),
+        (d, h, _) => format!("in {}d {}h", d, h),
+    }
+}
+
+/// Validate a cron expression (or crontab line), describe it, and list its
+/// next runs in the schedule's zone and each target zone. Syntax errors come
+/// back in `error` with their position rather than as a failure.
+pub fn explain_cron_text(request: ExplainCronRequest, now: DateTime<Utc>) -> crate::shared::error::AppResult<ExplainCronResponse> {
+    let lookup = |name: &str| zones::lookup_zone(name)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid timezone '{}'", name)));
+    let zone = match request.timezone.as_deref() {
+        Some(name) => lookup(name)?,
+        None => system_zone(),
+    };
+    let targets = request.target_timezones
+        .unwrap_or_default()
+        .iter()
+        .map(|name| lookup(name))
+        .collect::<crate::shared::error::AppResult<Vec<Tz>>>()?;
+    
+    let (expression, command, parsed) = match cron::find_cron(&request.expression) {
+        Some(found) => (found.expression, found.command, Ok(found.schedule)),
+        None => (request.expression.clone(), None, cron::parse(&request.expression)),
+    };
+    let schedule = match parsed {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            println!("[explain_cron_text] Invalid '{}': {}", expression, e);
+            return Ok(ExplainCronResponse {
+                expression,
+                command,
+                error: Some(CronSyntaxError {
+                    message: e.message,
+                    field: e.field.map(|kind| kind.name().to_string()),
+                    start: e.start as i32,
+                    end: e.end as i32,
+                }),
+                dialect: None,
+                description: None,
+                fields: Vec::new(),
+                timezone: zone.name().to_string(),
+                runs: Vec::new(),
+            });
+        }
+    };
+    
+    let fields = schedule.written_fields()
+        .into_iter()
+        .map(|field| CronFieldInfo {
+            name: field.kind.name().to_string(),
+            value: field.text.clone(),
+            description: cron::field_summary(field),
+            start: field.start as i32,
+            end: field.end as i32,
+        })
+        .collect();
+    
+    let limit = request.count
+        .map(|n| n.clamp(1, cron::MAX_RUNS as i32) as usize)
+        .unwrap_or(cron::DEFAULT_RUNS);
+    let runs = cron::next_runs(&schedule, zone, now, limit)
+        .into_iter()
+        .map(|run| {
+            let instant = run.at.with_timezone(&Utc);
+            let zones = std::iter::once(zone)
+                .chain(targets.iter().copied())
+                .map(|tz| timestamp_zone_time(instant, tz.name()))
+                .collect::<crate::shared::error::AppResult<Vec<_>>>()?;
+            Ok(CronRun {
+                iso: run.at.to_rfc3339(),
+                relative: format_until(instant - now),
+                zones,
+                note: run.note,
+            })
+        })
+        .collect::<crate::shared::error::AppResult<Vec<_>>>()?;
+    
+    println!("[explain_cron_text] '{}' ({:?}) → {} run(s) in {}", expression, schedule.dialect, runs.len(), zone.name());
+    
+    Ok(ExplainCronResponse {
+        expression,
+        command,
+        error: None,
+        dialect: Some(schedule.dialect),
+        description: Some(cron::describe(&schedule)),
+        fields,
+        timezone: zone.name().to_string(),
+        runs,
+    })
+}
+
+/// Detect a timestamp in the text and write it in every format and zone
+pub fn convert_timestamp_text(request: ConvertTimestampRequest) -> crate::shared::error::AppResult<ConvertTimestampResponse> {
+    let found = timestamps::find_timestamp(&request.text)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("No timestamp found in '{}'", request.text)))?;
+    let local_zone = system_zone();
+    
+    let renderings = timestamps::ALL_FORMATS
+        .iter()
+        .map(|format| TimestampRendering {
+            format: *format,
+            label: timestamps::format_label(*format).to_string(),
+            value: timestamps::render(found.instant, *format, local_zone),
+        })
+        .collect();
+    
+    let zones = request.target_timezones
+        .unwrap_or_default()
+        .iter()
+        .map(|iana_id| timestamp_zone_time(found.instant, iana_id))
+        .collect::<crate::shared::error::AppResult<Vec<_>>>()?;
+    
+    println!("[convert_timestamp_text] {:?} '{}' → {}", found.format, found.original, found.instant);
+    
+    Ok(ConvertTimestampResponse {
+        local: timestamp_zone_time(found.instant, local_zone.name())?,
+        original: found.original,
+        detected_format: found.format,
+        renderings,
+        zones,
+    })
+}
+
+/// Evaluate date/duration math with the business calendar from settings, unless overridden
+pub fn calculate_date_expression(request: DateCalcRequest) -> crate::shared::error::AppResult<DateCalcResponse> {
+    let settings = date_calc::business_calendar();
+    let weekend = request.weekend.unwrap_or(settings.weekend);
+    let country = request.holiday_country.or(settings.holiday_country);
+    
+    let calendar = date_calc::Calendar::new(&weekend, country.as_deref())
+        .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+    date_calc::calculate(&request.expression, Local::now().naive_local(), &calendar)
+        .map_err(|e| crate::shared::error::AppError::Validation(e))
+}
+
+/// One action per output format ("Convert timestamp to Unix seconds")
+pub fn generate_timestamp_commands() -> Vec<CommandItem> {
+    timestamps::ALL_FORMATS
+        .iter()
+        .map(|format| {
+            let format_id = serde_json::to_value(format)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            CommandItem {
+                id: format!("format_timestamp_{}", format_id),
+                label: format!("Convert timestamp to {}", timestamps::format_label(*format)),
+                description: None,
+                action_type: Some(ActionType::FormatTimestamp(TimestampPayload {
+                    format: *format,
+                    timezone: None,
+                })),
+                widget_type: None,
+                category: None,
+            }
+        })
+        .collect()
+}
+
+/// "Explain cron expression" and "Show next cron runs"
+pub fn generate_cron_commands() -> Vec<CommandItem> {
+    [
+        (CronOutput::Explain, "explain_cron", "Explain cron expression"),
+        (CronOutput::NextRuns, "cron_next_runs", "Show next cron runs"),
+    ]
+    .into_iter()
+    .map(|(output, id, label)| CommandItem {
+        id: id.to_string(),
+        label: label.to_string(),
+        description: None,
+        action_type: Some(ActionType::ExplainCron(CronPayload { output })),
+        widget_type: None,
+        category: None,
+    })
+    .collect()
+}
+
 pub fn generate_timezone_commands() -> Vec<CommandItem> {
     ALL_TIMEZONES
         .iter()
@@ -730,11 +1215,22 @@ impl FeatureSync for TimeConverterFeature {
     }
     
     fn action_commands(&self) -> Vec<CommandItem> {
-        generate_timezone_commands()
+        let mut commands = generate_timezone_commands();
+        commands.extend(generate_timestamp_commands());
+        commands.extend(generate_cron_commands());
+        commands
     }
     
-    fn get_context_boost(&self, _captured_text: &str) -> HashMap<String, f64> {
-        HashMap::new()
+    fn get_context_boost(&self, captured_text: &str) -> HashMap<String, f64> {
+        let mut boost_map = HashMap::new();
+        
+        // Boost cron actions when the text is a cron expression or crontab line
+        if cron::is_cron_text(captured_text) {
+            boost_map.insert("explain_cron".to_string(), 100.0);
+            boost_map.insert("cron_next_runs".to_string(), 90.0);
+        }
+        
+        boost_map
     }
 }
 
@@ -754,6 +1250,29 @@ impl FeatureAsync for TimeConverterFeature {
                 let parsed = parse_time_from_text(text_input)
                     .ok_or_else(|| crate::shared::error::AppError::Validation("Failed to parse time from text, likely a conversion result.".to_string()))?;
                 
+                // Ranges and multiple times: one line per time, next to the original
+                if parsed.mentions.is_some() {
+                    let response = convert_times_in_text(ConvertTimesInTextRequest {
+                        text: text_input.to_string(),
+                        target_timezone: payload.target_timezone.clone(),
+                        source_timezone: None,
+                    })?;
+                    let lines: Vec<String> = response.conversions
+                        .iter()
+                        .map(|c| format!("{} → {}", c.original, c.converted))
+                        .collect();
+                    
+                    return Ok(ExecuteActionResponse {
+                        result: lines.join("\n"),
+                        metadata: Some(serde_json::json!({
+                            "source_timezone": response.source_timezone,
+                            "target_timezone": payload.target_timezone,
+                            "matched_keyword": response.matched_keyword,
+                            "conversions": response.conversions,
+                        })),
+                    });
+                }
+                
                 let request = ConvertTimeRequest {
                     time_input: parsed.time_input,
                     target_timezone: payload.target_timezone.clone(),
@@ -773,6 +1292,66 @@ impl FeatureAsync for TimeConverterFeature {
                     })),
                 })
             }
+            ActionType::FormatTimestamp(payload) => {
+                let text_input = params.get("text")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                
+                let found = timestamps::find_timestamp(text_input)
+                    .ok_or_else(|| crate::shared::error::AppError::Validation(format!("No timestamp found in '{}'", text_input)))?;
+                let zone = match &payload.timezone {
+                    Some(iana_id) => zones::lookup_zone(iana_id)
+                        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid timezone '{}'", iana_id)))?,
+                    None => system_zone(),
+                };
+                
+                Ok(ExecuteActionResponse {
+                    result: timestamps::render(found.instant, payload.format, zone),
+                    metadata: Some(serde_json::json!({
+                        "original": found.original,
+                        "detected_format": found.format,
+                        "format": payload.format,
+                        "timezone": zone.name(),
+                    })),
+                })
+            }
+            ActionType::ExplainCron(payload) => {
+                let text_input = params.get("text")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                
+                let response = explain_cron_text(ExplainCronRequest {
+                    expression: text_input.to_string(),
+                    timezone: None,
+                    target_timezones: None,
+                    count: None,
+                }, Utc::now())?;
+                if let Some(error) = &response.error {
+                    return Err(crate::shared::error::AppError::Validation(format!(
+                        "Invalid cron expression: {} (column {})", error.message, error.start + 1
+                    )));
+                }
+                
+                let result = match payload.output {
+                    CronOutput::Explain => response.description.clone().unwrap_or_default(),
+                    CronOutput::NextRuns => response.runs
+                        .iter()
+                        .filter_map(|run| run.zones.first().map(|zone| format!("{} {} ({})", zone.time, zone.zone_abbr, run.relative)))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                };
+                
+                Ok(ExecuteActionResponse {
+                    result,
+                    metadata: Some(serde_json::json!({
+                        "expression": response.expression,
+                        "dialect": response.dialect,
+                        "description": response.description,
+                        "timezone": response.timezone,
+                        "runs": response.runs,
+                    })),
+                })
+            }
             _ => Err(crate::shared::error::AppError::Unknown("Unsupported action type".to_string())),
         }
     }
@@ -780,25 +1359,71 @@ impl FeatureAsync for TimeConverterFeature {
 
 /// Parse natural language time input and convert to target timezone
 pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::error::AppResult<ConvertTimeResponse> {
-    let source_tz_str = request.source_timezone.as_deref().unwrap_or("UTC");
     let now = Local::now();
     
     let parsed_local_dt = parse_date_string(&request.time_input, now, Dialect::Us)
         .map_err(|e| crate::shared::error::AppError::Validation(format!("Failed to parse time input '{}': {}", request.time_input, e)))?;
     
-    // Parse source timezone
-    let source_tz: Tz = source_tz_str.parse()
-        .map_err(|_| crate::shared::error::AppError::Validation(format!("Invalid source timezone: {}", source_tz_str)))?;
+    convert_naive(
+        parsed_local_dt.naive_local(),
+        request.source_timezone.as_deref(),
+        &request.target_timezone,
+        request.matched_keyword.as_deref(),
+    )
+}
+
+/// Convert a wall-clock time in the source timezone (UTC when unknown) to the target
+fn convert_naive(
+    naive: NaiveDateTime,
+    source_timezone: Option<&str>,
+    target_timezone: &str,
+    matched_keyword: Option<&str>,
+) -> crate::shared::error::AppResult<ConvertTimeResponse> {
+    let source_tz_str = source_timezone.unwrap_or("UTC");
     
-    // Interpret this naive datetime AS BEING IN the source timezone
-    let naive = parsed_local_dt.naive_local();
-    let source_dt = source_tz.from_local_datetime(&naive)
-        .single()
-        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Ambiguous or invalid time in timezone {}", source_tz_str)))?;
+    // Parse source timezone
+    let source_tz: Tz = zones::lookup_zone(source_tz_str)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid source timezone: {}", source_tz_str)))?;
     
     // Parse target timezone
-    let target_tz: Tz = request.target_timezone.parse()
-        .map_err(|e| crate::shared::error::AppError::Validation(format!("Invalid target timezone '{}': {:?}", request.target_timezone, e)))?;
+    let target_tz: Tz = zones::lookup_zone(target_timezone)
+        .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Invalid target timezone '{}'", target_timezone)))?;
+    
+    // Interpret this naive datetime AS BEING IN the source timezone, naming DST gaps and overlaps
+    let source_place = zones::city_name(source_tz.name());
+    let mut dst_notes = Vec::new();
+    let mut interpretations = None;
+    let source_dt = match dst::resolve_local(source_tz, naive) {
+        Some(dst::LocalTime::Single(dt)) => dt,
+        Some(dst::LocalTime::Gap { shifted, gap_seconds }) => {
+            dst_notes.push(dst::describe_gap(&source_place, naive, shifted, gap_seconds));
+            shifted
+        }
+        Some(dst::LocalTime::Ambiguous { earlier, later }) => {
+            dst_notes.push(dst::describe_overlap(&source_place, naive, earlier, later));
+            interpretations = Some(vec![
+                interpret(earlier, target_tz),
+                interpret(later, target_tz),
+            ]);
+            earlier
+        }
+        None => {
+            return Err(crate::shared::error::AppError::Validation(format!("Invalid time in timezone {}", source_tz_str)));
+        }
+    };
+    
+    // Clock changes within a week of this instant, in either zone
+    let instant = source_dt.with_timezone(&Utc);
+    let mut zones_to_check = vec![source_tz];
+    if target_tz != source_tz {
+        zones_to_check.push(target_tz);
+    }
+    for tz in zones_to_check {
+        for transition in dst::transitions_near(tz, instant, dst::WARNING_WINDOW_DAYS) {
+            dst_notes.push(dst::describe_transition(&zones::city_name(tz.name()), &transition, instant));
+        }
+    }
+    let dst_warning = if dst_notes.is_empty() { None } else { Some(dst_notes.join(" • ")) };
     
     // Convert
     let target_dt = source_dt.with_timezone(&target_tz);
@@ -812,8 +1437,8 @@ pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::err
     let source_zone_abbr = source_dt.format("%Z").to_string();
     let target_zone_abbr = target_dt.format("%Z").to_string();
     
-    let source_label = format_timezone_label_with_abbr(&source_tz_str, &source_zone_abbr);
-    let target_label = format_timezone_label_with_abbr(&request.target_timezone, &target_zone_abbr);
+    let source_label = format_timezone_label_with_abbr(source_tz_str, &source_zone_abbr);
+    let target_label = format_timezone_label_with_abbr(target_timezone, &target_zone_abbr);
     
     let diff_seconds = target_offset_seconds - source_offset_seconds;
     let abs_diff_seconds = diff_seconds.abs();
@@ -850,10 +1475,10 @@ pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::err
     };
     
     // SMART CITY DETECTION logic
-    if let Some(ref keyword) = request.matched_keyword {
-        if request.source_timezone.is_some() {
+    if let Some(keyword) = matched_keyword {
+        if source_timezone.is_some() {
             for (display_label, iana_id, _keywords) in ALL_TIMEZONES {
-                if iana_id == &source_tz_str {
+                if *iana_id == source_tz_str {
                     let primary_city = display_label
                         .split('/')
                         .next()
@@ -865,7 +1490,7 @@ pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::err
                          let keyword_capitalized = if let Some(first_char) = keyword.chars().next() {
                             first_char.to_uppercase().collect::<String>() + &keyword[1..]
                         } else {
-                            keyword.clone()
+                            keyword.to_string()
                         };
                         offset_description = format!("{} • Uses the same timezone as {}", offset_description, keyword_capitalized);
                     }
@@ -875,6 +1500,18 @@ pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::err
         }
     }
     
+    // Other zones an ambiguous abbreviation could mean, so the user can switch
+    let alternatives = matched_keyword
+        .filter(|keyword| zones::abbreviation_entries(keyword).is_some())
+        .map(|keyword| {
+            zones::resolve_abbreviation(keyword, &zones::ResolveHints::from_system())
+                .iter()
+                .filter(|ranked| ranked.iana_id != source_tz.name())
+                .filter_map(to_candidate)
+                .collect::<Vec<_>>()
+        })
+        .filter(|alternatives| !alternatives.is_empty());
+    
     let source_formatted = source_dt.format("%I:%M%P, %d %b").to_string();
     let target_formatted = target_dt.format("%I:%M%P, %d %b").to_string();
     
@@ -890,9 +1527,27 @@ pub fn parse_and_convert_time(request: ConvertTimeRequest) -> crate::shared::err
         date_change_indicator,
         source_zone_abbr,
         source_utc_offset,
+        alternatives,
+        dst_warning,
+        interpretations,
+        source_solar: solar::solar_info(source_tz, source_date),
+        target_solar: solar::solar_info(target_tz, target_date),
     })
 }
 
+/// One reading of an ambiguous source time, converted
+fn interpret(source_dt: DateTime<Tz>, target_tz: Tz) -> TimeInterpretation {
+    let target_dt = source_dt.with_timezone(&target_tz);
+    TimeInterpretation {
+        source_time: source_dt.format("%I:%M%P, %d %b").to_string(),
+        source_zone_abbr: source_dt.format("%Z").to_string(),
+        source_utc_offset: format_utc_offset(source_dt.offset().fix().local_minus_utc()),
+        target_time: target_dt.format("%I:%M%P, %d %b").to_string(),
+        target_zone_abbr: target_dt.format("%Z").to_string(),
+        target_utc_offset: format_utc_offset(target_dt.offset().fix().local_minus_utc()),
+    }
+}
+
 // ==================================================================================
 // COMMANDS
 // ==================================================================================
@@ -919,4 +1574,54 @@ pub async fn convert_time(request: ConvertTimeRequest) -> crate::shared::error::
 #[tauri::command]
 pub async fn get_timezones() -> crate::shared::error::AppResult<Vec<TimezoneInfo>> {
     Ok(get_all_timezones())
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn search_timezones(query: String, limit: Option<usize>) -> crate::shared::error::AppResult<Vec<TimezoneCandidate>> {
+    Ok(search_timezones_by_query(&query, limit.unwrap_or(zones::MAX_CANDIDATES)))
+}
+
+#[tauri::command]
+pub async fn plan_meeting(request: PlanMeetingRequest) -> crate::shared::error::AppResult<PlanMeetingResponse> {
+    let system_timezone = iana_time_zone::get_timezone().unwrap_or_else(|_| "UTC".to_string());
+    planner::plan_meeting(&request, &system_timezone)
+        .map_err(|e| crate::shared::error::AppError::Validation(e))
+}
+
+#[tauri::command]
+pub async fn convert_times_in_selection(request: ConvertTimesInTextRequest) -> crate::shared::error::AppResult<ConvertTimesInTextResponse> {
+    convert_times_in_text(request)
+}
+
+#[tauri::command]
+pub async fn convert_timestamp(request: ConvertTimestampRequest) -> crate::shared::error::AppResult<ConvertTimestampResponse> {
+    convert_timestamp_text(request)
+}
+
+#[tauri::command]
+pub async fn calculate_date(request: DateCalcRequest) -> crate::shared::error::AppResult<DateCalcResponse> {
+    calculate_date_expression(request)
+}
+
+#[tauri::command]
+pub async fn list_holiday_calendars() -> crate::shared::error::AppResult<Vec<HolidayCalendarInfo>> {
+    Ok(holidays::available_calendars()
+        .into_iter()
+        .map(|(country, name)| HolidayCalendarInfo { country, name })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn convert_ics(request: ConvertIcsRequest) -> crate::shared::error::AppResult<ConvertIcsResponse> {
+    convert_ics_text(request, Utc::now())
+}
+
+#[tauri::command]
+pub async fn explain_cron(request: ExplainCronRequest) -> crate::shared::error::AppResult<ExplainCronResponse> {
+    explain_cron_text(request, Utc::now())
+}
+
+#[tauri::command]
+pub async fn get_world_clock() -> crate::shared::error::AppResult<WorldClockSnapshot> {
+    Ok(world_clock::snapshot(&world_clock::pinned_zones(), Utc::now(), system_zone()))
+}
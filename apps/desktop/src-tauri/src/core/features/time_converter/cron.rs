@@ -0,0 +1,1108 @@
+//! Cron expressions: validation, plain-English descriptions and next runs
+//!
+//! Five fields are classic crontab (minute hour day-of-month month
+//! day-of-week). Six fields put seconds first, as Spring and node-cron do, and
+//! seven add a trailing year, as in Quartz. Quartz's `?`, `L`, `W` and `#` are
+//! accepted in every dialect. Days of the week run 0–7 with Sunday as 0 or 7,
+//! except in seven-field expressions, where Quartz numbers them 1 (Sunday) to 7.
+//!
+//! When both day fields are restricted a day matches either, as in Vixie cron;
+//! also as there, a day field starting with `*` (`*/2`) restricts the other
+//! instead, so a day has to match both.
+//! Runs are found in wall-clock time in the schedule's zone: a time skipped by
+//! a spring-forward runs right after the jump, and a repeated hour runs once.
+
+use std::fmt;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+
+use super::dst::{self, LocalTime};
+use crate::shared::types::CronDialect;
+
+/// Runs listed when the caller doesn't ask for a count
+pub const DEFAULT_RUNS: usize = 5;
+/// Upper bound on runs listed at once
+pub const MAX_RUNS: usize = 50;
+
+/// Last year any dialect accepts; the search for runs stops there
+const MAX_YEAR: u32 = 2099;
+/// Most times written out in full ("At 9:00am and 5:00pm") before summarising
+const MAX_LISTED_TIMES: usize = 6;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const ORDINALS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// A syntax error, with the character span it covers in the expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronError {
+    pub message: String,
+    /// Field the error is in, when it's inside one
+    pub field: Option<FieldKind>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.start + 1)
+    }
+}
+
+fn error(message: String, field: Option<FieldKind>, span: (usize, usize)) -> CronError {
+    CronError { message, field, start: span.0, end: span.1 }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Second,
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+    Year,
+}
+
+impl FieldKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldKind::Second => "second",
+            FieldKind::Minute => "minute",
+            FieldKind::Hour => "hour",
+            FieldKind::DayOfMonth => "day of month",
+            FieldKind::Month => "month",
+            FieldKind::DayOfWeek => "day of week",
+            FieldKind::Year => "year",
+        }
+    }
+
+    /// Singular and plural nouns for "every 5 minutes"
+    fn units(self) -> (&'static str, &'static str) {
+        match self {
+            FieldKind::Second => ("second", "seconds"),
+            FieldKind::Minute => ("minute", "minutes"),
+            FieldKind::Hour => ("hour", "hours"),
+            FieldKind::DayOfMonth => ("day", "days"),
+            FieldKind::Month => ("month", "months"),
+            FieldKind::DayOfWeek => ("day of the week", "days of the week"),
+            FieldKind::Year => ("year", "years"),
+        }
+    }
+
+    fn bounds(self, quartz_weekdays: bool) -> (u32, u32) {
+        match self {
+            FieldKind::Second | FieldKind::Minute => (0, 59),
+            FieldKind::Hour => (0, 23),
+            FieldKind::DayOfMonth => (1, 31),
+            FieldKind::Month => (1, 12),
+            FieldKind::DayOfWeek if quartz_weekdays => (1, 7),
+            FieldKind::DayOfWeek => (0, 7),
+            FieldKind::Year => (1970, MAX_YEAR),
+        }
+    }
+
+    fn is_day(self) -> bool {
+        matches!(self, FieldKind::DayOfMonth | FieldKind::DayOfWeek)
+    }
+}
+
+/// One comma-separated part of a field
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    /// `*` or `?`
+    Any,
+    /// `5`, `1-5`, `*/15`, `10/5` and `1-30/2` (plain values have step 1); weekdays as written
+    Range { start: u32, end: u32, step: u32, from_wildcard: bool },
+    /// `L`, or `L-3` for three days before the last
+    LastDay(u32),
+    /// `15W`: the weekday nearest the 15th, within the month
+    NearestWeekday(u32),
+    /// `LW`
+    LastWeekday,
+    /// `5L`: the last Friday (weekday 0 = Sunday)
+    LastWeekdayOf(u32),
+    /// `1#2`: the second Monday (weekday 0 = Sunday)
+    NthWeekday { weekday: u32, n: u32 },
+}
+
+/// A parsed field
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub kind: FieldKind,
+    pub text: String,
+    /// Character span in the expression
+    pub start: usize,
+    pub end: usize,
+    items: Vec<Item>,
+    /// Values matched by `Range` items, indexed from the field's minimum
+    /// (days of the week are indexed 0 = Sunday)
+    allowed: Vec<bool>,
+    quartz_weekdays: bool,
+}
+
+impl Field {
+    fn is_any(&self) -> bool {
+        self.items.iter().all(|item| *item == Item::Any)
+    }
+
+    /// Written starting with `*` or `?`, which Vixie cron reads as a day field
+    /// that narrows the other instead of adding days to it
+    fn starts_with_wildcard(&self) -> bool {
+        matches!(self.items.first(), Some(Item::Any | Item::Range { from_wildcard: true, .. }))
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        if self.is_any() {
+            return true;
+        }
+        let (min, _) = self.kind.bounds(self.quartz_weekdays);
+        value >= min && self.allowed.get((value - min) as usize).copied().unwrap_or(false)
+    }
+
+    /// Weekday written in this field → 0 = Sunday
+    fn weekday_index(&self, value: u32) -> u32 {
+        if self.quartz_weekdays { (value + 6) % 7 } else { value % 7 }
+    }
+
+    /// Plain values only ("0,15,45"), sorted
+    fn single_values(&self) -> Option<Vec<u32>> {
+        let mut values = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Range { start, end, .. } if start == end => values.push(*start),
+                _ => return None,
+            }
+        }
+        values.sort_unstable();
+        values.dedup();
+        Some(values)
+    }
+
+    fn is_single(&self, value: u32) -> bool {
+        self.single_values() == Some(vec![value])
+    }
+}
+
+/// A validated expression, always with all seven fields
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    pub dialect: CronDialect,
+    /// The expression's own text for macros ("@daily")
+    pub macro_name: Option<String>,
+    pub second: Field,
+    pub minute: Field,
+    pub hour: Field,
+    pub day_of_month: Field,
+    pub month: Field,
+    pub day_of_week: Field,
+    pub year: Field,
+}
+
+/// Whitespace-separated tokens with their character spans
+fn tokenize(text: &str) -> Vec<(String, (usize, usize))> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+    for (index, c) in text.chars().enumerate() {
+        if c.is_whitespace() {
+            if !current.is_empty() {
+                tokens.push((std::mem::take(&mut current), (start, index)));
+            }
+        } else {
+            if current.is_empty() {
+                start = index;
+            }
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        let end = start + current.chars().count();
+        tokens.push((current, (start, end)));
+    }
+    tokens
+}
+
+/// Span of `part`, found at byte `offset` of a token starting at character `token_start`
+fn sub_span(token: &str, token_start: usize, offset: usize, part: &str) -> (usize, usize) {
+    let start = token_start + token[..offset].chars().count();
+    (start, start + part.chars().count().max(1))
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let upper = name.to_ascii_uppercase();
+    MONTH_NAMES
+        .iter()
+        .position(|month| month[..3].eq_ignore_ascii_case(&upper) || month.eq_ignore_ascii_case(&upper))
+        .map(|index| index as u32 + 1)
+}
+
+/// Weekday name → 0 = Sunday
+fn weekday_number(name: &str) -> Option<u32> {
+    WEEKDAY_NAMES
+        .iter()
+        .position(|day| day[..3].eq_ignore_ascii_case(name) || day.eq_ignore_ascii_case(name))
+        .map(|index| index as u32)
+}
+
+/// A number or, for months and weekdays, a name
+fn parse_value(kind: FieldKind, raw: &str, span: (usize, usize), quartz: bool) -> Result<u32, CronError> {
+    let (min, max) = kind.bounds(quartz);
+    if raw.is_empty() {
+        return Err(error(format!("Missing {} value", kind.name()), Some(kind), span));
+    }
+    if raw.bytes().all(|b| b.is_ascii_digit()) {
+        return match raw.parse::<u32>() {
+            Ok(value) if (min..=max).contains(&value) => Ok(value),
+            _ => Err(error(
+                format!("{} {} is out of range ({}–{})", capitalize(kind.name()), raw, min, max),
+                Some(kind),
+                span,
+            )),
+        };
+    }
+    let named = match kind {
+        FieldKind::Month => month_number(raw),
+        // Names become the field's own numbering so ranges like MON-FRI compare correctly
+        FieldKind::DayOfWeek => weekday_number(raw).map(|day| if quartz { day + 1 } else { day }),
+        _ => None,
+    };
+    named.ok_or_else(|| {
+        let message = match kind {
+            FieldKind::Month | FieldKind::DayOfWeek => format!("Unknown {} '{}'", kind.name(), raw),
+            _ => format!("Expected a number for {}, found '{}'", kind.name(), raw),
+        };
+        error(message, Some(kind), span)
+    })
+}
+
+fn parse_number(kind: FieldKind, raw: &str, span: (usize, usize), what: &str, range: (u32, u32)) -> Result<u32, CronError> {
+    match raw.parse::<u32>() {
+        Ok(value) if (range.0..=range.1).contains(&value) => Ok(value),
+        _ => Err(error(format!("{} must be between {} and {}", what, range.0, range.1), Some(kind), span)),
+    }
+}
+
+/// One list item; `offset` is its byte offset in `token`
+fn parse_item(kind: FieldKind, token: &str, token_start: usize, offset: usize, raw: &str, quartz: bool) -> Result<Item, CronError> {
+    let span = sub_span(token, token_start, offset, raw);
+    let (min, max) = kind.bounds(quartz);
+    let upper = raw.to_ascii_uppercase();
+
+    if raw.is_empty() {
+        return Err(error(format!("Empty value in the {} list", kind.name()), Some(kind), span));
+    }
+    if raw == "*" {
+        return Ok(Item::Any);
+    }
+    if raw == "?" {
+        return if kind.is_day() {
+            Ok(Item::Any)
+        } else {
+            Err(error("'?' is only allowed in day of month and day of week".to_string(), Some(kind), span))
+        };
+    }
+
+    // Quartz specials; month names with an L (APR, JUL) are read as names below
+    if upper.contains('L') && kind != FieldKind::Month {
+        match kind {
+            FieldKind::DayOfMonth if upper == "L" => return Ok(Item::LastDay(0)),
+            FieldKind::DayOfMonth if upper == "LW" => return Ok(Item::LastWeekday),
+            FieldKind::DayOfMonth if upper.starts_with("L-") => {
+                let days = &raw[2..];
+                let days_span = sub_span(token, token_start, offset + 2, days);
+                return Ok(Item::LastDay(parse_number(kind, days, days_span, "Days before the last", (0, 30))?));
+            }
+            // Quartz reads a lone L here as the last day of the week
+            FieldKind::DayOfWeek if upper == "L" => {
+                let saturday = if quartz { 7 } else { 6 };
+                return Ok(Item::Range { start: saturday, end: saturday, step: 1, from_wildcard: false });
+            }
+            FieldKind::DayOfWeek if upper.ends_with('L') => {
+                let day = &raw[..raw.len() - 1];
+                let day_span = sub_span(token, token_start, offset, day);
+                let value = parse_value(kind, day, day_span, quartz)?;
+                let weekday = if quartz { (value + 6) % 7 } else { value % 7 };
+                return Ok(Item::LastWeekdayOf(weekday));
+            }
+            FieldKind::DayOfMonth | FieldKind::DayOfWeek => {
+                return Err(error(format!("Invalid use of 'L' in {}", kind.name()), Some(kind), span));
+            }
+            _ => {
+                return Err(error("'L' is only allowed in day of month and day of week".to_string(), Some(kind), span));
+            }
+        }
+    }
+    if let Some(day) = upper.strip_suffix('W') {
+        if kind != FieldKind::DayOfMonth {
+            return Err(error("'W' is only allowed in day of month".to_string(), Some(kind), span));
+        }
+        let day_span = sub_span(token, token_start, offset, day);
+        return Ok(Item::NearestWeekday(parse_number(kind, day, day_span, "Day of month", (1, 31))?));
+    }
+    if let Some((day, n)) = raw.split_once('#') {
+        if kind != FieldKind::DayOfWeek {
+            return Err(error("'#' is only allowed in day of week".to_string(), Some(kind), span));
+        }
+        let day_span = sub_span(token, token_start, offset, day);
+        let value = parse_value(kind, day, day_span, quartz)?;
+        let n_span = sub_span(token, token_start, offset + day.len() + 1, n);
+        let n = parse_number(kind, n, n_span, "Occurrence after '#'", (1, 5))?;
+        let weekday = if quartz { (value + 6) % 7 } else { value % 7 };
+        return Ok(Item::NthWeekday { weekday, n });
+    }
+
+    // Ranges and steps
+    let (base, step) = match raw.split_once('/') {
+        Some((base, step)) => {
+            let step_span = sub_span(token, token_start, offset + base.len() + 1, step);
+            if step.is_empty() || !step.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(error(format!("Expected a step after '/', found '{}'", step), Some(kind), step_span));
+            }
+            let step = parse_number(kind, step, step_span, "Step", (1, max - min + 1))?;
+            (base, Some(step))
+        }
+        None => (raw, None),
+    };
+
+    if base == "*" {
+        return Ok(Item::Range { start: min, end: max, step: step.unwrap_or(1), from_wildcard: true });
+    }
+    let (start, end) = match base.split_once('-') {
+        Some((first, last)) => {
+            let first_span = sub_span(token, token_start, offset, first);
+            let last_span = sub_span(token, token_start, offset + first.len() + 1, last);
+            let start = parse_value(kind, first, first_span, quartz)?;
+            let end = parse_value(kind, last, last_span, quartz)?;
+            if start > end {
+                let base_span = sub_span(token, token_start, offset, base);
+                return Err(error(format!("Range {} starts after it ends", base), Some(kind), base_span));
+            }
+            (start, end)
+        }
+        // "10/5" steps from 10 to the end of the field
+        None => {
+            let base_span = sub_span(token, token_start, offset, base);
+            let start = parse_value(kind, base, base_span, quartz)?;
+            (start, if step.is_some() { max } else { start })
+        }
+    };
+    Ok(Item::Range { start, end, step: step.unwrap_or(1), from_wildcard: false })
+}
+
+fn parse_field(kind: FieldKind, token: &str, span: (usize, usize), quartz: bool) -> Result<Field, CronError> {
+    let (min, max) = kind.bounds(quartz);
+    let mut items = Vec::new();
+    let mut offset = 0;
+    for raw in token.split(',') {
+        items.push(parse_item(kind, token, span.0, offset, raw, quartz)?);
+        offset += raw.len() + 1;
+    }
+    if items.len() > 1 && items.contains(&Item::Any) {
+        return Err(error(format!("'*' can't be combined with other {} values", kind.name()), Some(kind), span));
+    }
+
+    let weekdays = kind == FieldKind::DayOfWeek;
+    let mut allowed = vec![false; if weekdays { 7 } else { (max - min + 1) as usize }];
+    for item in &items {
+        if let Item::Range { start, end, step, .. } = item {
+            for value in (*start..=*end).step_by(*step as usize) {
+                let index = match (weekdays, quartz) {
+                    (true, true) => (value + 6) % 7,
+                    (true, false) => value % 7,
+                    _ => value - min,
+                };
+                allowed[index as usize] = true;
+            }
+        }
+    }
+
+    Ok(Field { kind, text: token.to_string(), start: span.0, end: span.1, items, allowed, quartz_weekdays: quartz })
+}
+
+/// Five-field expansion of a `@` macro
+fn expand_macro(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => Some("0 0 1 1 *"),
+        "@monthly" => Some("0 0 1 * *"),
+        "@weekly" => Some("0 0 * * 0"),
+        "@daily" | "@midnight" => Some("0 0 * * *"),
+        "@hourly" => Some("0 * * * *"),
+        _ => None,
+    }
+}
+
+/// Parse a 5, 6 or 7-field expression or a `@daily`-style macro
+pub fn parse(expression: &str) -> Result<CronSchedule, CronError> {
+    let tokens = tokenize(expression);
+    let whole = (0, expression.chars().count().max(1));
+
+    if let [(token, span)] = tokens.as_slice() {
+        if token.starts_with('@') {
+            if token.eq_ignore_ascii_case("@reboot") {
+                return Err(error("@reboot runs once at startup and has no schedule".to_string(), None, *span));
+            }
+            let expansion = expand_macro(token)
+                .ok_or_else(|| error(format!("Unknown macro '{}'", token), None, *span))?;
+            let mut schedule = parse(expansion)?;
+            for field in schedule.fields_mut() {
+                field.start = span.0;
+                field.end = span.1;
+            }
+            schedule.macro_name = Some(token.to_ascii_lowercase());
+            return Ok(schedule);
+        }
+    }
+
+    let dialect = match tokens.len() {
+        5 => CronDialect::Standard,
+        6 => CronDialect::WithSeconds,
+        7 => CronDialect::Quartz,
+        0 => return Err(error("Empty cron expression".to_string(), None, whole)),
+        count => {
+            // Point at the first extra field, or the whole expression when some are missing
+            let span = if count > 7 { (tokens[7].1 .0, whole.1) } else { whole };
+            return Err(error(format!("Expected 5, 6 or 7 fields, found {}", count), None, span));
+        }
+    };
+    let quartz = dialect == CronDialect::Quartz;
+
+    let kinds: &[FieldKind] = match dialect {
+        CronDialect::Standard => &[FieldKind::Minute, FieldKind::Hour, FieldKind::DayOfMonth, FieldKind::Month, FieldKind::DayOfWeek],
+        CronDialect::WithSeconds => &[FieldKind::Second, FieldKind::Minute, FieldKind::Hour, FieldKind::DayOfMonth, FieldKind::Month, FieldKind::DayOfWeek],
+        CronDialect::Quartz => &[FieldKind::Second, FieldKind::Minute, FieldKind::Hour, FieldKind::DayOfMonth, FieldKind::Month, FieldKind::DayOfWeek, FieldKind::Year],
+    };
+    let mut parsed = Vec::with_capacity(7);
+    for (kind, (token, span)) in kinds.iter().zip(&tokens) {
+        parsed.push(parse_field(*kind, token, *span, quartz)?);
+    }
+
+    // Fields the dialect leaves out: on second 0, in any year
+    let implicit = |kind: FieldKind, text: &str| parse_field(kind, text, (0, 0), quartz);
+    let field = |kind: FieldKind| parsed.iter().find(|f| f.kind == kind).cloned();
+    let second = match field(FieldKind::Second) {
+        Some(second) => second,
+        None => implicit(FieldKind::Second, "0")?,
+    };
+    let year = match field(FieldKind::Year) {
+        Some(year) => year,
+        None => implicit(FieldKind::Year, "*")?,
+    };
+    let (Some(minute), Some(hour), Some(day_of_month), Some(month), Some(day_of_week)) = (
+        field(FieldKind::Minute),
+        field(FieldKind::Hour),
+        field(FieldKind::DayOfMonth),
+        field(FieldKind::Month),
+        field(FieldKind::DayOfWeek),
+    ) else {
+        return Err(error("Missing fields".to_string(), None, whole));
+    };
+
+    if day_of_month.text == "?" && day_of_week.text == "?" {
+        return Err(error(
+            "'?' can't be used for both day of month and day of week".to_string(),
+            Some(FieldKind::DayOfWeek),
+            (day_of_week.start, day_of_week.end),
+        ));
+    }
+
+    Ok(CronSchedule { dialect, macro_name: None, second, minute, hour, day_of_month, month, day_of_week, year })
+}
+
+impl CronSchedule {
+    fn fields_mut(&mut self) -> [&mut Field; 7] {
+        [
+            &mut self.second, &mut self.minute, &mut self.hour, &mut self.day_of_month,
+            &mut self.month, &mut self.day_of_week, &mut self.year,
+        ]
+    }
+
+    /// The fields as written, in order
+    pub fn written_fields(&self) -> Vec<&Field> {
+        let mut fields = Vec::with_capacity(7);
+        if self.dialect != CronDialect::Standard {
+            fields.push(&self.second);
+        }
+        fields.extend([&self.minute, &self.hour, &self.day_of_month, &self.month, &self.day_of_week]);
+        if self.dialect == CronDialect::Quartz {
+            fields.push(&self.year);
+        }
+        fields
+    }
+
+    fn day_of_month_matches(&self, date: NaiveDate) -> bool {
+        let last = last_day_of_month(date);
+        let day = date.day();
+        self.day_of_month.items.iter().any(|item| match item {
+            Item::Any => true,
+            Item::Range { .. } => self.day_of_month.matches(day),
+            Item::LastDay(before) => last.checked_sub(*before) == Some(day),
+            Item::NearestWeekday(target) => nearest_weekday(date, *target) == Some(day),
+            Item::LastWeekday => nearest_weekday(date, last) == Some(day),
+            Item::LastWeekdayOf(_) | Item::NthWeekday { .. } => false,
+        })
+    }
+
+    fn day_of_week_matches(&self, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_sunday();
+        let day = date.day();
+        self.day_of_week.items.iter().any(|item| match item {
+            Item::Any => true,
+            Item::Range { .. } => self.day_of_week.allowed[weekday as usize],
+            Item::LastWeekdayOf(target) => weekday == *target && day + 7 > last_day_of_month(date),
+            Item::NthWeekday { weekday: target, n } => weekday == *target && (day - 1) / 7 + 1 == *n,
+            Item::LastDay(_) | Item::NearestWeekday(_) | Item::LastWeekday => false,
+        })
+    }
+
+    /// Whether a day has to match both day fields rather than either
+    fn days_match_both(&self) -> bool {
+        self.day_of_month.starts_with_wildcard() || self.day_of_week.starts_with_wildcard()
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if self.days_match_both() {
+            self.day_of_month_matches(date) && self.day_of_week_matches(date)
+        } else {
+            self.day_of_month_matches(date) || self.day_of_week_matches(date)
+        }
+    }
+
+    /// First matching wall-clock time at or after `from`
+    fn next_match(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let midnight = NaiveTime::MIN;
+        let mut t = from.with_nanosecond(0)?;
+        loop {
+            if t.year() > MAX_YEAR as i32 {
+                return None;
+            }
+            if !self.year.matches(t.year() as u32) {
+                t = NaiveDate::from_ymd_opt(t.year() + 1, 1, 1)?.and_time(midnight);
+                continue;
+            }
+            if !self.month.matches(t.month()) {
+                t = first_of_next_month(t.date())?.and_time(midnight);
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_time(midnight);
+                continue;
+            }
+            if !self.hour.matches(t.hour()) {
+                t = t.with_minute(0)?.with_second(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minute.matches(t.minute()) {
+                t = t.with_second(0)? + Duration::minutes(1);
+                continue;
+            }
+            if !self.second.matches(t.second()) {
+                t += Duration::seconds(1);
+                continue;
+            }
+            return Some(t);
+        }
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    first_of_next_month(date)
+        .and_then(|next| next.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
+
+/// The weekday nearest day `target` of `date`'s month, without leaving the month
+fn nearest_weekday(date: NaiveDate, target: u32) -> Option<u32> {
+    let last = last_day_of_month(date);
+    if target > last {
+        return None;
+    }
+    let day = NaiveDate::from_ymd_opt(date.year(), date.month(), target)?;
+    Some(match day.weekday() {
+        Weekday::Sat if target == 1 => 3,
+        Weekday::Sat => target - 1,
+        Weekday::Sun if target == last => target - 2,
+        Weekday::Sun => target + 1,
+        _ => target,
+    })
+}
+
+/// A run in the schedule's zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub at: DateTime<Tz>,
+    /// Set when a clock change moved or merged the run
+    pub note: Option<String>,
+}
+
+/// The next `limit` runs after `after`, in wall-clock time in `tz`
+pub fn next_runs(schedule: &CronSchedule, tz: Tz, after: DateTime<Utc>, limit: usize) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::with_capacity(limit);
+    let mut cursor = after.with_timezone(&tz).naive_local() + Duration::seconds(1);
+
+    while runs.len() < limit {
+        let Some(naive) = schedule.next_match(cursor) else { break };
+        cursor = naive + Duration::seconds(1);
+
+        let (at, note) = match dst::resolve_local(tz, naive) {
+            Some(LocalTime::Single(at)) => (at, None),
+            Some(LocalTime::Gap { shifted, .. }) => {
+                let note = format!("{} is skipped by the clock change; runs at {}", naive.format("%-I:%M%P"), shifted.format("%-I:%M%P %Z"));
+                (shifted, Some(note))
+            }
+            Some(LocalTime::Ambiguous { earlier, .. }) => {
+                let note = format!("{} happens twice; runs once, on {}", naive.format("%-I:%M%P"), earlier.format("%Z"));
+                (earlier, Some(note))
+            }
+            None => continue,
+        };
+        // A shifted run can land on a time that also matches
+        if at.with_timezone(&Utc) <= after || runs.last().is_some_and(|last| last.at >= at) {
+            continue;
+        }
+        runs.push(Run { at, note });
+    }
+    runs
+}
+
+/// A cron expression found in pasted text
+#[derive(Debug, Clone)]
+pub struct FoundCron {
+    pub expression: String,
+    pub schedule: CronSchedule,
+    /// The command after the schedule on a crontab line
+    pub command: Option<String>,
+}
+
+/// The whole text as an expression, or the schedule at the start of a crontab line
+pub fn find_cron(text: &str) -> Option<FoundCron> {
+    let line = text.trim();
+    if line.is_empty() || line.contains('\n') {
+        return None;
+    }
+    if let Ok(schedule) = parse(line) {
+        return Some(FoundCron { expression: line.to_string(), schedule, command: None });
+    }
+
+    let tokens = tokenize(line);
+    let fields = if tokens.first()?.0.starts_with('@') { 1 } else { 5 };
+    let (_, (command_start, _)) = tokens.get(fields)?;
+    let split = line.char_indices().nth(*command_start).map(|(byte, _)| byte)?;
+    let (expression, command) = line.split_at(split);
+    let schedule = parse(expression).ok()?;
+    Some(FoundCron {
+        expression: expression.trim_end().to_string(),
+        schedule,
+        command: Some(command.to_string()),
+    })
+}
+
+/// Cron syntax rather than a row of numbers: a wildcard, step, macro or name is required
+pub fn is_cron_text(text: &str) -> bool {
+    find_cron(text).is_some_and(|found| {
+        found.schedule.macro_name.is_some()
+            || found.expression.contains(['*', '?', '/'])
+            || [&found.schedule.month, &found.schedule.day_of_week]
+                .iter()
+                .any(|field| field.text.chars().any(|c| c.is_ascii_alphabetic()))
+    })
+}
+
+// ==================================================================================
+// DESCRIPTIONS
+// ==================================================================================
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// "a", "a and b", "a, b and c"
+fn natural_list(parts: &[String]) -> String {
+    match parts {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn ordinal_suffix(n: u32) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// "9am", "12pm"
+fn hour_label(hour: u32) -> String {
+    match hour {
+        0 => "12am".to_string(),
+        1..=11 => format!("{}am", hour),
+        12 => "12pm".to_string(),
+        _ => format!("{}pm", hour - 12),
+    }
+}
+
+/// "9:30am", "9:30:15am"
+fn clock_label(hour: u32, minute: u32, second: u32) -> String {
+    let (h, suffix) = match hour {
+        0 => (12, "am"),
+        1..=11 => (hour, "am"),
+        12 => (12, "pm"),
+        _ => (hour - 12, "pm"),
+    };
+    if second == 0 {
+        format!("{}:{:02}{}", h, minute, suffix)
+    } else {
+        format!("{}:{:02}:{:02}{}", h, minute, second, suffix)
+    }
+}
+
+fn value_label(field: &Field, value: u32) -> String {
+    match field.kind {
+        FieldKind::Hour => hour_label(value),
+        FieldKind::Month => MONTH_NAMES[(value as usize - 1) % 12].to_string(),
+        FieldKind::DayOfWeek => WEEKDAY_NAMES[field.weekday_index(value) as usize].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn item_summary(field: &Field, item: &Item) -> String {
+    let (unit, units) = field.kind.units();
+    let (min, max) = field.kind.bounds(field.quartz_weekdays);
+    match item {
+        Item::Any => format!("every {}", unit),
+        Item::Range { start, end, step: 1, .. } if start == end => value_label(field, *start),
+        Item::Range { start, end, step: 1, .. } => {
+            format!("{} through {}", value_label(field, *start), value_label(field, *end))
+        }
+        Item::Range { start, end, step, from_wildcard } => {
+            let every = format!("every {} {}", step, units);
+            if *from_wildcard || (*start == min && *end == max) {
+                every
+            } else if *end == max {
+                format!("{} from {}", every, value_label(field, *start))
+            } else {
+                format!("{} from {} through {}", every, value_label(field, *start), value_label(field, *end))
+            }
+        }
+        Item::LastDay(0) => "the last day of the month".to_string(),
+        Item::LastDay(1) => "the day before the last day of the month".to_string(),
+        Item::LastDay(before) => format!("{} days before the last day of the month", before),
+        Item::NearestWeekday(day) => format!("the weekday nearest the {}{}", day, ordinal_suffix(*day)),
+        Item::LastWeekday => "the last weekday of the month".to_string(),
+        Item::LastWeekdayOf(weekday) => format!("the last {} of the month", WEEKDAY_NAMES[*weekday as usize]),
+        Item::NthWeekday { weekday, n } => {
+            format!("the {} {} of the month", ORDINALS[(*n as usize - 1) % 5], WEEKDAY_NAMES[*weekday as usize])
+        }
+    }
+}
+
+/// What one field means on its own ("every 15 minutes", "Monday through Friday")
+pub fn field_summary(field: &Field) -> String {
+    if field.is_any() {
+        let (unit, _) = field.kind.units();
+        return match field.kind {
+            FieldKind::DayOfMonth | FieldKind::DayOfWeek if field.text == "?" => "any".to_string(),
+            _ => format!("every {}", unit),
+        };
+    }
+    if let Some(values) = field.single_values() {
+        let labels: Vec<String> = values.iter().map(|v| value_label(field, *v)).collect();
+        return natural_list(&labels);
+    }
+    let parts: Vec<String> = field.items.iter().map(|item| item_summary(field, item)).collect();
+    natural_list(&parts)
+}
+
+/// Sub-minute and sub-hour parts ("every 15 minutes", "at 5 minutes past the hour")
+fn time_phrases(schedule: &CronSchedule) -> Vec<String> {
+    let (second, minute, hour) = (&schedule.second, &schedule.minute, &schedule.hour);
+
+    // Few enough exact times to list them
+    if let (Some(seconds), Some(minutes), Some(hours)) = (second.single_values(), minute.single_values(), hour.single_values()) {
+        if seconds.len() == 1 && minutes.len() * hours.len() <= MAX_LISTED_TIMES {
+            let at_second = seconds[0];
+            let times: Vec<String> = hours
+                .iter()
+                .flat_map(|h| minutes.iter().map(move |m| clock_label(*h, *m, at_second)))
+                .collect();
+            return vec![format!("at {}", natural_list(&times))];
+        }
+    }
+
+    let mut phrases = Vec::new();
+    if second.is_any() {
+        phrases.push("every second".to_string());
+    } else if !second.is_single(0) {
+        phrases.push(match second.single_values() {
+            Some(values) => {
+                let labels: Vec<String> = values.iter().map(u32::to_string).collect();
+                let noun = if values.len() == 1 && values[0] == 1 { "second" } else { "seconds" };
+                format!("at {} {} past the minute", natural_list(&labels), noun)
+            }
+            None => field_summary(second),
+        });
+    }
+
+    let minute_values = minute.single_values();
+    if minute.is_any() {
+        // "Every 10 seconds" already covers every minute
+        if second.single_values().is_some_and(|values| values.len() == 1) {
+            phrases.push("every minute".to_string());
+        }
+    } else {
+        phrases.push(match &minute_values {
+            Some(values) if values == &[0] => {
+                if hour.is_any() { "every hour".to_string() } else { "on the hour".to_string() }
+            }
+            Some(values) => {
+                let labels: Vec<String> = values.iter().map(u32::to_string).collect();
+                let noun = if values.len() == 1 && values[0] == 1 { "minute" } else { "minutes" };
+                format!("at {} {} past the hour", natural_list(&labels), noun)
+            }
+            None => match minute.items.as_slice() {
+                [Item::Range { start, end, step: 1, .. }] => format!("every minute from {} through {}", start, end),
+                _ => field_summary(minute),
+            },
+        });
+    }
+
+    if !hour.is_any() {
+        phrases.push(match (hour.single_values(), hour.items.as_slice()) {
+            (Some(hours), _) if hours.len() == 1 => {
+                format!("between {} and {}", clock_label(hours[0], 0, 0), clock_label(hours[0], 59, 0))
+            }
+            (Some(hours), _) => {
+                let labels: Vec<String> = hours.iter().map(|h| hour_label(*h)).collect();
+                format!("during the {} hours", natural_list(&labels))
+            }
+            (None, [Item::Range { start, end, step: 1, .. }]) => {
+                format!("between {} and {}", clock_label(*start, 0, 0), clock_label(*end, 59, 0))
+            }
+            _ => field_summary(hour),
+        });
+    }
+    phrases
+}
+
+fn day_phrase(schedule: &CronSchedule) -> Option<String> {
+    let (dom, dow) = (&schedule.day_of_month, &schedule.day_of_week);
+    let dom_phrase = (!dom.is_any()).then(|| match (dom.single_values(), dom.items.as_slice()) {
+        (Some(days), _) => {
+            let labels: Vec<String> = days.iter().map(|d| format!("{}{}", d, ordinal_suffix(*d))).collect();
+            format!("on the {} of the month", natural_list(&labels))
+        }
+        (None, [Item::Range { start, end, step: 1, .. }]) => {
+            format!("between day {} and {} of the month", start, end)
+        }
+        (None, [Item::Range { step, from_wildcard: true, .. }]) => format!("every {} days", step),
+        _ => format!("on {}", field_summary(dom)),
+    });
+    // "Monday through Friday" reads on its own; everything else needs "on"
+    let dow_phrase = (!dow.is_any()).then(|| match (dow.single_values(), dow.items.as_slice()) {
+        (None, [Item::Range { step: 1, .. }]) => field_summary(dow),
+        _ => format!("on {}", field_summary(dow)),
+    });
+
+    match (dom_phrase, dow_phrase) {
+        (None, None) => None,
+        (Some(dom), None) => Some(dom),
+        (None, Some(dow)) => Some(match dow.strip_prefix("on ") {
+            Some(days) if schedule.day_of_week.single_values().is_some() => format!("only on {}", days),
+            _ => dow,
+        }),
+        (Some(dom), Some(dow)) if schedule.days_match_both() => Some(format!("{}, but only {}", dom, dow)),
+        (Some(dom), Some(dow)) => Some(format!("{} or {}", dom, dow)),
+    }
+}
+
+fn month_phrase(month: &Field) -> Option<String> {
+    if month.is_any() {
+        return None;
+    }
+    Some(match (month.single_values(), month.items.as_slice()) {
+        (Some(_), _) => format!("only in {}", field_summary(month)),
+        (None, [Item::Range { step: 1, .. }]) => format!("{} only", field_summary(month)),
+        _ => field_summary(month),
+    })
+}
+
+fn year_phrase(year: &Field) -> Option<String> {
+    if year.is_any() {
+        return None;
+    }
+    Some(match (year.single_values(), year.items.as_slice()) {
+        (Some(_), _) => format!("only in {}", field_summary(year)),
+        (None, [Item::Range { start, end, step: 1, .. }]) => format!("from {} through {}", start, end),
+        _ => field_summary(year),
+    })
+}
+
+/// The whole schedule as a sentence: "At 9:00am, Monday through Friday"
+pub fn describe(schedule: &CronSchedule) -> String {
+    let mut parts = time_phrases(schedule);
+    parts.extend(day_phrase(schedule));
+    parts.extend(month_phrase(&schedule.month));
+    parts.extend(year_phrase(&schedule.year));
+    capitalize(&parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn described(expression: &str) -> String {
+        describe(&parse(expression).unwrap())
+    }
+
+    fn runs(expression: &str, tz: &str, after: DateTime<Utc>, limit: usize) -> Vec<String> {
+        let tz: Tz = tz.parse().unwrap();
+        next_runs(&parse(expression).unwrap(), tz, after, limit)
+            .iter()
+            .map(|run| run.at.format("%Y-%m-%d %H:%M:%S %Z").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_dialects() {
+        assert_eq!(parse("*/5 * * * *").unwrap().dialect, CronDialect::Standard);
+        assert_eq!(parse("0 */5 * * * *").unwrap().dialect, CronDialect::WithSeconds);
+        let quartz = parse("0 0 12 ? * 2#1 2026").unwrap();
+        assert_eq!(quartz.dialect, CronDialect::Quartz);
+        assert_eq!(quartz.written_fields().len(), 7);
+        let daily = parse("@daily").unwrap();
+        assert_eq!(daily.macro_name.as_deref(), Some("@daily"));
+        assert_eq!((daily.minute.start, daily.minute.end), (0, 6));
+    }
+
+    #[test]
+    fn test_descriptions() {
+        assert_eq!(described("*/15 9-17 * * MON-FRI"), "Every 15 minutes, between 9:00am and 5:59pm, Monday through Friday");
+        assert_eq!(described("0 9 * * 1"), "At 9:00am, only on Monday");
+        assert_eq!(described("30 9,17 * * *"), "At 9:30am and 5:30pm");
+        assert_eq!(described("* * * * *"), "Every minute");
+        assert_eq!(described("5 * * * *"), "At 5 minutes past the hour");
+        assert_eq!(described("0 */2 * * *"), "On the hour, every 2 hours");
+        assert_eq!(described("30 2 1,15 * *"), "At 2:30am, on the 1st and 15th of the month");
+        assert_eq!(described("0 0 L * ?"), "At 12:00am, on the last day of the month");
+        assert_eq!(described("0 12 * * 5L"), "At 12:00pm, on the last Friday of the month");
+        assert_eq!(described("0 0 12 ? * 2#1 2026"), "At 12:00pm, on the first Monday of the month, only in 2026");
+        assert_eq!(described("*/10 * * * * *"), "Every 10 seconds");
+        assert_eq!(described("0 0 1 1,7 *"), "At 12:00am, on the 1st of the month, only in January and July");
+        assert_eq!(described("0 8 15W * *"), "At 8:00am, on the weekday nearest the 15th");
+        assert_eq!(described("0 0 1 * MON"), "At 12:00am, on the 1st of the month or on Monday");
+        assert_eq!(described("0 0 */2 * 1"), "At 12:00am, every 2 days, but only on Monday");
+        assert_eq!(described("@hourly"), "Every hour");
+    }
+
+    #[test]
+    fn test_errors_point_at_the_problem() {
+        let err = parse("61 * * * *").unwrap_err();
+        assert_eq!(err.message, "Minute 61 is out of range (0–59)");
+        assert_eq!((err.start, err.end, err.field), (0, 2, Some(FieldKind::Minute)));
+
+        let err = parse("0 9 * JNA *").unwrap_err();
+        assert_eq!(err.message, "Unknown month 'JNA'");
+        assert_eq!((err.start, err.end), (6, 9));
+
+        let err = parse("*/0 * * * *").unwrap_err();
+        assert_eq!((err.start, err.end), (2, 3));
+        assert!(err.message.starts_with("Step"), "{}", err.message);
+
+        let err = parse("0 9 * * 1,,5").unwrap_err();
+        assert_eq!(err.message, "Empty value in the day of week list");
+        assert_eq!(err.start, 10);
+
+        let err = parse("0 17-9 * * *").unwrap_err();
+        assert_eq!((err.start, err.end), (2, 6));
+
+        let err = parse("0 0 9 ? * 2#6").unwrap_err();
+        assert_eq!(err.message, "Occurrence after '#' must be between 1 and 5");
+        assert_eq!(err.start, 12);
+
+        assert_eq!(parse("* * *").unwrap_err().message, "Expected 5, 6 or 7 fields, found 3");
+        assert_eq!(parse("0 9 ? * ?").unwrap_err().field, Some(FieldKind::DayOfWeek));
+        assert!(parse("0 9W * * *").is_err());
+        assert!(parse("@reboot").is_err());
+        assert!(parse("0 0 * JUL-AUG SUN").is_ok());
+    }
+
+    #[test]
+    fn test_detection() {
+        assert!(is_cron_text("*/5 * * * *"));
+        assert!(is_cron_text("  0 9 * * MON-FRI "));
+        assert!(is_cron_text("@weekly"));
+        assert!(is_cron_text("0 0 1 JAN SUN"));
+        assert!(!is_cron_text("1 2 3 4 5"));
+        assert!(!is_cron_text("* * *"));
+        assert!(!is_cron_text("5 * 3 = 15"));
+
+        let found = find_cron("30 2 * * 1 /usr/local/bin/backup.sh --full").unwrap();
+        assert_eq!(found.expression, "30 2 * * 1");
+        assert_eq!(found.command.as_deref(), Some("/usr/local/bin/backup.sh --full"));
+        let found = find_cron("@daily  cleanup").unwrap();
+        assert_eq!((found.expression.as_str(), found.command.as_deref()), ("@daily", Some("cleanup")));
+    }
+
+    #[test]
+    fn test_next_runs() {
+        let after = Utc.with_ymd_and_hms(2025, 3, 14, 10, 7, 30).unwrap();
+        assert_eq!(
+            runs("*/15 * * * *", "UTC", after, 3),
+            vec!["2025-03-14 10:15:00 UTC", "2025-03-14 10:30:00 UTC", "2025-03-14 10:45:00 UTC"]
+        );
+        // Friday 14 Mar: next weekday runs are Monday and Tuesday
+        assert_eq!(
+            runs("0 9 * * MON-FRI", "Europe/London", after, 2),
+            vec!["2025-03-17 09:00:00 GMT", "2025-03-18 09:00:00 GMT"]
+        );
+        assert_eq!(runs("0 0 L 2 *", "UTC", after, 2), vec!["2026-02-28 00:00:00 UTC", "2027-02-28 00:00:00 UTC"]);
+        assert_eq!(runs("0 0 12 ? * 2#1 *", "UTC", after, 1), vec!["2025-04-07 12:00:00 UTC"]);
+        // 7-field weekdays are Quartz's: 6 = Friday
+        assert_eq!(runs("0 0 12 ? * 6L *", "UTC", after, 1), vec!["2025-03-28 12:00:00 UTC"]);
+        // Sat 15 Mar moves to Friday the 14th
+        let march = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(runs("0 8 15W * *", "UTC", march, 2), vec!["2025-03-14 08:00:00 UTC", "2025-04-15 08:00:00 UTC"]);
+        // `*/2` narrows the weekdays as in Vixie cron: odd-numbered Mondays only
+        assert_eq!(runs("0 0 */2 * 1", "UTC", after, 2), vec!["2025-03-17 00:00:00 UTC", "2025-03-31 00:00:00 UTC"]);
+        assert_eq!(runs("0 0 13 * 5", "UTC", after, 1), vec!["2025-03-21 00:00:00 UTC"]);
+        assert!(runs("0 0 30 2 *", "UTC", after, 1).is_empty());
+        assert!(runs("0 0 0 1 1 ? 2020", "UTC", after, 1).is_empty());
+    }
+
+    #[test]
+    fn test_runs_across_clock_changes() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+        // 2:30am doesn't exist on Sun 9 Mar 2025
+        let after = Utc.with_ymd_and_hms(2025, 3, 8, 12, 0, 0).unwrap();
+        let list = next_runs(&parse("30 2 * * *").unwrap(), tz, after, 2);
+        assert_eq!(list[0].at.format("%d %H:%M %Z").to_string(), "09 03:30 EDT");
+        assert!(list[0].note.is_some());
+        assert_eq!(list[1].at.format("%d %H:%M %Z").to_string(), "10 02:30 EDT");
+
+        // 1:30am happens twice on Sun 2 Nov 2025; the job runs once
+        let after = Utc.with_ymd_and_hms(2025, 11, 1, 12, 0, 0).unwrap();
+        let list = next_runs(&parse("30 1 * * *").unwrap(), tz, after, 2);
+        assert_eq!(list[0].at.format("%d %H:%M %Z").to_string(), "02 01:30 EDT");
+        assert_eq!(list[1].at.format("%d %H:%M %Z").to_string(), "03 01:30 EST");
+    }
+}
@@ -0,0 +1,738 @@
+//! Date and duration arithmetic
+//!
+//! Understands "today + 90 days", "2024-03-01 - 2023-11-15", "3h 20m + 45m",
+//! "+3 business days", "2 weeks ago" and "days until Christmas". Terms are read
+//! left to right; a minus needs spaces around it so ISO dates stay whole. Named
+//! days and ISO dates are read here, anything else goes to chrono-english.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_english::{parse_date_string, Dialect};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::holidays::{self, Holiday};
+use crate::shared::settings::BusinessCalendarSettings;
+use crate::shared::types::{DateCalcKind, DateCalcResponse};
+
+/// Most business days one expression may step through
+const MAX_BUSINESS_DAYS: i64 = 10_000;
+
+fn out_of_range() -> String {
+    "Date out of range".to_string()
+}
+
+/// Business calendar from settings (overridable per request)
+static BUSINESS_CALENDAR: Lazy<RwLock<BusinessCalendarSettings>> = Lazy::new(|| RwLock::new(BusinessCalendarSettings::default()));
+
+/// Replace the business calendar preference (called when settings are loaded or saved)
+pub fn set_business_calendar(settings: &BusinessCalendarSettings) {
+    println!("[date_calc] Weekend {:?}, holidays {:?}", settings.weekend, settings.holiday_country);
+    match BUSINESS_CALENDAR.write() {
+        Ok(mut guard) => *guard = settings.clone(),
+        Err(poisoned) => *poisoned.into_inner() = settings.clone(),
+    }
+}
+
+/// The active business calendar preference
+pub fn business_calendar() -> BusinessCalendarSettings {
+    match BUSINESS_CALENDAR.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Weekend days and the holiday calendar used for business days
+#[derive(Debug)]
+pub struct Calendar {
+    weekend: Vec<Weekday>,
+    country: Option<String>,
+    holidays_by_year: RefCell<HashMap<i32, Vec<Holiday>>>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            weekend: holidays::DEFAULT_WEEKEND.to_vec(),
+            country: None,
+            holidays_by_year: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl Calendar {
+    /// Weekend as day names ("sat", "Sunday") and an optional country code
+    pub fn new(weekend: &[String], country: Option<&str>) -> Result<Self, String> {
+        let weekend = weekend
+            .iter()
+            .map(|day| holidays::parse_weekday(day).ok_or_else(|| format!("Unknown weekday: '{}'", day)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if weekend.len() >= 7 {
+            return Err("The weekend can't cover the whole week".to_string());
+        }
+
+        let country = country.map(str::trim).filter(|c| !c.is_empty()).map(str::to_uppercase);
+        if let Some(code) = &country {
+            if !holidays::has_calendar(code) {
+                return Err(format!("No holiday calendar for '{}'", code));
+            }
+        }
+
+        Ok(Self { weekend, country, holidays_by_year: RefCell::new(HashMap::new()) })
+    }
+
+    fn holiday_on(&self, date: NaiveDate) -> Option<String> {
+        let country = self.country.as_deref()?;
+        let mut cache = self.holidays_by_year.borrow_mut();
+        let holidays = cache
+            .entry(date.year())
+            .or_insert_with(|| holidays::holidays_in_year(country, date.year(), &self.weekend).unwrap_or_default());
+        holidays.iter().find(|h| h.date == date).map(|h| h.name.clone())
+    }
+
+    fn is_weekend(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(&date.weekday())
+    }
+
+    fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.is_weekend(date) && self.holiday_on(date).is_none()
+    }
+
+    /// Step `count` business days from `date` (backwards when negative),
+    /// returning the landing day and the holidays skipped on the way
+    fn add_business_days(&self, date: NaiveDate, count: i64) -> Result<(NaiveDate, Vec<String>), String> {
+        if count.abs() > MAX_BUSINESS_DAYS {
+            return Err(format!("Too many business days: {} (max {})", count, MAX_BUSINESS_DAYS));
+        }
+        let step = Duration::days(count.signum());
+        let mut current = date;
+        let mut remaining = count.abs();
+        let mut skipped = Vec::new();
+        while remaining > 0 {
+            current = current.checked_add_signed(step).ok_or_else(out_of_range)?;
+            if self.is_weekend(current) {
+                continue;
+            }
+            match self.holiday_on(current) {
+                Some(name) => skipped.push(format!("{} ({})", name, current.format("%a %-d %b %Y"))),
+                None => remaining -= 1,
+            }
+        }
+        Ok((current, skipped))
+    }
+
+    /// Business days after `from` up to and including `to` (negative when `to` is earlier)
+    fn business_days_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let (start, end, sign) = if to >= from { (from, to, 1) } else { (to, from, -1) };
+        let count = start
+            .iter_days()
+            .skip(1)
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_business_day(*d))
+            .count() as i64;
+        count * sign
+    }
+}
+
+/// A calendar amount; months and business days only resolve against a date
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Span {
+    months: i64,
+    days: i64,
+    business_days: i64,
+    seconds: i64,
+}
+
+impl Span {
+    fn negate(self) -> Self {
+        Self {
+            months: self.months.saturating_neg(),
+            days: self.days.saturating_neg(),
+            business_days: self.business_days.saturating_neg(),
+            seconds: self.seconds.saturating_neg(),
+        }
+    }
+
+    fn plus(self, other: Self) -> Result<Self, String> {
+        Ok(Self {
+            months: self.months.checked_add(other.months).ok_or_else(out_of_range)?,
+            days: self.days.checked_add(other.days).ok_or_else(out_of_range)?,
+            business_days: self.business_days.checked_add(other.business_days).ok_or_else(out_of_range)?,
+            seconds: self.seconds.checked_add(other.seconds).ok_or_else(out_of_range)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Moment {
+    at: NaiveDateTime,
+    has_time: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Moment(Moment),
+    Span(Span),
+}
+
+static SPAN_PART_REGEX: OnceLock<Regex> = OnceLock::new();
+static UNTIL_REGEX: OnceLock<Regex> = OnceLock::new();
+static OPERATOR_REGEX: OnceLock<Regex> = OnceLock::new();
+static HAS_TIME_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_span_part_regex() -> &'static Regex {
+    SPAN_PART_REGEX.get_or_init(|| Regex::new(r"(\d+(?:\.\d+)?)\s*([a-z]+(?:\s+days?)?)").expect("Invalid span part regex"))
+}
+
+fn get_until_regex() -> &'static Regex {
+    UNTIL_REGEX.get_or_init(|| {
+        Regex::new(r"^(?P<unit>business days|working days|weekdays|days|weeks|months)\s+(?P<dir>until|till|to|since)\s+(?P<target>.+)$")
+            .expect("Invalid until regex")
+    })
+}
+
+fn get_operator_regex() -> &'static Regex {
+    OPERATOR_REGEX.get_or_init(|| Regex::new(r"\s+([+-])\s+").expect("Invalid operator regex"))
+}
+
+fn get_has_time_regex() -> &'static Regex {
+    HAS_TIME_REGEX.get_or_init(|| Regex::new(r"\d:\d|\d\s*[ap]\.?m\b|\bnoon\b|\bmidnight\b|\bnow\b").expect("Invalid time-of-day regex"))
+}
+
+enum SpanUnit {
+    Years,
+    Months,
+    Weeks,
+    BusinessDays,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+fn span_unit(unit: &str) -> Option<SpanUnit> {
+    match unit {
+        "y" | "yr" | "yrs" | "year" | "years" => Some(SpanUnit::Years),
+        "mo" | "mos" | "month" | "months" => Some(SpanUnit::Months),
+        "w" | "wk" | "wks" | "week" | "weeks" => Some(SpanUnit::Weeks),
+        "bd" | "business day" | "business days" | "working day" | "working days" | "weekday" | "weekdays" => {
+            Some(SpanUnit::BusinessDays)
+        }
+        "d" | "day" | "days" => Some(SpanUnit::Days),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(SpanUnit::Hours),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(SpanUnit::Minutes),
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(SpanUnit::Seconds),
+        _ => None,
+    }
+}
+
+/// "3h 20m", "90 days", "1 year, 2 months and 3 days"
+fn parse_span(term: &str) -> Result<Option<Span>, String> {
+    let regex = get_span_part_regex();
+    let mut span = Span::default();
+    let mut found = false;
+
+    for caps in regex.captures_iter(term) {
+        let Some(unit) = span_unit(&caps[2]) else {
+            return Ok(None);
+        };
+        let raw = &caps[1];
+        let value: f64 = raw.parse().map_err(|_| format!("Invalid number: {}", raw))?;
+        if value.abs() >= i64::MAX as f64 {
+            return Err(out_of_range());
+        }
+        let whole = || -> Result<i64, String> {
+            if value.fract() != 0.0 {
+                return Err(format!("Use whole numbers for {}: '{}'", &caps[2], caps[0].trim()));
+            }
+            Ok(value as i64)
+        };
+        // Float-to-int casts saturate, so only the sums and products can overflow
+        let (total, amount) = match unit {
+            SpanUnit::Years => (&mut span.months, whole()?.checked_mul(12)),
+            SpanUnit::Months => (&mut span.months, Some(whole()?)),
+            SpanUnit::Weeks => (&mut span.days, whole()?.checked_mul(7)),
+            SpanUnit::BusinessDays => (&mut span.business_days, Some(whole()?)),
+            SpanUnit::Days if value.fract() == 0.0 => (&mut span.days, Some(value as i64)),
+            SpanUnit::Days => (&mut span.seconds, Some((value * 86_400.0).round() as i64)),
+            SpanUnit::Hours => (&mut span.seconds, Some((value * 3_600.0).round() as i64)),
+            SpanUnit::Minutes => (&mut span.seconds, Some((value * 60.0).round() as i64)),
+            SpanUnit::Seconds => (&mut span.seconds, Some(value.round() as i64)),
+        };
+        *total = amount.and_then(|amount| total.checked_add(amount)).ok_or_else(out_of_range)?;
+        found = true;
+    }
+
+    // Nothing but separators may remain ("1 year, 2 months and 3 days")
+    let leftover = regex.replace_all(term, " ");
+    let only_separators = leftover
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .all(|word| word.is_empty() || word == "and");
+    Ok((found && only_separators).then_some(span))
+}
+
+/// Named days that recur every year, independent of the holiday calendar
+fn named_date(name: &str, year: i32) -> Option<NaiveDate> {
+    match name {
+        "christmas" | "christmas day" | "xmas" => NaiveDate::from_ymd_opt(year, 12, 25),
+        "christmas eve" | "xmas eve" => NaiveDate::from_ymd_opt(year, 12, 24),
+        "new year" | "new years" | "new years day" => NaiveDate::from_ymd_opt(year, 1, 1),
+        "new years eve" | "nye" => NaiveDate::from_ymd_opt(year, 12, 31),
+        "halloween" => NaiveDate::from_ymd_opt(year, 10, 31),
+        "valentines" | "valentines day" => NaiveDate::from_ymd_opt(year, 2, 14),
+        "easter" | "easter sunday" => holidays::easter_sunday(year),
+        "thanksgiving" => holidays::nth_weekday(year, 11, Weekday::Thu, 4),
+        _ => None,
+    }
+}
+
+fn midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN)
+}
+
+/// A date or date-time term; named days pick the next (or previous) occurrence
+fn parse_moment(term: &str, now: NaiveDateTime, calendar: &Calendar, prefer_past: bool) -> Result<Moment, String> {
+    let today = now.date();
+    let date_only = |date: NaiveDate| Moment { at: midnight(date), has_time: false };
+
+    match term {
+        "today" => return Ok(date_only(today)),
+        "now" => return Ok(Moment { at: now, has_time: true }),
+        "tomorrow" => return Ok(date_only(today + Duration::days(1))),
+        "yesterday" => return Ok(date_only(today - Duration::days(1))),
+        _ => {}
+    }
+
+    // Christmas, Thanksgiving, or any holiday in the selected calendar
+    let name = term.replace(['\'', '’'], "");
+    let in_year = |year: i32| {
+        calendar
+            .country
+            .as_deref()
+            .and_then(|country| holidays::find_by_name(country, &name, year).map(|h| h.date))
+            .or_else(|| named_date(&name, year))
+    };
+    if let Some(this_year) = in_year(today.year()) {
+        let date = match (prefer_past, this_year.cmp(&today)) {
+            (false, std::cmp::Ordering::Less) => in_year(today.year() + 1),
+            (true, std::cmp::Ordering::Greater) => in_year(today.year() - 1),
+            _ => Some(this_year),
+        };
+        return date.map(date_only).ok_or_else(|| format!("No date for '{}'", term));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(term, "%Y-%m-%d") {
+        return Ok(date_only(date));
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dt%H:%M:%S"] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(term, format) {
+            return Ok(Moment { at, has_time: true });
+        }
+    }
+
+    let base = Utc.from_utc_datetime(&now);
+    let parsed = parse_date_string(term, base, Dialect::Us)
+        .map_err(|_| format!("Couldn't read '{}' as a date or a duration", term))?
+        .naive_utc();
+    if get_has_time_regex().is_match(term) {
+        Ok(Moment { at: parsed, has_time: true })
+    } else {
+        Ok(date_only(parsed.date()))
+    }
+}
+
+fn parse_term(term: &str, now: NaiveDateTime, calendar: &Calendar) -> Result<Value, String> {
+    let term = term.trim();
+    if term.is_empty() {
+        return Err("Missing a date or duration".to_string());
+    }
+    match parse_span(term)? {
+        Some(span) => Ok(Value::Span(span)),
+        None => parse_moment(term, now, calendar, false).map(Value::Moment),
+    }
+}
+
+/// Rewrite phrases into "<date> +/- <duration>"
+fn rewrite_phrases(expression: &str) -> String {
+    let expression = expression.trim_start_matches("how many ").trim_end_matches('?').trim();
+    if let Some(amount) = expression.strip_suffix(" ago") {
+        return format!("today - {}", amount);
+    }
+    if let Some(amount) = expression.strip_prefix("in ") {
+        return format!("today + {}", amount);
+    }
+    for (word, op) in [(" from ", "+"), (" after ", "+"), (" before ", "-")] {
+        if let Some((amount, anchor)) = expression.split_once(word) {
+            let anchor = if anchor == "now" || anchor == "today" { "today" } else { anchor };
+            return format!("{} {} {}", anchor, op, amount);
+        }
+    }
+    // "+3 business days" starts from today
+    if expression.starts_with('+') || expression.starts_with("- ") {
+        return format!("today {} {}", &expression[..1], expression[1..].trim());
+    }
+    expression.to_string()
+}
+
+/// Apply a span to a moment, recording holidays skipped by business days
+fn shift(moment: Moment, span: Span, calendar: &Calendar, skipped: &mut Vec<String>) -> Result<Moment, String> {
+    let months = Months::new(u32::try_from(span.months.unsigned_abs()).map_err(|_| out_of_range())?);
+    let mut at = if span.months >= 0 {
+        moment.at.checked_add_months(months)
+    } else {
+        moment.at.checked_sub_months(months)
+    }
+    .ok_or_else(out_of_range)?;
+    let days = TimeDelta::try_days(span.days).ok_or_else(out_of_range)?;
+    at = at.checked_add_signed(days).ok_or_else(out_of_range)?;
+    if span.business_days != 0 {
+        let (date, holidays) = calendar.add_business_days(at.date(), span.business_days)?;
+        skipped.extend(holidays);
+        at = date.and_time(at.time());
+    }
+    let seconds = TimeDelta::try_seconds(span.seconds).ok_or_else(out_of_range)?;
+    at = at.checked_add_signed(seconds).ok_or_else(out_of_range)?;
+    Ok(Moment { at, has_time: moment.has_time || span.seconds != 0 })
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    format!("{} {}{}", count, unit, if count.abs() == 1 { "" } else { "s" })
+}
+
+/// "4h 5m", "1h 0m 30s"
+fn format_clock_amount(seconds: i64) -> String {
+    let (h, m, s) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    match (h, s) {
+        (0, 0) => format!("{}m", m),
+        (_, 0) => format!("{}h {}m", h, m),
+        _ => format!("{}h {}m {}s", h, m, s),
+    }
+}
+
+/// "1 year, 2 months, 3 business days, 4h 5m"
+fn format_span(span: Span) -> String {
+    let sign = if span.months < 0 || span.days < 0 || span.business_days < 0 || span.seconds < 0 { "-" } else { "" };
+    let span = if sign.is_empty() { span } else { span.negate() };
+    let mut parts = Vec::new();
+    if span.months >= 12 {
+        parts.push(plural(span.months / 12, "year"));
+    }
+    if span.months % 12 != 0 {
+        parts.push(plural(span.months % 12, "month"));
+    }
+    if span.days != 0 {
+        parts.push(plural(span.days, "day"));
+    }
+    if span.business_days != 0 {
+        parts.push(plural(span.business_days, "business day"));
+    }
+    if span.seconds != 0 {
+        parts.push(format_clock_amount(span.seconds));
+    }
+    if parts.is_empty() {
+        return "0m".to_string();
+    }
+    format!("{}{}", sign, parts.join(", "))
+}
+
+/// ISO 8601 duration ("P1Y2M3DT4H5M"); business days count as days
+fn iso_duration(span: Span) -> String {
+    let negative = span.months < 0 || span.days < 0 || span.business_days < 0 || span.seconds < 0;
+    let span = if negative { span.negate() } else { span };
+    let mut out = String::from(if negative { "-P" } else { "P" });
+    let (years, months, days) = (span.months / 12, span.months % 12, span.days.saturating_add(span.business_days));
+    for (value, unit) in [(years, 'Y'), (months, 'M'), (days, 'D')] {
+        if value != 0 {
+            out.push_str(&format!("{}{}", value, unit));
+        }
+    }
+    if span.seconds != 0 {
+        out.push('T');
+        let (h, m, s) = (span.seconds / 3600, span.seconds % 3600 / 60, span.seconds % 60);
+        for (value, unit) in [(h, 'H'), (m, 'M'), (s, 'S')] {
+            if value != 0 {
+                out.push_str(&format!("{}{}", value, unit));
+            }
+        }
+    }
+    if out.ends_with('P') {
+        out.push_str("T0S");
+    }
+    out
+}
+
+fn format_moment(moment: Moment) -> (String, String) {
+    if moment.has_time {
+        (
+            moment.at.format("%a %-d %b %Y, %-I:%M%P").to_string(),
+            moment.at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        )
+    } else {
+        (
+            moment.at.format("%a %-d %b %Y").to_string(),
+            moment.at.format("%Y-%m-%d").to_string(),
+        )
+    }
+}
+
+/// "in 90 days" / "3 days ago" / "today"
+fn relative_to_today(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (date - today).num_days();
+    match days {
+        0 => "today".to_string(),
+        d if d > 0 => format!("in {}", plural(d, "day")),
+        d => format!("{} ago", plural(-d, "day")),
+    }
+}
+
+/// Whole months then days from `from` to `to` ("3 months, 15 days")
+fn months_and_days(from: NaiveDate, to: NaiveDate) -> String {
+    let (start, end) = if to >= from { (from, to) } else { (to, from) };
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    let shifted = |m: i32| start.checked_add_months(Months::new(m.max(0) as u32));
+    while months > 0 && shifted(months).is_none_or(|d| d > end) {
+        months -= 1;
+    }
+    let days = (end - shifted(months).unwrap_or(start)).num_days();
+    format_span(Span { months: months as i64, days, ..Span::default() })
+}
+
+/// "19 weeks, 4 days"
+fn weeks_and_days(days: i64) -> String {
+    match (days.abs() / 7, days.abs() % 7) {
+        (weeks, 0) => plural(weeks, "week"),
+        (weeks, rest) => format!("{}, {}", plural(weeks, "week"), plural(rest, "day")),
+    }
+}
+
+fn difference(later: Moment, earlier: Moment, calendar: &Calendar) -> DateCalcResponse {
+    let days = (later.at.date() - earlier.at.date()).num_days();
+    let business_days = calendar.business_days_between(earlier.at.date(), later.at.date());
+
+    if later.has_time || earlier.has_time {
+        let seconds = (later.at - earlier.at).num_seconds();
+        let span = Span { days: seconds / 86_400, seconds: seconds % 86_400, ..Span::default() };
+        return DateCalcResponse {
+            kind: DateCalcKind::Difference,
+            result: format_span(span),
+            iso: iso_duration(span),
+            detail: None,
+            days: Some(days as i32),
+            business_days: Some(business_days as i32),
+            skipped_holidays: Vec::new(),
+        };
+    }
+
+    let detail = if days.abs() >= 7 {
+        Some(format!("{} ({})", months_and_days(earlier.at.date(), later.at.date()), weeks_and_days(days)))
+    } else {
+        None
+    };
+    DateCalcResponse {
+        kind: DateCalcKind::Difference,
+        result: plural(days, "day"),
+        iso: iso_duration(Span { days, ..Span::default() }),
+        detail,
+        days: Some(days as i32),
+        business_days: Some(business_days as i32),
+        skipped_holidays: Vec::new(),
+    }
+}
+
+/// "days until Christmas", "business days until 2025-01-31", "weeks since Mar 1"
+fn count_until(caps: &regex::Captures, now: NaiveDateTime, calendar: &Calendar) -> Result<DateCalcResponse, String> {
+    let since = &caps["dir"] == "since";
+    let target = parse_moment(caps["target"].trim(), now, calendar, since)?;
+    let today = Moment { at: midnight(now.date()), has_time: false };
+    let target = Moment { at: midnight(target.at.date()), has_time: false };
+    let (later, earlier) = if since { (today, target) } else { (target, today) };
+
+    let mut response = difference(later, earlier, calendar);
+    let days = response.days.unwrap_or(0) as i64;
+    let (label, _) = format_moment(target);
+    let amount = match &caps["unit"] {
+        "weeks" => weeks_and_days(days),
+        "months" => months_and_days(earlier.at.date(), later.at.date()),
+        "days" => plural(days, "day"),
+        _ => plural(response.business_days.unwrap_or(0) as i64, "business day"),
+    };
+    response.result = if since {
+        format!("{} since {}", amount, label)
+    } else {
+        format!("{} until {}", amount, label)
+    };
+    Ok(response)
+}
+
+/// Evaluate a date or duration expression relative to `now`
+pub fn calculate(expression: &str, now: NaiveDateTime, calendar: &Calendar) -> Result<DateCalcResponse, String> {
+    let normalized = expression.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    if normalized.is_empty() {
+        return Err("Enter a date or duration expression".to_string());
+    }
+
+    if let Some(caps) = get_until_regex().captures(normalized.trim_start_matches("how many ").trim_end_matches('?')) {
+        return count_until(&caps, now, calendar);
+    }
+
+    let rewritten = rewrite_phrases(&normalized);
+    let operator_regex = get_operator_regex();
+    let mut terms = operator_regex.split(&rewritten);
+    let operators: Vec<&str> = operator_regex.captures_iter(&rewritten).map(|c| c.get(1).unwrap().as_str()).collect();
+
+    let mut value = parse_term(terms.next().unwrap_or_default(), now, calendar)?;
+    let mut skipped = Vec::new();
+
+    for (op, term) in operators.iter().zip(terms) {
+        let rhs = parse_term(term, now, calendar)?;
+        value = match (value, *op, rhs) {
+            (Value::Moment(m), "+", Value::Span(s)) | (Value::Span(s), "+", Value::Moment(m)) => {
+                Value::Moment(shift(m, s, calendar, &mut skipped)?)
+            }
+            (Value::Moment(m), _, Value::Span(s)) => Value::Moment(shift(m, s.negate(), calendar, &mut skipped)?),
+            (Value::Span(a), "+", Value::Span(b)) => Value::Span(a.plus(b)?),
+            (Value::Span(a), _, Value::Span(b)) => Value::Span(a.plus(b.negate())?),
+            (Value::Moment(later), "-", Value::Moment(earlier)) if operators.len() == 1 => {
+                return Ok(difference(later, earlier, calendar));
+            }
+            (Value::Moment(_), "-", Value::Moment(_)) => {
+                return Err("Subtract two dates on their own, e.g. '2024-03-01 - 2023-11-15'".to_string());
+            }
+            (Value::Moment(_), _, Value::Moment(_)) => return Err("Two dates can't be added".to_string()),
+            (Value::Span(_), _, Value::Moment(_)) => {
+                return Err("A date can't be subtracted from a duration".to_string());
+            }
+        };
+    }
+
+    println!("[date_calc] '{}' → {:?}", expression, value);
+
+    Ok(match value {
+        Value::Moment(moment) => {
+            let (result, iso) = format_moment(moment);
+            DateCalcResponse {
+                kind: DateCalcKind::Date,
+                result,
+                iso,
+                detail: Some(relative_to_today(moment.at.date(), now.date())),
+                days: Some((moment.at.date() - now.date()).num_days() as i32),
+                business_days: None,
+                skipped_holidays: skipped,
+            }
+        }
+        Value::Span(span) => DateCalcResponse {
+            kind: DateCalcKind::Duration,
+            result: format_span(span),
+            iso: iso_duration(span),
+            detail: None,
+            days: None,
+            business_days: None,
+            skipped_holidays: skipped,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        // Friday
+        NaiveDate::from_ymd_opt(2024, 5, 24).unwrap().and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    fn calc(expression: &str) -> DateCalcResponse {
+        calculate(expression, now(), &Calendar::default()).unwrap()
+    }
+
+    #[test]
+    fn test_date_plus_span() {
+        let r = calc("today + 90 days");
+        assert_eq!(r.kind, DateCalcKind::Date);
+        assert_eq!(r.iso, "2024-08-22");
+        assert_eq!(r.result, "Thu 22 Aug 2024");
+        assert_eq!(r.detail.as_deref(), Some("in 90 days"));
+
+        assert_eq!(calc("2024-01-31 + 1 month").iso, "2024-02-29");
+        assert_eq!(calc("2 weeks ago").iso, "2024-05-10");
+        assert_eq!(calc("in 3 days").iso, "2024-05-27");
+        assert_eq!(calc("10 days before 2024-03-01").iso, "2024-02-20");
+        assert_eq!(calc("now + 3h 20m").iso, "2024-05-24T13:20:00");
+    }
+
+    #[test]
+    fn test_date_difference() {
+        let r = calc("2024-03-01 - 2023-11-15");
+        assert_eq!(r.kind, DateCalcKind::Difference);
+        assert_eq!(r.result, "107 days");
+        assert_eq!(r.iso, "P107D");
+        assert_eq!(r.detail.as_deref(), Some("3 months, 15 days (15 weeks, 2 days)"));
+        assert_eq!(r.business_days, Some(77));
+    }
+
+    #[test]
+    fn test_span_arithmetic() {
+        let r = calc("3h 20m + 45m");
+        assert_eq!(r.kind, DateCalcKind::Duration);
+        assert_eq!(r.result, "4h 5m");
+        assert_eq!(r.iso, "PT4H5M");
+        assert_eq!(calc("1 year, 2 months and 3 days").iso, "P1Y2M3D");
+        assert_eq!(calc("1h - 90m").iso, "-PT30M");
+    }
+
+    #[test]
+    fn test_days_until() {
+        let r = calc("days until Christmas");
+        assert_eq!(r.result, "215 days until Wed 25 Dec 2024");
+        assert_eq!(r.iso, "P215D");
+        assert_eq!(calc("weeks until christmas").result, "30 weeks, 5 days until Wed 25 Dec 2024");
+        // Past this year: next occurrence, or the previous one for "since"
+        assert_eq!(calc("days until valentine's day").result, "266 days until Fri 14 Feb 2025");
+        assert_eq!(calc("days since new year's day").result, "144 days since Mon 1 Jan 2024");
+    }
+
+    #[test]
+    fn test_business_days_with_holidays() {
+        // Friday + 3 business days skips the weekend
+        assert_eq!(calc("+3 business days").iso, "2024-05-29");
+
+        // ...and Memorial Day with the US calendar
+        let us = Calendar::new(&["sat".into(), "sun".into()], Some("us")).unwrap();
+        let r = calculate("+3 business days", now(), &us).unwrap();
+        assert_eq!(r.iso, "2024-05-30");
+        assert_eq!(r.skipped_holidays, vec!["Memorial Day (Mon 27 May 2024)".to_string()]);
+
+        // Friday–Saturday weekend
+        let gulf = Calendar::new(&["fri".into(), "sat".into()], None).unwrap();
+        assert_eq!(calculate("today + 1 business day", now(), &gulf).unwrap().iso, "2024-05-26");
+        assert_eq!(calculate("business days until 2024-06-07", now(), &us).unwrap().result, "9 business days until Fri 7 Jun 2024");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(calculate("", now(), &Calendar::default()).is_err());
+        assert!(calculate("1.5 months", now(), &Calendar::default()).is_err());
+        assert!(calculate("2h - today", now(), &Calendar::default()).is_err());
+        assert!(calculate("banana + 2 days", now(), &Calendar::default()).is_err());
+        assert!(Calendar::new(&["funday".into()], None).is_err());
+        assert!(Calendar::new(&[], Some("XX")).is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        for expression in [
+            "today + 9999999999999999 days",
+            "today + 9999999999999999 weeks",
+            "today + 999999999999999999 years",
+            "today - 9999999999999999 hours",
+            "today + 99999999999999999999999 seconds",
+            "9000000000000000000 days + 9000000000000000000 days",
+            "today + 262000 years + 3 business days",
+        ] {
+            let result = calculate(expression, now(), &Calendar::default());
+            assert_eq!(result.err().as_deref(), Some("Date out of range"), "{}", expression);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Daylight saving transitions: gaps, overlaps and nearby clock changes
+//!
+//! A wall-clock time can be missing (spring forward: 2:30am doesn't happen)
+//! or happen twice (fall back: 1:30am runs once in summer time, once in
+//! standard time). `resolve_local` reports which case applies instead of
+//! letting chrono pick, and `transitions_near` finds clock changes around an
+//! instant so conversions can warn before a meeting lands on the wrong hour.
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Days either side of a conversion to look for clock changes
+pub const WARNING_WINDOW_DAYS: i64 = 7;
+
+/// Coarse step when scanning for offset changes (no zone changes twice in 6 hours)
+const SCAN_STEP_HOURS: i64 = 6;
+
+/// How a wall-clock time maps onto a zone
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalTime {
+    /// Exactly one instant
+    Single(DateTime<Tz>),
+    /// Skipped by a spring-forward; `shifted` is the same time read with the
+    /// offset from before the jump (2:30am EST → 3:30am EDT)
+    Gap { shifted: DateTime<Tz>, gap_seconds: i32 },
+    /// Happens twice; `earlier` is the first occurrence (still on summer time)
+    Ambiguous { earlier: DateTime<Tz>, later: DateTime<Tz> },
+}
+
+/// A change of UTC offset in one zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub at: DateTime<Utc>,
+    pub offset_before: i32,
+    pub offset_after: i32,
+    pub abbr_before: String,
+    pub abbr_after: String,
+}
+
+fn offset_seconds(tz: Tz, instant: DateTime<Utc>) -> i32 {
+    tz.offset_from_utc_datetime(&instant.naive_utc()).fix().local_minus_utc()
+}
+
+fn abbreviation(tz: Tz, instant: DateTime<Utc>) -> String {
+    instant.with_timezone(&tz).format("%Z").to_string()
+}
+
+/// Map a wall-clock time in `tz` to instants, naming gaps and overlaps
+pub fn resolve_local(tz: Tz, naive: NaiveDateTime) -> Option<LocalTime> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(LocalTime::Single(dt)),
+        LocalResult::Ambiguous(a, b) => {
+            let (earlier, later) = if a <= b { (a, b) } else { (b, a) };
+            Some(LocalTime::Ambiguous { earlier, later })
+        }
+        LocalResult::None => {
+            // Read with the offset in force a day earlier, i.e. before the jump
+            let before = offset_seconds(tz, Utc.from_utc_datetime(&(naive - Duration::days(1))));
+            let utc = naive.checked_sub_signed(Duration::seconds(before as i64))?;
+            let shifted = Utc.from_utc_datetime(&utc).with_timezone(&tz);
+            let after = shifted.offset().fix().local_minus_utc();
+            Some(LocalTime::Gap { shifted, gap_seconds: after - before })
+        }
+    }
+}
+
+/// Find the instant an offset change happens between two instants (to the second)
+fn bisect(tz: Tz, mut low: DateTime<Utc>, mut high: DateTime<Utc>) -> DateTime<Utc> {
+    let offset_low = offset_seconds(tz, low);
+    while high - low > Duration::seconds(1) {
+        let mid = low + (high - low) / 2;
+        if offset_seconds(tz, mid) == offset_low {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    // Changes fall on whole seconds, and `high` is less than a second past it
+    Utc.timestamp_opt(high.timestamp(), 0).single().unwrap_or(high)
+}
+
+/// Clock changes in `tz` within `window_days` of `instant`, in order
+pub fn transitions_near(tz: Tz, instant: DateTime<Utc>, window_days: i64) -> Vec<Transition> {
+    let end = instant + Duration::days(window_days);
+    let mut cursor = instant - Duration::days(window_days);
+    let mut offset = offset_seconds(tz, cursor);
+    let mut transitions = Vec::new();
+
+    while cursor < end {
+        let next = (cursor + Duration::hours(SCAN_STEP_HOURS)).min(end);
+        let next_offset = offset_seconds(tz, next);
+        if next_offset != offset {
+            let at = bisect(tz, cursor, next);
+            transitions.push(Transition {
+                at,
+                offset_before: offset,
+                offset_after: next_offset,
+                abbr_before: abbreviation(tz, at - Duration::minutes(1)),
+                abbr_after: abbreviation(tz, at),
+            });
+            offset = next_offset;
+        }
+        cursor = next;
+    }
+    transitions
+}
+
+/// "1h" / "30m" / "1h 30m"
+fn format_amount(seconds: i32) -> String {
+    let minutes = seconds.abs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (h, 0) => format!("{}h", h),
+        (0, m) => format!("{}m", m),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+/// "3 days after" / "5h before" / "on the same day as"
+fn format_distance(transition: DateTime<Utc>, instant: DateTime<Utc>) -> String {
+    let delta = transition - instant;
+    let hours = delta.num_hours();
+    let (amount, direction) = match delta.num_days() {
+        0 if hours == 0 => return "right at".to_string(),
+        0 => (format!("{}h", hours.abs()), hours),
+        days => (format!("{} day{}", days.abs(), if days.abs() == 1 { "" } else { "s" }), days),
+    };
+    format!("{} {}", amount, if direction > 0 { "after" } else { "before" })
+}
+
+/// "Clocks in Paris go forward 1h on Sun 30 Mar at 2:00am (CET → CEST), 3 days after this time"
+pub fn describe_transition(place: &str, transition: &Transition, instant: DateTime<Utc>) -> String {
+    let change = transition.offset_after - transition.offset_before;
+    let wall_clock = transition.at.naive_utc() + Duration::seconds(transition.offset_before as i64);
+    let tense = if transition.at > Utc::now() { "go" } else { "went" };
+    format!(
+        "Clocks in {} {} {} {} on {} at {} ({} → {}), {} this time",
+        place,
+        tense,
+        if change > 0 { "forward" } else { "back" },
+        format_amount(change),
+        wall_clock.format("%a %-d %b"),
+        wall_clock.format("%-I:%M%P"),
+        transition.abbr_before,
+        transition.abbr_after,
+        format_distance(transition.at, instant),
+    )
+}
+
+/// "2:30am doesn't exist in New York on Sun 10 Mar (clocks skip 1h); showing 3:30am EDT"
+pub fn describe_gap(place: &str, naive: NaiveDateTime, shifted: DateTime<Tz>, gap_seconds: i32) -> String {
+    format!(
+        "{} doesn't exist in {} on {} (clocks skip {}); showing {} {}",
+        naive.format("%-I:%M%P"),
+        place,
+        naive.format("%a %-d %b"),
+        format_amount(gap_seconds),
+        shifted.format("%-I:%M%P"),
+        shifted.format("%Z"),
+    )
+}
+
+/// "1:30am happens twice in New York on Sun 3 Nov (EDT, then EST); showing the first"
+pub fn describe_overlap(place: &str, naive: NaiveDateTime, earlier: DateTime<Tz>, later: DateTime<Tz>) -> String {
+    format!(
+        "{} happens twice in {} on {} ({}, then {}); showing the first",
+        naive.format("%-I:%M%P"),
+        place,
+        naive.format("%a %-d %b"),
+        earlier.format("%Z"),
+        later.format("%Z"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn naive(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_spring_forward_gap() {
+        let ny: Tz = "America/New_York".parse().unwrap();
+        match resolve_local(ny, naive(2024, 3, 10, 2, 30)) {
+            Some(LocalTime::Gap { shifted, gap_seconds }) => {
+                assert_eq!(shifted.naive_local(), naive(2024, 3, 10, 3, 30));
+                assert_eq!(gap_seconds, 3600);
+            }
+            other => panic!("expected a gap, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fall_back_overlap() {
+        let ny: Tz = "America/New_York".parse().unwrap();
+        match resolve_local(ny, naive(2024, 11, 3, 1, 30)) {
+            Some(LocalTime::Ambiguous { earlier, later }) => {
+                assert_eq!(earlier.format("%Z").to_string(), "EDT");
+                assert_eq!(later.format("%Z").to_string(), "EST");
+                assert_eq!(later - earlier, Duration::hours(1));
+            }
+            other => panic!("expected an overlap, got {:?}", other),
+        }
+        assert!(matches!(resolve_local(ny, naive(2024, 11, 3, 3, 0)), Some(LocalTime::Single(_))));
+    }
+
+    #[test]
+    fn test_transitions_near() {
+        let paris: Tz = "Europe/Paris".parse().unwrap();
+        let instant = Utc.from_utc_datetime(&naive(2025, 3, 27, 12, 0));
+        let found = transitions_near(paris, instant, WARNING_WINDOW_DAYS);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at, Utc.from_utc_datetime(&naive(2025, 3, 30, 1, 0)));
+        assert_eq!((found[0].offset_before, found[0].offset_after), (3600, 7200));
+        assert_eq!((found[0].abbr_before.as_str(), found[0].abbr_after.as_str()), ("CET", "CEST"));
+
+        let text = describe_transition("Paris", &found[0], instant);
+        assert!(text.ends_with("forward 1h on Sun 30 Mar at 2:00am (CET → CEST), 2 days after this time"), "{}", text);
+
+        // Tokyo has no DST; a summer week in Paris has no change either
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        assert!(transitions_near(tokyo, instant, WARNING_WINDOW_DAYS).is_empty());
+        let july = Utc.from_utc_datetime(&naive(2025, 7, 1, 12, 0));
+        assert!(transitions_near(paris, july, WARNING_WINDOW_DAYS).is_empty());
+    }
+
+    #[test]
+    fn test_half_hour_change() {
+        // Lord Howe Island moves by 30 minutes
+        let lord_howe: Tz = "Australia/Lord_Howe".parse().unwrap();
+        let instant = Utc.from_utc_datetime(&naive(2025, 4, 4, 0, 0));
+        let found = transitions_near(lord_howe, instant, WARNING_WINDOW_DAYS);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].offset_before - found[0].offset_after, 1800);
+        assert!(describe_transition("Lord Howe", &found[0], instant).contains("back 30m"));
+    }
+}
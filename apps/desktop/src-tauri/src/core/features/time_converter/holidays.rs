@@ -0,0 +1,324 @@
+//! Public holiday calendars, bundled offline as one JSON file per country
+//!
+//! Each file lists rules rather than dates, so the calendars work for any
+//! year: fixed days ("12-25"), the nth weekday of a month ("4th Thursday of
+//! November"), the last weekday on or before a date (Victoria Day) and offsets
+//! from Easter Sunday. Weekend holidays can move to a substitute weekday,
+//! taking the caller's weekend into account.
+
+use std::collections::HashMap;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+const CALENDAR_FILES: &[&str] = &[
+    include_str!("holidays/au.json"),
+    include_str!("holidays/ca.json"),
+    include_str!("holidays/de.json"),
+    include_str!("holidays/fr.json"),
+    include_str!("holidays/gb.json"),
+    include_str!("holidays/us.json"),
+];
+
+/// Where a holiday that falls on a weekend is observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Observed {
+    /// First day of the weekend → the day before, later days → the day after
+    /// (Saturday → Friday, Sunday → Monday for the US federal calendar)
+    NearestWeekday,
+    /// The next weekday that isn't already a holiday (UK, Canada, Australia)
+    NextWeekday,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HolidayRule {
+    name: String,
+    #[serde(default)]
+    month: Option<u32>,
+    #[serde(default)]
+    day: Option<u32>,
+    #[serde(default)]
+    weekday: Option<String>,
+    /// 1-based occurrence of `weekday` in `month`; -1 is the last
+    #[serde(default)]
+    nth: Option<i32>,
+    /// `weekday` on or before `month`/`day`
+    #[serde(default)]
+    on_or_before: bool,
+    /// Days from Easter Sunday (Good Friday = -2)
+    #[serde(default)]
+    easter_offset: Option<i64>,
+    #[serde(default)]
+    observed: Option<Observed>,
+    #[serde(default)]
+    from_year: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CalendarFile {
+    country: String,
+    name: String,
+    holidays: Vec<HolidayRule>,
+}
+
+/// A holiday on a specific date
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+static CALENDARS: Lazy<HashMap<String, CalendarFile>> = Lazy::new(|| {
+    CALENDAR_FILES
+        .iter()
+        .filter_map(|json| match serde_json::from_str::<CalendarFile>(json) {
+            Ok(calendar) => Some((calendar.country.to_uppercase(), calendar)),
+            Err(e) => {
+                eprintln!("[holidays] Skipping invalid calendar: {}", e);
+                None
+            }
+        })
+        .collect()
+});
+
+/// Bundled calendars as (country code, display name), sorted by name
+pub fn available_calendars() -> Vec<(String, String)> {
+    let mut calendars: Vec<(String, String)> = CALENDARS
+        .values()
+        .map(|c| (c.country.clone(), c.name.clone()))
+        .collect();
+    calendars.sort_by(|a, b| a.1.cmp(&b.1));
+    calendars
+}
+
+/// Whether a calendar is bundled for the country code ("US", "gb")
+pub fn has_calendar(country: &str) -> bool {
+    CALENDARS.contains_key(&country.to_uppercase())
+}
+
+/// Easter Sunday (Gregorian, anonymous algorithm)
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// "mon" / "monday" → Weekday
+pub fn parse_weekday(name: &str) -> Option<Weekday> {
+    match name.trim().to_lowercase().get(..3)? {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The nth weekday of a month (nth = -1 for the last)
+pub fn nth_weekday(year: i32, month: u32, weekday: Weekday, nth: i32) -> Option<NaiveDate> {
+    if nth < 0 {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let last = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+        let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+        return Some(last - Duration::days(back as i64));
+    }
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, u8::try_from(nth).ok()?)
+}
+
+impl HolidayRule {
+    /// The date this rule gives in a year, before any weekend substitution
+    fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        if self.from_year.is_some_and(|from| year < from) {
+            return None;
+        }
+        if let Some(offset) = self.easter_offset {
+            return Some(easter_sunday(year)? + Duration::days(offset));
+        }
+
+        let month = self.month?;
+        match (self.weekday.as_deref().and_then(parse_weekday), self.nth, self.day) {
+            (Some(weekday), Some(nth), _) => nth_weekday(year, month, weekday, nth),
+            (Some(weekday), None, Some(day)) if self.on_or_before => {
+                let limit = NaiveDate::from_ymd_opt(year, month, day)?;
+                let back = (7 + limit.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+                Some(limit - Duration::days(back as i64))
+            }
+            (None, None, Some(day)) => NaiveDate::from_ymd_opt(year, month, day),
+            _ => None,
+        }
+    }
+}
+
+/// Saturday and Sunday, the weekend the bundled calendars are written for
+pub const DEFAULT_WEEKEND: [Weekday; 2] = [Weekday::Sat, Weekday::Sun];
+
+/// Holidays of a country in one year, with substitutes for those falling on
+/// `weekend`, sorted by date
+pub fn holidays_in_year(country: &str, year: i32, weekend: &[Weekday]) -> Result<Vec<Holiday>, String> {
+    if weekend.len() >= 7 {
+        return Err("The weekend can't cover the whole week".to_string());
+    }
+    let is_weekend = |date: NaiveDate| weekend.contains(&date.weekday());
+
+    let calendar = CALENDARS
+        .get(&country.to_uppercase())
+        .ok_or_else(|| format!("No holiday calendar for '{}'", country))?;
+
+    let mut holidays: Vec<Holiday> = calendar
+        .holidays
+        .iter()
+        .filter_map(|rule| Some(Holiday { date: rule.date_in(year)?, name: rule.name.clone() }))
+        .collect();
+    holidays.sort_by_key(|h| h.date);
+
+    // Substitutes are placed in date order so Christmas and Boxing Day don't collide
+    let mut substitutes: Vec<Holiday> = Vec::new();
+    for rule in calendar.holidays.iter() {
+        let (Some(observed), Some(date)) = (rule.observed, rule.date_in(year)) else {
+            continue;
+        };
+        if !is_weekend(date) {
+            continue;
+        }
+        let substitute = match observed {
+            Observed::NearestWeekday => {
+                let before = date - Duration::days(1);
+                let after = date + Duration::days(1);
+                let step = if !is_weekend(before) && is_weekend(after) { -1 } else { 1 };
+                let mut candidate = date + Duration::days(step);
+                while is_weekend(candidate) {
+                    candidate += Duration::days(step);
+                }
+                candidate
+            }
+            Observed::NextWeekday => {
+                let mut candidate = date + Duration::days(1);
+                while is_weekend(candidate)
+                    || holidays.iter().chain(substitutes.iter()).any(|h| h.date == candidate)
+                {
+                    candidate += Duration::days(1);
+                }
+                candidate
+            }
+        };
+        substitutes.push(Holiday { date: substitute, name: format!("{} (observed)", rule.name) });
+    }
+
+    holidays.extend(substitutes);
+    holidays.sort_by_key(|h| h.date);
+    Ok(holidays)
+}
+
+/// Find a holiday by name ("thanksgiving", "Boxing Day") in a country's calendar
+pub fn find_by_name(country: &str, name: &str, year: i32) -> Option<Holiday> {
+    let wanted = normalize_name(name);
+    holidays_in_year(country, year, &DEFAULT_WEEKEND)
+        .ok()?
+        .into_iter()
+        .filter(|h| !h.name.ends_with("(observed)"))
+        .find(|h| {
+            let candidate = normalize_name(&h.name);
+            candidate == wanted || candidate.strip_suffix(" day") == Some(wanted.as_str())
+        })
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn test_all_calendars_parse() {
+        assert_eq!(CALENDARS.len(), CALENDAR_FILES.len());
+        assert!(has_calendar("us") && has_calendar("GB"));
+        for (code, _) in available_calendars() {
+            assert!(!holidays_in_year(&code, 2025, &DEFAULT_WEEKEND).unwrap().is_empty(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_easter() {
+        assert_eq!(easter_sunday(2024), Some(d(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(d(2025, 4, 20)));
+        assert_eq!(easter_sunday(2038), Some(d(2038, 4, 25)));
+    }
+
+    #[test]
+    fn test_us_rules() {
+        let holidays = holidays_in_year("US", 2024, &DEFAULT_WEEKEND).unwrap();
+        let date_of = |name: &str| holidays.iter().find(|h| h.name == name).map(|h| h.date);
+        assert_eq!(date_of("Memorial Day"), Some(d(2024, 5, 27)));
+        assert_eq!(date_of("Thanksgiving"), Some(d(2024, 11, 28)));
+        assert_eq!(date_of("Martin Luther King Jr. Day"), Some(d(2024, 1, 15)));
+        // July 4th 2026 is a Saturday: observed on Friday
+        let holidays = holidays_in_year("US", 2026, &DEFAULT_WEEKEND).unwrap();
+        assert!(holidays.iter().any(|h| h.date == d(2026, 7, 3) && h.name == "Independence Day (observed)"));
+    }
+
+    #[test]
+    fn test_substitutes_do_not_collide() {
+        // Christmas 2021 on Saturday, Boxing Day on Sunday → Mon 27 and Tue 28
+        let holidays = holidays_in_year("GB", 2021, &DEFAULT_WEEKEND).unwrap();
+        let observed: Vec<NaiveDate> = holidays.iter().filter(|h| h.name.ends_with("(observed)")).map(|h| h.date).collect();
+        assert!(observed.contains(&d(2021, 12, 27)));
+        assert!(observed.contains(&d(2021, 12, 28)));
+    }
+
+    #[test]
+    fn test_substitutes_follow_the_weekend() {
+        let observed = |country: &str, year: i32, weekend: &[Weekday]| -> Vec<NaiveDate> {
+            holidays_in_year(country, year, weekend)
+                .unwrap()
+                .into_iter()
+                .filter(|h| h.name.ends_with("(observed)"))
+                .map(|h| h.date)
+                .collect()
+        };
+        // July 4th 2025 is a Friday: a weekday for Sat/Sun, the weekend's first day for Fri/Sat
+        assert!(!observed("US", 2025, &DEFAULT_WEEKEND).contains(&d(2025, 7, 3)));
+        assert!(observed("US", 2025, &[Weekday::Fri, Weekday::Sat]).contains(&d(2025, 7, 3)));
+        // July 4th 2026 is a Saturday, the second day of a Fri/Sat weekend → Sunday
+        assert!(observed("US", 2026, &[Weekday::Fri, Weekday::Sat]).contains(&d(2026, 7, 5)));
+        // Christmas 2022 is a Sunday: with a Sunday-only weekend Boxing Day stays put
+        // and Christmas moves to Tuesday 27th
+        assert!(observed("GB", 2022, &[Weekday::Sun]).contains(&d(2022, 12, 27)));
+        assert!(holidays_in_year("US", 2025, &[]).unwrap().iter().all(|h| !h.name.ends_with("(observed)")));
+    }
+
+    #[test]
+    fn test_on_or_before_and_names() {
+        assert_eq!(find_by_name("CA", "Victoria Day", 2025).map(|h| h.date), Some(d(2025, 5, 19)));
+        assert_eq!(find_by_name("US", "thanksgiving", 2025).map(|h| h.date), Some(d(2025, 11, 27)));
+        assert_eq!(find_by_name("GB", "boxing", 2025).map(|h| h.date), Some(d(2025, 12, 26)));
+        assert_eq!(find_by_name("US", "Juneteenth", 2019), None);
+    }
+}
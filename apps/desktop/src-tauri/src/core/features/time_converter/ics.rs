@@ -0,0 +1,1448 @@
+//! iCalendar (.ics) events: parsing, recurrence expansion and retargeting
+//!
+//! Reads `VEVENT`s from a pasted calendar or from bare invite text that only
+//! has `DTSTART;TZID=...` lines. `TZID`s may be IANA names, Outlook's Windows
+//! names ("Eastern Standard Time") or Mozilla-style paths. `RRULE`s are
+//! expanded in the event's own zone so occurrences keep their wall-clock time
+//! across DST. `retarget` rewrites the events into another zone, writes a
+//! `VTIMEZONE` for every `TZID` left in the file and checks that every
+//! upcoming occurrence still lands on the same instant.
+
+use chrono::{Datelike, DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta, TimeZone, Utc, Weekday};
+use chrono_tz::{OffsetComponents, Tz};
+
+use super::{dst, holidays, zones};
+
+/// Occurrences listed per event when the request doesn't say
+pub const DEFAULT_OCCURRENCES: usize = 5;
+/// Most occurrences listed per event
+pub const MAX_OCCURRENCES: usize = 100;
+/// Recurrence periods stepped through before giving up (27 years of days),
+/// counted from the first period near the query window
+const MAX_PERIODS: i64 = 10_000;
+/// Largest `INTERVAL` accepted
+const MAX_INTERVAL: i64 = 1_000;
+/// Octets per line before folding (RFC 5545 §3.1)
+const FOLD_WIDTH: usize = 75;
+/// Earliest year a written VTIMEZONE lists clock changes from
+const MIN_ZONE_YEAR: i32 = 1900;
+/// Latest year past this one a written VTIMEZONE lists clock changes to
+const MAX_ZONE_YEARS_AHEAD: i32 = 10;
+
+const PRODID: &str = "-//Productivity Widgets//Time Converter//EN";
+
+/// Windows zone names used by Outlook and Exchange
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("UTC", "UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("FLE Standard Time", "Europe/Kyiv"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+];
+
+/// One content line: `NAME;PARAM=value:VALUE`
+#[derive(Debug, Clone, PartialEq)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn is_date_only(&self) -> bool {
+        self.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+            || (self.value.len() == 8 && self.value.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    fn to_line(&self) -> String {
+        let mut line = self.name.clone();
+        for (key, value) in &self.params {
+            let needs_quotes = value.contains([':', ';', ',']);
+            if needs_quotes {
+                line.push_str(&format!(";{}=\"{}\"", key, value));
+            } else {
+                line.push_str(&format!(";{}={}", key, value));
+            }
+        }
+        line.push(':');
+        line.push_str(&self.value);
+        line
+    }
+}
+
+/// Join folded lines (a line starting with a space or tab continues the previous one)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split a content line at the first `:` outside quotes
+fn parse_property(line: &str) -> Option<Property> {
+    let line = line.trim();
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    in_quotes = false;
+    for c in head.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+
+    let name = parts.remove(0).trim().to_uppercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    let params = parts
+        .into_iter()
+        .filter_map(|p| p.split_once('=').map(|(k, v)| (k.trim().to_uppercase(), v.trim().to_string())))
+        .collect();
+    Some(Property { name, params, value: value.trim().to_string() })
+}
+
+/// Resolve a `TZID`: IANA name, Windows name or a path ending in an IANA name
+pub fn resolve_tzid(tzid: &str) -> Option<Tz> {
+    let tzid = tzid.trim().trim_matches('"');
+    if let Some(tz) = zones::lookup_zone(tzid) {
+        return Some(tz);
+    }
+    if let Some((_, iana)) = WINDOWS_ZONES.iter().find(|(name, _)| name.eq_ignore_ascii_case(tzid)) {
+        return zones::lookup_zone(iana);
+    }
+    // "/mozilla.org/20050126_1/America/New_York", "/citadel.org/.../Europe/Paris"
+    let segments: Vec<&str> = tzid.split('/').filter(|s| !s.is_empty()).collect();
+    (1..segments.len()).find_map(|start| zones::lookup_zone(&segments[start..].join("/")))
+}
+
+/// Start or end of an event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventTime {
+    /// All-day (`VALUE=DATE`)
+    Date(NaiveDate),
+    /// Wall-clock time in a zone (`Z` times are in UTC, floating times in the default zone)
+    Local(NaiveDateTime, Tz),
+}
+
+impl EventTime {
+    fn date(&self) -> NaiveDate {
+        match self {
+            EventTime::Date(date) => *date,
+            EventTime::Local(local, _) => local.date(),
+        }
+    }
+}
+
+/// Wall-clock time in a zone as one instant; gaps move forward, overlaps take the first
+fn to_instant(local: NaiveDateTime, tz: Tz) -> Option<DateTime<Tz>> {
+    match dst::resolve_local(tz, local)? {
+        dst::LocalTime::Single(dt) => Some(dt),
+        dst::LocalTime::Gap { shifted, .. } => Some(shifted),
+        dst::LocalTime::Ambiguous { earlier, .. } => Some(earlier),
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// One `DATE` or `DATE-TIME` value, read in `TZID`, UTC or the default zone
+fn parse_event_time(value: &str, tzid: Option<Tz>, date_only: bool, default_tz: Tz) -> Option<EventTime> {
+    let value = value.trim();
+    if date_only {
+        return parse_date(value).map(EventTime::Date);
+    }
+    let (digits, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(rest) => (rest, true),
+        None => (value, false),
+    };
+    let local = NaiveDateTime::parse_from_str(digits, "%Y%m%dT%H%M%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(digits, "%Y%m%dT%H%M"))
+        .ok()?;
+    let tz = if utc { Tz::UTC } else { tzid.unwrap_or(default_tz) };
+    Some(EventTime::Local(local, tz))
+}
+
+/// `P1D`, `PT1H30M`, `-PT15M`, `P2W`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().to_uppercase();
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest.to_string()),
+        None => (false, value.trim_start_matches('+').to_string()),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+                let part = match (unit, in_time) {
+                    ('W', false) => TimeDelta::try_weeks(n),
+                    ('D', false) => TimeDelta::try_days(n),
+                    ('H', true) => TimeDelta::try_hours(n),
+                    ('M', true) => TimeDelta::try_minutes(n),
+                    ('S', true) => TimeDelta::try_seconds(n),
+                    _ => return None,
+                };
+                total = total.checked_add(&part?)?;
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// When a recurrence stops
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Until {
+    Date(NaiveDate),
+    Instant(DateTime<Utc>),
+    Local(NaiveDateTime),
+}
+
+/// The parts of an `RRULE` this module expands
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: i64,
+    pub count: Option<usize>,
+    until: Option<Until>,
+    /// Weekday with an optional ordinal (`2TU` = second Tuesday, `-1FR` = last Friday)
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// `-1FR` → `("-1", "FR")`; `None` when the item is too short or the split
+/// would fall inside a character
+fn split_byday(item: &str) -> Option<(&str, &str)> {
+    let split = item.len().checked_sub(2)?;
+    item.is_char_boundary(split).then(|| item.split_at(split))
+}
+
+fn parse_weekday_code(code: &str) -> Option<Weekday> {
+    let weekday = match code {
+        "MO" => "mon",
+        "TU" => "tue",
+        "WE" => "wed",
+        "TH" => "thu",
+        "FR" => "fri",
+        "SA" => "sat",
+        "SU" => "sun",
+        _ => return None,
+    };
+    holidays::parse_weekday(weekday)
+}
+
+/// Parse an `RRULE` value; parts that change which days repeat but aren't
+/// supported are errors, the rest are ignored with a note
+fn parse_rrule(value: &str) -> Result<(RecurrenceRule, Vec<String>), String> {
+    let mut freq = None;
+    let mut rule = RecurrenceRule {
+        freq: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        by_day: Vec::new(),
+        by_month_day: Vec::new(),
+        by_month: Vec::new(),
+    };
+    let mut notes = Vec::new();
+
+    for part in value.split(';').filter(|p| !p.trim().is_empty()) {
+        let (key, val) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Malformed RRULE part '{}'", part))?;
+        let val = val.trim().to_uppercase();
+        match key.trim().to_uppercase().as_str() {
+            "FREQ" => {
+                freq = Some(match val.as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    other => return Err(format!("FREQ={} isn't supported", other)),
+                })
+            }
+            "INTERVAL" => {
+                rule.interval = val.parse().ok().filter(|n: &i64| (1..=MAX_INTERVAL).contains(n))
+                    .ok_or_else(|| format!("Invalid INTERVAL '{}'", val))?
+            }
+            "COUNT" => rule.count = Some(val.parse().map_err(|_| format!("Invalid COUNT '{}'", val))?),
+            "UNTIL" => {
+                rule.until = Some(if val.len() == 8 {
+                    Until::Date(parse_date(&val).ok_or_else(|| format!("Invalid UNTIL '{}'", val))?)
+                } else {
+                    match parse_event_time(&val, None, false, Tz::UTC) {
+                        Some(EventTime::Local(local, _)) if val.ends_with('Z') => Until::Instant(Utc.from_utc_datetime(&local)),
+                        Some(EventTime::Local(local, _)) => Until::Local(local),
+                        _ => return Err(format!("Invalid UNTIL '{}'", val)),
+                    }
+                })
+            }
+            "BYDAY" => {
+                for item in val.split(',') {
+                    let item = item.trim();
+                    let invalid = || format!("Invalid BYDAY '{}'", item);
+                    let (ordinal, code) = split_byday(item).ok_or_else(invalid)?;
+                    let weekday = parse_weekday_code(code).ok_or_else(invalid)?;
+                    let ordinal = match ordinal {
+                        "" => None,
+                        n => Some(
+                            n.trim_start_matches('+')
+                                .parse()
+                                .ok()
+                                .filter(|n: &i32| (1..=53).contains(&n.unsigned_abs()))
+                                .ok_or_else(invalid)?,
+                        ),
+                    };
+                    rule.by_day.push((ordinal, weekday));
+                }
+            }
+            "BYMONTHDAY" => {
+                for day in val.split(',') {
+                    let day: i32 = day
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|d: &i32| (1..=31).contains(&d.unsigned_abs()))
+                        .ok_or_else(|| format!("Invalid BYMONTHDAY '{}'", day))?;
+                    rule.by_month_day.push(day);
+                }
+            }
+            "BYMONTH" => {
+                for month in val.split(',') {
+                    let month: u32 = month.trim().parse().map_err(|_| format!("Invalid BYMONTH '{}'", month))?;
+                    if !(1..=12).contains(&month) {
+                        return Err(format!("Invalid BYMONTH '{}'", month));
+                    }
+                    rule.by_month.push(month);
+                }
+            }
+            "WKST" => {}
+            "BYSETPOS" | "BYWEEKNO" | "BYYEARDAY" | "BYHOUR" | "BYMINUTE" | "BYSECOND" => {
+                return Err(format!("{} isn't supported", key.trim().to_uppercase()));
+            }
+            other => notes.push(format!("Ignored RRULE part {}", other)),
+        }
+    }
+
+    rule.freq = freq.ok_or("RRULE has no FREQ")?;
+    Ok((rule, notes))
+}
+
+/// A calendar event
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub location: Option<String>,
+    pub start: EventTime,
+    /// End minus start; all-day events last at least a day
+    pub duration: Duration,
+    pub rule: Option<RecurrenceRule>,
+    /// The `RRULE` as written, for descriptions
+    pub rrule_text: Option<String>,
+    pub exdates: Vec<EventTime>,
+    properties: Vec<Property>,
+    /// Components nested in the event (VALARM, ...) as read, for output
+    components: Vec<String>,
+}
+
+/// Every event in a calendar, plus what couldn't be read
+#[derive(Debug, Clone, Default)]
+pub struct Calendar {
+    pub events: Vec<IcsEvent>,
+    pub warnings: Vec<String>,
+    /// VCALENDAR-level properties (PRODID, X-WR-CALNAME, ...) for output
+    header: Vec<Property>,
+    /// VTIMEZONE components as read, by `TZID`, for events left in their own zone
+    timezones: Vec<(String, Vec<String>)>,
+}
+
+/// Whether text looks like an iCalendar snippet or invite
+pub fn looks_like_ics(text: &str) -> bool {
+    unfold(text).iter().any(|line| {
+        let upper = line.trim_start().to_uppercase();
+        upper.starts_with("BEGIN:VEVENT") || upper.starts_with("DTSTART:") || upper.starts_with("DTSTART;")
+    })
+}
+
+/// Unescape TEXT values (`\,` `\;` `\n` `\\`)
+fn unescape(value: &str) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn build_event(properties: Vec<Property>, default_tz: Tz, warnings: &mut Vec<String>) -> Option<IcsEvent> {
+    let label = properties
+        .iter()
+        .find(|p| p.name == "SUMMARY")
+        .map(|p| format!("'{}'", unescape(&p.value)))
+        .unwrap_or_else(|| "An event".to_string());
+
+    let read_time = |property: &Property, warnings: &mut Vec<String>| -> Option<EventTime> {
+        let tzid = match property.param("TZID") {
+            Some(tzid) => match resolve_tzid(tzid) {
+                Some(tz) => Some(tz),
+                None => {
+                    warnings.push(format!("{}: unknown timezone '{}', read as {}", label, tzid, default_tz.name()));
+                    None
+                }
+            },
+            None => None,
+        };
+        parse_event_time(&property.value, tzid, property.is_date_only(), default_tz)
+    };
+
+    let Some(start) = properties
+        .iter()
+        .find(|p| p.name == "DTSTART")
+        .and_then(|p| read_time(p, warnings))
+    else {
+        warnings.push(format!("{} has no readable DTSTART", label));
+        return None;
+    };
+
+    let end = properties.iter().find(|p| p.name == "DTEND").and_then(|p| read_time(p, warnings));
+    let duration = match (start, end) {
+        (EventTime::Date(s), Some(EventTime::Date(e))) => e - s,
+        (EventTime::Local(s, s_tz), Some(EventTime::Local(e, e_tz))) => match (to_instant(s, s_tz), to_instant(e, e_tz)) {
+            (Some(s), Some(e)) => e.with_timezone(&Utc) - s.with_timezone(&Utc),
+            _ => Duration::zero(),
+        },
+        _ => properties
+            .iter()
+            .find(|p| p.name == "DURATION")
+            .and_then(|p| parse_duration(&p.value))
+            .unwrap_or_else(|| if matches!(start, EventTime::Date(_)) { Duration::days(1) } else { Duration::zero() }),
+    };
+
+    let rrule_text = properties.iter().find(|p| p.name == "RRULE").map(|p| p.value.clone());
+    let rule = match rrule_text.as_deref().map(parse_rrule) {
+        Some(Ok((rule, notes))) => {
+            warnings.extend(notes.into_iter().map(|note| format!("{}: {}", label, note)));
+            Some(rule)
+        }
+        Some(Err(e)) => {
+            warnings.push(format!("{}: {}; showing the first occurrence only", label, e));
+            None
+        }
+        None => None,
+    };
+
+    let exdates = properties
+        .iter()
+        .filter(|p| p.name == "EXDATE")
+        .flat_map(|p| {
+            let tzid = p.param("TZID").and_then(resolve_tzid);
+            let date_only = p.is_date_only();
+            p.value
+                .split(',')
+                .filter_map(|v| parse_event_time(v, tzid, date_only, default_tz))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let text = |name: &str| properties.iter().find(|p| p.name == name).map(|p| unescape(&p.value));
+    Some(IcsEvent {
+        uid: text("UID"),
+        summary: text("SUMMARY"),
+        location: text("LOCATION").filter(|l| !l.is_empty()),
+        start,
+        duration,
+        rule,
+        rrule_text,
+        exdates,
+        properties,
+        components: Vec::new(),
+    })
+}
+
+/// Read every `VEVENT`; bare invite text with a `DTSTART` line is one event.
+/// Floating times (no `TZID`, no `Z`) are read in `default_tz`.
+pub fn parse_calendar(text: &str, default_tz: Tz) -> Calendar {
+    let mut calendar = Calendar::default();
+    let mut depth: Vec<String> = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    let mut nested: Vec<String> = Vec::new();
+    let mut timezone: Option<(Option<String>, Vec<String>)> = None;
+    let mut loose: Vec<Property> = Vec::new();
+
+    for line in unfold(text) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.to_uppercase();
+                if component == "VEVENT" {
+                    current = Some(Vec::new());
+                    nested.clear();
+                } else if current.is_some() {
+                    nested.push(line);
+                } else if component == "VTIMEZONE" {
+                    timezone = Some((None, vec![line]));
+                } else if let Some((_, lines)) = &mut timezone {
+                    lines.push(line);
+                }
+                depth.push(component);
+            }
+            "END" => {
+                let component = property.value.to_uppercase();
+                if component == "VEVENT" {
+                    if let Some(properties) = current.take() {
+                        let components = std::mem::take(&mut nested);
+                        calendar.events.extend(
+                            build_event(properties, default_tz, &mut calendar.warnings)
+                                .map(|event| IcsEvent { components, ..event }),
+                        );
+                    }
+                } else if current.is_some() {
+                    nested.push(line);
+                } else if let Some((_, lines)) = &mut timezone {
+                    lines.push(line);
+                    if component == "VTIMEZONE" {
+                        if let Some((Some(tzid), lines)) = timezone.take() {
+                            calendar.timezones.push((tzid, lines));
+                        }
+                    }
+                }
+                if let Some(index) = depth.iter().rposition(|c| *c == component) {
+                    depth.truncate(index);
+                }
+            }
+            _ => match (&mut current, &mut timezone, depth.last().map(String::as_str)) {
+                (Some(properties), _, Some("VEVENT")) => properties.push(property),
+                (Some(_), _, Some(_)) => nested.push(line),
+                (_, Some((tzid, lines)), Some(component)) => {
+                    if component == "VTIMEZONE" && property.name == "TZID" {
+                        *tzid = Some(property.value.clone());
+                    }
+                    lines.push(line);
+                }
+                (_, _, Some("VCALENDAR")) => calendar.header.push(property),
+                (_, _, None) => loose.push(property),
+                _ => {}
+            },
+        }
+    }
+
+    if calendar.events.is_empty() && loose.iter().any(|p| p.name == "DTSTART") {
+        calendar.events.extend(build_event(loose, default_tz, &mut calendar.warnings));
+    }
+    calendar
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year.checked_add(1)?, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+/// The nth weekday of a month, counting from the end when negative
+fn nth_weekday_of_month(year: i32, month: u32, weekday: Weekday, nth: i32) -> Option<NaiveDate> {
+    if nth > 0 {
+        return holidays::nth_weekday(year, month, weekday, nth);
+    }
+    let last = holidays::nth_weekday(year, month, weekday, -1)?;
+    let date = last - Duration::weeks((-nth - 1) as i64);
+    (date.month() == month).then_some(date)
+}
+
+/// Days of one month a monthly or yearly rule lands on
+fn days_in_month(rule: &RecurrenceRule, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+    let Some(last) = last_day_of_month(year, month) else {
+        return Vec::new();
+    };
+    let mut days: Vec<NaiveDate> = if !rule.by_month_day.is_empty() {
+        rule.by_month_day
+            .iter()
+            .filter_map(|&day| match day {
+                d if d > 0 => NaiveDate::from_ymd_opt(year, month, d as u32),
+                d if d < 0 => {
+                    let date = last + Duration::days(d as i64 + 1);
+                    (date.month() == month).then_some(date)
+                }
+                _ => None,
+            })
+            .filter(|date| rule.by_day.is_empty() || rule.by_day.iter().any(|(_, wd)| *wd == date.weekday()))
+            .collect()
+    } else if !rule.by_day.is_empty() {
+        rule.by_day
+            .iter()
+            .flat_map(|&(ordinal, weekday)| match ordinal {
+                Some(nth) => nth_weekday_of_month(year, month, weekday, nth).into_iter().collect::<Vec<_>>(),
+                None => (1..=5).filter_map(|nth| holidays::nth_weekday(year, month, weekday, nth)).collect(),
+            })
+            .collect()
+    } else {
+        NaiveDate::from_ymd_opt(year, month, default_day).into_iter().collect()
+    };
+    days.sort();
+    days.dedup();
+    days
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Candidate dates of period `k` (the kth day, week, month or year from the
+/// start); none once the dates leave chrono's range
+fn period_dates(rule: &RecurrenceRule, start: NaiveDate, k: i64) -> Vec<NaiveDate> {
+    let Some(step) = k.checked_mul(rule.interval) else {
+        return Vec::new();
+    };
+    let in_month = |date: &NaiveDate| rule.by_month.is_empty() || rule.by_month.contains(&date.month());
+    let mut dates: Vec<NaiveDate> = match rule.freq {
+        Frequency::Daily => {
+            let Some(date) = TimeDelta::try_days(step).and_then(|days| start.checked_add_signed(days)) else {
+                return Vec::new();
+            };
+            let day_ok = rule.by_day.is_empty() || rule.by_day.iter().any(|(_, wd)| *wd == date.weekday());
+            let month_day_ok = rule.by_month_day.is_empty() || rule.by_month_day.contains(&(date.day() as i32));
+            if day_ok && month_day_ok { vec![date] } else { Vec::new() }
+        }
+        Frequency::Weekly => {
+            let Some(week_start) = TimeDelta::try_weeks(step).and_then(|weeks| monday_of(start).checked_add_signed(weeks)) else {
+                return Vec::new();
+            };
+            let weekdays: Vec<Weekday> = if rule.by_day.is_empty() {
+                vec![start.weekday()]
+            } else {
+                rule.by_day.iter().map(|(_, wd)| *wd).collect()
+            };
+            weekdays
+                .into_iter()
+                .filter_map(|wd| week_start.checked_add_signed(Duration::days(wd.num_days_from_monday() as i64)))
+                .collect()
+        }
+        Frequency::Monthly => {
+            let Some(months) = (start.year() as i64 * 12 + start.month0() as i64).checked_add(step) else {
+                return Vec::new();
+            };
+            let Ok(year) = i32::try_from(months.div_euclid(12)) else {
+                return Vec::new();
+            };
+            days_in_month(rule, year, (months.rem_euclid(12) + 1) as u32, start.day())
+        }
+        Frequency::Yearly => {
+            let Some(year) = i32::try_from(step).ok().and_then(|step| start.year().checked_add(step)) else {
+                return Vec::new();
+            };
+            let months = if rule.by_month.is_empty() { vec![start.month()] } else { rule.by_month.clone() };
+            months
+                .into_iter()
+                .flat_map(|month| days_in_month(rule, year, month, start.day()))
+                .collect()
+        }
+    };
+    dates.retain(|date| in_month(date));
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+/// The period to start expanding from so that nothing on or after `from` is
+/// missed; one period early so a period starting before `from` is still seen
+fn first_period(rule: &RecurrenceRule, start: NaiveDate, from: NaiveDate) -> i64 {
+    if from <= start {
+        return 0;
+    }
+    let elapsed = match rule.freq {
+        Frequency::Daily => (from - start).num_days(),
+        Frequency::Weekly => (monday_of(from) - monday_of(start)).num_weeks(),
+        Frequency::Monthly => {
+            (from.year() as i64 - start.year() as i64) * 12 + from.month0() as i64 - start.month0() as i64
+        }
+        Frequency::Yearly => from.year() as i64 - start.year() as i64,
+    };
+    (elapsed / rule.interval - 1).max(0)
+}
+
+/// One occurrence of an event
+#[derive(Debug, Clone, PartialEq)]
+pub enum Occurrence {
+    AllDay { start: NaiveDate, end: NaiveDate },
+    Timed { start: DateTime<Tz>, end: DateTime<Tz> },
+}
+
+impl Occurrence {
+    fn start_utc(&self) -> DateTime<Utc> {
+        match self {
+            Occurrence::AllDay { start, .. } => Utc.from_utc_datetime(&start.and_time(NaiveTime::MIN)),
+            Occurrence::Timed { start, .. } => start.with_timezone(&Utc),
+        }
+    }
+
+    fn end_utc(&self) -> DateTime<Utc> {
+        match self {
+            Occurrence::AllDay { end, .. } => Utc.from_utc_datetime(&end.and_time(NaiveTime::MIN)),
+            Occurrence::Timed { end, .. } => end.with_timezone(&Utc),
+        }
+    }
+}
+
+fn occurrence_on(event: &IcsEvent, date: NaiveDate) -> Option<Occurrence> {
+    match event.start {
+        EventTime::Date(_) => Some(Occurrence::AllDay {
+            start: date,
+            end: date.checked_add_signed(TimeDelta::try_days(event.duration.num_days().max(1))?)?,
+        }),
+        EventTime::Local(local, tz) => {
+            let start = to_instant(date.and_time(local.time()), tz)?;
+            Some(Occurrence::Timed { start, end: start.checked_add_signed(event.duration)? })
+        }
+    }
+}
+
+fn is_excluded(event: &IcsEvent, date: NaiveDate, occurrence: &Occurrence) -> bool {
+    event.exdates.iter().any(|exdate| match exdate {
+        EventTime::Date(d) => *d == date,
+        EventTime::Local(local, tz) => to_instant(*local, *tz).is_some_and(|t| t.with_timezone(&Utc) == occurrence.start_utc()),
+    })
+}
+
+fn past_until(until: Until, date: NaiveDate, occurrence: &Occurrence) -> bool {
+    match until {
+        Until::Date(until) => date > until,
+        Until::Instant(until) => occurrence.start_utc() > until,
+        Until::Local(until) => match occurrence {
+            Occurrence::Timed { start, .. } => start.naive_local() > until,
+            Occurrence::AllDay { start, .. } => *start > until.date(),
+        },
+    }
+}
+
+/// The next `limit` occurrences that haven't ended by `now`; an event without
+/// a recurrence has its one occurrence, past or not
+pub fn upcoming_occurrences(event: &IcsEvent, now: DateTime<Utc>, limit: usize) -> Vec<Occurrence> {
+    let start_date = event.start.date();
+    let Some(rule) = &event.rule else {
+        return occurrence_on(event, start_date).into_iter().collect();
+    };
+
+    // COUNT has to be counted from the first occurrence; otherwise skip ahead
+    // to the occurrences that could still be running at `now`
+    let first = match rule.count {
+        Some(_) => 0,
+        None => now
+            .date_naive()
+            .checked_sub_signed(event.duration.max(Duration::zero()))
+            .and_then(|date| date.checked_sub_signed(Duration::days(2)))
+            .map_or(0, |from| first_period(rule, start_date, from)),
+    };
+
+    let mut found = Vec::new();
+    let mut generated = 0usize;
+    for k in first..first.saturating_add(MAX_PERIODS) {
+        for date in period_dates(rule, start_date, k) {
+            if date < start_date {
+                continue;
+            }
+            let Some(occurrence) = occurrence_on(event, date) else {
+                continue;
+            };
+            if rule.until.is_some_and(|until| past_until(until, date, &occurrence)) {
+                return found;
+            }
+            generated += 1;
+            if rule.count.is_some_and(|count| generated > count) {
+                return found;
+            }
+            if occurrence.end_utc() > now && !is_excluded(event, date, &occurrence) {
+                found.push(occurrence);
+                if found.len() >= limit {
+                    return found;
+                }
+            }
+        }
+    }
+    found
+}
+
+fn ordinal(n: i32) -> String {
+    match n {
+        -1 => "last".to_string(),
+        -2 => "second to last".to_string(),
+        n if n < 0 => format!("{}th from last", -n),
+        n => {
+            let suffix = match (n % 10, n % 100) {
+                (1, 11) | (2, 12) | (3, 13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{}{}", n, suffix)
+        }
+    }
+}
+
+/// "Every 2 weeks on Tue, Thu, 10 times" / "Monthly on the last Fri"
+pub fn describe_rule(rule: &RecurrenceRule) -> String {
+    let (unit, adverb) = match rule.freq {
+        Frequency::Daily => ("day", "Daily"),
+        Frequency::Weekly => ("week", "Weekly"),
+        Frequency::Monthly => ("month", "Monthly"),
+        Frequency::Yearly => ("year", "Yearly"),
+    };
+    let mut text = if rule.interval == 1 {
+        adverb.to_string()
+    } else {
+        format!("Every {} {}s", rule.interval, unit)
+    };
+
+    let weekday_name = |wd: Weekday| format!("{:?}", wd);
+    if !rule.by_day.is_empty() {
+        let days: Vec<String> = rule
+            .by_day
+            .iter()
+            .map(|(n, wd)| match n {
+                Some(n) => format!("the {} {}", ordinal(*n), weekday_name(*wd)),
+                None => weekday_name(*wd),
+            })
+            .collect();
+        text.push_str(&format!(" on {}", days.join(", ")));
+    }
+    if !rule.by_month_day.is_empty() {
+        let days: Vec<String> = rule
+            .by_month_day
+            .iter()
+            .map(|d| if *d == -1 { "last day".to_string() } else { ordinal(*d) })
+            .collect();
+        text.push_str(&format!(" on the {}", days.join(", ")));
+    }
+    if !rule.by_month.is_empty() {
+        let months: Vec<String> = rule
+            .by_month
+            .iter()
+            .filter_map(|m| NaiveDate::from_ymd_opt(2000, *m, 1).map(|d| d.format("%b").to_string()))
+            .collect();
+        text.push_str(&format!(" in {}", months.join(", ")));
+    }
+    if let Some(count) = rule.count {
+        text.push_str(&format!(", {} times", count));
+    }
+    match rule.until {
+        Some(Until::Date(date)) => text.push_str(&format!(" until {}", date.format("%-d %b %Y"))),
+        Some(Until::Instant(instant)) => text.push_str(&format!(" until {}", instant.format("%-d %b %Y"))),
+        Some(Until::Local(local)) => text.push_str(&format!(" until {}", local.format("%-d %b %Y"))),
+        None => {}
+    }
+    text
+}
+
+fn format_local(local: NaiveDateTime) -> String {
+    local.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// A `DATE-TIME` property rewritten into `target`
+fn retarget_property(property: &Property, target: Tz, default_tz: Tz) -> Property {
+    if property.is_date_only() {
+        return property.clone();
+    }
+    let tzid = property.param("TZID").and_then(resolve_tzid);
+    let values: Option<Vec<String>> = property
+        .value
+        .split(',')
+        .map(|value| match parse_event_time(value, tzid, false, default_tz)? {
+            EventTime::Local(local, tz) => {
+                let moved = to_instant(local, tz)?.with_timezone(&target).naive_local();
+                Some(if target == Tz::UTC { format!("{}Z", format_local(moved)) } else { format_local(moved) })
+            }
+            EventTime::Date(_) => None,
+        })
+        .collect();
+    let Some(values) = values else {
+        return property.clone();
+    };
+
+    let mut params: Vec<(String, String)> = property.params.iter().filter(|(k, _)| k != "TZID").cloned().collect();
+    if target != Tz::UTC {
+        params.insert(0, ("TZID".to_string(), target.name().to_string()));
+    }
+    Property { name: property.name.clone(), params, value: values.join(",") }
+}
+
+/// Move `BYDAY` weekdays and `BYMONTHDAY` days by the days DTSTART moved
+/// (Tue 11pm New York → Wed in Tokyo). Ordinal weekdays ("last Friday") and
+/// month days near the end of a month have no equivalent a day later, so
+/// those rules are errors.
+fn shift_rrule(value: &str, day_shift: i64) -> Result<String, String> {
+    if day_shift == 0 {
+        return Ok(value.to_string());
+    }
+    let parts = value.split(';').map(|part| match part.split_once('=') {
+        Some((key, days)) if key.trim().eq_ignore_ascii_case("BYDAY") => {
+            let shifted = days
+                .split(',')
+                .map(|item| {
+                    let item = item.trim();
+                    let weekday = split_byday(item)
+                        .filter(|(ordinal, _)| ordinal.is_empty())
+                        .and_then(|(_, code)| parse_weekday_code(&code.to_uppercase()))
+                        .ok_or_else(|| format!("BYDAY={} can't move to another day", item))?;
+                    let moved = (weekday.num_days_from_monday() as i64 + day_shift).rem_euclid(7) as u8;
+                    Ok(weekday_code(Weekday::try_from(moved).unwrap_or(weekday)))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(format!("{}={}", key, shifted.join(",")))
+        }
+        Some((key, days)) if key.trim().eq_ignore_ascii_case("BYMONTHDAY") => {
+            let shifted = days
+                .split(',')
+                .map(|item| {
+                    // Every month has days 1-28 and -28..-1, so these stay in the same month
+                    let in_month = |d: i64| (1..=28).contains(&d.abs());
+                    item.trim()
+                        .parse::<i64>()
+                        .ok()
+                        .map(|day| (day, day + day_shift))
+                        .filter(|&(day, moved)| in_month(day) && in_month(moved) && day.signum() == moved.signum())
+                        .map(|(_, moved)| moved.to_string())
+                        .ok_or_else(|| format!("BYMONTHDAY={} can't move to another day", item.trim()))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(format!("{}={}", key, shifted.join(",")))
+        }
+        _ => Ok(part.to_string()),
+    });
+    Ok(parts.collect::<Result<Vec<_>, String>>()?.join(";"))
+}
+
+/// Fold a line at 75 octets without splitting a character
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > FOLD_WIDTH {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out
+}
+
+/// `+0100`, `-0330`; seconds only when the offset has them
+fn format_ics_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.unsigned_abs();
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if s == 0 { format!("{}{:02}{:02}", sign, h, m) } else { format!("{}{:02}{:02}{:02}", sign, h, m, s) }
+}
+
+fn is_daylight(tz: Tz, instant: DateTime<Utc>) -> bool {
+    !tz.offset_from_utc_datetime(&instant.naive_utc()).dst_offset().is_zero()
+}
+
+/// One STANDARD or DAYLIGHT observance starting at a clock change
+fn observance(tz: Tz, change: &dst::Transition, rrule: Option<String>) -> Vec<String> {
+    let kind = if is_daylight(tz, change.at) { "DAYLIGHT" } else { "STANDARD" };
+    let onset = change.at.naive_utc() + Duration::seconds(change.offset_before as i64);
+    let mut lines = vec![format!("BEGIN:{}", kind), format!("DTSTART:{}", format_local(onset))];
+    lines.extend(rrule);
+    lines.extend([
+        format!("TZOFFSETFROM:{}", format_ics_offset(change.offset_before)),
+        format!("TZOFFSETTO:{}", format_ics_offset(change.offset_after)),
+        format!("TZNAME:{}", change.abbr_after),
+        format!("END:{}", kind),
+    ]);
+    lines
+}
+
+/// The yearly `RRULE` a clock change follows (`BYMONTH=3;BYDAY=-1SU`) when
+/// it lands on the same weekday of the month at the same time in `later`
+fn yearly_rule(change: &dst::Transition, later: &[dst::Transition]) -> Option<String> {
+    let onset = change.at.naive_utc() + Duration::seconds(change.offset_before as i64);
+    let (date, month) = (onset.date(), onset.month());
+    let last = last_day_of_month(date.year(), month)?;
+    let nth = if date.day() + 7 > last.day() { -1 } else { (date.day() as i32 - 1) / 7 + 1 };
+
+    let mut years = later.iter().map(|c| (c.at.naive_utc() + Duration::seconds(c.offset_before as i64)).year()).collect::<Vec<_>>();
+    years.dedup();
+    let repeats = years.iter().all(|&year| {
+        nth_weekday_of_month(year, month, date.weekday(), nth).is_some_and(|day| {
+            let expected = day.and_time(onset.time());
+            later.iter().any(|c| {
+                c.offset_before == change.offset_before
+                    && c.offset_after == change.offset_after
+                    && c.at.naive_utc() + Duration::seconds(c.offset_before as i64) == expected
+            })
+        })
+    });
+    (!years.is_empty() && repeats)
+        .then(|| format!("RRULE:FREQ=YEARLY;BYMONTH={};BYDAY={}{}", month, nth, weekday_code(date.weekday())))
+}
+
+/// A VTIMEZONE for `tz` under the name `tzid`: the offset on 1 January of
+/// `first_year`, every clock change up to `last_year`, and that year's
+/// changes repeated yearly while the zone keeps following the same rule
+fn vtimezone(tzid: &str, tz: Tz, first_year: i32, last_year: i32) -> Vec<String> {
+    let new_year = |year: i32| Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single();
+    let (Some(from), Some(until), Some(checked_until)) = (new_year(first_year), new_year(last_year + 1), new_year(last_year + 4))
+    else {
+        return Vec::new();
+    };
+    let half = (checked_until - from).num_days() / 2 + 1;
+    let changes = dst::transitions_near(tz, from + Duration::days(half), half);
+    let (changes, later): (Vec<_>, Vec<_>) = changes.into_iter().filter(|c| c.at >= from).partition(|c| c.at < until);
+
+    let offset = tz.offset_from_utc_datetime(&from.naive_utc()).fix().local_minus_utc();
+    let kind = if is_daylight(tz, from) { "DAYLIGHT" } else { "STANDARD" };
+    let mut lines = vec![
+        "BEGIN:VTIMEZONE".to_string(),
+        format!("TZID:{}", tzid),
+        format!("BEGIN:{}", kind),
+        format!("DTSTART:{}", format_local(from.naive_utc())),
+        format!("TZOFFSETFROM:{}", format_ics_offset(offset)),
+        format!("TZOFFSETTO:{}", format_ics_offset(offset)),
+        format!("TZNAME:{}", from.with_timezone(&tz).format("%Z")),
+        format!("END:{}", kind),
+    ];
+    let last_year_from = new_year(last_year).unwrap_or(until);
+    for change in &changes {
+        let rrule = (change.at >= last_year_from).then(|| yearly_rule(change, &later)).flatten();
+        lines.extend(observance(tz, change, rrule));
+    }
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// The calendar with every event moved to `target`, and a note for each
+/// upcoming occurrence that would land on a different instant
+pub fn retarget(calendar: &Calendar, target: Tz, default_tz: Tz, now: DateTime<Utc>, check: usize) -> (String, Vec<String>) {
+    let mut lines: Vec<String> = vec!["BEGIN:VCALENDAR".to_string()];
+    if !calendar.header.iter().any(|p| p.name == "VERSION") {
+        lines.push("VERSION:2.0".to_string());
+    }
+    if !calendar.header.iter().any(|p| p.name == "PRODID") {
+        lines.push(format!("PRODID:{}", PRODID));
+    }
+    for property in &calendar.header {
+        match property.name.as_str() {
+            // Apple and Google's default zone for floating times
+            "X-WR-TIMEZONE" => lines.push(format!("X-WR-TIMEZONE:{}", target.name())),
+            _ => lines.push(property.to_line()),
+        }
+    }
+
+    let mut notes = Vec::new();
+    let mut event_lines: Vec<String> = Vec::new();
+    let mut tzids: Vec<String> = Vec::new();
+    let mut write_event = |properties: &[Property], components: &[String]| {
+        for tzid in properties.iter().filter_map(|p| p.param("TZID")) {
+            if !tzids.iter().any(|t| t == tzid) {
+                tzids.push(tzid.to_string());
+            }
+        }
+        event_lines.push("BEGIN:VEVENT".to_string());
+        event_lines.extend(properties.iter().map(Property::to_line));
+        event_lines.extend(components.iter().cloned());
+        event_lines.push("END:VEVENT".to_string());
+    };
+
+    for event in &calendar.events {
+        let day_shift = match event.start {
+            EventTime::Local(local, tz) => to_instant(local, tz)
+                .map(|t| (t.with_timezone(&target).date_naive() - local.date()).num_days())
+                .unwrap_or(0),
+            EventTime::Date(_) => 0,
+        };
+
+        let name = event.summary.clone().unwrap_or_else(|| "Event".to_string());
+        let rewritten: Result<Vec<Property>, String> = event
+            .properties
+            .iter()
+            .map(|property| match property.name.as_str() {
+                "DTSTART" | "DTEND" | "EXDATE" | "RDATE" | "RECURRENCE-ID" => Ok(retarget_property(property, target, default_tz)),
+                "RRULE" => Ok(Property { value: shift_rrule(&property.value, day_shift)?, ..property.clone() }),
+                _ => Ok(property.clone()),
+            })
+            .collect();
+        let rewritten = match rewritten {
+            Ok(rewritten) => rewritten,
+            Err(e) => {
+                notes.push(format!("{}: left in its own zone because {} in {}", name, e, target.name()));
+                write_event(&event.properties, &event.components);
+                continue;
+            }
+        };
+        write_event(&rewritten, &event.components);
+
+        // Check every upcoming occurrence still lands on the same instant: an
+        // offset of less than a day means the zones change clocks on different
+        // dates, anything more that the rule itself moved
+        if let Some(moved_event) = build_event(rewritten, target, &mut Vec::new()) {
+            let before = upcoming_occurrences(event, now, check);
+            let after = upcoming_occurrences(&moved_event, now, check);
+            let mismatch = before.iter().zip(after.iter()).find(|(a, b)| a.start_utc() != b.start_utc());
+            let moved_days = mismatch.is_some_and(|(old, new)| (new.start_utc() - old.start_utc()).num_days() != 0);
+            if let Some((old, new)) = mismatch.filter(|_| !moved_days) {
+                notes.push(format!(
+                    "{}: the occurrence on {} moves by {} in {} because the two zones change clocks on different dates",
+                    name,
+                    old.start_utc().format("%-d %b %Y"),
+                    dst_shift(new.start_utc() - old.start_utc()),
+                    target.name(),
+                ));
+            } else if moved_days || before.len() != after.len() {
+                notes.push(format!("{}: the recurrence lands on different days in {}; check the RRULE", name, target.name()));
+            }
+        }
+    }
+
+    // Every TZID needs a VTIMEZONE in the same file: the target's is written
+    // out, events left in their own zone keep the one they came with. A day's
+    // shift can cross New Year, so start a year before the earliest event
+    let this_year = now.year();
+    let years = calendar.events.iter().map(|e| e.start.date().year());
+    let first_year = years.clone().min().unwrap_or(this_year).clamp(MIN_ZONE_YEAR, this_year) - 1;
+    let last_year = years.max().unwrap_or(this_year).clamp(this_year, this_year + MAX_ZONE_YEARS_AHEAD);
+    for tzid in &tzids {
+        let original = calendar.timezones.iter().find(|(name, _)| name == tzid);
+        match (tzid == target.name(), original, resolve_tzid(tzid)) {
+            (false, Some((_, block)), _) => lines.extend(block.iter().cloned()),
+            (_, _, Some(tz)) => lines.extend(vtimezone(tzid, tz, first_year, last_year)),
+            _ => {}
+        }
+    }
+    lines.extend(event_lines);
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = lines.iter().map(|line| fold(line)).collect::<Vec<_>>().join("\r\n");
+    ics.push_str("\r\n");
+    (ics, notes)
+}
+
+/// "+1h" / "-30m"
+fn dst_shift(delta: Duration) -> String {
+    let minutes = delta.num_minutes();
+    let sign = if minutes < 0 { '-' } else { '+' };
+    match (minutes.abs() / 60, minutes.abs() % 60) {
+        (h, 0) => format!("{}{}h", sign, h),
+        (0, m) => format!("{}{}m", sign, m),
+        (h, m) => format!("{}{}h {}m", sign, h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    const STANDUP: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example//EN\r\nBEGIN:VTIMEZONE\r\nTZID:Eastern Standard Time\r\nBEGIN:STANDARD\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0400\r\nTZOFFSETTO:-0500\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:standup-1\r\nSUMMARY:Team standup\\, daily\r\nDTSTART;TZID=Eastern Standard Time:20250303T090000\r\nDTEND;TZID=Eastern Standard Time:20250303T091500\r\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=10\r\nEXDATE;TZID=Eastern Standard Time:20250305T090000\r\nLOCATION:Zoom\r\nDESCRIPTION:A long description that goes on and on so the line has to be folded when written\r\n  back out again\r\nBEGIN:VALARM\r\nTRIGGER:-PT15M\r\nACTION:DISPLAY\r\nDESCRIPTION:Standup soon\r\nEND:VALARM\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parse_calendar() {
+        let calendar = parse_calendar(STANDUP, Tz::UTC);
+        assert!(calendar.warnings.is_empty(), "{:?}", calendar.warnings);
+        assert_eq!(calendar.events.len(), 1);
+        let event = &calendar.events[0];
+        assert_eq!(event.summary.as_deref(), Some("Team standup, daily"));
+        assert_eq!(event.location.as_deref(), Some("Zoom"));
+        let ny: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(event.start, EventTime::Local(NaiveDate::from_ymd_opt(2025, 3, 3).unwrap().and_hms_opt(9, 0, 0).unwrap(), ny));
+        assert_eq!(event.duration, Duration::minutes(15));
+        assert!(!event.properties.iter().any(|p| p.name == "TRIGGER"));
+        assert_eq!(event.components.len(), 5);
+        assert_eq!(describe_rule(event.rule.as_ref().unwrap()), "Weekly on Mon, Wed, Fri, 10 times");
+    }
+
+    #[test]
+    fn test_weekly_expansion_keeps_wall_clock_across_dst() {
+        let event = &parse_calendar(STANDUP, Tz::UTC).events[0];
+        let all = upcoming_occurrences(event, utc(2025, 1, 1, 0, 0), 50);
+        // 10 generated, 5 Mar excluded
+        assert_eq!(all.len(), 9);
+        let starts: Vec<DateTime<Utc>> = all.iter().map(|o| o.start_utc()).collect();
+        assert_eq!(starts[0], utc(2025, 3, 3, 14, 0));
+        // 9am EDT after 9 March is 13:00 UTC
+        assert_eq!(starts[2], utc(2025, 3, 10, 13, 0));
+        assert!(!starts.contains(&utc(2025, 3, 5, 14, 0)));
+
+        let upcoming = upcoming_occurrences(event, utc(2025, 3, 12, 0, 0), 2);
+        assert_eq!(upcoming.iter().map(|o| o.start_utc()).collect::<Vec<_>>(), vec![utc(2025, 3, 12, 13, 0), utc(2025, 3, 14, 13, 0)]);
+    }
+
+    #[test]
+    fn test_monthly_and_yearly_rules() {
+        let invite = "DTSTART;TZID=Europe/London:20250128T160000\nDURATION:PT1H\nRRULE:FREQ=MONTHLY;BYDAY=-1TU;UNTIL=20250601T000000Z\nSUMMARY:Review";
+        let calendar = parse_calendar(invite, Tz::UTC);
+        let event = &calendar.events[0];
+        let dates: Vec<NaiveDate> = upcoming_occurrences(event, utc(2025, 1, 1, 0, 0), 10)
+            .iter()
+            .map(|o| o.start_utc().date_naive())
+            .collect();
+        assert_eq!(dates.len(), 5);
+        assert_eq!(dates[1], NaiveDate::from_ymd_opt(2025, 2, 25).unwrap());
+        assert_eq!(dates[4], NaiveDate::from_ymd_opt(2025, 5, 27).unwrap());
+        assert_eq!(describe_rule(event.rule.as_ref().unwrap()), "Monthly on the last Tue until 1 Jun 2025");
+
+        // Monthly on the 31st skips short months
+        let end_of_month = parse_calendar("DTSTART:20250131T120000Z\nRRULE:FREQ=MONTHLY;COUNT=3", Tz::UTC);
+        let months: Vec<u32> = upcoming_occurrences(&end_of_month.events[0], utc(2025, 1, 1, 0, 0), 10)
+            .iter()
+            .map(|o| o.start_utc().month())
+            .collect();
+        assert_eq!(months, vec![1, 3, 5]);
+
+        // All-day yearly birthday
+        let birthday = parse_calendar("BEGIN:VEVENT\nDTSTART;VALUE=DATE:20200229\nRRULE:FREQ=YEARLY\nEND:VEVENT", Tz::UTC);
+        let next = upcoming_occurrences(&birthday.events[0], utc(2025, 1, 1, 0, 0), 1);
+        assert_eq!(next, vec![Occurrence::AllDay {
+            start: NaiveDate::from_ymd_opt(2028, 2, 29).unwrap(),
+            end: NaiveDate::from_ymd_opt(2028, 3, 1).unwrap(),
+        }]);
+    }
+
+    #[test]
+    fn test_tzid_forms_and_bad_input() {
+        assert_eq!(resolve_tzid("/mozilla.org/20050126_1/America/New_York").map(|t| t.name()), Some("America/New_York"));
+        assert_eq!(resolve_tzid("\"W. Europe Standard Time\"").map(|t| t.name()), Some("Europe/Berlin"));
+        assert_eq!(resolve_tzid("Nowhere/Special"), None);
+
+        let calendar = parse_calendar("DTSTART;TZID=Nowhere/Special:20250101T100000\nRRULE:FREQ=HOURLY", Tz::UTC);
+        assert_eq!(calendar.events.len(), 1);
+        assert_eq!(calendar.warnings.len(), 2, "{:?}", calendar.warnings);
+        assert!(calendar.events[0].rule.is_none());
+        assert!(parse_calendar("Lunch at noon", Tz::UTC).events.is_empty());
+        assert!(looks_like_ics("Join us\nDTSTART;TZID=Europe/Paris:20250101T100000"));
+        assert!(!looks_like_ics("The DTSTART field"));
+    }
+
+    #[test]
+    fn test_retarget() {
+        let calendar = parse_calendar(STANDUP, Tz::UTC);
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let (ics, notes) = retarget(&calendar, tokyo, Tz::UTC, utc(2025, 3, 1, 0, 0), 20);
+
+        // 9am Monday in New York is 11pm Monday in Tokyo before DST, 10pm after
+        assert!(ics.contains("DTSTART;TZID=Asia/Tokyo:20250303T230000\r\n"), "{}", ics);
+        assert!(ics.contains("EXDATE;TZID=Asia/Tokyo:20250305T230000\r\n"));
+        assert!(ics.contains("BEGIN:VTIMEZONE\r\nTZID:Asia/Tokyo\r\nBEGIN:STANDARD\r\n"), "{}", ics);
+        assert!(ics.contains("TZOFFSETTO:+0900\r\nTZNAME:JST\r\n"));
+        assert!(!ics.contains("Eastern Standard Time"));
+        assert!(ics.contains("BEGIN:VALARM\r\nTRIGGER:-PT15M\r\nACTION:DISPLAY\r\nDESCRIPTION:Standup soon\r\nEND:VALARM\r\nEND:VEVENT\r\n"));
+        assert!(ics.contains("PRODID:-//Example//EN"));
+        assert!(ics.lines().all(|line| line.len() <= FOLD_WIDTH));
+        assert_eq!(notes.len(), 1, "{:?}", notes);
+        assert!(notes[0].contains("10 Mar 2025") && notes[0].contains("+1h"), "{}", notes[0]);
+
+        // Round trip through the output
+        let reparsed = parse_calendar(&ics, Tz::UTC);
+        assert_eq!(reparsed.events[0].summary.as_deref(), Some("Team standup, daily"));
+        assert!(reparsed.events[0].properties.iter().any(|p| p.name == "DESCRIPTION" && p.value.ends_with("back out again")));
+
+        // Late evening in Los Angeles is the next day in London: BYDAY follows
+        let weekly = parse_calendar("DTSTART;TZID=America/Los_Angeles:20250107T200000\nRRULE:FREQ=WEEKLY;BYDAY=TU", Tz::UTC);
+        let london: Tz = "Europe/London".parse().unwrap();
+        let (ics, _) = retarget(&weekly, london, Tz::UTC, utc(2025, 1, 1, 0, 0), 5);
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=WE"), "{}", ics);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::weeks(1)));
+        assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_duration("P1H"), None);
+        assert_eq!(parse_duration("P99999999999999999W"), None);
+        assert_eq!(parse_duration("P9999999999999W9999999999999D"), None);
+    }
+
+    #[test]
+    fn test_out_of_range_rules_are_errors() {
+        for rrule in [
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;INTERVAL=9223372036854775807",
+            "FREQ=MONTHLY;BYMONTHDAY=-100000000",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYDAY=54MO",
+            "FREQ=MONTHLY;BYDAY=-2147483648MO",
+            "FREQ=WEEKLY;BYDAY=1É",
+            "FREQ=WEEKLY;BYDAY=É",
+        ] {
+            let calendar = parse_calendar(&format!("DTSTART:20250101T100000Z\nRRULE:{}", rrule), Tz::UTC);
+            assert!(calendar.events[0].rule.is_none(), "{}", rrule);
+            assert_eq!(calendar.warnings.len(), 1, "{}: {:?}", rrule, calendar.warnings);
+        }
+
+        // Dates past chrono's range end the series instead of panicking
+        let huge = parse_calendar("DTSTART:20250101T100000Z\nDURATION:P99999999W\nRRULE:FREQ=DAILY", Tz::UTC);
+        assert!(upcoming_occurrences(&huge.events[0], utc(2025, 1, 1, 0, 0), 5).is_empty());
+        let millennia = parse_calendar("DTSTART;VALUE=DATE:20250101\nRRULE:FREQ=YEARLY;INTERVAL=1000", Tz::UTC);
+        assert_eq!(upcoming_occurrences(&millennia.events[0], utc(2026, 1, 1, 0, 0), 500).len(), 260);
+    }
+
+    #[test]
+    fn test_long_running_series_skip_to_now() {
+        let daily = parse_calendar("DTSTART;TZID=Europe/Paris:19900105T083000\nDURATION:PT30M\nRRULE:FREQ=DAILY", Tz::UTC);
+        let next = upcoming_occurrences(&daily.events[0], utc(2026, 10, 18, 7, 0), 2);
+        assert_eq!(next.iter().map(|o| o.start_utc()).collect::<Vec<_>>(), vec![utc(2026, 10, 19, 6, 30), utc(2026, 10, 20, 6, 30)]);
+
+        // Still running at `now`
+        let weekly = parse_calendar("DTSTART:19800101T100000Z\nDURATION:P3D\nRRULE:FREQ=WEEKLY;INTERVAL=2", Tz::UTC);
+        let running = upcoming_occurrences(&weekly.events[0], utc(2026, 10, 22, 0, 0), 1);
+        assert_eq!(running[0].start_utc(), utc(2026, 10, 20, 10, 0));
+
+        let monthly = parse_calendar("DTSTART:19700131T120000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1", Tz::UTC);
+        let months: Vec<NaiveDate> = upcoming_occurrences(&monthly.events[0], utc(2026, 10, 18, 0, 0), 2)
+            .iter()
+            .map(|o| o.start_utc().date_naive())
+            .collect();
+        assert_eq!(months, vec![NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(), NaiveDate::from_ymd_opt(2026, 11, 30).unwrap()]);
+    }
+
+    #[test]
+    fn test_shift_rrule() {
+        assert_eq!(shift_rrule("FREQ=WEEKLY;BYDAY=MO,SU", 1).as_deref(), Ok("FREQ=WEEKLY;BYDAY=TU,MO"));
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYMONTHDAY=15,-2", 1).as_deref(), Ok("FREQ=MONTHLY;BYMONTHDAY=16,-1"));
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYMONTHDAY=1", -1).ok(), None);
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYMONTHDAY=28", 1).ok(), None);
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYMONTHDAY=-1", 1).ok(), None);
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYDAY=-1FR", 1).ok(), None);
+        assert_eq!(shift_rrule("FREQ=WEEKLY;BYDAY=1É", 1).ok(), None);
+        assert_eq!(shift_rrule("FREQ=MONTHLY;BYDAY=-1FR", 0).as_deref(), Ok("FREQ=MONTHLY;BYDAY=-1FR"));
+
+        // The last Friday at 8pm in Los Angeles has no single weekday in London
+        let review = parse_calendar("DTSTART;TZID=America/Los_Angeles:20250131T200000\nRRULE:FREQ=MONTHLY;BYDAY=-1FR", Tz::UTC);
+        let london: Tz = "Europe/London".parse().unwrap();
+        let (ics, notes) = retarget(&review, london, Tz::UTC, utc(2025, 1, 1, 0, 0), 5);
+        assert!(ics.contains("DTSTART;TZID=America/Los_Angeles:20250131T200000\r\n"), "{}", ics);
+        assert!(ics.contains("RRULE:FREQ=MONTHLY;BYDAY=-1FR\r\n"));
+        assert_eq!(notes.len(), 1, "{:?}", notes);
+        assert!(notes[0].contains("left in its own zone"), "{}", notes[0]);
+        // Bare invite: the zone it stays in gets a VTIMEZONE of its own
+        assert!(ics.contains("TZID:America/Los_Angeles\r\n"), "{}", ics);
+        assert!(ics.contains("RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r\nTZOFFSETFROM:-0800\r\nTZOFFSETTO:-0700\r\n"), "{}", ics);
+        assert!(ics.contains("RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r\nTZOFFSETFROM:-0700\r\nTZOFFSETTO:-0800\r\n"));
+        assert!(!ics.contains("TZID:Europe/London"));
+
+        // An Outlook event that stays put keeps the VTIMEZONE defining its name
+        let outlook = "BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Pacific Standard Time\r\nBEGIN:STANDARD\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0700\r\nTZOFFSETTO:-0800\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nDTSTART;TZID=Pacific Standard Time:20250131T200000\r\nRRULE:FREQ=MONTHLY;BYDAY=-1FR\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let (ics, _) = retarget(&parse_calendar(outlook, Tz::UTC), london, Tz::UTC, utc(2025, 1, 1, 0, 0), 5);
+        assert!(ics.contains("BEGIN:VTIMEZONE\r\nTZID:Pacific Standard Time\r\nBEGIN:STANDARD\r\nDTSTART:16010101T020000\r\n"), "{}", ics);
+        assert!(ics.contains("DTSTART;TZID=Pacific Standard Time:20250131T200000\r\n"));
+
+        // Mid-month days move with DTSTART and keep every instant
+        let rent = parse_calendar("DTSTART;TZID=America/Los_Angeles:20250115T200000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=15", Tz::UTC);
+        let (ics, notes) = retarget(&rent, london, Tz::UTC, utc(2025, 1, 1, 0, 0), 12);
+        assert!(ics.contains("RRULE:FREQ=MONTHLY;BYMONTHDAY=16\r\n"), "{}", ics);
+        assert!(notes.iter().all(|n| n.contains("change clocks")), "{:?}", notes);
+    }
+}
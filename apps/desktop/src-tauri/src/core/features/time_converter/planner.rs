@@ -0,0 +1,381 @@
+//! Meeting planner: find slots inside as many participants' working hours as possible
+//!
+//! Candidate start times are stepped in UTC across the organizer's date range and
+//! each participant's wall-clock time is derived per instant, so a DST change in
+//! any zone on any date moves that participant's working window correctly.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use super::zones;
+use crate::shared::types::{
+    MeetingParticipant, MeetingSlot, ParticipantSlotTime, PlanMeetingRequest, PlanMeetingResponse,
+};
+
+pub const DEFAULT_STEP_MINUTES: u32 = 30;
+pub const DEFAULT_MAX_RESULTS: usize = 5;
+/// Longest date range searched, in days
+pub const MAX_RANGE_DAYS: i64 = 31;
+/// Monday–Friday as ISO weekday numbers
+const DEFAULT_WORK_DAYS: &[u32] = &[1, 2, 3, 4, 5];
+
+/// A participant with parsed zone and hours
+struct Attendee {
+    name: String,
+    tz: Tz,
+    work_start: NaiveTime,
+    work_end: NaiveTime,
+    work_days: Vec<u32>,
+}
+
+impl Attendee {
+    fn from_participant(index: usize, participant: &MeetingParticipant) -> Result<Self, String> {
+        let tz = zones::lookup_zone(&participant.timezone)
+            .ok_or_else(|| format!("Participant {}: invalid timezone '{}'", index + 1, participant.timezone))?;
+        let work_start = parse_clock(&participant.work_start)
+            .ok_or_else(|| format!("Participant {}: invalid start time '{}' (use HH:MM)", index + 1, participant.work_start))?;
+        let work_end = parse_clock(&participant.work_end)
+            .ok_or_else(|| format!("Participant {}: invalid end time '{}' (use HH:MM)", index + 1, participant.work_end))?;
+        if work_start == work_end {
+            return Err(format!("Participant {}: working hours start and end at the same time", index + 1));
+        }
+
+        let work_days = participant.work_days.clone().unwrap_or_else(|| DEFAULT_WORK_DAYS.to_vec());
+        if let Some(day) = work_days.iter().find(|d| !(1..=7).contains(*d)) {
+            return Err(format!("Participant {}: invalid weekday {} (1 = Monday … 7 = Sunday)", index + 1, day));
+        }
+
+        let name = participant
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from)
+            .unwrap_or_else(|| zones::city_name(tz.name()));
+
+        Ok(Self { name, tz, work_start, work_end, work_days })
+    }
+
+    /// Minutes of slack between the slot and the edges of the working window, if inside it
+    fn margin_minutes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<i64> {
+        let local_start = start.with_timezone(&self.tz).naive_local();
+        let local_end = end.with_timezone(&self.tz).naive_local();
+
+        // The shift containing the slot starts today or, for overnight shifts, yesterday
+        [local_start.date().pred_opt(), Some(local_start.date())]
+            .into_iter()
+            .flatten()
+            .filter(|day| self.work_days.contains(&day.weekday().number_from_monday()))
+            .filter_map(|day| {
+                let (shift_start, shift_end) = self.shift_window(day)?;
+                (local_start >= shift_start && local_end <= shift_end).then(|| {
+                    (local_start - shift_start).num_minutes().min((shift_end - local_end).num_minutes())
+                })
+            })
+            .max()
+    }
+
+    fn shift_window(&self, day: NaiveDate) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let end_day = if self.work_end > self.work_start { day } else { day.succ_opt()? };
+        Some((day.and_time(self.work_start), end_day.and_time(self.work_end)))
+    }
+}
+
+fn parse_clock(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid {} '{}' (use YYYY-MM-DD)", field, value))
+}
+
+/// First instant of a local date (skips forward if midnight falls in a DST gap)
+fn start_of_day(tz: &Tz, date: NaiveDate) -> Option<DateTime<Utc>> {
+    (0..=2).find_map(|hour| {
+        tz.from_local_datetime(&date.and_hms_opt(hour, 0, 0)?)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+    })
+}
+
+struct Candidate {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    available: Vec<bool>,
+    available_count: usize,
+    /// Smallest slack among available participants (higher is more comfortable)
+    margin: i64,
+}
+
+/// Rank meeting slots across the date range
+pub fn plan_meeting(request: &PlanMeetingRequest, default_organizer: &str) -> Result<PlanMeetingResponse, String> {
+    if request.participants.is_empty() {
+        return Err("Add at least one participant".to_string());
+    }
+    if request.duration_minutes == 0 {
+        return Err("Meeting duration must be greater than zero".to_string());
+    }
+
+    let attendees = request
+        .participants
+        .iter()
+        .enumerate()
+        .map(|(i, p)| Attendee::from_participant(i, p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let organizer_id = request.organizer_timezone.as_deref().unwrap_or(default_organizer);
+    let organizer = zones::lookup_zone(organizer_id)
+        .ok_or_else(|| format!("Invalid organizer timezone '{}'", organizer_id))?;
+
+    let start_date = parse_date(&request.start_date, "start date")?;
+    let end_date = parse_date(&request.end_date, "end date")?;
+    if end_date < start_date {
+        return Err("End date is before start date".to_string());
+    }
+    if (end_date - start_date).num_days() >= MAX_RANGE_DAYS {
+        return Err(format!("Date range is limited to {} days", MAX_RANGE_DAYS));
+    }
+
+    let range_start = start_of_day(&organizer, start_date).ok_or("Start date is out of range")?;
+    let range_end = end_date
+        .succ_opt()
+        .and_then(|d| start_of_day(&organizer, d))
+        .ok_or("End date is out of range")?;
+
+    let step = Duration::minutes(i64::from(request.step_minutes.unwrap_or(DEFAULT_STEP_MINUTES).max(1)));
+    let duration = Duration::minutes(i64::from(request.duration_minutes));
+
+    let mut candidates = Vec::new();
+    let mut start = range_start;
+    while start + duration <= range_end {
+        let end = start + duration;
+        let margins: Vec<Option<i64>> = attendees.iter().map(|a| a.margin_minutes(start, end)).collect();
+        let available_count = margins.iter().filter(|m| m.is_some()).count();
+        if available_count > 0 {
+            candidates.push(Candidate {
+                start,
+                end,
+                available: margins.iter().map(Option::is_some).collect(),
+                available_count,
+                margin: margins.iter().flatten().copied().min().unwrap_or(0),
+            });
+        }
+        start += step;
+    }
+
+    candidates.sort_by(|a, b| {
+        b.available_count
+            .cmp(&a.available_count)
+            .then(b.margin.cmp(&a.margin))
+            .then(a.start.cmp(&b.start))
+    });
+
+    // Best slots first, without offering overlapping times
+    let max_results = request.max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1);
+    let mut picked: Vec<&Candidate> = Vec::new();
+    for candidate in &candidates {
+        if picked.len() >= max_results {
+            break;
+        }
+        if picked.iter().all(|p| candidate.end <= p.start || candidate.start >= p.end) {
+            picked.push(candidate);
+        }
+    }
+
+    println!(
+        "[plan_meeting] {} participants, {} candidate slots, {} picked",
+        attendees.len(),
+        candidates.len(),
+        picked.len()
+    );
+
+    let slots: Vec<MeetingSlot> = picked
+        .iter()
+        .map(|c| build_slot(c, &attendees, &organizer, request.duration_minutes))
+        .collect();
+
+    let summary = match slots.split_first() {
+        None => "No time found inside anyone's working hours in this date range.".to_string(),
+        Some((best, [])) => best.summary.clone(),
+        Some((best, others)) => {
+            let options: Vec<String> = picked[1..]
+                .iter()
+                .zip(others)
+                .map(|(c, slot)| {
+                    format!(
+                        "• {} ({}/{} available)",
+                        format_organizer_start(c.start, &organizer),
+                        slot.available_count,
+                        slot.participant_count
+                    )
+                })
+                .collect();
+            format!("{}\n\nOther options:\n{}", best.summary, options.join("\n"))
+        }
+    };
+
+    Ok(PlanMeetingResponse { slots, summary })
+}
+
+fn format_organizer_start(start: DateTime<Utc>, organizer: &Tz) -> String {
+    let local = start.with_timezone(organizer);
+    format!("{} {}", local.format("%a %d %b, %H:%M"), local.format("%Z"))
+}
+
+fn build_slot(candidate: &Candidate, attendees: &[Attendee], organizer: &Tz, duration_minutes: u32) -> MeetingSlot {
+    let participants: Vec<ParticipantSlotTime> = attendees
+        .iter()
+        .zip(&candidate.available)
+        .map(|(attendee, available)| {
+            let local_start = candidate.start.with_timezone(&attendee.tz);
+            let local_end = candidate.end.with_timezone(&attendee.tz);
+            let end_format = if local_end.date_naive() == local_start.date_naive() { "%H:%M" } else { "%a %d %b, %H:%M" };
+            ParticipantSlotTime {
+                name: attendee.name.clone(),
+                timezone: attendee.tz.name().to_string(),
+                local_start: local_start.format("%a %d %b, %H:%M").to_string(),
+                local_end: local_end.format(end_format).to_string(),
+                zone_abbr: local_start.format("%Z").to_string(),
+                in_working_hours: *available,
+            }
+        })
+        .collect();
+
+    let organizer_start = candidate.start.with_timezone(organizer);
+    let organizer_end = candidate.end.with_timezone(organizer);
+    let mut lines = vec![
+        format!(
+            "{}–{} {} ({} min)",
+            organizer_start.format("%a %d %b %Y, %H:%M"),
+            organizer_end.format("%H:%M"),
+            organizer_start.format("%Z"),
+            duration_minutes
+        ),
+        format!("{} of {} participants within working hours", candidate.available_count, attendees.len()),
+    ];
+    for p in &participants {
+        let note = if p.in_working_hours { "" } else { " ⚠ outside working hours" };
+        lines.push(format!(
+            "• {} — {}–{} {} ({}){}",
+            p.name, p.local_start, p.local_end, p.zone_abbr, p.timezone, note
+        ));
+    }
+
+    MeetingSlot {
+        start_utc: candidate.start.to_rfc3339(),
+        end_utc: candidate.end.to_rfc3339(),
+        available_count: candidate.available_count,
+        participant_count: attendees.len(),
+        participants,
+        summary: lines.join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(tz: &str, start: &str, end: &str) -> MeetingParticipant {
+        MeetingParticipant {
+            name: None,
+            timezone: tz.to_string(),
+            work_start: start.to_string(),
+            work_end: end.to_string(),
+            work_days: None,
+        }
+    }
+
+    fn request(participants: Vec<MeetingParticipant>, start: &str, end: &str, minutes: u32) -> PlanMeetingRequest {
+        PlanMeetingRequest {
+            participants,
+            start_date: start.to_string(),
+            end_date: end.to_string(),
+            duration_minutes: minutes,
+            organizer_timezone: Some("UTC".to_string()),
+            step_minutes: None,
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn test_finds_full_overlap() {
+        // New York 09–17 and Berlin 09–17 overlap 14:00–16:00 UTC in winter
+        let req = request(
+            vec![participant("America/New_York", "09:00", "17:00"), participant("Europe/Berlin", "09:00", "17:00")],
+            "2025-01-14",
+            "2025-01-14",
+            60,
+        );
+        let response = plan_meeting(&req, "UTC").unwrap();
+        let best = &response.slots[0];
+        assert_eq!(best.available_count, 2);
+        assert!(best.start_utc.starts_with("2025-01-14T14:") || best.start_utc.starts_with("2025-01-14T15:"));
+        assert!(response.summary.contains("2 of 2 participants"));
+    }
+
+    #[test]
+    fn test_respects_dst_transition() {
+        // US clocks change on 9 March 2025, Europe not until 30 March: the overlap
+        // shifts an hour earlier in UTC for New York only
+        let ny = participant("America/New_York", "09:00", "10:00");
+        let before = plan_meeting(&request(vec![ny.clone()], "2025-03-07", "2025-03-07", 60), "UTC").unwrap();
+        let after = plan_meeting(&request(vec![ny], "2025-03-10", "2025-03-10", 60), "UTC").unwrap();
+        assert_eq!(before.slots[0].start_utc, "2025-03-07T14:00:00+00:00");
+        assert_eq!(after.slots[0].start_utc, "2025-03-10T13:00:00+00:00");
+        assert_eq!(after.slots[0].participants[0].zone_abbr, "EDT");
+    }
+
+    #[test]
+    fn test_ranks_partial_overlap_and_skips_weekends() {
+        // Tokyo and Los Angeles have no weekday working-hours overlap
+        let req = request(
+            vec![participant("Asia/Tokyo", "09:00", "18:00"), participant("America/Los_Angeles", "09:00", "17:00")],
+            "2025-01-18",
+            "2025-01-19",
+            30,
+        );
+        let response = plan_meeting(&req, "UTC").unwrap();
+        // Tokyo's weekend covers the whole range; only Friday afternoon in Los Angeles
+        // (early Saturday UTC) qualifies
+        assert!(!response.slots.is_empty());
+        assert!(response.slots.iter().all(|s| s.available_count == 1));
+        assert!(response.slots.iter().all(|s| s.participants[1].in_working_hours));
+        assert!(response.slots.iter().all(|s| s.start_utc.starts_with("2025-01-18T00:")));
+    }
+
+    #[test]
+    fn test_overnight_shift() {
+        let req = request(vec![participant("UTC", "22:00", "06:00")], "2025-01-14", "2025-01-14", 60);
+        let response = plan_meeting(&req, "UTC").unwrap();
+        assert!(!response.slots.is_empty());
+        for slot in &response.slots {
+            let hour: u32 = slot.start_utc[11..13].parse().unwrap();
+            assert!(!(6..22).contains(&hour), "slot outside shift: {}", slot.start_utc);
+        }
+    }
+
+    #[test]
+    fn test_slots_do_not_overlap() {
+        let req = request(vec![participant("Europe/London", "09:00", "17:00")], "2025-01-14", "2025-01-14", 60);
+        let response = plan_meeting(&req, "UTC").unwrap();
+        assert_eq!(response.slots.len(), DEFAULT_MAX_RESULTS);
+        let parse = |s: &str| DateTime::parse_from_rfc3339(s).unwrap();
+        for (i, a) in response.slots.iter().enumerate() {
+            for b in &response.slots[i + 1..] {
+                assert!(parse(&a.end_utc) <= parse(&b.start_utc) || parse(&a.start_utc) >= parse(&b.end_utc));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let ok = participant("Europe/Paris", "09:00", "17:00");
+        assert!(plan_meeting(&request(vec![], "2025-01-14", "2025-01-14", 30), "UTC").is_err());
+        assert!(plan_meeting(&request(vec![ok.clone()], "2025-01-14", "2025-01-13", 30), "UTC").is_err());
+        assert!(plan_meeting(&request(vec![ok.clone()], "2025-01-01", "2025-03-01", 30), "UTC").is_err());
+        assert!(plan_meeting(&request(vec![ok], "2025-01-14", "2025-01-14", 0), "UTC").is_err());
+        assert!(plan_meeting(&request(vec![participant("Mars/Base", "09:00", "17:00")], "2025-01-14", "2025-01-14", 30), "UTC").is_err());
+        assert!(plan_meeting(&request(vec![participant("UTC", "9am", "17:00")], "2025-01-14", "2025-01-14", 30), "UTC").is_err());
+    }
+}
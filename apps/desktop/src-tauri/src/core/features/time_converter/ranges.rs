@@ -0,0 +1,207 @@
+//! Find every time and time range in a selection
+//!
+//! "9:30–11am EST" yields one range (the start borrows "am" from the end);
+//! "Sessions at 2pm and 5pm CET" yields two times. A bare number only counts
+//! as a time when it has minutes or am/pm, so dates and quantities are skipped.
+
+use std::sync::OnceLock;
+use chrono::NaiveTime;
+use regex::{Captures, Regex};
+
+/// A time or range found in text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSpan {
+    /// The text as written ("9:30–11am")
+    pub original: String,
+    pub start: NaiveTime,
+    /// End of a range; earlier than `start` when it crosses midnight
+    pub end: Option<NaiveTime>,
+}
+
+static TIME_SPAN_REGEX: OnceLock<Regex> = OnceLock::new();
+static DATE_CONTEXT_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_time_span_regex() -> &'static Regex {
+    TIME_SPAN_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?ix)
+            (?:^|[^\d:/.\-])
+            (?P<span>
+                (?P<h1>\d{1,2})(?::(?P<m1>[0-5]\d))?\s*(?P<ap1>[ap]\.?m\b\.?)?
+                (?:\s*(?:-|–|—|\bto\b|\buntil\b|\btill\b)\s*
+                    (?P<h2>\d{1,2})(?::(?P<m2>[0-5]\d))?\s*(?P<ap2>[ap]\.?m\b\.?)?
+                )?
+            )",
+        )
+        .expect("Invalid time span regex")
+    })
+}
+
+fn get_date_context_regex() -> &'static Regex {
+    DATE_CONTEXT_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(today|tonight|tomorrow|yesterday|(?:next\s+|this\s+)?(?:mon|tues|wednes|thurs|fri|satur|sun)day|\d{1,2}\s+(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*(?:\s+\d{4})?|(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\s+\d{1,2}(?:,?\s+\d{4})?|\d{4}-\d{2}-\d{2})\b",
+        )
+        .expect("Invalid date context regex")
+    })
+}
+
+/// Whether an am/pm marker means pm
+fn is_pm(marker: &str) -> bool {
+    marker.to_lowercase().starts_with('p')
+}
+
+/// Build a clock time from hour, minutes and an optional am/pm marker
+fn to_time(hour: u32, minute: u32, meridiem: Option<bool>) -> Option<NaiveTime> {
+    let hour = match meridiem {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn number(caps: &Captures, name: &str) -> Option<u32> {
+    caps.name(name).and_then(|m| m.as_str().parse().ok())
+}
+
+/// All times and ranges in the text, in order
+pub fn find_time_spans(text: &str) -> Vec<TimeSpan> {
+    get_time_span_regex()
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let span = caps.name("span")?;
+            let h1 = number(&caps, "h1")?;
+            let m1 = number(&caps, "m1");
+            let ap1 = caps.name("ap1").map(|m| is_pm(m.as_str()));
+
+            // The start on its own: needs minutes or am/pm to count as a time
+            let start_only = || {
+                if m1.is_none() && ap1.is_none() {
+                    return None;
+                }
+                let start_end = ["ap1", "m1", "h1"].iter().find_map(|name| caps.name(name))?.end();
+                Some(TimeSpan {
+                    original: text[span.start()..start_end].trim().to_string(),
+                    start: to_time(h1, m1.unwrap_or(0), ap1)?,
+                    end: None,
+                })
+            };
+
+            let Some(h2) = number(&caps, "h2") else {
+                return start_only();
+            };
+
+            let m2 = number(&caps, "m2");
+            let ap2 = caps.name("ap2").map(|m| is_pm(m.as_str()));
+            if ap1.is_none() && ap2.is_none() && (m1.is_none() || m2.is_none()) {
+                // "3-5" is more likely a score than a range, and "10:30-12"
+                // has no clear end: keep only a start that reads as a time
+                return start_only();
+            }
+
+            let Some(end) = to_time(h2, m2.unwrap_or(0), ap2) else {
+                return start_only();
+            };
+            let start = match (ap1, ap2) {
+                (Some(_), _) | (None, None) => to_time(h1, m1.unwrap_or(0), ap1)?,
+                // "9:30–11am": borrow the end's marker unless that puts the start after the end
+                (None, Some(pm)) => {
+                    let borrowed = to_time(h1, m1.unwrap_or(0), Some(pm))?;
+                    if borrowed <= end { borrowed } else { to_time(h1, m1.unwrap_or(0), Some(!pm))? }
+                }
+            };
+
+            Some(TimeSpan {
+                original: span.as_str().trim().to_string(),
+                start,
+                end: Some(end),
+            })
+        })
+        .collect()
+}
+
+/// Date words that apply to every time in the text ("tomorrow", "15 Mar", "2025-03-15")
+pub fn find_date_context(text: &str) -> Option<String> {
+    get_date_context_regex()
+        .find(text)
+        .map(|m| m.as_str().to_string())
+}
+
+/// "9:30am" / "11am" / "12:15pm"
+pub fn format_clock(time: NaiveTime) -> String {
+    let formatted = time.format("%-I:%M%P").to_string();
+    formatted.replace(":00", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).expect("Invalid test time")
+    }
+
+    #[test]
+    fn test_range_borrows_meridiem() {
+        let spans = find_time_spans("9:30–11am EST");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].original, "9:30–11am");
+        assert_eq!(spans[0].start, t(9, 30));
+        assert_eq!(spans[0].end, Some(t(11, 0)));
+
+        // 11–1pm starts in the morning
+        let spans = find_time_spans("11-1pm");
+        assert_eq!(spans[0].start, t(11, 0));
+        assert_eq!(spans[0].end, Some(t(13, 0)));
+    }
+
+    #[test]
+    fn test_multiple_times() {
+        let spans = find_time_spans("Sessions at 2pm and 5pm CET");
+        let starts: Vec<NaiveTime> = spans.iter().map(|s| s.start).collect();
+        assert_eq!(starts, vec![t(14, 0), t(17, 0)]);
+        assert!(spans.iter().all(|s| s.end.is_none()));
+    }
+
+    #[test]
+    fn test_ranges_with_words_and_midnight() {
+        let spans = find_time_spans("from 10pm to 1am PST");
+        assert_eq!(spans[0].start, t(22, 0));
+        assert_eq!(spans[0].end, Some(t(1, 0)));
+
+        let spans = find_time_spans("14:00 - 15:30 CET");
+        assert_eq!(spans[0].start, t(14, 0));
+        assert_eq!(spans[0].end, Some(t(15, 30)));
+    }
+
+    #[test]
+    fn test_ignores_dates_and_numbers() {
+        assert!(find_time_spans("15 Mar 2025").is_empty());
+        assert!(find_time_spans("2025-03-15").is_empty());
+        assert!(find_time_spans("the score was 3-5").is_empty());
+        assert!(find_time_spans("13pm").is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_end_keeps_start() {
+        let spans = find_time_spans("Call 10:30 - 12");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].original, "10:30");
+        assert_eq!(spans[0].start, t(10, 30));
+        assert_eq!(spans[0].end, None);
+
+        let spans = find_time_spans("2pm-13pm");
+        assert_eq!((spans[0].original.as_str(), spans[0].start, spans[0].end), ("2pm", t(14, 0), None));
+    }
+
+    #[test]
+    fn test_date_context_and_format() {
+        assert_eq!(find_date_context("tomorrow 2pm and 5pm").as_deref(), Some("tomorrow"));
+        assert_eq!(find_date_context("Mar 15, 9-11am").as_deref(), Some("Mar 15"));
+        assert_eq!(find_date_context("2pm CET"), None);
+        assert_eq!(format_clock(t(9, 30)), "9:30am");
+        assert_eq!(format_clock(t(23, 0)), "11pm");
+    }
+}
@@ -0,0 +1,232 @@
+//! Sunrise, sunset and civil twilight, computed offline
+//!
+//! Coordinates for the curated cities in `ALL_TIMEZONES` are bundled in
+//! `cities.json`. The sun's position uses the sunrise equation (NOAA's
+//! simplified form), which is good to about a minute away from the poles:
+//! enough to tell whether a call lands after dark.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use super::zones;
+use crate::shared::types::{DayPeriod, SolarCondition, SolarInfo};
+
+/// Sun altitude at sunrise/sunset: refraction plus the sun's radius
+const SUNRISE_ALTITUDE: f64 = -0.833;
+/// Sun altitude at the start and end of civil twilight
+const CIVIL_ALTITUDE: f64 = -6.0;
+/// Earth's axial tilt in degrees
+const OBLIQUITY: f64 = 23.4397;
+/// Julian date of 2000-01-01 12:00 UTC
+const J2000: f64 = 2_451_545.0;
+/// Julian date of the Unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// A city's position, bundled per timezone
+#[derive(Debug, Clone, Deserialize)]
+pub struct CityCoordinates {
+    pub timezone: String,
+    pub city: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Cities by zone name, under both the listed and the current IANA name
+static CITIES: Lazy<HashMap<String, CityCoordinates>> = Lazy::new(|| {
+    let cities: Vec<CityCoordinates> = match serde_json::from_str(include_str!("cities.json")) {
+        Ok(cities) => cities,
+        Err(e) => {
+            eprintln!("[solar] Invalid city coordinates: {}", e);
+            Vec::new()
+        }
+    };
+
+    let mut index = HashMap::new();
+    for city in cities {
+        if let Some(tz) = zones::lookup_zone(&city.timezone) {
+            index.insert(tz.name().to_string(), city.clone());
+        }
+        index.insert(city.timezone.clone(), city);
+    }
+    index
+});
+
+/// Bundled coordinates for a zone's main city
+pub fn coordinates_for(tz: Tz) -> Option<&'static CityCoordinates> {
+    CITIES.get(tz.name())
+}
+
+/// The sun over one place on one local date
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolarDay {
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+    pub civil_dawn: Option<DateTime<Utc>>,
+    pub civil_dusk: Option<DateTime<Utc>>,
+    pub day_length: Duration,
+    pub condition: SolarCondition,
+}
+
+fn julian_to_utc(jd: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(((jd - UNIX_EPOCH_JD) * 86_400.0).round() as i64, 0)
+}
+
+/// Hour angle (degrees) at which the sun crosses `altitude`; Err(true) when it
+/// stays above all day, Err(false) when it stays below
+fn hour_angle(lat: f64, declination: f64, altitude: f64) -> Result<f64, bool> {
+    let (lat, declination) = (lat.to_radians(), declination.to_radians());
+    let cos_omega = (altitude.to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    if cos_omega < -1.0 {
+        Err(true)
+    } else if cos_omega > 1.0 {
+        Err(false)
+    } else {
+        Ok(cos_omega.acos().to_degrees())
+    }
+}
+
+/// Sunrise, sunset and civil twilight at a position (east longitude positive)
+pub fn solar_day(lat: f64, lon: f64, date: NaiveDate) -> SolarDay {
+    let days = date.signed_duration_since(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or(date)).num_days() as f64;
+
+    // Mean solar noon at this longitude, then the sun's true position
+    let mean_noon = days + 0.0009 - lon / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.0200 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    let transit = J2000 + mean_noon + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic_longitude.to_radians()).sin();
+    let declination = (ecliptic_longitude.to_radians().sin() * OBLIQUITY.to_radians().sin()).asin().to_degrees();
+
+    let crossings = |altitude: f64| match hour_angle(lat, declination, altitude) {
+        Ok(omega) => (julian_to_utc(transit - omega / 360.0), julian_to_utc(transit + omega / 360.0), None),
+        Err(always_above) => (None, None, Some(always_above)),
+    };
+    let (sunrise, sunset, polar) = crossings(SUNRISE_ALTITUDE);
+    let (civil_dawn, civil_dusk, _) = crossings(CIVIL_ALTITUDE);
+
+    let (condition, day_length) = match (polar, sunrise, sunset) {
+        (Some(true), _, _) => (SolarCondition::PolarDay, Duration::hours(24)),
+        (Some(false), _, _) => (SolarCondition::PolarNight, Duration::zero()),
+        (None, Some(rise), Some(set)) => (SolarCondition::Normal, set - rise),
+        _ => (SolarCondition::Normal, Duration::zero()),
+    };
+
+    SolarDay { sunrise, sunset, civil_dawn, civil_dusk, day_length, condition }
+}
+
+fn between(instant: DateTime<Utc>, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> bool {
+    matches!((start, end), (Some(start), Some(end)) if start <= instant && instant < end)
+}
+
+/// Day, twilight or night at an instant on this solar day
+pub fn day_period_at(day: &SolarDay, instant: DateTime<Utc>) -> DayPeriod {
+    match day.condition {
+        SolarCondition::PolarDay => DayPeriod::Day,
+        _ if between(instant, day.sunrise, day.sunset) => DayPeriod::Day,
+        // No civil dawn or dusk on a day with a sunrise: the sky never gets fully dark
+        SolarCondition::Normal if day.civil_dawn.is_none() => DayPeriod::Twilight,
+        _ if between(instant, day.civil_dawn, day.civil_dusk) => DayPeriod::Twilight,
+        _ => DayPeriod::Night,
+    }
+}
+
+/// "16h 38m"
+fn format_day_length(length: Duration) -> String {
+    let minutes = length.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// The solar day for `tz`'s main city on a local date, with times in `tz`
+pub fn solar_info(tz: Tz, date: NaiveDate) -> Option<SolarInfo> {
+    let city = coordinates_for(tz)?;
+    let day = solar_day(city.lat, city.lon, date);
+    let local = |instant: Option<DateTime<Utc>>| instant.map(|t| t.with_timezone(&tz).format("%-I:%M%P").to_string());
+
+    Some(SolarInfo {
+        city: city.city.clone(),
+        date: date.format("%a %-d %b").to_string(),
+        sunrise: local(day.sunrise),
+        sunset: local(day.sunset),
+        civil_dawn: local(day.civil_dawn),
+        civil_dusk: local(day.civil_dusk),
+        day_length: format_day_length(day.day_length),
+        day_length_minutes: day.day_length.num_minutes() as i32,
+        condition: day.condition,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::core::features::time_converter::ALL_TIMEZONES;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn assert_near(actual: Option<DateTime<Utc>>, expected: DateTime<Utc>) {
+        let actual = actual.expect("expected a time");
+        assert!((actual - expected).num_minutes().abs() <= 3, "{} vs {}", actual, expected);
+    }
+
+    #[test]
+    fn test_every_curated_zone_has_coordinates() {
+        for (label, iana_id, _) in ALL_TIMEZONES {
+            let tz: Tz = iana_id.parse().unwrap();
+            assert!(coordinates_for(tz).is_some(), "{} ({})", label, iana_id);
+        }
+        let kyiv = zones::lookup_zone("Europe/Kyiv").unwrap();
+        assert_eq!(coordinates_for(kyiv).map(|c| c.city.as_str()), Some("Kyiv"));
+    }
+
+    #[test]
+    fn test_london_midsummer() {
+        let day = solar_day(51.51, -0.13, d(2025, 6, 21));
+        // 4:43am and 9:21pm BST
+        assert_near(day.sunrise, Utc.with_ymd_and_hms(2025, 6, 21, 3, 43, 0).unwrap());
+        assert_near(day.sunset, Utc.with_ymd_and_hms(2025, 6, 21, 20, 21, 0).unwrap());
+        assert!(day.civil_dawn.unwrap() < day.sunrise.unwrap());
+        assert_eq!(day.condition, SolarCondition::Normal);
+        assert!((day.day_length.num_minutes() - (16 * 60 + 38)).abs() <= 4);
+    }
+
+    #[test]
+    fn test_southern_hemisphere_and_local_times() {
+        // Sydney midwinter: 7:00am to 4:54pm AEST
+        let sydney: Tz = "Australia/Sydney".parse().unwrap();
+        let info = solar_info(sydney, d(2025, 6, 21)).unwrap();
+        assert_eq!(info.city, "Sydney");
+        assert!(matches!(info.sunrise.as_deref(), Some("6:59am" | "7:00am" | "7:01am")), "{:?}", info.sunrise);
+        assert!(matches!(info.sunset.as_deref(), Some("4:53pm" | "4:54pm" | "4:55pm")), "{:?}", info.sunset);
+        assert!(info.day_length.starts_with("9h 5"), "{}", info.day_length);
+    }
+
+    #[test]
+    fn test_polar_days() {
+        // Tromsø: midnight sun in June; in December the sun stays down but civil twilight remains
+        let summer = solar_day(69.65, 18.96, d(2025, 6, 21));
+        assert_eq!(summer.condition, SolarCondition::PolarDay);
+        assert_eq!(summer.day_length, Duration::hours(24));
+
+        let winter = solar_day(69.65, 18.96, d(2025, 12, 21));
+        assert_eq!(winter.condition, SolarCondition::PolarNight);
+        assert!(winter.sunrise.is_none() && winter.civil_dawn.is_some());
+        let noon = Utc.with_ymd_and_hms(2025, 12, 21, 10, 45, 0).unwrap();
+        assert_eq!(day_period_at(&winter, noon), DayPeriod::Twilight);
+        assert_eq!(day_period_at(&winter, noon + Duration::hours(8)), DayPeriod::Night);
+    }
+
+    #[test]
+    fn test_day_period_at() {
+        let day = solar_day(40.71, -74.01, d(2025, 3, 1));
+        let sunrise = day.sunrise.unwrap();
+        assert_eq!(day_period_at(&day, sunrise + Duration::hours(2)), DayPeriod::Day);
+        assert_eq!(day_period_at(&day, sunrise - Duration::minutes(10)), DayPeriod::Twilight);
+        assert_eq!(day_period_at(&day, sunrise - Duration::hours(3)), DayPeriod::Night);
+    }
+}
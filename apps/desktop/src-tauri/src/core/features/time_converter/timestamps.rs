@@ -0,0 +1,378 @@
+//! Machine-readable timestamps: Unix epochs, ISO 8601, RFC 2822 and RFC 3339
+//!
+//! Epoch precision is inferred from the digit count (10 = seconds, 13 = ms,
+//! 16 = µs, 19 = ns) and only accepted when it lands between 1971 and 2100, so
+//! order numbers are left alone. A number with a unit or currency next to it
+//! ("384400000 m", "1500000000 JPY") is a quantity, never an epoch. A selection
+//! only counts as a timestamp on its own when it is a bare 10- or 13-digit
+//! epoch no more than a few years ahead, so phone numbers aren't read as dates.
+//! ISO 8601 without an offset is local time, as the standard says.
+
+use std::sync::OnceLock;
+use chrono::{Datelike, DateTime, FixedOffset, Local, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+
+use crate::shared::types::TimestampFormat;
+
+/// Every format, in the order outputs are listed
+pub const ALL_FORMATS: &[TimestampFormat] = &[
+    TimestampFormat::LocalTime,
+    TimestampFormat::Iso8601,
+    TimestampFormat::Rfc3339,
+    TimestampFormat::Rfc2822,
+    TimestampFormat::EpochSeconds,
+    TimestampFormat::EpochMillis,
+    TimestampFormat::EpochMicros,
+    TimestampFormat::EpochNanos,
+];
+
+const MIN_PLAUSIBLE_YEAR: i32 = 1971;
+const MAX_PLAUSIBLE_YEAR: i32 = 2100;
+
+/// How far ahead a bare number may point and still be taken for a timestamp
+const MAX_YEARS_AHEAD: i64 = 5;
+
+/// Currency symbols that make a number an amount
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹', '₩', '₽', '₿'];
+
+/// Unit and currency words longer than an abbreviation
+const UNIT_WORDS: &[&str] = &[
+    "meters", "metres", "kilometers", "kilometres", "miles", "feet", "inches",
+    "grams", "kilograms", "pounds", "ounces", "tons", "tonnes", "liters", "litres", "gallons",
+    "bytes", "kilobytes", "megabytes", "gigabytes", "dollars", "euros", "rupees", "yuan",
+];
+
+/// Short words that follow numbers in prose and are not units
+const SHORT_WORDS: &[&str] = &["a", "an", "and", "as", "at", "by", "for", "is", "of", "on", "or", "the", "to", "was"];
+
+/// A timestamp found in text
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedTimestamp {
+    /// The text as written
+    pub original: String,
+    pub instant: DateTime<Utc>,
+    pub format: TimestampFormat,
+}
+
+static TIMESTAMP_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_timestamp_regex() -> &'static Regex {
+    TIMESTAMP_REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?x)
+            # ISO 8601 / RFC 3339, extended and basic
+            \d{4}-\d{2}-\d{2}[Tt\x20]\d{2}:\d{2}(?::\d{2}(?:[.,]\d{1,9})?)?(?:[Zz]|[+-]\d{2}(?::?\d{2})?)?
+            | \d{8}[Tt]\d{4}(?:\d{2}(?:[.,]\d{1,9})?)?(?:[Zz]|[+-]\d{2}(?:\d{2})?)?
+            # RFC 2822
+            | (?:(?:Mon|Tue|Wed|Thu|Fri|Sat|Sun),\s*)?\d{1,2}\s+(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+\d{4}
+              \s+\d{2}:\d{2}(?::\d{2})?\s+(?:[+-]\d{4}|UT|GMT|[ECMP][SD]T|Z)
+            # Unix epochs
+            | \b\d{10,19}(?:\.\d{1,9})?\b",
+        )
+        .expect("Invalid timestamp regex")
+    })
+}
+
+fn plausible(instant: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    (MIN_PLAUSIBLE_YEAR..=MAX_PLAUSIBLE_YEAR).contains(&instant.year()).then_some(instant)
+}
+
+/// Unix epoch in seconds (optionally fractional), ms, µs or ns by digit count
+fn parse_epoch(token: &str) -> Option<(DateTime<Utc>, TimestampFormat)> {
+    let (whole, fraction) = match token.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (token, None),
+    };
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: i64 = whole.parse().ok()?;
+
+    let (instant, format) = match (whole.len(), fraction) {
+        (10, fraction) => {
+            let nanos = match fraction {
+                Some(f) if !f.is_empty() && f.len() <= 9 && f.bytes().all(|b| b.is_ascii_digit()) => {
+                    format!("{:0<9}", f).parse::<u32>().ok()?
+                }
+                Some(_) => return None,
+                None => 0,
+            };
+            (Utc.timestamp_opt(value, nanos).single()?, TimestampFormat::EpochSeconds)
+        }
+        // Sub-second epochs are never written with a fraction
+        (_, Some(_)) => return None,
+        (11..=13, None) => (Utc.timestamp_millis_opt(value).single()?, TimestampFormat::EpochMillis),
+        (14..=16, None) => (DateTime::from_timestamp_micros(value)?, TimestampFormat::EpochMicros),
+        (17..=19, None) => (DateTime::from_timestamp_nanos(value), TimestampFormat::EpochNanos),
+        _ => return None,
+    };
+    Some((plausible(instant)?, format))
+}
+
+/// ISO 8601 with an offset in any of its spellings ("+02:00", "+0200", "+02", "Z")
+fn parse_iso_with_offset(token: &str) -> Option<DateTime<FixedOffset>> {
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f%#z",
+        "%Y-%m-%dT%H:%M%#z",
+        "%Y-%m-%d %H:%M:%S%.f%#z",
+        "%Y-%m-%d %H:%M%#z",
+        "%Y%m%dT%H%M%S%.f%#z",
+        "%Y%m%dT%H%M%#z",
+    ];
+    // chrono reads "Z" only through RFC 3339, so spell it as an offset
+    let normalized = token.replace(',', ".").replace('t', "T");
+    let normalized = match normalized.strip_suffix(['Z', 'z']) {
+        Some(rest) => format!("{}+00:00", rest),
+        None => normalized,
+    };
+    FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(&normalized, format).ok())
+}
+
+/// ISO 8601 without an offset, read as local time
+fn parse_iso_naive(token: &str) -> Option<DateTime<Utc>> {
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        // A space separator needs seconds, otherwise it's an ordinary date and time
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y%m%dT%H%M%S%.f",
+        "%Y%m%dT%H%M",
+    ];
+    let normalized = token.replace(',', ".").replace('t', "T");
+    let naive = FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Parse one whole token as a timestamp
+pub fn parse_timestamp(token: &str) -> Option<(DateTime<Utc>, TimestampFormat)> {
+    let token = token.trim();
+    if token.is_empty() {
+        return None;
+    }
+
+    if token.as_bytes()[0].is_ascii_digit() && !token.contains(['-', ':', 'T', 't', ' ']) {
+        return parse_epoch(token);
+    }
+
+    // RFC 3339 is the strict subset: full date, time with seconds, "Z" or "+hh:mm"
+    let rfc3339 = DateTime::parse_from_rfc3339(token)
+        .ok()
+        .filter(|_| token.as_bytes().get(10).is_some_and(|b| *b == b'T' || *b == b't'));
+    let (instant, format) = if let Some(dt) = rfc3339 {
+        (dt.with_timezone(&Utc), TimestampFormat::Rfc3339)
+    } else if let Ok(dt) = DateTime::parse_from_rfc2822(token) {
+        (dt.with_timezone(&Utc), TimestampFormat::Rfc2822)
+    } else if let Some(dt) = parse_iso_with_offset(token) {
+        (dt.with_timezone(&Utc), TimestampFormat::Iso8601)
+    } else {
+        (parse_iso_naive(token)?, TimestampFormat::Iso8601)
+    };
+    Some((plausible(instant)?, format))
+}
+
+/// Whether the number at `start..end` is a quantity: a currency symbol before
+/// it, or a unit or currency after it ("384400000 m", "1500000000 JPY")
+fn is_quantity(text: &str, start: usize, end: usize) -> bool {
+    if text[..start].trim_end().ends_with(CURRENCY_SYMBOLS) {
+        return true;
+    }
+    let after = text[end..].trim_start();
+    if after.starts_with(CURRENCY_SYMBOLS) || after.starts_with('%') {
+        return true;
+    }
+    let word: String = after.chars().take_while(|c| c.is_alphabetic()).collect::<String>().to_lowercase();
+    match word.chars().count() {
+        0 => false,
+        1..=3 => !SHORT_WORDS.contains(&word.as_str()),
+        _ => UNIT_WORDS.contains(&word.as_str()),
+    }
+}
+
+fn is_epoch(format: TimestampFormat) -> bool {
+    matches!(
+        format,
+        TimestampFormat::EpochSeconds
+            | TimestampFormat::EpochMillis
+            | TimestampFormat::EpochMicros
+            | TimestampFormat::EpochNanos
+    )
+}
+
+/// All timestamps in the text, in order
+pub fn find_timestamps(text: &str) -> Vec<DetectedTimestamp> {
+    get_timestamp_regex()
+        .find_iter(text)
+        .filter_map(|m| {
+            let (instant, format) = parse_timestamp(m.as_str())?;
+            if is_epoch(format) && is_quantity(text, m.start(), m.end()) {
+                return None;
+            }
+            Some(DetectedTimestamp {
+                original: m.as_str().trim().to_string(),
+                instant,
+                format,
+            })
+        })
+        .collect()
+}
+
+/// The first timestamp in the text
+pub fn find_timestamp(text: &str) -> Option<DetectedTimestamp> {
+    find_timestamps(text).into_iter().next()
+}
+
+/// Whether the selection is mostly a timestamp (a log line prefix doesn't count).
+/// Epochs must be the whole selection, 10 or 13 digits, and not far ahead.
+pub fn is_timestamp_text(text: &str) -> bool {
+    let trimmed = text.trim();
+    let Some(found) = find_timestamp(trimmed) else { return false };
+    if is_epoch(found.format) {
+        let latest = Utc::now() + chrono::Duration::days(365 * MAX_YEARS_AHEAD);
+        return found.original == trimmed
+            && matches!(trimmed.len(), 10 | 13)
+            && trimmed.bytes().all(|b| b.is_ascii_digit())
+            && found.instant <= latest;
+    }
+    found.original.len() * 2 >= trimmed.len()
+}
+
+/// Display name of a format
+pub fn format_label(format: TimestampFormat) -> &'static str {
+    match format {
+        TimestampFormat::EpochSeconds => "Unix seconds",
+        TimestampFormat::EpochMillis => "Unix milliseconds",
+        TimestampFormat::EpochMicros => "Unix microseconds",
+        TimestampFormat::EpochNanos => "Unix nanoseconds",
+        TimestampFormat::Iso8601 => "ISO 8601",
+        TimestampFormat::Rfc2822 => "RFC 2822",
+        TimestampFormat::Rfc3339 => "RFC 3339",
+        TimestampFormat::LocalTime => "Local time",
+    }
+}
+
+/// Write the instant in a format; `zone` applies to the date formats only
+pub fn render(instant: DateTime<Utc>, format: TimestampFormat, zone: Tz) -> String {
+    let zoned = instant.with_timezone(&zone);
+    match format {
+        TimestampFormat::EpochSeconds => match instant.timestamp_subsec_nanos() {
+            0 => instant.timestamp().to_string(),
+            nanos => {
+                let fraction = format!("{:09}", nanos);
+                format!("{}.{}", instant.timestamp(), fraction.trim_end_matches('0'))
+            }
+        },
+        TimestampFormat::EpochMillis => instant.timestamp_millis().to_string(),
+        TimestampFormat::EpochMicros => instant.timestamp_micros().to_string(),
+        TimestampFormat::EpochNanos => instant
+            .timestamp_nanos_opt()
+            .map(|nanos| nanos.to_string())
+            .unwrap_or_else(|| "out of range".to_string()),
+        // "Z" for UTC, milliseconds only when present
+        TimestampFormat::Iso8601 => zoned.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        TimestampFormat::Rfc3339 => zoned.to_rfc3339(),
+        TimestampFormat::Rfc2822 => zoned.to_rfc2822(),
+        TimestampFormat::LocalTime => zoned.format("%a %d %b %Y, %I:%M:%S%P %Z").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_epoch_precision_by_digits() {
+        let expected = utc("2025-03-14T14:30:00Z");
+        assert_eq!(parse_timestamp("1741962600"), Some((expected, TimestampFormat::EpochSeconds)));
+        assert_eq!(parse_timestamp("1741962600000"), Some((expected, TimestampFormat::EpochMillis)));
+        assert_eq!(parse_timestamp("1741962600000000"), Some((expected, TimestampFormat::EpochMicros)));
+        assert_eq!(parse_timestamp("1741962600000000000"), Some((expected, TimestampFormat::EpochNanos)));
+
+        let (fractional, _) = parse_timestamp("1741962600.25").unwrap();
+        assert_eq!(fractional.timestamp_subsec_millis(), 250);
+    }
+
+    #[test]
+    fn test_rejects_implausible_numbers() {
+        // Year 2286 and 1970-01-01 are not timestamps anyone means
+        assert_eq!(parse_timestamp("9999999999"), None);
+        assert_eq!(parse_timestamp("12345"), None);
+        assert_eq!(parse_timestamp("1741962600000.5"), None);
+        assert!(find_timestamps("Order 42 shipped").is_empty());
+    }
+
+    #[test]
+    fn test_quantities_and_phone_numbers_are_not_epochs() {
+        assert_eq!(parse_timestamp("999999999"), None, "9-digit epochs are not read");
+        for text in ["384400000 m", "1500000000 mg", "1500000000 JPY", "$1500000000", "1500000000 dollars", "1500000000%"] {
+            assert!(find_timestamps(text).is_empty(), "{} is a quantity", text);
+            assert!(!is_timestamp_text(text), "{} is a quantity", text);
+        }
+        assert_eq!(find_timestamps("deployed 1741962600 on prod").len(), 1);
+        // Phone numbers dated decades ahead are left alone
+        assert!(!is_timestamp_text("3125551234"));
+        assert!(!is_timestamp_text("call 3125551234"));
+    }
+
+    #[test]
+    fn test_iso_and_rfc_variants() {
+        let expected = utc("2025-03-14T14:30:00Z");
+        assert_eq!(parse_timestamp("2025-03-14T14:30:00Z"), Some((expected, TimestampFormat::Rfc3339)));
+        assert_eq!(parse_timestamp("2025-03-14T15:30:00+01:00"), Some((expected, TimestampFormat::Rfc3339)));
+        assert_eq!(parse_timestamp("2025-03-14T15:30:00+0100"), Some((expected, TimestampFormat::Iso8601)));
+        assert_eq!(parse_timestamp("20250314T143000Z"), Some((expected, TimestampFormat::Iso8601)));
+        assert_eq!(parse_timestamp("2025-03-14T10:30-04"), Some((expected, TimestampFormat::Iso8601)));
+        assert_eq!(parse_timestamp("Fri, 14 Mar 2025 14:30:00 +0000"), Some((expected, TimestampFormat::Rfc2822)));
+        assert_eq!(parse_timestamp("14 Mar 2025 09:30:00 EST"), Some((expected, TimestampFormat::Rfc2822)));
+
+        // No offset means local time
+        let (naive, format) = parse_timestamp("2025-03-14T14:30:00").unwrap();
+        assert_eq!(format, TimestampFormat::Iso8601);
+        assert_eq!(naive.with_timezone(&Local).naive_local().to_string(), "2025-03-14 14:30:00");
+    }
+
+    #[test]
+    fn test_find_in_log_line() {
+        let found = find_timestamps("[2025-03-14T14:30:00.123Z] worker exited at 1741962600");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].original, "2025-03-14T14:30:00.123Z");
+        assert_eq!(found[1].format, TimestampFormat::EpochSeconds);
+
+        assert!(is_timestamp_text(" 1741962600 "));
+        assert!(is_timestamp_text("1741962600000"));
+        assert!(!is_timestamp_text("1741962600000000"));
+        assert!(!is_timestamp_text("1741962600.5"));
+        assert!(!is_timestamp_text("2025-03-14T14:30:00Z worker 7 exited with status 1 after retries"));
+        assert!(!is_timestamp_text("2025-03-15"));
+    }
+
+    #[test]
+    fn test_render_all_formats() {
+        let instant = utc("2025-03-14T14:30:00Z");
+        let paris: Tz = "Europe/Paris".parse().unwrap();
+        assert_eq!(render(instant, TimestampFormat::EpochSeconds, paris), "1741962600");
+        assert_eq!(render(instant, TimestampFormat::EpochMillis, paris), "1741962600000");
+        assert_eq!(render(instant, TimestampFormat::Iso8601, Tz::UTC), "2025-03-14T14:30:00Z");
+        assert_eq!(render(instant, TimestampFormat::Iso8601, paris), "2025-03-14T15:30:00+01:00");
+        assert_eq!(render(instant, TimestampFormat::Rfc3339, Tz::UTC), "2025-03-14T14:30:00+00:00");
+        assert_eq!(render(instant, TimestampFormat::Rfc2822, paris), "Fri, 14 Mar 2025 15:30:00 +0100");
+        assert_eq!(render(instant, TimestampFormat::LocalTime, paris), "Fri 14 Mar 2025, 03:30:00pm CET");
+
+        // Round trip through every format
+        for format in ALL_FORMATS.iter().filter(|f| **f != TimestampFormat::LocalTime) {
+            let written = render(instant, *format, paris);
+            assert_eq!(parse_timestamp(&written).map(|(i, _)| i), Some(instant), "{:?}", format);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! World clock: pinned zones with custom labels, refreshed every minute
+//!
+//! Pinned zones come from `UserPreferences::world_clock`. `snapshot` renders
+//! them for one instant, with sunrise and sunset where the city is known, and
+//! `start_ticker` emits a fresh snapshot at the top of every minute so the
+//! clock widget stays current without polling.
+
+use std::sync::RwLock;
+use chrono::{DateTime, Offset, SecondsFormat, Timelike, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+
+use super::{solar, zones};
+use crate::shared::emit::emit_event;
+use crate::shared::events::AppEvent;
+use crate::shared::settings::PinnedZone;
+use crate::shared::types::{DayPeriod, WorldClockEntry, WorldClockSnapshot};
+
+/// Local hours counted as daytime (06:00–17:59) when the zone's city is unknown
+const DAY_HOURS: std::ops::Range<u32> = 6..18;
+
+/// Pinned zones from settings
+static PINNED_ZONES: Lazy<RwLock<Vec<PinnedZone>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Replace the pinned zones (called when settings are loaded or saved)
+pub fn set_pinned_zones(pinned: &[PinnedZone]) {
+    println!("[world_clock] {} pinned zone(s)", pinned.len());
+    match PINNED_ZONES.write() {
+        Ok(mut guard) => *guard = pinned.to_vec(),
+        Err(poisoned) => *poisoned.into_inner() = pinned.to_vec(),
+    }
+}
+
+/// The pinned zones, in display order
+pub fn pinned_zones() -> Vec<PinnedZone> {
+    match PINNED_ZONES.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Day or night from the local hour alone
+pub fn day_period(hour: u32) -> DayPeriod {
+    if DAY_HOURS.contains(&hour) {
+        DayPeriod::Day
+    } else {
+        DayPeriod::Night
+    }
+}
+
+/// "+9h" / "-5h 30m" / "Same time"
+fn format_offset_difference(seconds: i32) -> String {
+    if seconds == 0 {
+        return "Same time".to_string();
+    }
+    let sign = if seconds > 0 { '+' } else { '-' };
+    let minutes = seconds.abs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (h, 0) => format!("{}{}h", sign, h),
+        (0, m) => format!("{}{}m", sign, m),
+        (h, m) => format!("{}{}h {}m", sign, h, m),
+    }
+}
+
+/// One pinned zone at `now`; None when the zone name isn't recognised
+pub fn clock_entry(pin: &PinnedZone, now: DateTime<Utc>, local: Tz) -> Option<WorldClockEntry> {
+    let tz = zones::lookup_zone(&pin.timezone)?;
+    let zoned = now.with_timezone(&tz);
+    let here = now.with_timezone(&local);
+    let offset = zoned.offset().fix().local_minus_utc();
+    let label = pin
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| zones::city_name(tz.name()));
+
+    let date = zoned.date_naive();
+    let day_period = match solar::coordinates_for(tz) {
+        Some(city) => solar::day_period_at(&solar::solar_day(city.lat, city.lon, date), now),
+        None => day_period(zoned.hour()),
+    };
+
+    Some(WorldClockEntry {
+        timezone: tz.name().to_string(),
+        label,
+        time: zoned.format("%-I:%M%P").to_string(),
+        date: zoned.format("%a %-d %b").to_string(),
+        iso: zoned.to_rfc3339_opts(SecondsFormat::Secs, false),
+        abbreviation: zoned.format("%Z").to_string(),
+        utc_offset: super::format_utc_offset(offset),
+        offset_from_local: format_offset_difference(offset - here.offset().fix().local_minus_utc()),
+        day_offset: (date - here.date_naive()).num_days() as i32,
+        day_period,
+        solar: solar::solar_info(tz, date),
+    })
+}
+
+/// Every pinned zone at `now`, skipping unknown zones
+pub fn snapshot(pinned: &[PinnedZone], now: DateTime<Utc>, local: Tz) -> WorldClockSnapshot {
+    let entries = pinned
+        .iter()
+        .filter_map(|pin| {
+            let entry = clock_entry(pin, now, local);
+            if entry.is_none() {
+                eprintln!("[world_clock] Skipping unknown zone '{}'", pin.timezone);
+            }
+            entry
+        })
+        .collect();
+
+    WorldClockSnapshot {
+        generated_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        local_timezone: local.name().to_string(),
+        entries,
+    }
+}
+
+/// Emit a `WorldClockTick` at the start of every minute while any zone is pinned
+pub fn start_ticker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        println!("[world_clock] Ticker started");
+        loop {
+            let now = Utc::now();
+            let into_minute = std::time::Duration::new(now.second() as u64, now.nanosecond() % 1_000_000_000);
+            let until_next = std::time::Duration::from_secs(60).saturating_sub(into_minute);
+            tokio::time::sleep(until_next).await;
+
+            let pinned = pinned_zones();
+            if pinned.is_empty() {
+                continue;
+            }
+            emit_event(&app, AppEvent::WorldClockTick(snapshot(&pinned, Utc::now(), super::system_zone())));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn pin(timezone: &str, label: Option<&str>) -> PinnedZone {
+        PinnedZone { timezone: timezone.to_string(), label: label.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_clock_entry() {
+        let london: Tz = "Europe/London".parse().unwrap();
+        // 22:30 in London (BST) is 06:30 the next day in Tokyo
+        let now = Utc.with_ymd_and_hms(2025, 6, 10, 21, 30, 0).unwrap();
+
+        let tokyo = clock_entry(&pin("Asia/Tokyo", Some("Tokyo office")), now, london).unwrap();
+        assert_eq!(tokyo.label, "Tokyo office");
+        assert_eq!(tokyo.time, "6:30am");
+        assert_eq!(tokyo.date, "Wed 11 Jun");
+        assert_eq!(tokyo.abbreviation, "JST");
+        assert_eq!(tokyo.utc_offset, "UTC+09:00");
+        assert_eq!(tokyo.offset_from_local, "+8h");
+        assert_eq!(tokyo.day_offset, 1);
+        assert_eq!(tokyo.day_period, DayPeriod::Day);
+
+        let delhi = clock_entry(&pin("Asia/Kolkata", Some("  ")), now, london).unwrap();
+        assert_eq!(delhi.label, "Kolkata");
+        assert_eq!(delhi.offset_from_local, "+4h 30m");
+        assert_eq!(delhi.day_period, DayPeriod::Night);
+
+        let same = clock_entry(&pin("Europe/London", None), now, london).unwrap();
+        assert_eq!((same.offset_from_local.as_str(), same.day_offset), ("Same time", 0));
+        // 10:30pm BST in June: past sunset and civil dusk
+        assert_eq!(same.day_period, DayPeriod::Night);
+        assert_eq!(same.solar.map(|s| s.city), Some("London".to_string()));
+    }
+
+    #[test]
+    fn test_snapshot_skips_unknown_zones() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        let pinned = vec![pin("America/New_York", None), pin("Mars/Olympus", None), pin("utc", None)];
+        let snap = snapshot(&pinned, now, Tz::UTC);
+        assert_eq!(snap.entries.len(), 2);
+        assert_eq!(snap.entries[0].offset_from_local, "-5h");
+        assert_eq!(snap.entries[0].iso, "2025-01-15T07:00:00-05:00");
+        assert_eq!(snap.generated_at, "2025-01-15T12:00:00Z");
+    }
+}
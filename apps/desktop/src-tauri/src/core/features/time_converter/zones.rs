@@ -0,0 +1,633 @@
+//! Full timezone database search and abbreviation resolution
+//!
+//! Covers every zone compiled into chrono-tz rather than just the curated list.
+//! Abbreviations are collected by sampling each zone's offsets back to 1970, so
+//! ones a zone no longer uses still resolve. Ambiguous abbreviations ("CST" is
+//! US Central, China and Cuba) return every candidate, ranked by current use,
+//! the system timezone and the user's locale.
+
+use std::collections::HashMap;
+use chrono::{Datelike, NaiveDate, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetName, Tz, TZ_VARIANTS};
+use once_cell::sync::Lazy;
+
+use super::{format_utc_offset, ALL_TIMEZONES, TIMEZONE_ABBREVIATIONS_MAP};
+
+/// Most candidates returned for one abbreviation or search
+pub const MAX_CANDIDATES: usize = 8;
+
+/// First year sampled when collecting historical abbreviations
+const HISTORY_START_YEAR: i32 = 1970;
+
+/// Renamed or legacy names chrono-tz still accepts, mapped to the current zone
+pub const ALIASES: &[(&str, &str)] = &[
+    ("Asia/Calcutta", "Asia/Kolkata"),
+    ("Asia/Saigon", "Asia/Ho_Chi_Minh"),
+    ("Asia/Katmandu", "Asia/Kathmandu"),
+    ("Asia/Rangoon", "Asia/Yangon"),
+    ("Asia/Dacca", "Asia/Dhaka"),
+    ("Asia/Ulan_Bator", "Asia/Ulaanbaatar"),
+    ("Asia/Istanbul", "Europe/Istanbul"),
+    ("Europe/Kiev", "Europe/Kyiv"),
+    ("Europe/Belfast", "Europe/London"),
+    ("America/Buenos_Aires", "America/Argentina/Buenos_Aires"),
+    ("America/Indianapolis", "America/Indiana/Indianapolis"),
+    ("America/Montreal", "America/Toronto"),
+    ("Pacific/Truk", "Pacific/Chuuk"),
+    ("US/Eastern", "America/New_York"),
+    ("US/Central", "America/Chicago"),
+    ("US/Mountain", "America/Denver"),
+    ("US/Pacific", "America/Los_Angeles"),
+    ("US/Arizona", "America/Phoenix"),
+    ("US/Alaska", "America/Anchorage"),
+    ("US/Hawaii", "Pacific/Honolulu"),
+    ("Canada/Eastern", "America/Toronto"),
+    ("Canada/Central", "America/Winnipeg"),
+    ("Canada/Mountain", "America/Edmonton"),
+    ("Canada/Pacific", "America/Vancouver"),
+    ("GB", "Europe/London"),
+    ("Eire", "Europe/Dublin"),
+    ("PRC", "Asia/Shanghai"),
+    ("ROC", "Asia/Taipei"),
+    ("ROK", "Asia/Seoul"),
+    ("Japan", "Asia/Tokyo"),
+    ("Cuba", "America/Havana"),
+    ("Israel", "Asia/Jerusalem"),
+    ("Hongkong", "Asia/Hong_Kong"),
+    ("Singapore", "Asia/Singapore"),
+    ("Turkey", "Europe/Istanbul"),
+    ("Egypt", "Africa/Cairo"),
+    ("Iran", "Asia/Tehran"),
+    ("UTC", "Etc/UTC"),
+    ("UCT", "Etc/UTC"),
+    ("Universal", "Etc/UTC"),
+    ("Zulu", "Etc/UTC"),
+    ("GMT", "Etc/GMT"),
+    ("Greenwich", "Etc/GMT"),
+];
+
+/// Zones picked first when nothing else separates candidates
+const PREFERRED_TIMEZONES: &[&str] = &[
+    "America/New_York",
+    "America/Los_Angeles",
+    "America/Chicago",
+    "Europe/London",
+    "Europe/Paris",
+    "Asia/Tokyo",
+    "Asia/Kolkata",
+    "Asia/Shanghai",
+];
+
+/// Locale region (ISO 3166 code) → zones used in that country
+const REGION_ZONES: &[(&str, &[&str])] = &[
+    ("US", &["America/New_York", "America/Chicago", "America/Denver", "America/Phoenix", "America/Los_Angeles", "America/Anchorage", "Pacific/Honolulu"]),
+    ("CA", &["America/St_Johns", "America/Halifax", "America/Toronto", "America/Winnipeg", "America/Regina", "America/Edmonton", "America/Vancouver"]),
+    ("MX", &["America/Mexico_City", "America/Cancun", "America/Chihuahua", "America/Tijuana"]),
+    ("CU", &["America/Havana"]),
+    ("BR", &["America/Sao_Paulo", "America/Manaus", "America/Noronha"]),
+    ("AR", &["America/Argentina/Buenos_Aires"]),
+    ("GB", &["Europe/London"]),
+    ("IE", &["Europe/Dublin"]),
+    ("DE", &["Europe/Berlin"]),
+    ("FR", &["Europe/Paris"]),
+    ("CH", &["Europe/Zurich"]),
+    ("ES", &["Europe/Madrid", "Atlantic/Canary"]),
+    ("IT", &["Europe/Rome"]),
+    ("NL", &["Europe/Amsterdam"]),
+    ("PT", &["Europe/Lisbon", "Atlantic/Azores"]),
+    ("RU", &["Europe/Moscow", "Asia/Yekaterinburg", "Asia/Novosibirsk", "Asia/Vladivostok"]),
+    ("TR", &["Europe/Istanbul"]),
+    ("IL", &["Asia/Jerusalem"]),
+    ("IN", &["Asia/Kolkata"]),
+    ("PK", &["Asia/Karachi"]),
+    ("BD", &["Asia/Dhaka"]),
+    ("CN", &["Asia/Shanghai"]),
+    ("HK", &["Asia/Hong_Kong"]),
+    ("TW", &["Asia/Taipei"]),
+    ("JP", &["Asia/Tokyo"]),
+    ("KR", &["Asia/Seoul"]),
+    ("PH", &["Asia/Manila"]),
+    ("SG", &["Asia/Singapore"]),
+    ("AE", &["Asia/Dubai"]),
+    ("SA", &["Asia/Riyadh"]),
+    ("AU", &["Australia/Sydney", "Australia/Brisbane", "Australia/Adelaide", "Australia/Darwin", "Australia/Perth"]),
+    ("NZ", &["Pacific/Auckland"]),
+    ("ZA", &["Africa/Johannesburg"]),
+    ("NG", &["Africa/Lagos"]),
+    ("EG", &["Africa/Cairo"]),
+];
+
+/// Abbreviations also read when written in lowercase. Everything else only
+/// counts in capitals: the tzdb index holds words like "get" (Tbilisi), "vet"
+/// (Caracas) and "cot" (Bogotá)
+const LOWERCASE_ABBREVIATIONS: &[&str] = &[
+    "est", "edt", "pst", "pdt", "cst", "cdt", "mst", "mdt",
+    "gmt", "utc", "bst", "cet", "cest", "ist", "jst", "aest",
+];
+
+/// Zones whose city is spotted in free text, on top of the curated
+/// `ALL_TIMEZONES` labels and keywords. Only large cities with their own
+/// primary zone: link zones and cities that read as names or words
+/// ("Christmas", "Regina", "Stanley", "Guam") would hijack ordinary text.
+const TEXT_CITY_ZONES: &[&str] = &[
+    // Americas
+    "America/Winnipeg", "America/Edmonton", "America/Halifax", "America/St_Johns",
+    "America/Detroit", "America/Boise", "America/Juneau", "America/Tijuana",
+    "America/Monterrey", "America/Cancun", "America/Chihuahua", "America/Panama",
+    "America/Santo_Domingo", "America/Puerto_Rico", "America/Santiago", "America/La_Paz",
+    "America/Manaus", "America/Recife", "America/Fortaleza",
+    // Europe
+    "Europe/Dublin", "Europe/Brussels", "Europe/Warsaw", "Europe/Minsk",
+    "Europe/Kaliningrad", "Europe/Samara", "Europe/Volgograd",
+    // Asia
+    "Asia/Kathmandu", "Asia/Hong_Kong", "Asia/Taipei", "Asia/Ulaanbaatar",
+    "Asia/Tbilisi", "Asia/Baku", "Asia/Qatar", "Asia/Novosibirsk",
+    "Asia/Yekaterinburg", "Asia/Vladivostok", "Asia/Krasnoyarsk", "Asia/Irkutsk",
+    "Asia/Makassar", "Asia/Jayapura",
+    // Africa
+    "Africa/Lagos", "Africa/Nairobi", "Africa/Casablanca", "Africa/Tunis",
+    "Africa/Abidjan", "Africa/Windhoek",
+    // Oceania
+    "Australia/Brisbane", "Australia/Melbourne", "Australia/Darwin", "Australia/Hobart",
+    "Pacific/Fiji", "Pacific/Noumea", "Pacific/Tahiti",
+];
+
+/// One zone an abbreviation can refer to
+#[derive(Debug, Clone)]
+pub struct AbbreviationEntry {
+    pub iana_id: &'static str,
+    /// Abbreviation as the database writes it ("CST", "ChST")
+    pub abbreviation: String,
+    /// Still used by the zone this year or next
+    pub current: bool,
+}
+
+/// Hints for breaking ties between candidates
+#[derive(Debug, Clone, Default)]
+pub struct ResolveHints {
+    pub system_timezone: Option<String>,
+    /// ISO 3166 region from the user's locale ("US", "CN")
+    pub locale_region: Option<String>,
+}
+
+impl ResolveHints {
+    /// Read hints from the OS timezone, locale environment and number format preference
+    pub fn from_system() -> Self {
+        let system_timezone = iana_time_zone::get_timezone().ok();
+        let locale_region = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find_map(|value| region_from_locale(&value))
+            .or_else(|| region_from_number_locale(crate::shared::number_locale::number_locale()));
+
+        Self { system_timezone, locale_region }
+    }
+}
+
+/// A ranked zone suggestion
+#[derive(Debug, Clone)]
+pub struct RankedZone {
+    pub iana_id: &'static str,
+    /// What the query matched: abbreviation, alias, city or IANA name
+    pub matched: String,
+    /// Abbreviation no longer used by this zone
+    pub historical: bool,
+    pub score: i32,
+}
+
+/// Lowercase zone name (including aliases) → zone
+static NAME_INDEX: Lazy<HashMap<String, Tz>> = Lazy::new(|| {
+    TZ_VARIANTS
+        .iter()
+        .map(|tz| (tz.name().to_lowercase(), *tz))
+        .collect()
+});
+
+/// City name (as written in the zone name) → zone, for spotting cities in text
+static CITY_INDEX: Lazy<Vec<(String, &'static str)>> = Lazy::new(|| {
+    TEXT_CITY_ZONES
+        .iter()
+        .filter_map(|name| canonical_id(name))
+        .map(|name| (city_name(name), name))
+        .collect()
+});
+
+/// Lowercase abbreviation → zones that use or used it
+static ABBREVIATION_INDEX: Lazy<HashMap<String, Vec<AbbreviationEntry>>> = Lazy::new(build_abbreviation_index);
+
+fn build_abbreviation_index() -> HashMap<String, Vec<AbbreviationEntry>> {
+    let current_year = Utc::now().year();
+    let mut index: HashMap<String, Vec<AbbreviationEntry>> = HashMap::new();
+
+    let mut add = |iana_id: &'static str, abbreviation: &str, current: bool| {
+        let entries = index.entry(abbreviation.to_lowercase()).or_default();
+        match entries.iter_mut().find(|e| e.iana_id == iana_id) {
+            Some(existing) => existing.current |= current,
+            None => entries.push(AbbreviationEntry {
+                iana_id,
+                abbreviation: abbreviation.to_string(),
+                current,
+            }),
+        }
+    };
+
+    for tz in TZ_VARIANTS.iter().filter(|tz| is_canonical(tz.name())) {
+        for year in HISTORY_START_YEAR..=current_year + 1 {
+            for month in [1, 7] {
+                let Some(instant) = NaiveDate::from_ymd_opt(year, month, 15).and_then(|d| d.and_hms_opt(12, 0, 0)) else {
+                    continue;
+                };
+                let offset = tz.offset_from_utc_datetime(&instant);
+                let Some(abbreviation) = offset.abbreviation() else {
+                    continue;
+                };
+                // Numeric placeholders ("+03") carry no name to match
+                if abbreviation.starts_with(['+', '-']) {
+                    continue;
+                }
+                add(tz.name(), abbreviation, year >= current_year);
+            }
+        }
+    }
+
+    // Names tzdata has since dropped but people still write ("SGT", "PHT", "GST")
+    for (label, abbreviation) in TIMEZONE_ABBREVIATIONS_MAP {
+        let curated = ALL_TIMEZONES.iter().find(|(l, _, _)| l == label);
+        if let Some(iana_id) = curated.and_then(|(_, id, _)| canonical_id(id)) {
+            add(iana_id, abbreviation, true);
+        }
+    }
+
+    index
+}
+
+/// Whether a zone name is a primary location rather than a legacy link
+fn is_canonical(name: &str) -> bool {
+    if ALIASES.iter().any(|(alias, _)| *alias == name) {
+        return false;
+    }
+    if name == "Etc/UTC" {
+        return true;
+    }
+    const LEGACY_PREFIXES: &[&str] = &["Etc/", "US/", "Canada/", "Mexico/", "Brazil/", "Chile/", "SystemV/"];
+    name.contains('/') && !LEGACY_PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+/// Parse a zone name case-insensitively, following aliases to the current name
+pub fn lookup_zone(name: &str) -> Option<Tz> {
+    let tz = *NAME_INDEX.get(&name.trim().to_lowercase())?;
+    let canonical = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == tz.name())
+        .and_then(|(_, target)| target.parse::<Tz>().ok());
+    Some(canonical.unwrap_or(tz))
+}
+
+/// Current name of a zone (curated entries may still use old names like "Europe/Kiev")
+fn canonical_id(name: &str) -> Option<&'static str> {
+    lookup_zone(name).map(|tz| tz.name())
+}
+
+/// Zones known to use (or have used) an abbreviation
+pub fn abbreviation_entries(abbreviation: &str) -> Option<&'static [AbbreviationEntry]> {
+    ABBREVIATION_INDEX
+        .get(&abbreviation.to_lowercase())
+        .map(|entries| entries.as_slice())
+}
+
+/// Whether a word written in text should be read as a timezone abbreviation
+pub fn is_abbreviation_token(token: &str) -> bool {
+    if token.len() < 3 || abbreviation_entries(token).is_none() {
+        return false;
+    }
+    let written_in_caps = token.chars().filter(|c| c.is_alphabetic()).all(|c| c.is_uppercase());
+    written_in_caps || LOWERCASE_ABBREVIATIONS.contains(&token)
+}
+
+/// All zones an abbreviation may mean, best first
+pub fn resolve_abbreviation(abbreviation: &str, hints: &ResolveHints) -> Vec<RankedZone> {
+    let Some(entries) = abbreviation_entries(abbreviation) else {
+        return Vec::new();
+    };
+
+    let mut ranked: Vec<RankedZone> = entries
+        .iter()
+        .map(|entry| RankedZone {
+            iana_id: entry.iana_id,
+            matched: entry.abbreviation.clone(),
+            historical: !entry.current,
+            score: if entry.current { 100 } else { 0 } + hint_score(entry.iana_id, hints),
+        })
+        .collect();
+
+    sort_ranked(&mut ranked);
+
+    // Zones with identical offsets give the same answer; keep the best-ranked one
+    let mut seen = Vec::new();
+    ranked.retain(|r| {
+        let signature = offset_signature(r.iana_id);
+        let fresh = !seen.contains(&signature);
+        seen.push(signature);
+        fresh
+    });
+
+    ranked.truncate(MAX_CANDIDATES);
+    ranked
+}
+
+/// UTC offsets across this year's seasons, used to spot interchangeable zones
+fn offset_signature(iana_id: &str) -> Vec<i32> {
+    let Ok(tz) = iana_id.parse::<Tz>() else {
+        return Vec::new();
+    };
+    let year = Utc::now().year();
+    [1, 4, 7, 10]
+        .iter()
+        .filter_map(|month| NaiveDate::from_ymd_opt(year, *month, 15)?.and_hms_opt(12, 0, 0))
+        .map(|instant| tz.offset_from_utc_datetime(&instant).fix().local_minus_utc())
+        .collect()
+}
+
+/// Search the full database by IANA name, alias, city or abbreviation
+pub fn search_zones(query: &str, hints: &ResolveHints) -> Vec<RankedZone> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query_underscored = query.replace(' ', "_");
+
+    let mut ranked: Vec<RankedZone> = resolve_abbreviation(&query, hints);
+
+    let mut push = |iana_id: &'static str, matched: &str, score: i32| {
+        match ranked.iter_mut().find(|r| r.iana_id == iana_id) {
+            Some(existing) if existing.score >= score => {}
+            Some(existing) => {
+                existing.score = score;
+                existing.matched = matched.to_string();
+            }
+            None => ranked.push(RankedZone {
+                iana_id,
+                matched: matched.to_string(),
+                historical: false,
+                score,
+            }),
+        }
+    };
+
+    for tz in TZ_VARIANTS.iter() {
+        let name = tz.name();
+        let name_lower = name.to_lowercase();
+        let city_lower = city_name(name).to_lowercase();
+
+        let base = if name_lower == query_underscored || city_lower == query {
+            150
+        } else if city_lower.starts_with(&query) || name_lower.starts_with(&query_underscored) {
+            90
+        } else if name_lower.contains(&query_underscored) {
+            60
+        } else {
+            continue;
+        };
+
+        // Aliases point at their current zone but report the name that matched
+        let Some(target) = lookup_zone(name) else {
+            continue;
+        };
+        let target_name = target.name();
+        let penalty = if is_canonical(name) { 0 } else { 5 };
+        push(target_name, name, base - penalty + hint_score(target_name, hints));
+    }
+
+    // Curated country and city keywords ("india", "bengaluru")
+    for (label, iana_id, keywords) in ALL_TIMEZONES {
+        let label_lower = label.to_lowercase();
+        let keyword_hit = keywords.split_whitespace().any(|k| k == query);
+        if keyword_hit || label_lower.contains(&query) {
+            if let Some(iana_id) = canonical_id(iana_id) {
+                push(iana_id, label, 120 + hint_score(iana_id, hints));
+            }
+        }
+    }
+
+    sort_ranked(&mut ranked);
+    ranked.truncate(MAX_CANDIDATES);
+    ranked
+}
+
+fn sort_ranked(ranked: &mut [RankedZone]) {
+    ranked.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.iana_id.cmp(b.iana_id)));
+}
+
+/// Tie-break bonus from the system timezone, locale and curated list
+fn hint_score(iana_id: &str, hints: &ResolveHints) -> i32 {
+    let mut score = 0;
+
+    if let Some(system) = hints.system_timezone.as_deref() {
+        let system = canonical_id(system).unwrap_or(system);
+        if system == iana_id {
+            score += 50;
+        } else if continent(system) == continent(iana_id) {
+            score += 10;
+        }
+    }
+
+    if let Some(region) = hints.locale_region.as_deref() {
+        if let Some((_, zones)) = REGION_ZONES.iter().find(|(r, _)| r.eq_ignore_ascii_case(region)) {
+            if zones.contains(&iana_id) {
+                score += 30;
+            } else if zones.iter().any(|z| continent(z) == continent(iana_id)) {
+                score += 5;
+            }
+        }
+    }
+
+    if ALL_TIMEZONES.iter().any(|(_, id, _)| canonical_id(id) == Some(iana_id)) {
+        score += 5;
+    }
+    if PREFERRED_TIMEZONES.contains(&iana_id) {
+        score += 3;
+    }
+
+    score
+}
+
+fn continent(iana_id: &str) -> &str {
+    iana_id.split('/').next().unwrap_or(iana_id)
+}
+
+/// City part of an IANA name ("America/Argentina/Buenos_Aires" → "Buenos Aires")
+pub fn city_name(iana_id: &str) -> String {
+    iana_id.rsplit('/').next().unwrap_or(iana_id).replace('_', " ")
+}
+
+/// Zones of major cities outside the curated list that may be matched by
+/// their city name in free text
+pub fn city_zones() -> &'static [(String, &'static str)] {
+    &CITY_INDEX
+}
+
+/// Display label: the curated "City/Country" label, else built from the IANA name
+pub fn display_label(iana_id: &str) -> String {
+    if let Some((label, _, _)) = ALL_TIMEZONES.iter().find(|(_, id, _)| *id == iana_id) {
+        return label.to_string();
+    }
+    match iana_id.split_once('/') {
+        Some((region, _)) => format!("{}/{}", city_name(iana_id), region),
+        None => iana_id.to_string(),
+    }
+}
+
+/// Current abbreviation and UTC offset of a zone
+pub fn current_offset(tz: Tz) -> (String, String) {
+    let now = Utc::now().with_timezone(&tz);
+    let offset = now.offset().fix().local_minus_utc();
+    (now.format("%Z").to_string(), format_utc_offset(offset))
+}
+
+/// "en_US.UTF-8" / "zh-CN" → "US" / "CN"
+fn region_from_locale(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?;
+    let region = tag.split(['_', '-']).nth(1)?;
+    (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic())).then(|| region.to_uppercase())
+}
+
+fn region_from_number_locale(locale: crate::shared::settings::NumberLocale) -> Option<String> {
+    use crate::shared::settings::NumberLocale;
+    match locale {
+        NumberLocale::Auto => None,
+        NumberLocale::EnUs => Some("US".to_string()),
+        NumberLocale::DeDe => Some("DE".to_string()),
+        NumberLocale::FrFr => Some("FR".to_string()),
+        NumberLocale::DeCh => Some("CH".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(system: Option<&str>, region: Option<&str>) -> ResolveHints {
+        ResolveHints {
+            system_timezone: system.map(String::from),
+            locale_region: region.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_aliases_exist() {
+        for (alias, target) in ALIASES {
+            assert!(alias.parse::<Tz>().is_ok(), "alias missing from chrono-tz: {}", alias);
+            assert!(target.parse::<Tz>().is_ok(), "alias target missing from chrono-tz: {}", target);
+        }
+        for (_, zones) in REGION_ZONES {
+            for zone in *zones {
+                assert!(zone.parse::<Tz>().is_ok(), "region zone missing: {}", zone);
+            }
+        }
+    }
+
+    #[test]
+    fn test_city_zones_skip_links_and_words() {
+        assert_eq!(city_zones().len(), TEXT_CITY_ZONES.len(), "every text city is a primary zone");
+        for zone in TEXT_CITY_ZONES {
+            assert_eq!(canonical_id(zone), Some(*zone));
+            assert!(is_canonical(zone), "{} is a link", zone);
+            assert!(!ALL_TIMEZONES.iter().any(|(_, id, _)| id == zone), "{} is already curated", zone);
+        }
+        let cities: Vec<&str> = city_zones().iter().map(|(city, _)| city.as_str()).collect();
+        assert!(cities.contains(&"Winnipeg"));
+        for word in ["General", "Center", "Wake", "Easter", "Jersey", "Universal", "UTC", "Central",
+                     "Christmas", "Regina", "Stanley", "Oral", "Midway", "Reunion", "Dawson", "Guam"] {
+            assert!(!cities.contains(&word), "{} should not be a city", word);
+        }
+        assert!(!is_abbreviation_token("wet"));
+        assert!(is_abbreviation_token("WET"));
+    }
+
+    #[test]
+    fn test_text_detection_ignores_zone_words() {
+        use super::super::detect_timezone_from_text;
+        assert_eq!(detect_timezone_from_text("Christmas party at 6pm"), None);
+        assert_eq!(detect_timezone_from_text("Call Regina at 3pm"), None);
+        assert_eq!(detect_timezone_from_text("Let's get lunch at 1pm"), None);
+        assert_eq!(detect_timezone_from_text("vet at 3pm"), None);
+        assert_eq!(
+            detect_timezone_from_text("Standup at 9am Winnipeg").map(|(zone, _)| zone).as_deref(),
+            Some("America/Winnipeg")
+        );
+    }
+
+    #[test]
+    fn test_lookup_follows_aliases() {
+        assert_eq!(lookup_zone("asia/calcutta").map(|tz| tz.name()), Some("Asia/Kolkata"));
+        assert_eq!(lookup_zone("US/Central").map(|tz| tz.name()), Some("America/Chicago"));
+        assert_eq!(lookup_zone("America/Argentina/Buenos_Aires").map(|tz| tz.name()), Some("America/Argentina/Buenos_Aires"));
+        assert!(lookup_zone("Mars/Olympus").is_none());
+    }
+
+    #[test]
+    fn test_cst_candidates_ranked_by_hints() {
+        let us = resolve_abbreviation("CST", &hints(Some("America/Denver"), Some("US")));
+        let ids: Vec<&str> = us.iter().map(|r| r.iana_id).collect();
+        assert_eq!(ids.first(), Some(&"America/Chicago"));
+        assert!(ids.contains(&"Asia/Shanghai"));
+        assert!(ids.contains(&"America/Havana"));
+
+        let china = resolve_abbreviation("cst", &hints(Some("Asia/Shanghai"), Some("CN")));
+        assert_eq!(china.first().map(|r| r.iana_id), Some("Asia/Shanghai"));
+
+        let cuba = resolve_abbreviation("CST", &hints(None, Some("CU")));
+        assert_eq!(cuba.first().map(|r| r.iana_id), Some("America/Havana"));
+    }
+
+    #[test]
+    fn test_ist_is_ambiguous() {
+        let ranked = resolve_abbreviation("IST", &hints(None, Some("IE")));
+        assert_eq!(ranked.first().map(|r| r.iana_id), Some("Europe/Dublin"));
+        let ids: Vec<&str> = ranked.iter().map(|r| r.iana_id).collect();
+        assert!(ids.contains(&"Asia/Kolkata"));
+        assert!(ids.contains(&"Asia/Jerusalem"));
+    }
+
+    #[test]
+    fn test_historical_abbreviations() {
+        // Moscow summer time was abolished in 2011
+        let ranked = resolve_abbreviation("MSD", &ResolveHints::default());
+        let moscow = ranked.iter().find(|r| r.iana_id == "Europe/Moscow").unwrap();
+        assert!(moscow.historical);
+    }
+
+    #[test]
+    fn test_abbreviation_tokens() {
+        assert!(is_abbreviation_token("EST"));
+        assert!(is_abbreviation_token("est"));
+        assert!(is_abbreviation_token("CAT"));
+        assert!(!is_abbreviation_token("cat"));
+        assert!(!is_abbreviation_token("west"));
+        assert!(is_abbreviation_token("WEST"));
+        assert!(!is_abbreviation_token("meeting"));
+        assert!(!is_abbreviation_token("get"));
+        assert!(!is_abbreviation_token("vet"));
+        assert!(!is_abbreviation_token("Est"));
+        assert!(is_abbreviation_token("cest"));
+    }
+
+    #[test]
+    fn test_search_full_database() {
+        let none = ResolveHints::default();
+        assert_eq!(search_zones("winnipeg", &none).first().map(|r| r.iana_id), Some("America/Winnipeg"));
+        assert_eq!(search_zones("buenos aires", &none).first().map(|r| r.iana_id), Some("America/Argentina/Buenos_Aires"));
+        let calcutta = search_zones("Asia/Calcutta", &none);
+        assert_eq!(calcutta.first().map(|r| r.iana_id), Some("Asia/Kolkata"));
+        assert_eq!(calcutta.first().map(|r| r.matched.as_str()), Some("Asia/Calcutta"));
+        assert!(search_zones("  ", &none).is_empty());
+    }
+
+    #[test]
+    fn test_locale_region_parsing() {
+        assert_eq!(region_from_locale("en_US.UTF-8").as_deref(), Some("US"));
+        assert_eq!(region_from_locale("zh-CN").as_deref(), Some("CN"));
+        assert_eq!(region_from_locale("C"), None);
+        assert_eq!(display_label("America/Winnipeg"), "Winnipeg/America");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::shared::types::{ActionType, CommandItem, ConvertUnitsRequest, ConvertUnitsResponse, ExecuteActionResponse, ParseUnitResponse, GetUnitsResponse, UnitDTO};
+use crate::shared::types::{ActionType, CommandItem, RecipePayload, UnitSystem, ConvertUnitsRequest, ConvertUnitsResponse, ExecuteActionResponse, ParseUnitResponse, GetUnitsResponse, TypographyContext, GetMaterialsResponse, MaterialDTO, RoundingMode, UnitDTO};
 
 // Error constants - inline for now
 const ERR_MISSING_TEXT_PARAM: &str = "Missing 'text' parameter";
@@ -12,7 +12,17 @@ use async_trait::async_trait;
 use serde_json::json;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
 use std::collections::HashMap;
+use std::str::FromStr;
+use crate::shared::number_locale;
+use crate::shared::settings::NumberLocale;
+
+pub mod materials;
+pub mod recipe;
+pub mod typography;
+use self::materials::MaterialDensity;
 
 // ============================================================================
 // Unit Registry - Backend-First Pattern
@@ -26,6 +36,7 @@ pub enum UnitCategory {
     Volume,
     Temperature,
     Speed,
+    Typography,
 }
 
 /// Unit definition with conversion factors
@@ -36,6 +47,7 @@ pub struct UnitDefinition {
     pub category: UnitCategory,
     pub base_factor: f64,  // Multiplier to convert to base unit
     pub offset: f64,       // Offset for affine conversions (e.g., temperature)
+    pub exact_factor: &'static str, // Exact base_factor for decimal mode ("0.0254" or "5/9")
 }
 
 /// Thread-safe unit registry initialized once at startup
@@ -45,108 +57,159 @@ static UNIT_REGISTRY: Lazy<HashMap<&'static str, UnitDefinition>> = Lazy::new(||
     // Length (base: meters)
     registry.insert("mm", UnitDefinition { 
         symbol: "mm", name: "Millimeters", category: UnitCategory::Length, 
-        base_factor: 0.001, offset: 0.0 
+        base_factor: 0.001, offset: 0.0, exact_factor: "0.001"
     });
     registry.insert("cm", UnitDefinition { 
         symbol: "cm", name: "Centimeters", category: UnitCategory::Length, 
-        base_factor: 0.01, offset: 0.0 
+        base_factor: 0.01, offset: 0.0, exact_factor: "0.01"
     });
     registry.insert("m", UnitDefinition { 
         symbol: "m", name: "Meters", category: UnitCategory::Length, 
-        base_factor: 1.0, offset: 0.0 
+        base_factor: 1.0, offset: 0.0, exact_factor: "1"
     });
     registry.insert("km", UnitDefinition { 
         symbol: "km", name: "Kilometers", category: UnitCategory::Length, 
-        base_factor: 1000.0, offset: 0.0 
+        base_factor: 1000.0, offset: 0.0, exact_factor: "1000"
     });
     registry.insert("in", UnitDefinition { 
         symbol: "in", name: "Inches", category: UnitCategory::Length, 
-        base_factor: 0.0254, offset: 0.0 
+        base_factor: 0.0254, offset: 0.0, exact_factor: "0.0254"
     });
     registry.insert("ft", UnitDefinition { 
         symbol: "ft", name: "Feet", category: UnitCategory::Length, 
-        base_factor: 0.3048, offset: 0.0 
+        base_factor: 0.3048, offset: 0.0, exact_factor: "0.3048"
     });
     registry.insert("yd", UnitDefinition { 
         symbol: "yd", name: "Yards", category: UnitCategory::Length, 
-        base_factor: 0.9144, offset: 0.0 
+        base_factor: 0.9144, offset: 0.0, exact_factor: "0.9144"
     });
     registry.insert("mi", UnitDefinition { 
         symbol: "mi", name: "Miles", category: UnitCategory::Length, 
-        base_factor: 1609.344, offset: 0.0 
+        base_factor: 1609.344, offset: 0.0, exact_factor: "1609.344"
     });
     
     // Mass (base: grams)
     registry.insert("mg", UnitDefinition { 
         symbol: "mg", name: "Milligrams", category: UnitCategory::Mass, 
-        base_factor: 0.001, offset: 0.0 
+        base_factor: 0.001, offset: 0.0, exact_factor: "0.001"
     });
     registry.insert("g", UnitDefinition { 
         symbol: "g", name: "Grams", category: UnitCategory::Mass, 
-        base_factor: 1.0, offset: 0.0 
+        base_factor: 1.0, offset: 0.0, exact_factor: "1"
     });
     registry.insert("kg", UnitDefinition { 
         symbol: "kg", name: "Kilograms", category: UnitCategory::Mass, 
-        base_factor: 1000.0, offset: 0.0 
+        base_factor: 1000.0, offset: 0.0, exact_factor: "1000"
     });
     registry.insert("oz", UnitDefinition { 
         symbol: "oz", name: "Ounces", category: UnitCategory::Mass, 
-        base_factor: 28.3495, offset: 0.0 
+        base_factor: 28.3495, offset: 0.0, exact_factor: "28.349523125"
     });
     registry.insert("lb", UnitDefinition { 
         symbol: "lb", name: "Pounds", category: UnitCategory::Mass, 
-        base_factor: 453.592, offset: 0.0 
+        base_factor: 453.592, offset: 0.0, exact_factor: "453.59237"
     });
     
     // Volume (base: liters)
     registry.insert("ml", UnitDefinition { 
         symbol: "ml", name: "Milliliters", category: UnitCategory::Volume, 
-        base_factor: 0.001, offset: 0.0 
+        base_factor: 0.001, offset: 0.0, exact_factor: "0.001"
     });
     registry.insert("L", UnitDefinition { 
         symbol: "L", name: "Liters", category: UnitCategory::Volume, 
-        base_factor: 1.0, offset: 0.0 
+        base_factor: 1.0, offset: 0.0, exact_factor: "1"
+    });
+    registry.insert("tsp", UnitDefinition { 
+        symbol: "tsp", name: "Teaspoons", category: UnitCategory::Volume, 
+        base_factor: 0.00492892, offset: 0.0, exact_factor: "0.00492892159375"
+    });
+    registry.insert("tbsp", UnitDefinition { 
+        symbol: "tbsp", name: "Tablespoons", category: UnitCategory::Volume, 
+        base_factor: 0.0147868, offset: 0.0, exact_factor: "0.01478676478125"
     });
     registry.insert("fl-oz", UnitDefinition { 
         symbol: "fl-oz", name: "Fluid Ounces", category: UnitCategory::Volume, 
-        base_factor: 0.0295735, offset: 0.0 
+        base_factor: 0.0295735, offset: 0.0, exact_factor: "0.0295735295625"
     });
     registry.insert("cup", UnitDefinition { 
         symbol: "cup", name: "Cups", category: UnitCategory::Volume, 
-        base_factor: 0.236588, offset: 0.0 
+        base_factor: 0.236588, offset: 0.0, exact_factor: "0.2365882365"
     });
     registry.insert("pint", UnitDefinition { 
         symbol: "pint", name: "Pints", category: UnitCategory::Volume, 
-        base_factor: 0.473176, offset: 0.0 
+        base_factor: 0.473176, offset: 0.0, exact_factor: "0.473176473"
     });
     registry.insert("quart", UnitDefinition { 
         symbol: "quart", name: "Quarts", category: UnitCategory::Volume, 
-        base_factor: 0.946353, offset: 0.0 
+        base_factor: 0.946353, offset: 0.0, exact_factor: "0.946352946"
     });
     registry.insert("gal", UnitDefinition { 
         symbol: "gal", name: "Gallons", category: UnitCategory::Volume, 
-        base_factor: 3.78541, offset: 0.0 
+        base_factor: 3.78541, offset: 0.0, exact_factor: "3.785411784"
     });
     
     // Temperature (base: Celsius)
     // For affine conversions: base_value = (value + offset) * base_factor
     registry.insert("C", UnitDefinition { 
         symbol: "C", name: "Celsius", category: UnitCategory::Temperature, 
-        base_factor: 1.0, offset: 0.0 
+        base_factor: 1.0, offset: 0.0, exact_factor: "1"
     });
     registry.insert("F", UnitDefinition { 
         symbol: "F", name: "Fahrenheit", category: UnitCategory::Temperature, 
-        base_factor: 5.0/9.0, offset: -32.0 
+        base_factor: 5.0/9.0, offset: -32.0, exact_factor: "5/9"
     });
     
     // Speed (base: m/s)
     registry.insert("km/h", UnitDefinition { 
         symbol: "km/h", name: "Kilometers/Hour", category: UnitCategory::Speed, 
-        base_factor: 1.0/3.6, offset: 0.0 
+        base_factor: 1.0/3.6, offset: 0.0, exact_factor: "1000/3600"
     });
     registry.insert("m/h", UnitDefinition { 
         symbol: "m/h", name: "Miles/Hour", category: UnitCategory::Speed, 
-        base_factor: 0.44704, offset: 0.0 
+        base_factor: 0.44704, offset: 0.0, exact_factor: "0.44704"
+    });
+    
+    // Typography (base: pixels at the default 96 dpi / 16px root font size)
+    // Context-dependent: conversions go through `typography` with the request's metrics
+    registry.insert("px", UnitDefinition { 
+        symbol: "px", name: "Pixels", category: UnitCategory::Typography, 
+        base_factor: 1.0, offset: 0.0, exact_factor: "1"
+    });
+    registry.insert("pt", UnitDefinition { 
+        symbol: "pt", name: "Points", category: UnitCategory::Typography, 
+        base_factor: 96.0/72.0, offset: 0.0, exact_factor: "96/72"
+    });
+    registry.insert("pc", UnitDefinition { 
+        symbol: "pc", name: "Picas", category: UnitCategory::Typography, 
+        base_factor: 16.0, offset: 0.0, exact_factor: "16"
+    });
+    registry.insert("em", UnitDefinition { 
+        symbol: "em", name: "Em", category: UnitCategory::Typography, 
+        base_factor: 16.0, offset: 0.0, exact_factor: "16"
+    });
+    registry.insert("rem", UnitDefinition { 
+        symbol: "rem", name: "Root Em", category: UnitCategory::Typography, 
+        base_factor: 16.0, offset: 0.0, exact_factor: "16"
+    });
+    registry.insert("vw", UnitDefinition { 
+        symbol: "vw", name: "Viewport Width", category: UnitCategory::Typography, 
+        base_factor: 19.2, offset: 0.0, exact_factor: "19.2"
+    });
+    registry.insert("vh", UnitDefinition { 
+        symbol: "vh", name: "Viewport Height", category: UnitCategory::Typography, 
+        base_factor: 10.8, offset: 0.0, exact_factor: "10.8"
+    });
+    registry.insert("dp", UnitDefinition { 
+        symbol: "dp", name: "Android dp", category: UnitCategory::Typography, 
+        base_factor: 0.6, offset: 0.0, exact_factor: "96/160"
+    });
+    registry.insert("sp", UnitDefinition { 
+        symbol: "sp", name: "Android sp", category: UnitCategory::Typography, 
+        base_factor: 0.6, offset: 0.0, exact_factor: "96/160"
+    });
+    registry.insert("ios-pt", UnitDefinition { 
+        symbol: "ios-pt", name: "iOS Points", category: UnitCategory::Typography, 
+        base_factor: 96.0/163.0, offset: 0.0, exact_factor: "96/163"
     });
     
     registry
@@ -160,13 +223,28 @@ fn category_to_string(category: UnitCategory) -> String {
         UnitCategory::Volume => "volume",
         UnitCategory::Temperature => "temperature",
         UnitCategory::Speed => "speed",
+        UnitCategory::Typography => "typography",
     }.to_string()
 }
 
+/// Resolve display metrics when a conversion involves typography units
+/// (typography ↔ typography, or typography ↔ length via DPI); None otherwise
+fn typography_metrics(from_unit: &str, to_unit: &str, context: Option<&TypographyContext>) -> Result<Option<typography::Metrics>, String> {
+    let category = |unit: &str| UNIT_REGISTRY.get(unit).map(|def| def.category);
+    match (category(from_unit), category(to_unit)) {
+        (Some(UnitCategory::Typography), Some(UnitCategory::Typography | UnitCategory::Length))
+        | (Some(UnitCategory::Length), Some(UnitCategory::Typography)) => {
+            typography::Metrics::from_context(context).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
 /// Generic conversion function using the unit registry
 /// Supports both multiplicative (base_factor) and affine (offset) conversions
-/// Also handles cross-category conversions (Mass ↔ Volume) using water density as bridge
-fn convert_value(value: f64, from_unit: &str, to_unit: &str) -> Result<f64, String> {
+/// Also handles cross-category conversions (Mass ↔ Volume) using the material's density
+/// as bridge (water when no material is given)
+fn convert_value(value: f64, from_unit: &str, to_unit: &str, material: Option<&str>) -> Result<f64, String> {
     // Same unit, no conversion needed
     if from_unit == to_unit {
         return Ok(value);
@@ -181,16 +259,18 @@ fn convert_value(value: f64, from_unit: &str, to_unit: &str) -> Result<f64, Stri
     // Check for category mismatch (Mass ↔ Volume bridge)
     let bridge_factor = match (&from_def.category, &to_def.category) {
         (UnitCategory::Mass, UnitCategory::Volume) => {
-            // Mass to Volume: 1000 g = 1 L (water density)
-            // So: grams / 1000 = liters
-            println!("[convert_value] Bridge: Mass → Volume (water density: 1000 g/L)");
-            Some(1.0 / 1000.0) // Convert grams to liters
+            // Mass to Volume: 1 L = density * 1000 g (water: 1000 g = 1 L)
+            // So: grams / (density * 1000) = liters
+            let density = bridge_material(material)?;
+            println!("[convert_value] Bridge: Mass → Volume ({} density: {} g/mL)", density.name, density.grams_per_ml);
+            Some(1.0 / (density.grams_per_ml_f64() * 1000.0)) // Convert grams to liters
         },
         (UnitCategory::Volume, UnitCategory::Mass) => {
-            // Volume to Mass: 1 L = 1000 g (water density)
-            // So: liters * 1000 = grams
-            println!("[convert_value] Bridge: Volume → Mass (water density: 1000 g/L)");
-            Some(1000.0) // Convert liters to grams
+            // Volume to Mass: 1 L = density * 1000 g (water: 1 L = 1000 g)
+            // So: liters * density * 1000 = grams
+            let density = bridge_material(material)?;
+            println!("[convert_value] Bridge: Volume → Mass ({} density: {} g/mL)", density.name, density.grams_per_ml);
+            Some(density.grams_per_ml_f64() * 1000.0) // Convert liters to grams
         },
         (from_cat, to_cat) if from_cat != to_cat => {
             return Err(format!(
@@ -222,6 +302,123 @@ fn convert_value(value: f64, from_unit: &str, to_unit: &str) -> Result<f64, Stri
     Ok(result)
 }
 
+/// Parse a registry `exact_factor` into a (numerator, denominator) pair
+/// Accepts plain decimals ("0.0254") and fractions ("5/9") so ratios like
+/// km/h → m/s stay exact instead of being rounded to a repeating decimal
+fn parse_exact_factor(factor: &str) -> Result<(Decimal, Decimal), String> {
+    let (num, den) = match factor.split_once('/') {
+        Some((num, den)) => (num, den),
+        None => (factor, "1"),
+    };
+    let num = Decimal::from_str(num.trim())
+        .map_err(|e| format!("Invalid exact factor '{}': {}", factor, e))?;
+    let den = Decimal::from_str(den.trim())
+        .map_err(|e| format!("Invalid exact factor '{}': {}", factor, e))?;
+    if den.is_zero() {
+        return Err(format!("Invalid exact factor '{}': zero denominator", factor));
+    }
+    Ok((num, den))
+}
+
+/// Convert an f64 amount to Decimal using its shortest round-trip representation
+/// (so 0.1 becomes exactly 0.1 rather than 0.1000000000000000055511151231257827)
+fn decimal_from_f64(value: f64) -> Result<Decimal, String> {
+    if !value.is_finite() {
+        return Err(format!("Amount {} cannot be converted exactly", value));
+    }
+    Decimal::from_str(&value.to_string())
+        .or_else(|_| Decimal::from_scientific(&format!("{:e}", value)))
+        .map_err(|_| format!("Amount {} is out of range for exact conversion", value))
+}
+
+/// Exact conversion using decimal arithmetic and the registry's `exact_factor`s
+///
+/// Mirrors `convert_value` step by step, but multiplies every numerator before
+/// dividing once at the end, so multiplicative conversions like 1 in → cm
+/// return exactly 2.54 instead of 2.5400000000000005.
+fn convert_value_exact(value: Decimal, from_unit: &str, to_unit: &str, material: Option<&str>) -> Result<Decimal, String> {
+    if from_unit == to_unit {
+        return Ok(value);
+    }
+
+    let from_def = UNIT_REGISTRY.get(from_unit)
+        .ok_or_else(|| format!("Unknown source unit: {}", from_unit))?;
+    let to_def = UNIT_REGISTRY.get(to_unit)
+        .ok_or_else(|| format!("Unknown target unit: {}", to_unit))?;
+
+    // Mass ↔ Volume bridge (density in g/mL * 1000 = g/L) as an exact ratio
+    let grams_per_liter = |material: Option<&str>| -> Result<Decimal, String> {
+        bridge_material(material)?
+            .grams_per_ml
+            .checked_mul(Decimal::ONE_THOUSAND)
+            .ok_or_else(|| "Material density out of range".to_string())
+    };
+    let (bridge_num, bridge_den) = match (&from_def.category, &to_def.category) {
+        (UnitCategory::Mass, UnitCategory::Volume) => (Decimal::ONE, grams_per_liter(material)?),
+        (UnitCategory::Volume, UnitCategory::Mass) => (grams_per_liter(material)?, Decimal::ONE),
+        (from_cat, to_cat) if from_cat != to_cat => {
+            return Err(format!(
+                "Cannot convert between {:?} and {:?} (incompatible categories)",
+                from_cat, to_cat
+            ));
+        },
+        _ => (Decimal::ONE, Decimal::ONE),
+    };
+
+    let (from_num, from_den) = parse_exact_factor(from_def.exact_factor)?;
+    let (to_num, to_den) = parse_exact_factor(to_def.exact_factor)?;
+    let from_offset = Decimal::from_f64(from_def.offset)
+        .ok_or_else(|| format!("Invalid offset for unit: {}", from_unit))?;
+    let to_offset = Decimal::from_f64(to_def.offset)
+        .ok_or_else(|| format!("Invalid offset for unit: {}", to_unit))?;
+
+    let overflow = || format!("Value too large for exact conversion: {} {}", value, from_unit);
+
+    // result = (value + from_offset) * from_factor * bridge / to_factor - to_offset
+    let numerator = value
+        .checked_add(from_offset)
+        .and_then(|v| v.checked_mul(from_num))
+        .and_then(|v| v.checked_mul(bridge_num))
+        .and_then(|v| v.checked_mul(to_den))
+        .ok_or_else(overflow)?;
+    let denominator = from_den
+        .checked_mul(bridge_den)
+        .and_then(|v| v.checked_mul(to_num))
+        .ok_or_else(overflow)?;
+    let result = numerator
+        .checked_div(denominator)
+        .and_then(|v| v.checked_sub(to_offset))
+        .ok_or_else(overflow)?;
+
+    println!(
+        "[convert_value_exact] {} {} → {} {}",
+        value, from_unit, result, to_unit
+    );
+
+    Ok(result.normalize())
+}
+
+/// Density used to bridge Mass ↔ Volume: the named material, or water when none is given
+fn bridge_material(material: Option<&str>) -> Result<MaterialDensity, String> {
+    match material.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(name) => materials::lookup_density(name)
+            .ok_or_else(|| format!("Unknown material: {}", name)),
+        None => Ok(materials::water()),
+    }
+}
+
+/// Name of the material a conversion will bridge through, if it crosses Mass ↔ Volume
+fn bridged_material_name(from_unit: &str, to_unit: &str, material: Option<&str>) -> Result<Option<String>, String> {
+    let categories = UNIT_REGISTRY.get(from_unit).zip(UNIT_REGISTRY.get(to_unit))
+        .map(|(from_def, to_def)| (from_def.category, to_def.category));
+    match categories {
+        Some((UnitCategory::Mass, UnitCategory::Volume)) | Some((UnitCategory::Volume, UnitCategory::Mass)) => {
+            bridge_material(material).map(|density| Some(density.name))
+        }
+        _ => Ok(None),
+    }
+}
+
 // ============================================================================
 // Feature Implementation
 // ============================================================================
@@ -271,6 +468,8 @@ impl FeatureSync for UnitConverterFeature {
             // Volume conversions
             ("convert_to_ml", "Convert to Milliliters", "ml"),
             ("convert_to_l", "Convert to Liters", "L"),
+            ("convert_to_tsp", "Convert to Teaspoons", "tsp"),
+            ("convert_to_tbsp", "Convert to Tablespoons", "tbsp"),
             ("convert_to_fl_oz", "Convert to Fluid Ounces", "fl-oz"),
             ("convert_to_cup", "Convert to Cups", "cup"),
             ("convert_to_pint", "Convert to Pints", "pint"),
@@ -284,6 +483,18 @@ impl FeatureSync for UnitConverterFeature {
             // Speed conversions
             ("convert_to_kmh", "Convert to Kilometers/Hour", "km/h"),
             ("convert_to_mph", "Convert to Miles/Hour", "m/h"),
+            
+            // Typography conversions
+            ("convert_to_px", "Convert to Pixels", "px"),
+            ("convert_to_pt", "Convert to Points", "pt"),
+            ("convert_to_pc", "Convert to Picas", "pc"),
+            ("convert_to_em", "Convert to em", "em"),
+            ("convert_to_rem", "Convert to rem", "rem"),
+            ("convert_to_vw", "Convert to Viewport Width (vw)", "vw"),
+            ("convert_to_vh", "Convert to Viewport Height (vh)", "vh"),
+            ("convert_to_dp", "Convert to Android dp", "dp"),
+            ("convert_to_sp", "Convert to Android sp", "sp"),
+            ("convert_to_ios_pt", "Convert to iOS Points", "ios-pt"),
         ]
         .into_iter()
         .map(|(id, label, target_unit)| CommandItem {
@@ -296,6 +507,29 @@ impl FeatureSync for UnitConverterFeature {
             widget_type: None,
             category: None, // Will be assigned by get_action_category
         })
+        .chain(
+            // Recipe actions rewrite a whole ingredient list
+            vec![
+                ("scale_recipe_half", "Scale Recipe ×½", Some(0.5), None),
+                ("scale_recipe_double", "Scale Recipe ×2", Some(2.0), None),
+                ("scale_recipe_triple", "Scale Recipe ×3", Some(3.0), None),
+                ("recipe_to_metric", "Convert Recipe to Metric", None, Some(UnitSystem::Metric)),
+                ("recipe_to_imperial", "Convert Recipe to Imperial", None, Some(UnitSystem::Imperial)),
+            ]
+            .into_iter()
+            .map(|(id, label, factor, unit_system)| CommandItem {
+                id: id.to_string(),
+                label: label.to_string(),
+                description: Some("Rewrite the selected ingredient list".to_string()),
+                action_type: Some(ActionType::ScaleRecipe(RecipePayload {
+                    factor,
+                    servings: None,
+                    unit_system,
+                })),
+                widget_type: None,
+                category: None, // Will be assigned by get_action_category
+            }),
+        )
         .collect()
     }
 
@@ -327,15 +561,60 @@ impl FeatureAsync for UnitConverterFeature {
                 let (amount, source_unit) = parse_unit_from_text(text)
                     .map_err(|e| crate::shared::error::AppError::Calculation(e))?;
 
-                let result = convert_value(amount, &source_unit, target)
-                    .map_err(|e| crate::shared::error::AppError::Calculation(e))?;
-                
                 let target_unit = target.as_str();
-                let converted_value = result;
 
-                // Format result with beautiful number formatting
-                let formatted_value = format_number(converted_value);
-                let result_string = format!("{} {}", formatted_value, target_unit);
+                // Material: explicit param wins, otherwise detect it in the text ("1 cup flour")
+                let material = params.get("material")
+                    .and_then(|v| v.as_str())
+                    .map(|m| m.to_string())
+                    .or_else(|| materials::detect_material_in_text(text).map(|m| m.name));
+                let material = material.as_deref();
+                let material_used = bridged_material_name(&source_unit, target_unit, material)
+                    .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+
+                // Typography units need display metrics (widget may pass them as params)
+                let typography_context = match params.get("typography") {
+                    Some(value) if !value.is_null() => Some(serde_json::from_value::<TypographyContext>(value.clone())
+                        .map_err(|e| crate::shared::error::AppError::Validation(format!("Invalid typography context: {}", e)))?),
+                    _ => None,
+                };
+                let metrics = typography_metrics(&source_unit, target_unit, typography_context.as_ref())
+                    .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+                let format_options = match metrics {
+                    Some(_) => FormatOptions { max_decimals: typography::FORMAT_DECIMALS, ..FormatOptions::default() },
+                    None => FormatOptions::default(),
+                };
+
+                // Prefer the exact decimal path so results carry no float noise;
+                // fall back to f64 for amounts outside Decimal's range
+                let exact_value = decimal_from_f64(amount)
+                    .and_then(|exact_amount| match &metrics {
+                        Some(metrics) => typography::convert_exact(exact_amount, &source_unit, target_unit, metrics),
+                        None => convert_value_exact(exact_amount, &source_unit, target_unit, material),
+                    })
+                    .ok();
+
+                let (converted_value, formatted_value) = match exact_value {
+                    Some(exact) => (
+                        exact.to_f64().unwrap_or(f64::NAN),
+                        format_decimal(exact, &format_options),
+                    ),
+                    None => {
+                        let result = match &metrics {
+                            Some(metrics) => typography::convert(amount, &source_unit, target_unit, metrics),
+                            None => convert_value(amount, &source_unit, target_unit, material),
+                        }
+                        .map_err(|e| crate::shared::error::AppError::Calculation(e))?;
+                        (result, format_number_with(result, &format_options))
+                    }
+                };
+
+                // Format result with beautiful number formatting (CSS units glued: "24px")
+                let result_string = match &material_used {
+                    Some(name) => format!("{} {} ({})", formatted_value, target_unit, name.to_lowercase()),
+                    None if typography::is_css_unit(target_unit) => format!("{}{}", formatted_value, target_unit),
+                    None => format!("{} {}", formatted_value, target_unit),
+                };
 
                 Ok(ExecuteActionResponse {
                     result: result_string,
@@ -344,6 +623,63 @@ impl FeatureAsync for UnitConverterFeature {
                         "target_unit": target_unit,
                         "original_amount": amount,
                         "converted_amount": converted_value,
+                        "exact_amount": exact_value.map(|v| v.to_string()),
+                        "material": material_used,
+                        "widget": "unit_converter"
+                    })),
+                })
+            },
+            ActionType::ScaleRecipe(payload) => {
+                let text = params.get("text")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| crate::shared::error::AppError::Validation(ERR_MISSING_TEXT_PARAM.to_string()))?;
+
+                // Params override the payload (the widget passes explicit values)
+                let unit_system = match params.get("unit_system") {
+                    Some(value) if !value.is_null() => Some(serde_json::from_value::<UnitSystem>(value.clone())
+                        .map_err(|e| crate::shared::error::AppError::Validation(format!("Invalid unit system: {}", e)))?),
+                    _ => payload.unit_system,
+                };
+                let servings_to = params.get("servings")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32)
+                    .or(payload.servings);
+                let servings_from = params.get("servings_from")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as u32)
+                    .or_else(|| recipe::detect_servings(text));
+
+                let factor = match servings_to {
+                    Some(target) => {
+                        let from = servings_from.filter(|n| *n > 0).ok_or_else(|| crate::shared::error::AppError::Validation(
+                            "Recipe doesn't say how many it serves (add \"Serves N\" or pass servings_from)".to_string()
+                        ))?;
+                        target as f64 / from as f64
+                    }
+                    None => params.get("factor")
+                        .and_then(|v| v.as_f64())
+                        .or(payload.factor)
+                        .unwrap_or(1.0),
+                };
+
+                let scaled = recipe::scale_recipe(text, &recipe::RecipeOptions { factor, unit_system })
+                    .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+
+                if scaled.lines_scaled == 0 {
+                    return Err(crate::shared::error::AppError::Validation(
+                        "No ingredient quantities found in the selected text".to_string()
+                    ));
+                }
+
+                Ok(ExecuteActionResponse {
+                    result: scaled.text,
+                    metadata: Some(json!({
+                        "factor": factor,
+                        "servings_from": servings_from,
+                        "servings_to": servings_to,
+                        "unit_system": unit_system,
+                        "lines_scaled": scaled.lines_scaled,
+                        "lines_total": scaled.lines_total,
                         "widget": "unit_converter"
                     })),
                 })
@@ -367,11 +703,15 @@ pub async fn parse_text_command(text: String) -> crate::shared::error::AppResult
         .ok_or_else(|| crate::shared::error::AppError::Validation(format!("Unknown unit: {}", unit)))?;
     
     let category = category_to_string(unit_def.category);
+    let material = materials::detect_material_in_text(&text).map(|m| m.name);
+    let (_, number_confidence) = number_locale::normalize_numbers(&text);
     
     Ok(ParseUnitResponse {
         amount,
         unit,
         category,
+        material,
+        number_confidence: Some(number_confidence),
     })
 }
 
@@ -402,19 +742,78 @@ pub async fn get_all_units_command() -> crate::shared::error::AppResult<GetUnits
 /// Convert units using the registry
 #[tauri::command]
 pub async fn convert_units_command(request: ConvertUnitsRequest) -> crate::shared::error::AppResult<ConvertUnitsResponse> {
-    let result = convert_value(request.amount, &request.from_unit, &request.to_unit)
+    let material = request.material.as_deref();
+    let material_used = bridged_material_name(&request.from_unit, &request.to_unit, material)
+        .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+    let metrics = typography_metrics(&request.from_unit, &request.to_unit, request.typography.as_ref())
+        .map_err(|e| crate::shared::error::AppError::Validation(e))?;
+
+    let format_options = FormatOptions {
+        max_decimals: if metrics.is_some() { typography::FORMAT_DECIMALS } else { FormatOptions::default().max_decimals },
+        significant_figures: request.significant_figures.filter(|sf| *sf > 0),
+        rounding: request.rounding_mode.unwrap_or_default(),
+        ..FormatOptions::default()
+    };
+
+    if request.exact.unwrap_or(false) {
+        // Exact mode: decimal arithmetic end-to-end, amount preferably taken from the string form
+        let amount = match request.exact_amount.as_deref() {
+            Some(raw) => Decimal::from_str(raw.trim())
+                .or_else(|_| Decimal::from_scientific(raw.trim()))
+                .map_err(|e| crate::shared::error::AppError::Validation(format!("Invalid exact amount '{}': {}", raw, e)))?,
+            None => decimal_from_f64(request.amount)
+                .map_err(|e| crate::shared::error::AppError::Validation(e))?,
+        };
+
+        let exact = match &metrics {
+            Some(metrics) => typography::convert_exact(amount, &request.from_unit, &request.to_unit, metrics),
+            None => convert_value_exact(amount, &request.from_unit, &request.to_unit, material),
+        }
         .map_err(|e| crate::shared::error::AppError::Calculation(e))?;
+
+        return Ok(ConvertUnitsResponse {
+            result: exact.to_f64().unwrap_or(f64::NAN),
+            formatted_result: format_decimal(exact, &format_options),
+            from_unit: request.from_unit,
+            to_unit: request.to_unit,
+            exact_result: Some(exact.to_string()),
+            material: material_used,
+        });
+    }
+
+    let result = match &metrics {
+        Some(metrics) => typography::convert(request.amount, &request.from_unit, &request.to_unit, metrics),
+        None => convert_value(request.amount, &request.from_unit, &request.to_unit, material),
+    }
+    .map_err(|e| crate::shared::error::AppError::Calculation(e))?;
     
-    let formatted_value = format_number(result);
+    let formatted_value = format_number_with(result, &format_options);
     
     Ok(ConvertUnitsResponse {
         result,
         formatted_result: formatted_value,
         from_unit: request.from_unit,
         to_unit: request.to_unit,
+        exact_result: None,
+        material: material_used,
     })
 }
 
+/// Get all materials available for Mass ↔ Volume conversions (user-defined first)
+#[tauri::command]
+pub async fn get_all_materials_command() -> crate::shared::error::AppResult<GetMaterialsResponse> {
+    let materials = materials::all_materials()
+        .into_iter()
+        .map(|m| MaterialDTO {
+            density_g_per_ml: m.grams_per_ml_f64(),
+            name: m.name,
+            custom: m.custom,
+        })
+        .collect();
+
+    Ok(GetMaterialsResponse { materials })
+}
+
 // ============================================================================
 // Legacy Commands (Deprecated - kept for backward compatibility)
 // ============================================================================
@@ -431,9 +830,10 @@ pub async fn get_units_for_category(category: String) -> crate::shared::error::A
     let units = match category.as_str() {
         "length" => vec!["mm", "cm", "m", "km", "in", "ft", "yd", "mi"],
         "mass" => vec!["mg", "g", "kg", "oz", "lb"],
-        "volume" => vec!["ml", "L", "fl-oz", "cup", "pint", "quart", "gal"],
+        "volume" => vec!["ml", "L", "tsp", "tbsp", "fl-oz", "cup", "pint", "quart", "gal"],
         "temperature" => vec!["C", "F"],
         "speed" => vec!["km/h", "m/h"],
+        "typography" => vec!["px", "pt", "pc", "em", "rem", "vw", "vh", "dp", "sp", "ios-pt"],
         _ => return Err(crate::shared::error::AppError::Validation(format!("Unknown category: {}", category))),
     };
     
@@ -477,6 +877,8 @@ fn normalize_unit(unit: &str) -> Option<&'static str> {
         // Volume
         "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => Some("ml"),
         "l" | "L" | "liter" | "liters" | "litre" | "litres" => Some("L"),
+        "tsp" | "teaspoon" | "teaspoons" => Some("tsp"),
+        "tbsp" | "tbs" | "tablespoon" | "tablespoons" => Some("tbsp"),
         "fl-oz" | "floz" | "fluid ounce" | "fluid ounces" => Some("fl-oz"),
         "cup" | "cups" => Some("cup"),
         "gal" | "gallon" | "gallons" => Some("gal"),
@@ -486,6 +888,17 @@ fn normalize_unit(unit: &str) -> Option<&'static str> {
         // Speed
         "km/h" | "kmh" | "kph" | "kilometers/hour" | "kilometers per hour" => Some("km/h"),
         "m/h" | "mph" | "miles/hour" | "miles per hour" => Some("m/h"),
+        // Typography
+        "px" | "pixel" | "pixels" => Some("px"),
+        "pt" | "point" | "points" => Some("pt"),
+        "pc" | "pica" | "picas" => Some("pc"),
+        "em" | "ems" => Some("em"),
+        "rem" | "rems" => Some("rem"),
+        "vw" => Some("vw"),
+        "vh" => Some("vh"),
+        "dp" | "dip" | "dips" => Some("dp"),
+        "sp" => Some("sp"),
+        "ios-pt" | "iospt" | "ios pt" | "ios points" => Some("ios-pt"),
         _ => None,
     }
 }
@@ -508,10 +921,6 @@ static RE_PATTERN_3: Lazy<Regex> = Lazy::new(|| {
         .expect("Failed to compile regex pattern 3")
 });
 
-static RE_THOUSANDS: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(\d),(\d{3})").expect("Failed to compile regex RE_THOUSANDS")
-});
-
 // Parse amount and unit from text (e.g., "100m", "12 km", "3.5 meters", "2km to miles")
 // LAX PARSING: Extracts the first number/unit pair found anywhere in the string
 fn parse_unit_from_text(text: &str) -> Result<(f64, String), String> {
@@ -520,15 +929,8 @@ fn parse_unit_from_text(text: &str) -> Result<(f64, String), String> {
         return Err("Empty text".to_string());
     }
 
-    // Handle thousands separators (e.g. "10,000" -> "10000")
-    // Note: Iterative replacement handles overlapping matches like "1,000,000"
-    let mut normalized_text = text.to_string();
-    while RE_THOUSANDS.is_match(&normalized_text) {
-        normalized_text = RE_THOUSANDS.replace_all(&normalized_text, "$1$2").to_string();
-    }
-
-    // Normalize remaining decimal commas to dots (e.g. "10,5" -> "10.5")
-    let normalized_text = normalized_text.replace(',', ".");
+    // Rewrite locale-specific numbers to canonical form (e.g. "10,000" -> "10000", "1.234,5" -> "1234.5")
+    let (normalized_text, _) = number_locale::normalize_numbers(text);
 
     // Pattern 1: Number followed by unit (e.g., "12km", "12 km", "12 kilometers")
     // LAX: Use .find() to locate the pattern anywhere in the string
@@ -582,9 +984,12 @@ fn parse_unit_from_text(text: &str) -> Result<(f64, String), String> {
                     ("milliliters", "ml"), ("milliliter", "ml"), ("millilitres", "ml"), ("millilitre", "ml"),
                     ("gallons", "gal"), ("gallon", "gal"),
                     ("cups", "cup"), ("cup", "cup"),
+                    ("teaspoons", "tsp"), ("teaspoon", "tsp"),
+                    ("tablespoons", "tbsp"), ("tablespoon", "tbsp"),
                     ("fluid ounces", "fl-oz"), ("fluid ounce", "fl-oz"),
                     ("celsius", "C"), ("fahrenheit", "F"),
                     ("kilometers per hour", "km/h"), ("miles per hour", "m/h"),
+                    ("pixels", "px"), ("pixel", "px"), ("picas", "pc"), ("pica", "pc"),
                 ] {
                     if text_lower.contains(alias) {
                         println!("[parse_unit_from_text] ✓ Extracted: {} {} from '{}'", amount, canonical, text);
@@ -834,102 +1239,200 @@ fn from_meters_per_second(value: f64, unit: &str) -> Result<f64, String> {
     })
 }
 
-// Helper to add thousands separators to a string
-fn add_thousands_separators(s: &str) -> String {
-    let mut result = String::new();
-    let chars: Vec<char> = s.chars().rev().collect();
-    for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
+/// Options controlling how converted values are rounded and displayed
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    /// Maximum decimal places when `significant_figures` is not set
+    pub max_decimals: u32,
+    /// Round to N significant figures instead of `max_decimals`
+    pub significant_figures: Option<u32>,
+    pub rounding: RoundingMode,
+    /// Separators for display (defaults to the user's number locale)
+    pub locale: NumberLocale,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_decimals: 2,
+            significant_figures: None,
+            rounding: RoundingMode::HalfUp,
+            locale: number_locale::number_locale(),
+        }
+    }
+}
+
+impl From<RoundingMode> for RoundingStrategy {
+    fn from(mode: RoundingMode) -> Self {
+        match mode {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+            RoundingMode::Up => RoundingStrategy::AwayFromZero,
+            RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+            RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
         }
-        result.push(*ch);
     }
-    result.chars().rev().collect()
 }
 
 // Format number with thousands separators, max 2 decimals, strip trailing zeros
 // Examples: 130000.000 -> "130,000", 12.500 -> "12.5", 12.567 -> "12.57"
 fn format_number(value: f64) -> String {
-    // Round to 2 decimal places
-    let rounded = (value * 100.0).round() / 100.0;
-    
+    format_number_with(value, &FormatOptions::default())
+}
+
+/// Format an f64 with the given rounding options (see `format_decimal`)
+fn format_number_with(value: f64, options: &FormatOptions) -> String {
     // Handle special cases
-    if rounded.is_nan() {
+    if value.is_nan() {
         return "NaN".to_string();
     }
-    if rounded.is_infinite() {
-        return if rounded.is_sign_positive() { "∞".to_string() } else { "-∞".to_string() };
-    }
-    
-    // Handle overflow for very large numbers
-    let integer_part = if rounded.abs() > i64::MAX as f64 {
-        // For numbers beyond i64::MAX, use string formatting directly
-        let formatted = format!("{:.2}", rounded)
-            .replace(".00", "")
-            .trim_end_matches('0')
-            .trim_end_matches('.')
-            .to_string();
-        // Add thousands separators manually for large numbers
-        // Safe: Check parts length before indexing to prevent panic
-        return if formatted.contains('.') {
-            let parts: Vec<&str> = formatted.split('.').collect();
-            if parts.len() >= 2 {
-                format!("{}.{}", add_thousands_separators(parts[0]), parts[1])
-            } else {
-                add_thousands_separators(&formatted)
-            }
-        } else {
-            add_thousands_separators(&formatted)
-        };
-    } else {
-        rounded.trunc() as i64
-    };
-    let decimal_part = (rounded.fract() * 100.0).round() as i64;
-    
-    // Format integer part with thousands separators
-    let integer_str = format!("{}", integer_part.abs());
-    let formatted_integer = add_thousands_separators(&integer_str);
-    
-    // Handle negative sign
-    let formatted_integer = if integer_part < 0 {
-        format!("-{}", formatted_integer)
-    } else {
-        formatted_integer
-    };
-    
-    // Format decimal part (strip trailing zeros)
-    if decimal_part == 0 {
-        // No decimal part needed
-        formatted_integer
-    } else {
-        // Format with up to 2 decimal places, strip trailing zeros
-        let decimal_str = format!("{:02}", decimal_part);
-        let trimmed_decimal = decimal_str.trim_end_matches('0');
-        
-        if trimmed_decimal.is_empty() {
-            formatted_integer
-        } else {
-            format!("{}.{}", formatted_integer, trimmed_decimal)
+    if value.is_infinite() {
+        return if value.is_sign_positive() { "∞".to_string() } else { "-∞".to_string() };
+    }
+
+    match Decimal::from_f64(value) {
+        Some(decimal) => format_decimal(decimal, options),
+        None => {
+            // Beyond Decimal's range (~7.9e28): plain string formatting
+            number_locale::format_canonical_in(&format!("{:.0}", value), options.locale)
         }
     }
 }
 
-// Material density lookup (g/mL)
-// Default is water (1.0 g/mL)
-fn get_material_density(material: &str) -> Option<f64> {
-    let material_lower = material.to_lowercase();
-    match material_lower.as_str() {
-        "water" | "h2o" => Some(1.0),
-        "milk" => Some(1.03),
-        "oil" | "vegetable oil" => Some(0.92),
-        "gasoline" | "petrol" => Some(0.74),
-        "alcohol" | "ethanol" => Some(0.79),
-        "mercury" => Some(13.6),
-        "iron" => Some(7.87),
-        "aluminum" | "aluminium" => Some(2.70),
-        "gold" => Some(19.3),
-        "lead" => Some(11.3),
-        _ => None, // Unknown material, will use default (water)
+/// Format a decimal with thousands separators, rounding per `options`, trailing zeros stripped
+// Examples (default options): 130000.000 -> "130,000", 12.500 -> "12.5", 12.565 -> "12.57"
+// Examples (3 significant figures): 1234.5 -> "1,230", 0.0012345 -> "0.00123"
+fn format_decimal(value: Decimal, options: &FormatOptions) -> String {
+    let strategy = RoundingStrategy::from(options.rounding);
+    let rounded = match options.significant_figures {
+        Some(digits) if digits > 0 => value
+            .round_sf_with_strategy(digits, strategy)
+            .unwrap_or_else(|| value.round_dp_with_strategy(options.max_decimals, strategy)),
+        _ => value.round_dp_with_strategy(options.max_decimals, strategy),
+    }
+    .normalize();
+
+    let formatted = number_locale::format_canonical_in(&rounded.abs().to_string(), options.locale);
+
+    // Handle negative sign (avoid "-0" after rounding)
+    if rounded.is_sign_negative() && !rounded.is_zero() {
+        format!("-{}", formatted)
+    } else {
+        formatted
     }
 }
 
+// Material density lookup (g/mL), backed by the material database
+// Returns None for unknown materials (callers default to water)
+fn get_material_density(material: &str) -> Option<f64> {
+    materials::lookup_density(material).map(|m| m.grams_per_ml_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_length_conversion() {
+        let result = convert_value_exact(Decimal::ONE, "in", "cm", None).unwrap();
+        assert_eq!(result, Decimal::from_str("2.54").unwrap());
+
+        let result = convert_value_exact(Decimal::ONE, "mi", "km", None).unwrap();
+        assert_eq!(result, Decimal::from_str("1.609344").unwrap());
+    }
+
+    #[test]
+    fn test_exact_temperature_conversion() {
+        let result = convert_value_exact(Decimal::from(212), "F", "C", None).unwrap();
+        assert_eq!(result, Decimal::from(100));
+
+        let result = convert_value_exact(Decimal::from(100), "C", "F", None).unwrap();
+        assert_eq!(result, Decimal::from(212));
+    }
+
+    #[test]
+    fn test_exact_speed_conversion() {
+        let result = convert_value_exact(Decimal::from(36), "km/h", "m/h", None).unwrap();
+        let expected = Decimal::from(10) / Decimal::from_str("0.44704").unwrap();
+        assert_eq!(result.round_dp(10), expected.round_dp(10));
+    }
+
+    #[test]
+    fn test_exact_rejects_incompatible_units() {
+        assert!(convert_value_exact(Decimal::ONE, "m", "kg", None).is_err());
+    }
+
+    #[test]
+    fn test_material_bridge() {
+        // 1 cup of water ≈ 236.59 g; flour is about half as dense
+        let water = convert_value_exact(Decimal::ONE, "cup", "g", None).unwrap();
+        assert_eq!(water, Decimal::from_str("236.5882365").unwrap());
+
+        let flour = convert_value(1.0, "cup", "g", Some("flour")).unwrap();
+        assert!((flour - 124.92).abs() < 0.01);
+
+        let back = convert_value_exact(Decimal::from(1000), "g", "L", Some("milk")).unwrap();
+        assert_eq!(back.round_dp(4), Decimal::from_str("0.9709").unwrap());
+
+        assert!(convert_value(1.0, "cup", "g", Some("unobtanium")).is_err());
+    }
+
+    #[test]
+    fn test_format_default_rounding() {
+        assert_eq!(format_number(130000.0), "130,000");
+        assert_eq!(format_number(12.5), "12.5");
+        assert_eq!(format_number(12.565), "12.57");
+        assert_eq!(format_number(-0.001), "0");
+    }
+
+    #[test]
+    fn test_format_significant_figures() {
+        let options = FormatOptions { significant_figures: Some(3), ..FormatOptions::default() };
+        assert_eq!(format_decimal(Decimal::from_str("1234.5").unwrap(), &options), "1,230");
+        assert_eq!(format_decimal(Decimal::from_str("0.0012345").unwrap(), &options), "0.00123");
+    }
+
+    #[test]
+    fn test_format_rounding_modes() {
+        let value = Decimal::from_str("2.345").unwrap();
+        let with = |rounding| FormatOptions { rounding, ..FormatOptions::default() };
+        assert_eq!(format_decimal(value, &with(RoundingMode::HalfUp)), "2.35");
+        assert_eq!(format_decimal(value, &with(RoundingMode::HalfEven)), "2.34");
+        assert_eq!(format_decimal(value, &with(RoundingMode::Down)), "2.34");
+        assert_eq!(format_decimal(-value, &with(RoundingMode::Floor)), "-2.35");
+        assert_eq!(format_decimal(-value, &with(RoundingMode::Ceiling)), "-2.34");
+    }
+
+    #[test]
+    fn test_format_number_locale() {
+        let value = Decimal::from_str("1234567.891").unwrap();
+        let with = |locale| FormatOptions { locale, ..FormatOptions::default() };
+        assert_eq!(format_decimal(value, &with(NumberLocale::EnUs)), "1,234,567.89");
+        assert_eq!(format_decimal(value, &with(NumberLocale::DeDe)), "1.234.567,89");
+        assert_eq!(format_decimal(-value, &with(NumberLocale::DeCh)), "-1'234'567.89");
+    }
+
+    #[test]
+    fn test_parse_localized_numbers() {
+        assert_eq!(parse_unit_from_text("1.234,5 km").unwrap(), (1234.5, "km".to_string()));
+        assert_eq!(parse_unit_from_text("1'234.5 m").unwrap(), (1234.5, "m".to_string()));
+        assert_eq!(parse_unit_from_text("10,000 g").unwrap(), (10000.0, "g".to_string()));
+        assert_eq!(parse_unit_from_text("2,5 kg").unwrap(), (2.5, "kg".to_string()));
+    }
+
+    #[test]
+    fn test_parse_typography_units() {
+        assert_eq!(parse_unit_from_text("16px").unwrap(), (16.0, "px".to_string()));
+        assert_eq!(parse_unit_from_text("font-size: 1.5rem;").unwrap(), (1.5, "rem".to_string()));
+        assert_eq!(parse_unit_from_text("14 sp").unwrap(), (14.0, "sp".to_string()));
+    }
+
+    #[test]
+    fn test_typography_metrics_routing() {
+        assert!(typography_metrics("px", "rem", None).unwrap().is_some());
+        assert!(typography_metrics("mm", "px", None).unwrap().is_some());
+        assert!(typography_metrics("mm", "in", None).unwrap().is_none());
+        assert!(typography_metrics("kg", "lb", None).unwrap().is_none());
+    }
+}
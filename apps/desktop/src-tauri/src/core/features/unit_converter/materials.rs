@@ -0,0 +1,290 @@
+//! Material density database for Mass ↔ Volume conversions
+//!
+//! Built-in densities cover common cooking ingredients, liquids and metals.
+//! Users can add their own (or override built-ins) via `UserPreferences::custom_materials`;
+//! those are pushed here at startup and whenever settings are saved.
+
+use crate::shared::settings::CustomMaterial;
+use once_cell::sync::Lazy;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// Built-in material definition (density in g/mL)
+#[derive(Debug, Clone, Copy)]
+pub struct MaterialDefinition {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub density: &'static str, // Exact string so decimal mode stays exact
+    pub liquid: bool,           // Measured by volume in recipes (kept in ml when converting to metric)
+}
+
+/// Resolved density for a conversion, from either the built-in table or user settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialDensity {
+    pub name: String,
+    pub grams_per_ml: Decimal,
+    pub custom: bool,
+    pub liquid: bool,
+}
+
+impl MaterialDensity {
+    pub fn grams_per_ml_f64(&self) -> f64 {
+        self.grams_per_ml.to_f64().unwrap_or(1.0)
+    }
+}
+
+// Culinary densities are derived from standard US cup weights (1 cup = 236.588 mL),
+// e.g. all-purpose flour 125 g/cup ≈ 0.528 g/mL
+const BUILTIN_MATERIALS: &[MaterialDefinition] = &[
+    // Liquids
+    MaterialDefinition { id: "water", name: "Water", aliases: &["h2o"], density: "1", liquid: true },
+    MaterialDefinition { id: "seawater", name: "Seawater", aliases: &["sea water", "salt water"], density: "1.025", liquid: true },
+    MaterialDefinition { id: "milk", name: "Milk", aliases: &["whole milk"], density: "1.03", liquid: true },
+    MaterialDefinition { id: "cream", name: "Heavy Cream", aliases: &["heavy cream", "whipping cream"], density: "1.01", liquid: true },
+    MaterialDefinition { id: "yogurt", name: "Yogurt", aliases: &["yoghurt", "greek yogurt"], density: "1.036", liquid: true },
+    MaterialDefinition { id: "oil", name: "Vegetable Oil", aliases: &["vegetable oil", "canola oil", "sunflower oil", "cooking oil"], density: "0.92", liquid: true },
+    MaterialDefinition { id: "olive_oil", name: "Olive Oil", aliases: &["olive oil"], density: "0.91", liquid: true },
+    MaterialDefinition { id: "honey", name: "Honey", aliases: &[], density: "1.42", liquid: true },
+    MaterialDefinition { id: "maple_syrup", name: "Maple Syrup", aliases: &["maple syrup", "syrup"], density: "1.32", liquid: true },
+    MaterialDefinition { id: "ethanol", name: "Ethanol", aliases: &["alcohol"], density: "0.789", liquid: true },
+    MaterialDefinition { id: "gasoline", name: "Gasoline", aliases: &["petrol", "gas"], density: "0.74", liquid: true },
+    MaterialDefinition { id: "diesel", name: "Diesel", aliases: &["diesel fuel"], density: "0.832", liquid: true },
+    MaterialDefinition { id: "glycerin", name: "Glycerin", aliases: &["glycerine", "glycerol"], density: "1.26", liquid: true },
+    MaterialDefinition { id: "mercury", name: "Mercury", aliases: &[], density: "13.534", liquid: true },
+
+    // Baking & pantry
+    MaterialDefinition { id: "flour", name: "All-Purpose Flour", aliases: &["all-purpose flour", "all purpose flour", "plain flour"], density: "0.528", liquid: false },
+    MaterialDefinition { id: "bread_flour", name: "Bread Flour", aliases: &["bread flour", "strong flour"], density: "0.537", liquid: false },
+    MaterialDefinition { id: "whole_wheat_flour", name: "Whole Wheat Flour", aliases: &["whole wheat flour", "wholemeal flour"], density: "0.507", liquid: false },
+    MaterialDefinition { id: "sugar", name: "Granulated Sugar", aliases: &["granulated sugar", "white sugar", "caster sugar"], density: "0.845", liquid: false },
+    MaterialDefinition { id: "brown_sugar", name: "Brown Sugar (packed)", aliases: &["brown sugar"], density: "0.93", liquid: false },
+    MaterialDefinition { id: "powdered_sugar", name: "Powdered Sugar", aliases: &["powdered sugar", "icing sugar", "confectioners sugar"], density: "0.507", liquid: false },
+    MaterialDefinition { id: "butter", name: "Butter", aliases: &[], density: "0.959", liquid: false },
+    MaterialDefinition { id: "salt", name: "Table Salt", aliases: &["table salt"], density: "1.217", liquid: false },
+    MaterialDefinition { id: "rice", name: "Rice (uncooked)", aliases: &["white rice"], density: "0.782", liquid: false },
+    MaterialDefinition { id: "oats", name: "Rolled Oats", aliases: &["rolled oats", "oatmeal"], density: "0.38", liquid: false },
+    MaterialDefinition { id: "cocoa", name: "Cocoa Powder", aliases: &["cocoa powder"], density: "0.359", liquid: false },
+    MaterialDefinition { id: "cornstarch", name: "Cornstarch", aliases: &["corn starch", "cornflour"], density: "0.541", liquid: false },
+    MaterialDefinition { id: "peanut_butter", name: "Peanut Butter", aliases: &["peanut butter"], density: "1.09", liquid: false },
+
+    // Building materials & other solids
+    MaterialDefinition { id: "ice", name: "Ice", aliases: &[], density: "0.917", liquid: false },
+    MaterialDefinition { id: "sand", name: "Sand (dry)", aliases: &["dry sand"], density: "1.6", liquid: false },
+    MaterialDefinition { id: "concrete", name: "Concrete", aliases: &[], density: "2.4", liquid: false },
+
+    // Metals
+    MaterialDefinition { id: "aluminum", name: "Aluminum", aliases: &["aluminium"], density: "2.70", liquid: false },
+    MaterialDefinition { id: "titanium", name: "Titanium", aliases: &[], density: "4.506", liquid: false },
+    MaterialDefinition { id: "steel", name: "Steel", aliases: &["stainless steel"], density: "7.85", liquid: false },
+    MaterialDefinition { id: "iron", name: "Iron", aliases: &[], density: "7.874", liquid: false },
+    MaterialDefinition { id: "copper", name: "Copper", aliases: &[], density: "8.96", liquid: false },
+    MaterialDefinition { id: "silver", name: "Silver", aliases: &[], density: "10.49", liquid: false },
+    MaterialDefinition { id: "lead", name: "Lead", aliases: &[], density: "11.34", liquid: false },
+    MaterialDefinition { id: "gold", name: "Gold", aliases: &[], density: "19.32", liquid: false },
+];
+
+/// User-defined materials from settings (checked before the built-in table)
+static CUSTOM_MATERIALS: Lazy<RwLock<Vec<CustomMaterial>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Replace the user-defined materials (called when settings are loaded or saved)
+pub fn set_custom_materials(materials: &[CustomMaterial]) {
+    let valid: Vec<CustomMaterial> = materials
+        .iter()
+        .filter(|m| {
+            let ok = !m.name.trim().is_empty()
+                && m.density_g_per_ml.is_finite()
+                && m.density_g_per_ml > 0.0;
+            if !ok {
+                eprintln!("[materials] Skipping invalid custom material '{}' ({} g/mL)", m.name, m.density_g_per_ml);
+            }
+            ok
+        })
+        .cloned()
+        .collect();
+
+    println!("[materials] Loaded {} custom material(s)", valid.len());
+    match CUSTOM_MATERIALS.write() {
+        Ok(mut guard) => *guard = valid,
+        Err(poisoned) => *poisoned.into_inner() = valid,
+    }
+}
+
+fn custom_materials() -> Vec<CustomMaterial> {
+    match CUSTOM_MATERIALS.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Density of water, used when no material is specified
+pub fn water() -> MaterialDensity {
+    MaterialDensity {
+        name: "Water".to_string(),
+        grams_per_ml: Decimal::ONE,
+        custom: false,
+        liquid: true,
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn builtin_density(def: &MaterialDefinition) -> MaterialDensity {
+    MaterialDensity {
+        name: def.name.to_string(),
+        // Safe: built-in densities are constant decimal literals (covered by tests)
+        grams_per_ml: Decimal::from_str(def.density).unwrap_or(Decimal::ONE),
+        custom: false,
+        liquid: def.liquid,
+    }
+}
+
+fn custom_density(material: &CustomMaterial) -> Option<MaterialDensity> {
+    Some(MaterialDensity {
+        name: material.name.trim().to_string(),
+        grams_per_ml: Decimal::from_f64(material.density_g_per_ml)?,
+        custom: true,
+        liquid: material.liquid,
+    })
+}
+
+/// All names a material answers to (lowercased), including its id and display name
+fn builtin_names(def: &MaterialDefinition) -> impl Iterator<Item = String> + '_ {
+    [def.id.replace('_', " "), def.name.to_lowercase()]
+        .into_iter()
+        .chain(def.aliases.iter().map(|a| a.to_string()))
+}
+
+fn custom_names(material: &CustomMaterial) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(normalize_name(&material.name))
+        .chain(material.aliases.iter().map(|a| normalize_name(a)))
+        .filter(|n| !n.is_empty())
+}
+
+/// Look up a material by id, name or alias (case-insensitive; user materials win)
+pub fn lookup_density(material: &str) -> Option<MaterialDensity> {
+    let wanted = normalize_name(&material.replace('_', " "));
+    if wanted.is_empty() {
+        return None;
+    }
+
+    // Accept simple plurals ("lemons" → "lemon")
+    let singular = wanted.strip_suffix('s').map(str::to_string);
+    let matches = |name: &str| name == wanted || singular.as_deref() == Some(name);
+
+    for custom in custom_materials() {
+        if custom_names(&custom).any(|n| matches(&n)) {
+            return custom_density(&custom);
+        }
+    }
+
+    BUILTIN_MATERIALS
+        .iter()
+        .find(|def| builtin_names(def).any(|n| matches(&n)))
+        .map(builtin_density)
+}
+
+/// Find a material mentioned in free text ("1 cup flour in grams")
+///
+/// Prefers the longest matching name so "brown sugar" wins over "sugar".
+/// Names must sit on word boundaries so "oil" does not match "boil".
+pub fn detect_material_in_text(text: &str) -> Option<MaterialDensity> {
+    let haystack = normalize_name(text);
+    let mut best: Option<(usize, MaterialDensity)> = None;
+
+    let mut consider = |name: &str, density: MaterialDensity| {
+        if name.is_empty() || !contains_word(&haystack, name) {
+            return;
+        }
+        if best.as_ref().is_none_or(|(len, _)| name.len() > *len) {
+            best = Some((name.len(), density));
+        }
+    };
+
+    for custom in custom_materials() {
+        if let Some(density) = custom_density(&custom) {
+            for name in custom_names(&custom) {
+                consider(&name, density.clone());
+            }
+        }
+    }
+    for def in BUILTIN_MATERIALS {
+        for name in builtin_names(def) {
+            consider(&name, builtin_density(def));
+        }
+    }
+
+    if let Some((_, ref density)) = best {
+        println!("[detect_material_in_text] ✓ Detected material: {}", density.name);
+    }
+    best.map(|(_, density)| density)
+}
+
+/// Word-boundary substring check (boundaries are non-alphanumeric chars or string ends)
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before_ok = haystack[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        // Allow a plural "s" ("2 cups oats")
+        let rest = &haystack[start + needle.len()..];
+        let rest = rest.strip_prefix('s').unwrap_or(rest);
+        let after_ok = rest.chars().next().is_none_or(|c| !c.is_alphanumeric());
+        before_ok && after_ok
+    })
+}
+
+/// Every known material (user-defined first), for populating the widget's picker
+pub fn all_materials() -> Vec<MaterialDensity> {
+    let mut materials: Vec<MaterialDensity> = custom_materials()
+        .iter()
+        .filter_map(custom_density)
+        .collect();
+    materials.extend(BUILTIN_MATERIALS.iter().map(builtin_density));
+    materials
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_densities_parse() {
+        for def in BUILTIN_MATERIALS {
+            let density = Decimal::from_str(def.density);
+            assert!(density.is_ok(), "invalid density for {}", def.id);
+            assert!(density.unwrap() > Decimal::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_alias_and_case() {
+        assert_eq!(lookup_density("Flour").unwrap().name, "All-Purpose Flour");
+        assert_eq!(lookup_density("aluminium").unwrap().name, "Aluminum");
+        assert_eq!(lookup_density("brown_sugar").unwrap().name, "Brown Sugar (packed)");
+        assert!(lookup_density("unobtanium").is_none());
+    }
+
+    #[test]
+    fn test_detect_material_prefers_longest_name() {
+        let found = detect_material_in_text("1 cup brown sugar in grams").unwrap();
+        assert_eq!(found.name, "Brown Sugar (packed)");
+
+        let found = detect_material_in_text("1 cup flour in grams").unwrap();
+        assert_eq!(found.name, "All-Purpose Flour");
+    }
+
+    #[test]
+    fn test_detect_material_respects_word_boundaries() {
+        assert!(detect_material_in_text("boil 2 cups").is_none());
+        assert!(detect_material_in_text("100 km").is_none());
+    }
+}
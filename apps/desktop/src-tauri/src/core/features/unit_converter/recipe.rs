@@ -0,0 +1,474 @@
+//! Recipe scaling and cooking measurement normaliser
+//!
+//! Parses an ingredient list line by line ("1 1/2 cups flour", "½ tsp salt", "2-3 cloves garlic"),
+//! scales every quantity by a factor (or to a target number of servings), optionally converts to
+//! metric or imperial, and rewrites the list with cook-friendly rounding (0.33 cup → 1/3 cup).
+//! Lines without a leading quantity are passed through unchanged.
+
+use super::materials;
+use super::{convert_value, format_number};
+use crate::shared::types::UnitSystem;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Unicode vulgar fractions accepted in quantities
+const UNICODE_FRACTIONS: &[(char, f64)] = &[
+    ('½', 1.0 / 2.0), ('⅓', 1.0 / 3.0), ('⅔', 2.0 / 3.0), ('¼', 1.0 / 4.0), ('¾', 3.0 / 4.0),
+    ('⅕', 1.0 / 5.0), ('⅖', 2.0 / 5.0), ('⅗', 3.0 / 5.0), ('⅘', 4.0 / 5.0), ('⅙', 1.0 / 6.0),
+    ('⅚', 5.0 / 6.0), ('⅐', 1.0 / 7.0), ('⅛', 1.0 / 8.0), ('⅜', 3.0 / 8.0), ('⅝', 5.0 / 8.0),
+    ('⅞', 7.0 / 8.0), ('⅑', 1.0 / 9.0), ('⅒', 1.0 / 10.0),
+];
+
+/// Cooking unit aliases → registry symbol (longest aliases first so "fl oz" beats "oz")
+const RECIPE_UNITS: &[(&str, &str)] = &[
+    ("fluid ounces", "fl-oz"), ("fluid ounce", "fl-oz"), ("fl. oz", "fl-oz"), ("fl oz", "fl-oz"), ("fl-oz", "fl-oz"),
+    ("tablespoons", "tbsp"), ("tablespoon", "tbsp"), ("tbsp", "tbsp"), ("tbs", "tbsp"),
+    ("teaspoons", "tsp"), ("teaspoon", "tsp"), ("tsp", "tsp"),
+    ("milliliters", "ml"), ("milliliter", "ml"), ("millilitres", "ml"), ("millilitre", "ml"), ("ml", "ml"),
+    ("liters", "L"), ("liter", "L"), ("litres", "L"), ("litre", "L"), ("l", "L"),
+    ("kilograms", "kg"), ("kilogram", "kg"), ("kg", "kg"),
+    ("milligrams", "mg"), ("milligram", "mg"), ("mg", "mg"),
+    ("grams", "g"), ("gram", "g"), ("g", "g"),
+    ("ounces", "oz"), ("ounce", "oz"), ("oz", "oz"),
+    ("pounds", "lb"), ("pound", "lb"), ("lbs", "lb"), ("lb", "lb"),
+    ("cups", "cup"), ("cup", "cup"),
+    ("pints", "pint"), ("pint", "pint"), ("pt", "pint"),
+    ("quarts", "quart"), ("quart", "quart"), ("qt", "quart"),
+    ("gallons", "gal"), ("gallon", "gal"), ("gal", "gal"),
+];
+
+// Quantity: mixed number ("1 1/2", "1½"), fraction ("1/2", "½"), or decimal ("1.5")
+const QTY: &str = r"(?:\d+\s+\d+\s*[/⁄]\s*\d+|\d+\s*[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅐⅛⅜⅝⅞⅑⅒]|\d+\s*[/⁄]\s*\d+|[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅐⅛⅜⅝⅞⅑⅒]|\d+(?:\.\d+)?)";
+
+static RE_INGREDIENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"^(?P<prefix>\s*(?:[-*•·]\s*)?)(?P<qty>{qty})(?:\s*(?:-|–|—|to)\s*(?P<qty2>{qty}))?(?P<rest>.*)$",
+        qty = QTY
+    ))
+    .expect("Failed to compile regex RE_INGREDIENT")
+});
+
+static RE_SERVINGS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?P<head>\s*(?:serves|servings|yield|makes)\s*:?\s*)(?P<n>\d+)(?P<tail>.*)$")
+        .expect("Failed to compile regex RE_SERVINGS")
+});
+
+/// How to rewrite an ingredient list
+#[derive(Debug, Clone, Copy)]
+pub struct RecipeOptions {
+    pub factor: f64,
+    pub unit_system: Option<UnitSystem>,
+}
+
+/// Result of rewriting an ingredient list
+#[derive(Debug, Clone)]
+pub struct ScaledRecipe {
+    pub text: String,
+    pub lines_total: usize,
+    pub lines_scaled: usize,
+}
+
+/// A single measure (quantity + optional registry unit)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Measure {
+    low: f64,
+    high: Option<f64>, // Upper bound for ranges ("2-3 cups")
+    unit: Option<&'static str>,
+}
+
+/// Parse a quantity token: "1 1/2", "1½", "1/2", "½", "1.5"
+pub fn parse_quantity(token: &str) -> Option<f64> {
+    let token = token.trim().replace('⁄', "/");
+    if token.is_empty() {
+        return None;
+    }
+
+    // Trailing unicode fraction, optionally after a whole number ("1½", "1 ½", "½")
+    if let Some(last) = token.chars().last() {
+        if let Some((_, frac)) = UNICODE_FRACTIONS.iter().find(|(c, _)| *c == last) {
+            let whole = token[..token.len() - last.len_utf8()].trim();
+            let whole = if whole.is_empty() { 0.0 } else { whole.parse::<f64>().ok()? };
+            return Some(whole + frac);
+        }
+    }
+
+    // Mixed number or simple fraction ("1 1/2", "3/4")
+    if let Some((left, den)) = token.split_once('/') {
+        let den: f64 = den.trim().parse().ok()?;
+        if den == 0.0 {
+            return None;
+        }
+        let mut parts = left.split_whitespace();
+        let first: f64 = parts.next()?.parse().ok()?;
+        return match parts.next() {
+            Some(num) => Some(first + num.parse::<f64>().ok()? / den),
+            None => Some(first / den),
+        };
+    }
+
+    token.parse::<f64>().ok()
+}
+
+/// Match a cooking unit at the start of `rest`, returning (symbol, remaining text)
+fn split_unit(rest: &str) -> (Option<&'static str>, &str) {
+    let trimmed = rest.trim_start();
+    let lower = trimmed.to_lowercase();
+    for (alias, symbol) in RECIPE_UNITS {
+        if let (Some(after_lower), Some(after)) = (lower.strip_prefix(alias), trimmed.get(alias.len()..)) {
+            // Require a word boundary so "l" doesn't match "large" and "g" doesn't match "garlic"
+            if after_lower.chars().next().is_none_or(|c| !c.is_alphanumeric()) {
+                return (Some(symbol), after.strip_prefix('.').unwrap_or(after));
+            }
+        }
+    }
+    (None, rest)
+}
+
+/// Detect "Serves 4" / "Servings: 6" / "Makes 12" in the text
+pub fn detect_servings(text: &str) -> Option<u32> {
+    text.lines()
+        .filter_map(|line| RE_SERVINGS.captures(line))
+        .find_map(|caps| caps.name("n")?.as_str().parse().ok())
+        .filter(|n| *n > 0)
+}
+
+/// Scale and/or convert every ingredient line in `text`
+pub fn scale_recipe(text: &str, options: &RecipeOptions) -> Result<ScaledRecipe, String> {
+    if !options.factor.is_finite() || options.factor <= 0.0 {
+        return Err(format!("Invalid scale factor: {}", options.factor));
+    }
+
+    let mut lines_total = 0;
+    let mut lines_scaled = 0;
+    let mut output = Vec::new();
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            output.push(line.to_string());
+            continue;
+        }
+        lines_total += 1;
+
+        // "Serves 4" → "Serves 8"
+        if let Some(caps) = RE_SERVINGS.captures(line) {
+            if let Some(n) = caps.name("n").and_then(|n| n.as_str().parse::<f64>().ok()) {
+                let head = caps.name("head").map_or("", |m| m.as_str());
+                let tail = caps.name("tail").map_or("", |m| m.as_str());
+                output.push(format!("{}{}{}", head, format_count(n * options.factor), tail));
+                lines_scaled += 1;
+                continue;
+            }
+        }
+
+        match rewrite_ingredient(line, options) {
+            Some(rewritten) => {
+                lines_scaled += 1;
+                output.push(rewritten);
+            }
+            None => output.push(line.to_string()),
+        }
+    }
+
+    println!(
+        "[scale_recipe] factor {} → {}/{} lines rewritten",
+        options.factor, lines_scaled, lines_total
+    );
+
+    Ok(ScaledRecipe {
+        text: output.join("\n"),
+        lines_total,
+        lines_scaled,
+    })
+}
+
+fn rewrite_ingredient(line: &str, options: &RecipeOptions) -> Option<String> {
+    let caps = RE_INGREDIENT.captures(line)?;
+    let prefix = caps.name("prefix").map_or("", |m| m.as_str());
+    let low = parse_quantity(caps.name("qty")?.as_str())?;
+    let high = match caps.name("qty2") {
+        Some(m) => Some(parse_quantity(m.as_str())?),
+        None => None,
+    };
+    let rest = caps.name("rest").map_or("", |m| m.as_str());
+    let (unit, ingredient) = split_unit(rest);
+
+    // Not a quantity: step numbers ("1. Preheat"), temperatures ("350°F"), pan sizes ("9x13")
+    if unit.is_none() && rest.chars().next().is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+
+    let scaled = Measure {
+        low: low * options.factor,
+        high: high.map(|h| h * options.factor),
+        unit,
+    };
+    let measure = normalize_measure(scaled, ingredient, options.unit_system);
+
+    let quantity = match measure.high {
+        Some(high) => format!("{}-{}", format_quantity(measure.low, measure.unit), format_quantity(high, measure.unit)),
+        None => format_quantity(measure.low, measure.unit),
+    };
+    // Plural follows what the reader sees: "1 cup", "1/2 cup", "1 1/2 cups", "2-3 cups"
+    let plural = measure.high.is_some() || !(quantity == "1" || quantity.starts_with("0.") || (quantity.contains('/') && !quantity.contains(' ')));
+
+    Some(match measure.unit {
+        Some(unit) => format!("{}{} {} {}", prefix, quantity, unit_label(unit, plural), ingredient.trim_start()).trim_end().to_string(),
+        None => format!("{}{}{}", prefix, quantity, ingredient),
+    })
+}
+
+/// Convert a measure to the requested system (or tidy it within its own system)
+fn normalize_measure(measure: Measure, ingredient: &str, system: Option<UnitSystem>) -> Measure {
+    let Some(unit) = measure.unit else {
+        return measure;
+    };
+    let Some(category) = super::UNIT_REGISTRY.get(unit).map(|def| def.category) else {
+        return measure;
+    };
+
+    let target = match (system, category) {
+        (Some(UnitSystem::Metric), super::UnitCategory::Volume) => {
+            // Dry ingredients are weighed in metric kitchens: 1 cup flour → 125 g
+            match materials::detect_material_in_text(ingredient) {
+                Some(material) if !material.liquid => {
+                    return convert_measure(measure, "g", Some(&material.name))
+                        .map(|m| pick_metric(m, "g", "kg"))
+                        .unwrap_or(measure);
+                }
+                _ => pick_metric_unit(measure, "ml", "L"),
+            }
+        }
+        (Some(UnitSystem::Metric), super::UnitCategory::Mass) => pick_metric_unit(measure, "g", "kg"),
+        (Some(UnitSystem::Imperial), super::UnitCategory::Volume) => pick_imperial_volume(measure),
+        (Some(UnitSystem::Imperial), super::UnitCategory::Mass) => pick_imperial_mass(measure),
+        // Keep the original system, only stepping within its ladder (tsp ↔ tbsp ↔ cup, g ↔ kg)
+        (None, _) => match unit {
+            "tsp" | "tbsp" | "cup" => pick_imperial_volume(measure),
+            "oz" | "lb" => pick_imperial_mass(measure),
+            "ml" | "L" => pick_metric_unit(measure, "ml", "L"),
+            "g" | "kg" => pick_metric_unit(measure, "g", "kg"),
+            _ => unit,
+        },
+        (Some(_), _) => unit,
+    };
+
+    convert_measure(measure, target, None).unwrap_or(measure)
+}
+
+fn convert_measure(measure: Measure, target: &'static str, material: Option<&str>) -> Option<Measure> {
+    let unit = measure.unit?;
+    let low = convert_value(measure.low, unit, target, material).ok()?;
+    let high = match measure.high {
+        Some(high) => Some(convert_value(high, unit, target, material).ok()?),
+        None => None,
+    };
+    Some(Measure { low, high, unit: Some(target) })
+}
+
+/// Amount of the measure in `base` units (used to pick a unit on the ladder)
+fn amount_in(measure: &Measure, base: &str) -> f64 {
+    let amount = measure.high.unwrap_or(measure.low);
+    measure.unit
+        .and_then(|unit| convert_value(amount, unit, base, None).ok())
+        .unwrap_or(amount)
+}
+
+fn pick_metric_unit(measure: Measure, small: &'static str, large: &'static str) -> &'static str {
+    if amount_in(&measure, small) >= 1000.0 { large } else { small }
+}
+
+fn pick_metric(measure: Measure, small: &'static str, large: &'static str) -> Measure {
+    let target = pick_metric_unit(measure, small, large);
+    convert_measure(measure, target, None).unwrap_or(measure)
+}
+
+// Ladder: below 1 tbsp → tsp, below 1/4 cup → tbsp, otherwise cups
+fn pick_imperial_volume(measure: Measure) -> &'static str {
+    let tsp = amount_in(&measure, "tsp");
+    if tsp < 3.0 - 1e-6 {
+        "tsp"
+    } else if tsp < 12.0 - 1e-6 {
+        "tbsp"
+    } else {
+        "cup"
+    }
+}
+
+fn pick_imperial_mass(measure: Measure) -> &'static str {
+    if amount_in(&measure, "oz") >= 16.0 { "lb" } else { "oz" }
+}
+
+fn unit_label(unit: &str, plural: bool) -> &'static str {
+    match unit {
+        "cup" => if plural { "cups" } else { "cup" },
+        "pint" => if plural { "pints" } else { "pint" },
+        "quart" => if plural { "quarts" } else { "quart" },
+        "fl-oz" => "fl oz",
+        "tsp" => "tsp",
+        "tbsp" => "tbsp",
+        "ml" => "ml",
+        "L" => "L",
+        "mg" => "mg",
+        "g" => "g",
+        "kg" => "kg",
+        "oz" => "oz",
+        "lb" => "lb",
+        "gal" => "gal",
+        _ => "",
+    }
+}
+
+/// Format a quantity the way a cook would write it
+/// Metric weights/volumes use decimals; everything else uses fractions (0.33 → 1/3)
+fn format_quantity(value: f64, unit: Option<&str>) -> String {
+    match unit {
+        Some("g") | Some("ml") | Some("mg") => {
+            let rounded = if value < 10.0 {
+                (value * 2.0).round() / 2.0
+            } else if value < 100.0 {
+                value.round()
+            } else {
+                (value / 5.0).round() * 5.0
+            };
+            format_number(rounded)
+        }
+        Some("kg") | Some("L") => format_number(value),
+        Some("tsp") => format_fraction(value, &[2, 4, 8]),
+        Some("tbsp") | Some("oz") | Some("lb") | Some("fl-oz") => format_fraction(value, &[2, 4]),
+        Some(_) => format_fraction(value, &[2, 3, 4, 8]),
+        None => format_count(value),
+    }
+}
+
+/// Unitless counts ("2 eggs", "Serves 6"): halves, thirds and quarters
+fn format_count(value: f64) -> String {
+    format_fraction(value, &[2, 3, 4])
+}
+
+/// Round to the nearest fraction with one of the given denominators ("1 1/2", "2/3")
+fn format_fraction(value: f64, denominators: &[u32]) -> String {
+    let whole = value.floor();
+    let frac = value - whole;
+
+    // Large amounts don't need fine fractions
+    let denominators: &[u32] = if value >= 10.0 { &[2] } else { denominators };
+
+    let (mut num, mut den) = (0u32, 1u32);
+    let mut best_err = frac; // Rounding down to the whole number
+    if 1.0 - frac < best_err {
+        best_err = 1.0 - frac;
+        num = 1;
+    }
+    for &d in denominators {
+        for n in 1..d {
+            let err = (frac - n as f64 / d as f64).abs();
+            if err < best_err - 1e-9 {
+                best_err = err;
+                num = n;
+                den = d;
+            }
+        }
+    }
+
+    let mut whole = whole as u64;
+    if den == 1 {
+        whole += num as u64;
+        num = 0;
+    }
+
+    // Never round a real amount down to nothing: fall back to a decimal
+    if whole == 0 && num == 0 {
+        return format_number(value);
+    }
+
+    match (whole, num) {
+        (w, 0) => w.to_string(),
+        (0, n) => format!("{}/{}", n, den),
+        (w, n) => format!("{} {}/{}", w, n, den),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scale(text: &str, factor: f64, unit_system: Option<UnitSystem>) -> String {
+        scale_recipe(text, &RecipeOptions { factor, unit_system }).unwrap().text
+    }
+
+    #[test]
+    fn test_parse_quantity_forms() {
+        assert_eq!(parse_quantity("1 1/2"), Some(1.5));
+        assert_eq!(parse_quantity("3/4"), Some(0.75));
+        assert_eq!(parse_quantity("½"), Some(0.5));
+        assert_eq!(parse_quantity("1½"), Some(1.5));
+        assert_eq!(parse_quantity("2.25"), Some(2.25));
+        assert_eq!(parse_quantity("1/0"), None);
+    }
+
+    #[test]
+    fn test_scale_by_factor_with_friendly_fractions() {
+        assert_eq!(scale("1 1/2 cups flour", 2.0, None), "3 cups flour");
+        assert_eq!(scale("1 cup sugar", 1.0 / 3.0, None), "1/3 cup sugar");
+        assert_eq!(scale("- ½ tsp salt", 0.5, None), "- 1/4 tsp salt");
+        assert_eq!(scale("3 eggs", 0.5, None), "1 1/2 eggs");
+    }
+
+    #[test]
+    fn test_scale_ranges_and_passthrough() {
+        assert_eq!(scale("2-3 cloves garlic", 2.0, None), "4-6 cloves garlic");
+        assert_eq!(scale("Salt to taste", 2.0, None), "Salt to taste");
+        assert_eq!(scale("Serves 4", 1.5, None), "Serves 6");
+    }
+
+    #[test]
+    fn test_normalizes_within_ladder() {
+        // 1/4 cup halved is 2 tbsp, 2 tbsp doubled is 1/4 cup
+        assert_eq!(scale("1/4 cup butter", 0.5, None), "2 tbsp butter");
+        assert_eq!(scale("2 tbsp oil", 2.0, None), "1/4 cup oil");
+        assert_eq!(scale("600 g potatoes", 2.0, None), "1.2 kg potatoes");
+    }
+
+    #[test]
+    fn test_convert_to_metric_weighs_dry_ingredients() {
+        assert_eq!(scale("1 cup flour", 1.0, Some(UnitSystem::Metric)), "125 g flour");
+        assert_eq!(scale("1 cup milk", 1.0, Some(UnitSystem::Metric)), "235 ml milk");
+        assert_eq!(scale("1 lb beef", 1.0, Some(UnitSystem::Metric)), "455 g beef");
+    }
+
+    #[test]
+    fn test_custom_liquids_stay_in_volume() {
+        use crate::shared::settings::CustomMaterial;
+        let custom = |name: &str, density_g_per_ml: f64, liquid: bool| CustomMaterial {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            density_g_per_ml,
+            liquid,
+        };
+        materials::set_custom_materials(&[custom("Birch Syrup", 1.37, true), custom("Cricket Powder", 0.5, false)]);
+        assert!(materials::lookup_density("birch syrup").unwrap().liquid);
+        assert!(!materials::lookup_density("cricket powder").unwrap().liquid);
+        assert_eq!(scale("1 cup birch syrup", 1.0, Some(UnitSystem::Metric)), "235 ml birch syrup");
+        assert!(scale("1 cup cricket powder", 1.0, Some(UnitSystem::Metric)).ends_with(" g cricket powder"));
+    }
+
+    #[test]
+    fn test_convert_to_imperial() {
+        assert_eq!(scale("250 ml water", 1.0, Some(UnitSystem::Imperial)), "1 cup water");
+        assert_eq!(scale("500 g butter", 1.0, Some(UnitSystem::Imperial)), "1 lb butter");
+        assert_eq!(scale("15 ml vinegar", 1.0, Some(UnitSystem::Imperial)), "1 tbsp vinegar");
+    }
+
+    #[test]
+    fn test_skips_non_quantity_numbers() {
+        assert_eq!(scale("1. Preheat the oven", 2.0, None), "1. Preheat the oven");
+        assert_eq!(scale("350°F for 20 minutes", 2.0, None), "350°F for 20 minutes");
+        assert_eq!(scale("9x13 inch pan", 2.0, None), "9x13 inch pan");
+    }
+
+    #[test]
+    fn test_detect_servings() {
+        assert_eq!(detect_servings("Pancakes\nServes: 4\n1 cup flour"), Some(4));
+        assert_eq!(detect_servings("1 cup flour"), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Typography and screen units: px, pt, pc, em, rem, vw/vh, Android dp/sp, iOS points
+//!
+//! Every unit converts through pixels at the context's DPI, which also bridges
+//! physical lengths (mm, in) to screen units. Defaults follow CSS: 96 dpi,
+//! 16px root font size.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::{decimal_from_f64, parse_exact_factor, UnitCategory, UNIT_REGISTRY};
+use crate::shared::types::TypographyContext;
+
+pub const DEFAULT_DPI: f64 = 96.0;
+pub const DEFAULT_ROOT_FONT_SIZE: f64 = 16.0;
+pub const DEFAULT_VIEWPORT_WIDTH: f64 = 1920.0;
+pub const DEFAULT_VIEWPORT_HEIGHT: f64 = 1080.0;
+pub const DEFAULT_FONT_SCALE: f64 = 1.0;
+
+/// Decimal places shown for typography results (0.875rem must not become 0.88rem)
+pub const FORMAT_DECIMALS: u32 = 4;
+
+/// Units written without a space in CSS ("24px", "1.5rem")
+const CSS_UNITS: &[&str] = &["px", "pt", "pc", "em", "rem", "vw", "vh"];
+
+/// Display metrics with defaults applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    pub dpi: f64,
+    pub root_font_size: f64,
+    pub font_size: f64,
+    pub viewport_width: f64,
+    pub viewport_height: f64,
+    pub font_scale: f64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            dpi: DEFAULT_DPI,
+            root_font_size: DEFAULT_ROOT_FONT_SIZE,
+            font_size: DEFAULT_ROOT_FONT_SIZE,
+            viewport_width: DEFAULT_VIEWPORT_WIDTH,
+            viewport_height: DEFAULT_VIEWPORT_HEIGHT,
+            font_scale: DEFAULT_FONT_SCALE,
+        }
+    }
+}
+
+impl Metrics {
+    /// Resolve request context against defaults, rejecting non-positive values
+    pub fn from_context(context: Option<&TypographyContext>) -> Result<Self, String> {
+        let Some(context) = context else {
+            return Ok(Self::default());
+        };
+
+        let pick = |name: &str, value: Option<f64>, default: f64| -> Result<f64, String> {
+            match value {
+                Some(v) if v.is_finite() && v > 0.0 => Ok(v),
+                Some(v) => Err(format!("Invalid {}: {} (must be a positive number)", name, v)),
+                None => Ok(default),
+            }
+        };
+
+        let root_font_size = pick("root font size", context.root_font_size, DEFAULT_ROOT_FONT_SIZE)?;
+        Ok(Self {
+            dpi: pick("DPI", context.dpi, DEFAULT_DPI)?,
+            root_font_size,
+            font_size: pick("font size", context.font_size, root_font_size)?,
+            viewport_width: pick("viewport width", context.viewport_width, DEFAULT_VIEWPORT_WIDTH)?,
+            viewport_height: pick("viewport height", context.viewport_height, DEFAULT_VIEWPORT_HEIGHT)?,
+            font_scale: pick("font scale", context.font_scale, DEFAULT_FONT_SCALE)?,
+        })
+    }
+}
+
+/// Whether a unit is written glued to its number in CSS
+pub fn is_css_unit(unit: &str) -> bool {
+    CSS_UNITS.contains(&unit)
+}
+
+/// Pixels per one `unit`, as an exact (numerator, denominator) ratio
+fn px_ratio(unit: &str, metrics: &Metrics) -> Result<(Decimal, Decimal), String> {
+    let dpi = decimal_from_f64(metrics.dpi)?;
+    let ratio = |num: f64, den: i64| -> Result<(Decimal, Decimal), String> {
+        Ok((decimal_from_f64(num)?, Decimal::from(den)))
+    };
+
+    match unit {
+        "px" => Ok((Decimal::ONE, Decimal::ONE)),
+        // Typographic point and pica: 1/72 in and 1/6 in
+        "pt" => Ok((dpi, Decimal::from(72))),
+        "pc" => Ok((dpi, Decimal::from(6))),
+        "em" => ratio(metrics.font_size, 1),
+        "rem" => ratio(metrics.root_font_size, 1),
+        "vw" => ratio(metrics.viewport_width, 100),
+        "vh" => ratio(metrics.viewport_height, 100),
+        // Android: 1dp is one pixel on a 160 dpi (mdpi) screen; sp also follows the font scale
+        "dp" => Ok((dpi, Decimal::from(160))),
+        "sp" => {
+            let scaled = dpi
+                .checked_mul(decimal_from_f64(metrics.font_scale)?)
+                .ok_or_else(|| format!("Font scale out of range: {}", metrics.font_scale))?;
+            Ok((scaled, Decimal::from(160)))
+        }
+        // iOS: 1pt is one pixel on a 163 ppi (@1x) screen
+        "ios-pt" => Ok((dpi, Decimal::from(163))),
+        other => match UNIT_REGISTRY.get(other) {
+            Some(def) if def.category == UnitCategory::Length => {
+                // meters per unit / 0.0254 m per inch * dpi
+                let (num, den) = parse_exact_factor(def.exact_factor)?;
+                let overflow = || format!("Value out of range for unit: {}", other);
+                Ok((
+                    num.checked_mul(dpi).ok_or_else(overflow)?,
+                    den.checked_mul(Decimal::new(254, 4)).ok_or_else(overflow)?,
+                ))
+            }
+            _ => Err(format!("{} is not a typography or length unit", other)),
+        },
+    }
+}
+
+/// Convert between typography units (or to/from lengths) exactly
+pub fn convert_exact(value: Decimal, from_unit: &str, to_unit: &str, metrics: &Metrics) -> Result<Decimal, String> {
+    if from_unit == to_unit {
+        return Ok(value);
+    }
+
+    let (from_num, from_den) = px_ratio(from_unit, metrics)?;
+    let (to_num, to_den) = px_ratio(to_unit, metrics)?;
+    if to_num.is_zero() {
+        return Err(format!("Cannot convert to {} with a zero size", to_unit));
+    }
+
+    let overflow = || format!("Value too large for conversion: {} {}", value, from_unit);
+
+    // result = value * (from px per unit) / (to px per unit)
+    let numerator = value
+        .checked_mul(from_num)
+        .and_then(|v| v.checked_mul(to_den))
+        .ok_or_else(overflow)?;
+    let denominator = from_den.checked_mul(to_num).ok_or_else(overflow)?;
+    let result = numerator.checked_div(denominator).ok_or_else(overflow)?;
+
+    println!(
+        "[typography] {} {} → {} {} (dpi: {}, root: {}px)",
+        value, from_unit, result, to_unit, metrics.dpi, metrics.root_font_size
+    );
+
+    Ok(result.normalize())
+}
+
+/// f64 wrapper around `convert_exact`
+pub fn convert(value: f64, from_unit: &str, to_unit: &str, metrics: &Metrics) -> Result<f64, String> {
+    let exact = convert_exact(decimal_from_f64(value)?, from_unit, to_unit, metrics)?;
+    exact
+        .to_f64()
+        .ok_or_else(|| format!("Result out of range: {} {}", exact, to_unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_css_defaults() {
+        let m = Metrics::default();
+        assert_eq!(convert_exact(dec("24"), "px", "rem", &m).unwrap(), dec("1.5"));
+        assert_eq!(convert_exact(dec("12"), "pt", "px", &m).unwrap(), dec("16"));
+        assert_eq!(convert_exact(dec("1"), "pc", "pt", &m).unwrap(), dec("12"));
+        assert_eq!(convert_exact(dec("1"), "in", "px", &m).unwrap(), dec("96"));
+        assert_eq!(convert_exact(dec("50"), "vw", "px", &m).unwrap(), dec("960"));
+    }
+
+    #[test]
+    fn test_context_overrides() {
+        let context = TypographyContext {
+            dpi: Some(320.0),
+            root_font_size: Some(10.0),
+            font_scale: Some(1.3),
+            ..TypographyContext::default()
+        };
+        let m = Metrics::from_context(Some(&context)).unwrap();
+        assert_eq!(m.font_size, 10.0);
+        assert_eq!(convert_exact(dec("16"), "dp", "px", &m).unwrap(), dec("32"));
+        assert_eq!(convert_exact(dec("10"), "sp", "px", &m).unwrap(), dec("26"));
+        assert_eq!(convert_exact(dec("2"), "rem", "px", &m).unwrap(), dec("20"));
+        assert_eq!(convert_exact(dec("25.4"), "mm", "px", &m).unwrap(), dec("320"));
+    }
+
+    #[test]
+    fn test_ios_points() {
+        let m = Metrics { dpi: 326.0, ..Metrics::default() };
+        assert_eq!(convert_exact(dec("16"), "ios-pt", "px", &m).unwrap(), dec("32"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_context() {
+        let context = TypographyContext { dpi: Some(0.0), ..TypographyContext::default() };
+        assert!(Metrics::from_context(Some(&context)).is_err());
+        assert!(convert_exact(dec("1"), "px", "kg", &Metrics::default()).is_err());
+    }
+
+    #[test]
+    fn test_registry_matches_defaults() {
+        // Registry factors are the default-context px ratios (used for listings and generic paths)
+        let m = Metrics::default();
+        for (symbol, def) in UNIT_REGISTRY.iter().filter(|(_, d)| d.category == UnitCategory::Typography) {
+            let (num, den) = px_ratio(symbol, &m).unwrap();
+            let (reg_num, reg_den) = parse_exact_factor(def.exact_factor).unwrap();
+            assert_eq!(num * reg_den, reg_num * den, "exact factor mismatch for {}", symbol);
+            let expected = (num / den).to_f64().unwrap();
+            assert!((def.base_factor - expected).abs() < 1e-9, "base factor mismatch for {}", symbol);
+        }
+    }
+}
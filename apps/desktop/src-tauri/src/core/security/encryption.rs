@@ -2,26 +2,56 @@ use chacha20poly1305::{
     aead::{Aead, AeadCore, KeyInit, OsRng},
     XChaCha20Poly1305, XNonce
 };
+use hmac::{Hmac, Mac};
 use keyring::Entry;
 use rand::RngCore;
+use sha2::Sha256;
 use crate::shared::error::{AppError, AppResult};
 
 const ENCRYPTION_SERVICE: &str = "productivity-widgets-db-key";
 const ENCRYPTION_KEY_ID: &str = "master_key";
+/// Label for deriving the keyed-hash key, so it never equals the cipher key
+const HASH_KEY_LABEL: &[u8] = b"productivity-widgets keyed hash v1";
 
 /// Encryption manager that handles key retrieval/generation and encryption/decryption
 pub struct EncryptionManager {
     cipher: XChaCha20Poly1305,
+    hash_key: [u8; 32],
 }
 
 impl EncryptionManager {
     /// Initialize encryption manager (gets/creates key from keyring)
     pub fn new() -> AppResult<Self> {
-        let key_bytes = Self::get_or_create_key()?;
-        let cipher = XChaCha20Poly1305::new_from_slice(&key_bytes)
+        Self::from_key(&Self::get_or_create_key()?)
+    }
+
+    /// Encryption manager with a throwaway key, for tests that can't reach the keyring
+    #[cfg(test)]
+    pub fn with_random_key() -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        Self::from_key(&key).expect("32-byte key")
+    }
+
+    fn from_key(key_bytes: &[u8]) -> AppResult<Self> {
+        let cipher = XChaCha20Poly1305::new_from_slice(key_bytes)
             .map_err(|e| AppError::System(format!("Failed to create cipher: {}", e)))?;
-        
-        Ok(Self { cipher })
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key_bytes)
+            .map_err(|e| AppError::System(format!("Failed to derive hash key: {}", e)))?;
+        mac.update(HASH_KEY_LABEL);
+        let hash_key = mac.finalize().into_bytes().into();
+
+        Ok(Self { cipher, hash_key })
+    }
+
+    /// HMAC-SHA256 of `data` under a key derived from the master key: equal
+    /// inputs give equal outputs, but nothing about the input can be read
+    /// back without the key
+    pub fn keyed_hash(&self, data: &[u8]) -> [u8; 32] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.hash_key)
+            .expect("HMAC accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes().into()
     }
 
     /// Encrypt data. Returns [Nonce + Ciphertext]
@@ -112,10 +112,12 @@ pub fn run() {
             // Load settings
             // Load settings asynchronously to avoid blocking main thread
             tauri::async_runtime::spawn(async {
-                if let Err(e) = shared::settings::AppSettings::load().await {
-                    eprintln!("Failed to load settings: {}", e);
-                } else {
-                    println!("✅ Settings loaded");
+                match shared::settings::AppSettings::load().await {
+                    Ok(settings) => {
+                        settings.apply_runtime();
+                        println!("✅ Settings loaded");
+                    }
+                    Err(e) => eprintln!("Failed to load settings: {}", e),
                 }
             });
 
@@ -158,6 +160,9 @@ pub fn run() {
             clipboard_monitor.start(app.handle().clone());
             println!("✅ Clipboard monitoring started");
 
+            // Push world clock snapshots once a minute
+            core::features::time_converter::world_clock::start_ticker(app.handle().clone());
+
             // Create tray menu (Command Palette as single access point)
             let palette_item = MenuItem::with_id(app, "palette", "Open Command Palette", true, None::<&str>)?;
 
@@ -457,12 +462,36 @@ pub fn run() {
             core::features::translator::translate_text,
             core::features::currency::convert_currency,
             core::features::clipboard::get_clipboard_history,
+            core::features::clipboard::search_clipboard_history,
+            core::features::clipboard::get_clipboard_pins,
+            core::features::clipboard::pin_clipboard_item,
+            core::features::clipboard::update_pinned_item,
+            core::features::clipboard::unpin_clipboard_item,
+            core::features::clipboard::reorder_pinned_items,
+            core::features::clipboard::save_clipboard_collection,
+            core::features::clipboard::delete_clipboard_collection,
+            core::features::clipboard::reorder_clipboard_collections,
             core::features::clipboard::paste_clipboard_item,
+            core::features::clipboard::set_copy_stack_active,
+            core::features::clipboard::get_copy_stack,
+            core::features::clipboard::clear_copy_stack,
+            core::features::clipboard::paste_copy_stack,
+            core::features::clipboard::export_clipboard_history,
+            core::features::clipboard::import_clipboard_history,
 
             core::features::time_converter::convert_time,
             core::features::time_converter::get_timezones,
             core::features::time_converter::parse_time_from_selection,
             core::features::time_converter::get_system_timezone,
+            core::features::time_converter::search_timezones,
+            core::features::time_converter::plan_meeting,
+            core::features::time_converter::convert_times_in_selection,
+            core::features::time_converter::convert_timestamp,
+            core::features::time_converter::calculate_date,
+            core::features::time_converter::list_holiday_calendars,
+            core::features::time_converter::get_world_clock,
+            core::features::time_converter::convert_ics,
+            core::features::time_converter::explain_cron,
             core::features::definition::lookup_definition,
             core::features::text_analyser::analyze_text,
 
@@ -470,6 +499,7 @@ pub fn run() {
             core::features::unit_converter::parse_text_command,
             core::features::unit_converter::get_all_units_command,
             core::features::unit_converter::convert_units_command,
+            core::features::unit_converter::get_all_materials_command,
         ])
         .run(tauri::generate_context!())
         .unwrap_or_else(|e| {
@@ -1,5 +1,6 @@
 pub mod types;
 pub mod settings;
+pub mod number_locale;
 
 pub mod error;
 pub mod events;
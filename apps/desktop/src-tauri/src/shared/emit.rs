@@ -17,6 +17,11 @@ pub fn emit_event(app: &AppHandle, event: AppEvent) {
                 eprintln!("Failed to emit clipboard update: {}", e);
             }
         }
+        AppEvent::ClipboardExpired(ids) => {
+            if let Err(e) = app.emit("clipboard://expired", ids) {
+                eprintln!("Failed to emit clipboard expiry: {}", e);
+            }
+        }
 
         AppEvent::SettingsUpdated(settings) => {
             if let Err(e) = app.emit("settings://update", settings) {
@@ -28,5 +33,10 @@ pub fn emit_event(app: &AppHandle, event: AppEvent) {
                 eprintln!("Failed to emit window focus: {}", e);
             }
         }
+        AppEvent::WorldClockTick(snapshot) => {
+            if let Err(e) = app.emit("world-clock://tick", snapshot) {
+                eprintln!("Failed to emit world clock tick: {}", e);
+            }
+        }
     }
 }
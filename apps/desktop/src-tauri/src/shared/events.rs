@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 use ts_rs::TS;
-use super::types::ClipboardHistoryItem;
+use super::types::{ClipboardHistoryItem, WorldClockSnapshot};
 use super::settings::AppSettings;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -9,10 +9,17 @@ use super::settings::AppSettings;
 pub enum AppEvent {
     #[serde(rename = "clipboard://updated")]
     ClipboardUpdated(ClipboardHistoryItem),
+
+    /// IDs of items deleted because their TTL ran out
+    #[serde(rename = "clipboard://expired")]
+    ClipboardExpired(Vec<String>),
     
     #[serde(rename = "settings://updated")]
-    SettingsUpdated(AppSettings),
+    SettingsUpdated(Box<AppSettings>),
     
     #[serde(rename = "window://focus-changed")]
     WindowFocusChanged(bool),
+
+    #[serde(rename = "world-clock://tick")]
+    WorldClockTick(WorldClockSnapshot),
 }
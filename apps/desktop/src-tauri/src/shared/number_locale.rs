@@ -0,0 +1,381 @@
+//! Locale-aware number parsing and formatting
+//!
+//! Text selected in other apps uses its author's separators: "1,234.56" (en),
+//! "1.234,56" (de), "1 234,56" (fr) or "1'234.56" (de-CH). Parsing first tries
+//! the user's `NumberLocale` preference, then falls back to auto-detection and
+//! reports how confident it is in the reading. Formatting always follows the
+//! preference (`auto` displays as en-US).
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use crate::shared::settings::NumberLocale;
+
+/// Confidence when separators leave only one possible reading
+const CONFIDENCE_CERTAIN: f32 = 1.0;
+/// Confidence when the structure strongly implies the reading (e.g. "1.234,56")
+const CONFIDENCE_STRUCTURAL: f32 = 0.95;
+/// Confidence when a lone separator is not followed by exactly 3 digits ("12,5")
+const CONFIDENCE_LIKELY: f32 = 0.9;
+/// Confidence for a lone separator followed by 3 digits ("1,234" / "1.234")
+const CONFIDENCE_AMBIGUOUS: f32 = 0.5;
+/// Penalty when the input contradicts an explicit locale preference
+const PREFERENCE_MISMATCH_FACTOR: f32 = 0.8;
+
+/// Grouping characters that are never a decimal point
+const SOFT_GROUP_CHARS: [char; 5] = [' ', '\u{00A0}', '\u{202F}', '\'', '’'];
+
+// Numeric tokens inside free text: digits joined by separators, ending on a digit
+static RE_NUMBER_TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\d(?:[\d.,'’\u{00A0}\u{202F}]*\d)?")
+        .expect("Failed to compile regex RE_NUMBER_TOKEN")
+});
+
+// Same as RE_NUMBER_TOKEN but also joins plain-space groups ("1 234,5"), used for space-grouping locales
+static RE_NUMBER_TOKEN_SPACED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\d(?:[\d.,'’\u{00A0}\u{202F} ]*\d)?")
+        .expect("Failed to compile regex RE_NUMBER_TOKEN_SPACED")
+});
+
+static NUMBER_LOCALE: Lazy<RwLock<NumberLocale>> = Lazy::new(|| RwLock::new(NumberLocale::Auto));
+
+/// Replace the active number locale (called when settings are loaded or saved)
+pub fn set_number_locale(locale: NumberLocale) {
+    println!("[number_locale] Using {:?}", locale);
+    match NUMBER_LOCALE.write() {
+        Ok(mut guard) => *guard = locale,
+        Err(poisoned) => *poisoned.into_inner() = locale,
+    }
+}
+
+/// The active number locale preference
+pub fn number_locale() -> NumberLocale {
+    match NUMBER_LOCALE.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// Decimal point and grouping separator of a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Separators {
+    pub decimal: char,
+    pub group: char,
+}
+
+impl NumberLocale {
+    /// Separators used for display (`Auto` displays as en-US)
+    pub fn separators(self) -> Separators {
+        match self {
+            NumberLocale::Auto | NumberLocale::EnUs => Separators { decimal: '.', group: ',' },
+            NumberLocale::DeDe => Separators { decimal: ',', group: '.' },
+            NumberLocale::FrFr => Separators { decimal: ',', group: '\u{202F}' },
+            NumberLocale::DeCh => Separators { decimal: '.', group: '\'' },
+        }
+    }
+
+    fn groups_with_spaces(self) -> bool {
+        self.separators().group.is_whitespace()
+    }
+}
+
+/// A number read from text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedNumber {
+    pub value: Decimal,
+    /// Locale-neutral form: optional '-', digits, optional '.' and fraction digits
+    pub canonical: String,
+    /// Character read as the decimal point, if any
+    pub decimal_separator: Option<char>,
+    /// 1.0 when the separators allow a single reading, 0.5 for a coin toss
+    pub confidence: f32,
+}
+
+/// Parse a standalone number ("1.234,56", "-1'234.5") using the active locale
+pub fn parse_number(raw: &str) -> Option<ParsedNumber> {
+    parse_number_in(raw, number_locale())
+}
+
+/// Parse a standalone number, preferring `locale` and falling back to auto-detection
+pub fn parse_number_in(raw: &str, locale: NumberLocale) -> Option<ParsedNumber> {
+    let raw = raw.trim();
+    let (negative, body) = match raw.chars().next()? {
+        '-' | '−' => (true, raw[raw.chars().next()?.len_utf8()..].trim_start()),
+        '+' => (false, raw[1..].trim_start()),
+        _ => (false, raw),
+    };
+
+    if !body.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',' || SOFT_GROUP_CHARS.contains(&c)) {
+        return None;
+    }
+
+    let (digits, decimal_separator, confidence) = match locale {
+        NumberLocale::Auto => read_auto(body)?,
+        explicit => {
+            let separators = explicit.separators();
+            match read_digits(body, Some(separators.decimal), &group_chars(Some(separators.decimal))) {
+                Some(digits) => {
+                    let used = body.contains(separators.decimal).then_some(separators.decimal);
+                    (digits, used, CONFIDENCE_CERTAIN)
+                }
+                None => {
+                    // Input written in another convention ("3.5" with de-DE): detect, but trust it less
+                    let (digits, used, confidence) = read_auto(body)?;
+                    (digits, used, confidence * PREFERENCE_MISMATCH_FACTOR)
+                }
+            }
+        }
+    };
+
+    let canonical = if negative { format!("-{}", digits) } else { digits };
+    let value = Decimal::from_str(&canonical).ok()?;
+
+    Some(ParsedNumber { value, canonical, decimal_separator, confidence })
+}
+
+/// Find and parse the first number in free text using the active locale
+pub fn first_number(text: &str) -> Option<ParsedNumber> {
+    let locale = number_locale();
+    token_regex(locale)
+        .find_iter(text)
+        .find_map(|m| parse_number_in(m.as_str(), locale))
+}
+
+/// Rewrite every number in `text` to its canonical form ("1.234,5 kg" -> "1234.5 kg")
+///
+/// Tokens that are not valid numbers in any convention (versions, dates, "5'10")
+/// are left untouched. Returns the rewritten text and the lowest confidence seen.
+pub fn normalize_numbers(text: &str) -> (String, f32) {
+    normalize_numbers_in(text, number_locale())
+}
+
+/// `normalize_numbers` with an explicit locale
+pub fn normalize_numbers_in(text: &str, locale: NumberLocale) -> (String, f32) {
+    let mut confidence = CONFIDENCE_CERTAIN;
+    let normalized = token_regex(locale).replace_all(text, |caps: &regex::Captures| {
+        let token = &caps[0];
+        match parse_number_in(token, locale) {
+            Some(parsed) => {
+                confidence = confidence.min(parsed.confidence);
+                parsed.canonical
+            }
+            None => token.to_string(),
+        }
+    });
+
+    (normalized.into_owned(), confidence)
+}
+
+/// Format a canonical number string ("-1234.5") with the active locale's separators
+pub fn format_canonical(canonical: &str) -> String {
+    format_canonical_in(canonical, number_locale())
+}
+
+/// Format a canonical number string ("-1234.5") with `locale`'s separators
+pub fn format_canonical_in(canonical: &str, locale: NumberLocale) -> String {
+    let separators = locale.separators();
+    let (sign, unsigned) = match canonical.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", canonical),
+    };
+    let (integer_part, fraction_part) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut formatted = format!("{}{}", sign, group_digits(integer_part, separators.group));
+    if let Some(fraction) = fraction_part {
+        formatted.push(separators.decimal);
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Insert `group` every three digits from the right ("1234567" -> "1,234,567")
+pub fn group_digits(digits: &str, group: char) -> String {
+    let mut result = String::new();
+    let chars: Vec<char> = digits.chars().rev().collect();
+    for (i, ch) in chars.iter().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.push(group);
+        }
+        result.push(*ch);
+    }
+    result.chars().rev().collect()
+}
+
+fn token_regex(locale: NumberLocale) -> &'static Regex {
+    if locale.groups_with_spaces() {
+        &RE_NUMBER_TOKEN_SPACED
+    } else {
+        &RE_NUMBER_TOKEN
+    }
+}
+
+/// Every separator character except the decimal point
+fn group_chars(decimal: Option<char>) -> Vec<char> {
+    ['.', ',']
+        .into_iter()
+        .chain(SOFT_GROUP_CHARS)
+        .filter(|c| Some(*c) != decimal)
+        .collect()
+}
+
+/// Guess the decimal point from the separators present, then read the digits
+fn read_auto(body: &str) -> Option<(String, Option<char>, f32)> {
+    let dots = body.matches('.').count();
+    let commas = body.matches(',').count();
+    let has_soft_groups = body.contains(SOFT_GROUP_CHARS);
+
+    let (decimal, confidence) = match (dots, commas) {
+        (0, 0) => (None, CONFIDENCE_CERTAIN),
+        // Both present: the last one is the decimal point ("1.234,56", "1,234.56")
+        (d, c) if d > 0 && c > 0 => {
+            let last = body.rfind(['.', ',']).and_then(|i| body[i..].chars().next());
+            (last, CONFIDENCE_STRUCTURAL)
+        }
+        // Repeated separator can only be grouping ("1,000,000", "1.000.000")
+        (n, 0) | (0, n) if n > 1 => (None, CONFIDENCE_STRUCTURAL),
+        _ => {
+            let mark = if dots == 1 { '.' } else { ',' };
+            let (integer_part, fraction_part) = body.split_once(mark)?;
+
+            if has_soft_groups {
+                // "1'234.5", "1 234,5": spaces/apostrophes already group, so the mark is decimal
+                (Some(mark), CONFIDENCE_STRUCTURAL)
+            } else if fraction_part.len() != 3 || integer_part.trim_start_matches('0').is_empty() {
+                // "12,5", "0,125", ",5": grouping would need exactly 3 digits after a non-zero lead
+                (Some(mark), CONFIDENCE_LIKELY)
+            } else {
+                // "1,234" / "1.234": could be either, resolve the en-US way
+                ((mark == '.').then_some('.'), CONFIDENCE_AMBIGUOUS)
+            }
+        }
+    };
+
+    let digits = read_digits(body, decimal, &group_chars(decimal))?;
+    let used = decimal.filter(|d| body.contains(*d));
+
+    Some((digits, used, confidence))
+}
+
+/// Validate separators against a decimal point and grouping set, returning "1234.56"
+fn read_digits(body: &str, decimal: Option<char>, groups: &[char]) -> Option<String> {
+    let (integer_part, fraction_part) = match decimal {
+        Some(d) if body.matches(d).count() > 1 => return None,
+        Some(d) => match body.split_once(d) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (body, None),
+        },
+        None => (body, None),
+    };
+
+    let chunks: Vec<&str> = integer_part.split(|c: char| groups.contains(&c)).collect();
+    if chunks.iter().any(|chunk| !chunk.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    if chunks.len() > 1 {
+        let first_ok = (1..=3).contains(&chunks[0].len());
+        let rest_ok = chunks[1..].iter().all(|chunk| chunk.len() == 3);
+        if !first_ok || !rest_ok {
+            return None;
+        }
+    }
+
+    let mut digits: String = chunks.concat();
+    if let Some(fraction) = fraction_part {
+        if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        digits.push('.');
+        digits.push_str(fraction);
+    }
+
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto(raw: &str) -> Option<(String, f32)> {
+        parse_number_in(raw, NumberLocale::Auto).map(|p| (p.canonical, p.confidence))
+    }
+
+    #[test]
+    fn test_auto_detects_unambiguous_separators() {
+        assert_eq!(auto("1.234,56"), Some(("1234.56".to_string(), CONFIDENCE_STRUCTURAL)));
+        assert_eq!(auto("1,234.56"), Some(("1234.56".to_string(), CONFIDENCE_STRUCTURAL)));
+        assert_eq!(auto("1'234.5"), Some(("1234.5".to_string(), CONFIDENCE_STRUCTURAL)));
+        assert_eq!(auto("1\u{202F}234,5"), Some(("1234.5".to_string(), CONFIDENCE_STRUCTURAL)));
+        assert_eq!(auto("1.000.000"), Some(("1000000".to_string(), CONFIDENCE_STRUCTURAL)));
+        assert_eq!(auto("12,5"), Some(("12.5".to_string(), CONFIDENCE_LIKELY)));
+        assert_eq!(auto("0,125"), Some(("0.125".to_string(), CONFIDENCE_LIKELY)));
+        assert_eq!(auto("-42"), Some(("-42".to_string(), CONFIDENCE_CERTAIN)));
+    }
+
+    #[test]
+    fn test_auto_flags_ambiguous_separators() {
+        assert_eq!(auto("1,234"), Some(("1234".to_string(), CONFIDENCE_AMBIGUOUS)));
+        assert_eq!(auto("1.234"), Some(("1.234".to_string(), CONFIDENCE_AMBIGUOUS)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_grouping() {
+        assert_eq!(auto("1.2.3"), None);
+        assert_eq!(auto("1,2,3"), None);
+        assert_eq!(auto("5'10"), None);
+        assert_eq!(auto("1.234,5.6"), None);
+        assert_eq!(auto("12a"), None);
+    }
+
+    #[test]
+    fn test_explicit_locale_resolves_ambiguity() {
+        let de = parse_number_in("1.234", NumberLocale::DeDe).unwrap();
+        assert_eq!(de.canonical, "1234");
+        assert_eq!(de.confidence, CONFIDENCE_CERTAIN);
+
+        let de = parse_number_in("1,5", NumberLocale::DeDe).unwrap();
+        assert_eq!(de.canonical, "1.5");
+        assert_eq!(de.decimal_separator, Some(','));
+
+        // Contradicting input falls back to detection with reduced confidence
+        let de = parse_number_in("3.25", NumberLocale::DeDe).unwrap();
+        assert_eq!(de.canonical, "3.25");
+        assert!(de.confidence < CONFIDENCE_LIKELY);
+    }
+
+    #[test]
+    fn test_normalize_numbers_in_text() {
+        let (text, confidence) = normalize_numbers_in("1.234,5 kg to lb", NumberLocale::Auto);
+        assert_eq!(text, "1234.5 kg to lb");
+        assert_eq!(confidence, CONFIDENCE_STRUCTURAL);
+
+        let (text, _) = normalize_numbers_in("v1.2.3 is 5'10 tall", NumberLocale::Auto);
+        assert_eq!(text, "v1.2.3 is 5'10 tall");
+
+        let (text, _) = normalize_numbers_in("1 234,5 m", NumberLocale::FrFr);
+        assert_eq!(text, "1234.5 m");
+        let (text, _) = normalize_numbers_in("2 500 m", NumberLocale::EnUs);
+        assert_eq!(text, "2 500 m");
+    }
+
+    #[test]
+    fn test_format_canonical() {
+        assert_eq!(format_canonical_in("1234567.5", NumberLocale::EnUs), "1,234,567.5");
+        assert_eq!(format_canonical_in("1234567.5", NumberLocale::DeDe), "1.234.567,5");
+        assert_eq!(format_canonical_in("-1234.5", NumberLocale::DeCh), "-1'234.5");
+        assert_eq!(format_canonical_in("1234.5", NumberLocale::FrFr), "1\u{202F}234,5");
+        assert_eq!(format_canonical_in("999", NumberLocale::Auto), "999");
+    }
+}
@@ -38,6 +38,214 @@ pub struct UserPreferences {
     pub default_currency_from: String,
     pub default_currency_to: String,
     pub theme: String,
+    /// User-defined material densities for Mass ↔ Volume conversions
+    #[serde(default)]
+    pub custom_materials: Vec<CustomMaterial>,
+    /// Separators used to read and display numbers (`auto` detects them per input)
+    #[serde(default)]
+    pub number_locale: NumberLocale,
+    /// Weekend and public holidays used by business-day date math
+    #[serde(default)]
+    pub business_calendar: BusinessCalendarSettings,
+    /// Zones pinned to the world clock, in display order
+    #[serde(default)]
+    pub world_clock: Vec<PinnedZone>,
+    /// Clipboard history retention
+    #[serde(default)]
+    pub clipboard: ClipboardSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub struct ClipboardSettings {
+    /// Most items kept in history
+    pub max_items: u32,
+    /// Items older than this are deleted (None = keep forever)
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// Oldest items are deleted once stored history exceeds this (None = no limit)
+    #[serde(default)]
+    pub max_total_mb: Option<u32>,
+    /// Text placed between copies when the copy stack is pasted at once
+    #[serde(default = "default_stack_separator")]
+    pub stack_separator: String,
+    /// Checked in order before a copy is stored; the first rule that matches decides
+    #[serde(default = "default_capture_rules")]
+    pub capture_rules: Vec<CaptureRule>,
+    /// Seconds the original of a copy stored with its secrets masked stays
+    /// available to paste, in memory only (None = discarded at once)
+    #[serde(default)]
+    pub redacted_original_secs: Option<u32>,
+    /// Seconds after a copy with secrets in it before the system clipboard
+    /// is cleared, if it still holds that copy (None = never)
+    #[serde(default)]
+    pub clear_sensitive_after_secs: Option<u32>,
+    /// Minutes items with secrets in them stay in history (None = like any other item)
+    #[serde(default)]
+    pub sensitive_item_ttl_mins: Option<u32>,
+}
+
+fn default_stack_separator() -> String {
+    "\n".to_string()
+}
+
+/// Password managers are never recorded unless the user removes this rule
+fn default_capture_rules() -> Vec<CaptureRule> {
+    let apps = [
+        // Any app whose name or bundle ID says what it is
+        "*password*",
+        "*keychain*",
+        "*bitwarden*",
+        "*keepass*",
+        "*lastpass*",
+        "*dashlane*",
+        "*enpass*",
+        // Known managers, including those whose names don't
+        "com.apple.Passwords",
+        "com.agilebits.onepassword7",
+        "com.agilebits.onepassword",
+        "com.1password.1password",
+        "com.bitwarden.desktop",
+        "org.keepassxc.keepassxc",
+        "com.apple.keychainaccess",
+        "co.lastpass.lpmacosx",
+        "in.sinew.Enpass-Desktop",
+        "com.dashlane.dashlanephonefinal",
+        "me.proton.pass.electron",
+        "Proton Pass",
+        "com.nordsec.nordpass",
+        "NordPass",
+        "com.siber.roboform",
+        "RoboForm",
+    ];
+    vec![CaptureRule {
+        name: "Password managers".to_string(),
+        enabled: true,
+        apps: apps.iter().map(|app| app.to_string()).collect(),
+        pattern: None,
+        max_size_kb: None,
+        action: CaptureAction::Drop,
+    }]
+}
+
+/// What happens to a copy that matches a capture rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub enum CaptureAction {
+    /// Store it as is, skipping later rules and the built-in secret check
+    Allow,
+    /// Don't store it
+    Drop,
+    /// Store it with the rule's pattern matches (or the whole text) masked
+    Redact,
+    /// Store it on this device only, never in sync or exports
+    NoSync,
+}
+
+/// A user rule deciding whether and how a copy is stored. Every condition
+/// that is set must hold for the rule to match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub struct CaptureRule {
+    /// Shown in logs when the rule fires
+    pub name: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    /// App names or bundle IDs, matched case-insensitively; `*` stands for
+    /// any run of characters, as in `*password*` (empty = any app)
+    #[serde(default)]
+    pub apps: Vec<String>,
+    /// Regex the copied text must contain a match of
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Only copies larger than this many KB
+    #[serde(default)]
+    pub max_size_kb: Option<u32>,
+    pub action: CaptureAction,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+impl Default for ClipboardSettings {
+    fn default() -> Self {
+        Self {
+            max_items: 500,
+            max_age_days: Some(30),
+            max_total_mb: Some(100),
+            stack_separator: default_stack_separator(),
+            capture_rules: default_capture_rules(),
+            redacted_original_secs: None,
+            clear_sensitive_after_secs: None,
+            sensitive_item_ttl_mins: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub struct BusinessCalendarSettings {
+    /// Weekend day names, e.g. ["sat", "sun"]
+    pub weekend: Vec<String>,
+    /// Bundled holiday calendar country code, e.g. "US" (None = weekends only)
+    #[serde(default)]
+    pub holiday_country: Option<String>,
+}
+
+impl Default for BusinessCalendarSettings {
+    fn default() -> Self {
+        Self {
+            weekend: vec!["sat".to_string(), "sun".to_string()],
+            holiday_country: None,
+        }
+    }
+}
+
+/// A zone on the world clock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub struct PinnedZone {
+    /// IANA ID, e.g. "Asia/Tokyo"
+    pub timezone: String,
+    /// Custom name shown instead of the city, e.g. "Tokyo office"
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub struct CustomMaterial {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub density_g_per_ml: f64,
+    /// Measured by volume in recipes, like milk or syrup (kept in ml when converting to metric)
+    #[serde(default)]
+    pub liquid: bool,
+}
+
+/// Number formatting convention (decimal and grouping separators)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/settings.ts")]
+pub enum NumberLocale {
+    /// Detect separators from the input; display as `en-US`
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    /// 1,234.56
+    #[serde(rename = "en-US")]
+    EnUs,
+    /// 1.234,56
+    #[serde(rename = "de-DE")]
+    DeDe,
+    /// 1 234,56
+    #[serde(rename = "fr-FR")]
+    FrFr,
+    /// 1'234.56
+    #[serde(rename = "de-CH")]
+    DeCh,
 }
 
 impl Default for AppSettings {
@@ -58,6 +266,11 @@ impl Default for AppSettings {
                 default_currency_from: "USD".to_string(),
                 default_currency_to: "EUR".to_string(),
                 theme: "system".to_string(),
+                custom_materials: Vec::new(),
+                number_locale: NumberLocale::Auto,
+                business_calendar: BusinessCalendarSettings::default(),
+                world_clock: Vec::new(),
+                clipboard: ClipboardSettings::default(),
             },
         }
     }
@@ -122,6 +335,8 @@ impl AppSettings {
 
         // Save stripped config to disk
         self.save_to_disk().await?;
+
+        self.apply_runtime();
             
         // Emit update event (send FULL settings to app state listeners, they might need the keys)
         // Wait, should we emit masked or full? 
@@ -131,11 +346,24 @@ impl AppSettings {
         // However, `emit_event` sends to frontend.
         // Let's emit MASKED settings for security.
         let masked = self.masked();
-        emit_event(app, AppEvent::SettingsUpdated(masked));
+        emit_event(app, AppEvent::SettingsUpdated(Box::new(masked)));
         
         Ok(())
     }
 
+    /// Push preferences that backend features read at runtime into their in-memory state
+    pub fn apply_runtime(&self) {
+        crate::core::features::unit_converter::materials::set_custom_materials(&self.preferences.custom_materials);
+        crate::shared::number_locale::set_number_locale(self.preferences.number_locale);
+        crate::core::features::time_converter::date_calc::set_business_calendar(&self.preferences.business_calendar);
+        crate::core::features::time_converter::world_clock::set_pinned_zones(&self.preferences.world_clock);
+        crate::core::clipboard::history::set_retention(&self.preferences.clipboard);
+        crate::core::clipboard::stack::set_separator(&self.preferences.clipboard);
+        crate::core::clipboard::rules::set_rules(&self.preferences.clipboard.capture_rules);
+        crate::core::clipboard::originals::set_hold_time(&self.preferences.clipboard);
+        crate::core::clipboard::expiry::set_expiry(&self.preferences.clipboard);
+    }
+
     /// Return a copy of settings with secrets masked (for frontend/logging)
     pub fn masked(&self) -> Self {
         let mut copy = self.clone();
@@ -50,6 +50,57 @@ pub struct ConvertUnitsRequest {
     pub from_unit: String,
     pub to_unit: String,
     pub material: Option<String>,
+    /// Use exact decimal arithmetic instead of f64 (None = false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub exact: Option<bool>,
+    /// Decimal string form of `amount` for exact mode (avoids JS float precision loss)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub exact_amount: Option<String>,
+    /// Round the formatted result to N significant figures instead of 2 decimals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub significant_figures: Option<u32>,
+    /// Rounding mode for the formatted result (None = half up)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rounding_mode: Option<RoundingMode>,
+    /// Display metrics for typography/screen units (px, em, rem, vw, dp, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub typography: Option<TypographyContext>,
+}
+
+/// Display metrics used by typography conversions (unset fields use CSS defaults)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TypographyContext {
+    /// Screen density in pixels per inch (default 96, the CSS reference pixel;
+    /// 160 = Android mdpi, 163 = iPhone @1x)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dpi: Option<f64>,
+    /// Root font size in px, used by `rem` (default 16)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub root_font_size: Option<f64>,
+    /// Element font size in px, used by `em` (default: root font size)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub font_size: Option<f64>,
+    /// Viewport width in px, used by `vw` (default 1920)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub viewport_width: Option<f64>,
+    /// Viewport height in px, used by `vh` (default 1080)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub viewport_height: Option<f64>,
+    /// Android font scale, used by `sp` (default 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub font_scale: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -59,6 +110,30 @@ pub struct ConvertUnitsResponse {
     pub formatted_result: String,
     pub from_unit: String,
     pub to_unit: String,
+    /// Unrounded decimal result (only set in exact mode)
+    pub exact_result: Option<String>,
+    /// Material whose density bridged a Mass ↔ Volume conversion
+    pub material: Option<String>,
+}
+
+/// Rounding mode used when formatting numeric results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum RoundingMode {
+    /// Round half away from zero (2.5 -> 3, -2.5 -> -3)
+    #[default]
+    HalfUp,
+    /// Round half to even, a.k.a. bankers rounding (2.5 -> 2)
+    HalfEven,
+    /// Truncate toward zero
+    Down,
+    /// Round away from zero
+    Up,
+    /// Round toward negative infinity
+    Floor,
+    /// Round toward positive infinity
+    Ceiling,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -75,6 +150,11 @@ pub struct ParseUnitResponse {
     pub amount: f64,
     pub unit: String,
     pub category: String,
+    pub material: Option<String>, // Material detected in the text (e.g., "All-Purpose Flour")
+    /// How sure the number reading is (below 1.0 when separators were ambiguous, e.g. "1,234")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub number_confidence: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -92,6 +172,21 @@ pub struct UnitDTO {
     pub category: String, // Category (e.g., "Length", "Mass")
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct GetMaterialsResponse {
+    pub materials: Vec<MaterialDTO>,
+}
+
+// Material density entry for the unit converter's material picker
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct MaterialDTO {
+    pub name: String,
+    pub density_g_per_ml: f64,
+    pub custom: bool, // User-defined in settings
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/bindings.ts")]
 pub struct OpenWidgetRequest {
@@ -133,6 +228,15 @@ pub enum ActionType {
     
     // Generic unit conversion (already structured)
     ConvertUnit { target: String },
+
+    // Recipe scaling / measurement normalisation (multi-line ingredient lists)
+    ScaleRecipe(RecipePayload),
+
+    // Rewrite a Unix timestamp / ISO 8601 / RFC 2822 date in another format
+    FormatTimestamp(TimestampPayload),
+
+    // Explain a cron expression or list its next runs
+    ExplainCron(CronPayload),
 }
 
 // ===== NEW: Payload Structures (Phase 1) =====
@@ -167,6 +271,82 @@ pub struct TimePayload {
     pub target_timezone: String,
 }
 
+/// Payload for recipe scaling actions
+/// Either `factor` or `servings` may be set; params can override both at execution time
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct RecipePayload {
+    /// Multiply every quantity by this factor (e.g., 2.0 doubles the recipe)
+    pub factor: Option<f64>,
+    /// Scale to this many servings (requires "Serves N" in the text or a `servings_from` param)
+    pub servings: Option<u32>,
+    /// Convert all measurements to this system (None = keep original units)
+    pub unit_system: Option<UnitSystem>,
+}
+
+/// Payload for timestamp formatting actions
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimestampPayload {
+    /// Output format
+    pub format: TimestampFormat,
+    /// Zone for ISO 8601 / RFC 2822 / RFC 3339 / local output (None = system timezone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub timezone: Option<String>,
+}
+
+/// Machine-readable date formats, detected in selections and offered as outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum TimestampFormat {
+    /// Unix epoch seconds (10 digits today)
+    EpochSeconds,
+    /// Unix epoch milliseconds (13 digits)
+    EpochMillis,
+    /// Unix epoch microseconds (16 digits)
+    EpochMicros,
+    /// Unix epoch nanoseconds (19 digits)
+    EpochNanos,
+    /// ISO 8601, including basic format and offsets without a colon
+    Iso8601,
+    /// RFC 2822 (email headers, HTTP dates)
+    Rfc2822,
+    /// RFC 3339 (the internet profile of ISO 8601)
+    Rfc3339,
+    /// Human-readable wall-clock time (output only)
+    LocalTime,
+}
+
+/// Payload for cron actions
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct CronPayload {
+    pub output: CronOutput,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum CronOutput {
+    /// The schedule in plain English
+    Explain,
+    /// Upcoming runs in the system timezone
+    NextRuns,
+}
+
+/// Measurement system for recipe output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum UnitSystem {
+    /// Grams, milliliters, liters, kilograms
+    Metric,
+    /// US customary cooking measures (tsp, tbsp, cup, oz, lb)
+    Imperial,
+}
+
 /// Payload for text analysis actions
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "../../src/types/bindings.ts")]
@@ -229,6 +409,116 @@ pub struct ConvertTimeResponse {
     pub date_change_indicator: Option<String>, // "Next day" / "Previous day"
     pub source_zone_abbr: String,          // e.g., "KST"
     pub source_utc_offset: String,         // e.g., "UTC+09:00"
+    /// Other zones an ambiguous abbreviation may mean ("CST" → China, Cuba), best first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub alternatives: Option<Vec<TimezoneCandidate>>,
+    /// Skipped or repeated wall-clock time, or a clock change within a week in either zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dst_warning: Option<String>,
+    /// Both readings when the source time happens twice (fall-back overlap), first one first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub interpretations: Option<Vec<TimeInterpretation>>,
+    /// Sunrise and sunset on the source date, when the zone's city is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub source_solar: Option<SolarInfo>,
+    /// Sunrise and sunset on the target date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub target_solar: Option<SolarInfo>,
+}
+
+/// One reading of a wall-clock time that happens twice
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimeInterpretation {
+    pub source_time: String,       // e.g., "01:30am, 03 Nov"
+    pub source_zone_abbr: String,  // e.g., "EDT"
+    pub source_utc_offset: String, // e.g., "UTC-04:00"
+    pub target_time: String,
+    pub target_zone_abbr: String,
+    pub target_utc_offset: String,
+}
+
+/// A timezone suggested by search or abbreviation resolution
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimezoneCandidate {
+    pub iana_id: String,
+    pub label: String,        // e.g., "Beijing/China (CST)"
+    pub abbreviation: String, // current abbreviation
+    pub utc_offset: String,   // e.g., "UTC+08:00"
+    pub matched: String,      // abbreviation, alias or city that matched
+    pub historical: bool,     // matched abbreviation is no longer used by this zone
+}
+
+/// A meeting attendee and their local working hours
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct MeetingParticipant {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+    pub timezone: String,   // IANA ID
+    pub work_start: String, // "09:00" local
+    pub work_end: String,   // "17:00" local; earlier than start means an overnight shift
+    /// ISO weekdays worked (1 = Monday … 7 = Sunday); defaults to Monday–Friday
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub work_days: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct PlanMeetingRequest {
+    pub participants: Vec<MeetingParticipant>,
+    pub start_date: String, // "2025-03-24", in the organizer's timezone
+    pub end_date: String,   // inclusive
+    pub duration_minutes: u32,
+    /// Organizer's IANA zone for the date range and summary; defaults to the system timezone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub organizer_timezone: Option<String>,
+    /// Granularity of candidate start times (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub step_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_results: Option<usize>,
+}
+
+/// One participant's view of a candidate slot
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ParticipantSlotTime {
+    pub name: String,
+    pub timezone: String,
+    pub local_start: String, // e.g., "Tue 25 Mar, 09:00"
+    pub local_end: String,
+    pub zone_abbr: String,   // DST-aware, e.g., "CET" / "CEST"
+    pub in_working_hours: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct MeetingSlot {
+    pub start_utc: String, // RFC 3339
+    pub end_utc: String,
+    pub available_count: usize,
+    pub participant_count: usize,
+    pub participants: Vec<ParticipantSlotTime>,
+    pub summary: String,   // pasteable into an invite
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct PlanMeetingResponse {
+    pub slots: Vec<MeetingSlot>, // best first, non-overlapping
+    pub summary: String,         // best slot plus the other options
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -246,6 +536,148 @@ pub struct ParsedTimeInput {
     pub source_timezone: Option<String>,  // IANA ID or None for Local
     #[serde(skip_serializing_if = "Option::is_none")]
     pub matched_keyword: Option<String>,  // NEW: Which keyword triggered timezone detection
+    /// Every time or range in the text when there is more than one, or a range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub mentions: Option<Vec<TimeMention>>,
+}
+
+/// A time or time range found in a selection
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimeMention {
+    pub original: String,    // as written, e.g., "9:30–11am"
+    pub start: String,       // e.g., "9:30am"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub end: Option<String>, // e.g., "11am"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertTimesInTextRequest {
+    pub text: String,
+    pub target_timezone: String,
+    /// Overrides the timezone detected in the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub source_timezone: Option<String>,
+}
+
+/// One time or range converted, shown next to the original
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimeMentionConversion {
+    pub original: String,            // e.g., "9:30–11am"
+    pub converted: String,           // e.g., "03:30pm – 05:00pm, 15 Mar CET"
+    pub start: ConvertTimeResponse,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub end: Option<ConvertTimeResponse>, // carries its own date_change_indicator
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertTimesInTextResponse {
+    pub conversions: Vec<TimeMentionConversion>,
+    pub source_timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_keyword: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertTimestampRequest {
+    pub text: String, // e.g., "1741962600", "2025-03-14T14:30:00Z", "Fri, 14 Mar 2025 14:30:00 +0000"
+    /// Zones to show the instant in, besides local time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub target_timezones: Option<Vec<String>>,
+}
+
+/// The instant written in one timestamp format
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimestampRendering {
+    pub format: TimestampFormat,
+    pub label: String, // e.g., "Unix seconds"
+    pub value: String, // e.g., "1741962600"
+}
+
+/// The instant as wall-clock time in one zone
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct TimestampZoneTime {
+    pub iana_id: String,
+    pub label: String,      // e.g., "Paris, France (CET)"
+    pub time: String,       // e.g., "03:30:00pm, Fri 14 Mar 2025"
+    pub zone_abbr: String,  // e.g., "CET"
+    pub utc_offset: String, // e.g., "UTC+01:00"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertTimestampResponse {
+    pub original: String, // the timestamp as found in the text
+    pub detected_format: TimestampFormat,
+    pub renderings: Vec<TimestampRendering>,
+    pub local: TimestampZoneTime,
+    pub zones: Vec<TimestampZoneTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct DateCalcRequest {
+    pub expression: String, // e.g., "today + 90 days", "2024-03-01 - 2023-11-15", "days until Christmas"
+    /// Weekend days for business-day math (None = from settings)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub weekend: Option<Vec<String>>,
+    /// Holiday calendar country code, e.g. "US" (None = from settings)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub holiday_country: Option<String>,
+}
+
+/// What a date expression evaluated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum DateCalcKind {
+    /// A date or date-time ("today + 90 days")
+    Date,
+    /// An amount of time ("3h 20m + 45m")
+    Duration,
+    /// The distance between two dates ("2024-03-01 - 2023-11-15", "days until Christmas")
+    Difference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct DateCalcResponse {
+    pub kind: DateCalcKind,
+    pub result: String, // e.g., "Thu 22 Aug 2024", "4h 5m", "107 days"
+    pub iso: String,    // e.g., "2024-08-22", "PT4H5M", "P107D"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub detail: Option<String>, // e.g., "in 90 days", "3 months, 15 days (15 weeks, 2 days)"
+    /// Days from today (dates) or between the two dates (differences)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub days: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub business_days: Option<i32>,
+    /// Holidays passed over while counting business days, e.g. "Memorial Day (Mon 27 May 2024)"
+    pub skipped_holidays: Vec<String>,
+}
+
+/// A bundled public holiday calendar
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct HolidayCalendarInfo {
+    pub country: String, // e.g., "US"
+    pub name: String,    // e.g., "United States (federal)"
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -325,6 +757,19 @@ pub enum ClipboardItemType {
     Html,
     Rtf,
     Image,
+    Files,
+}
+
+/// Size and thumbnail of a copied image (the full PNG is stored separately)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardImageInfo {
+    pub width: i32,
+    pub height: i32,
+    /// Size of the stored PNG
+    pub byte_size: i32,
+    /// Small PNG as a `data:` URL
+    pub thumbnail: String,
 }
 
 /// A single clipboard history item
@@ -333,11 +778,38 @@ pub enum ClipboardItemType {
 pub struct ClipboardHistoryItem {
     pub id: String,
     pub item_type: ClipboardItemType,
-    pub content: String, // For images, this would be base64 or path
+    pub content: String, // Plain text; local paths for files, empty for images
     pub preview: String, // Truncated preview for display
     #[ts(type = "string")]
     pub timestamp: DateTime<Utc>,
     pub source_app: Option<String>,
+    /// HTML copied alongside the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub html: Option<String>,
+    /// RTF copied alongside the text (macOS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub rtf: Option<String>,
+    /// Copied files as `file://` URLs
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub image: Option<ClipboardImageInfo>,
+    /// Times this content was copied (0 in items saved before counting began)
+    #[serde(default)]
+    pub use_count: i32,
+    /// Kept on this device only: never included in sync or exports
+    #[serde(default)]
+    pub no_sync: bool,
+    /// Secrets in the text were masked before it was stored
+    #[serde(default)]
+    pub redacted: bool,
+    /// Deleted from history at this time (sensitive items only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "string")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 // SECURITY: Custom Debug implementation to prevent sensitive content from leaking into logs
@@ -350,6 +822,14 @@ impl std::fmt::Debug for ClipboardHistoryItem {
             .field("preview", &format!("[REDACTED {} chars]", self.preview.len()))
             .field("timestamp", &self.timestamp)
             .field("source_app", &self.source_app)
+            .field("html", &self.html.as_ref().map(|html| format!("[REDACTED {} chars]", html.len())))
+            .field("rtf", &self.rtf.as_ref().map(|rtf| format!("[REDACTED {} chars]", rtf.len())))
+            .field("files", &self.files.len())
+            .field("image", &self.image.as_ref().map(|image| (image.width, image.height)))
+            .field("use_count", &self.use_count)
+            .field("no_sync", &self.no_sync)
+            .field("redacted", &self.redacted)
+            .field("expires_at", &self.expires_at)
             .finish()
     }
 }
@@ -357,12 +837,7 @@ impl std::fmt::Debug for ClipboardHistoryItem {
 impl ClipboardHistoryItem {
     /// Create a new text clipboard item
     pub fn new_text(content: String, source_app: Option<String>) -> Self {
-        // Safe: Clamped to length to prevent panic on short strings
-        let preview = if content.len() > 100 {
-            format!("{}...", &content[..content.len().min(100)])
-        } else {
-            content.clone()
-        };
+        let preview = text_preview(&content);
 
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -371,6 +846,14 @@ impl ClipboardHistoryItem {
             preview,
             timestamp: Utc::now(),
             source_app,
+            html: None,
+            rtf: None,
+            files: Vec::new(),
+            image: None,
+            use_count: 1,
+            no_sync: false,
+            redacted: false,
+            expires_at: None,
         }
     }
 
@@ -392,13 +875,7 @@ impl ClipboardHistoryItem {
         // I should keep constructors if possible or move them to `impl ClipboardHistoryItem` in `history.rs` via trait?
         // No, `impl` blocks can be anywhere. Using it here is fine.
         
-        let preview = strip_html_tags(&content);
-        // Safe: Clamped to length to prevent panic on short strings
-        let preview = if preview.len() > 100 {
-            format!("{}...", &preview[..preview.len().min(100)])
-        } else {
-            preview
-        };
+        let preview = text_preview(&strip_html_tags(&content));
 
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -407,20 +884,65 @@ impl ClipboardHistoryItem {
             preview,
             timestamp: Utc::now(),
             source_app,
+            html: None,
+            rtf: None,
+            files: Vec::new(),
+            image: None,
+            use_count: 1,
+            no_sync: false,
+            redacted: false,
+            expires_at: None,
         }
     }
 
-    /// Create a new image clipboard item
-    pub fn new_image(content: String, source_app: Option<String>) -> Self {
+    /// Create a new image clipboard item (the PNG itself is stored as a blob)
+    pub fn new_image(image: ClipboardImageInfo, source_app: Option<String>) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             item_type: ClipboardItemType::Image,
-            content,
-            preview: "[Image]".to_string(),
+            content: String::new(),
+            preview: format!("Image {}×{}", image.width, image.height),
             timestamp: Utc::now(),
             source_app,
+            html: None,
+            rtf: None,
+            files: Vec::new(),
+            image: Some(image),
+            use_count: 1,
+            no_sync: false,
+            redacted: false,
+            expires_at: None,
+        }
+    }
+
+    /// Replace text content and refresh the preview. Rich formats captured
+    /// with the old text no longer match it, so the item becomes plain text.
+    pub fn set_content(&mut self, content: String) {
+        self.preview = match self.item_type {
+            ClipboardItemType::Html if self.html.is_none() => text_preview(&strip_html_tags(&content)),
+            ClipboardItemType::Image => self.preview.clone(),
+            _ => text_preview(&content),
+        };
+        if self.html.is_some() || self.rtf.is_some() || !self.files.is_empty() {
+            self.html = None;
+            self.rtf = None;
+            self.files.clear();
+            self.item_type = ClipboardItemType::Text;
         }
+        self.content = content;
+    }
+}
+
+/// First 100 bytes (cut on a character boundary) with an ellipsis when truncated
+fn text_preview(text: &str) -> String {
+    if text.len() <= 100 {
+        return text.to_string();
     }
+    let mut end = 100;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &text[..end])
 }
 
 /// Simple HTML tag stripper for preview generation (Helper)
@@ -440,6 +962,205 @@ fn strip_html_tags(html: &str) -> String {
     result.trim().to_string()
 }
 
+/// Search or page through clipboard history (an empty query lists everything)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct SearchClipboardRequest {
+    /// Words to find; each may be a prefix or contain a small typo
+    #[serde(default)]
+    pub query: String,
+    /// Only items copied from an app whose name contains this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub source_app: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub item_type: Option<ClipboardItemType>,
+    /// Earliest copy time, RFC 3339 or a local `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub from: Option<String>,
+    /// Latest copy time, RFC 3339 or a local `YYYY-MM-DD` (whole day included)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub to: Option<String>,
+    /// `next_cursor` from the previous page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub limit: Option<i32>,
+}
+
+/// One page of clipboard history, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardHistoryPage {
+    pub items: Vec<ClipboardHistoryItem>,
+    /// Pass back as `cursor` for the next page (absent = no more results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub next_cursor: Option<String>,
+}
+
+/// A clipboard item kept outside history so it never expires
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct PinnedClipboardItem {
+    pub item: ClipboardHistoryItem,
+    /// Name shown in the palette instead of the preview
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// Collection holding the item (absent = top-level pins)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub collection_id: Option<String>,
+    /// Order within its collection
+    pub position: i32,
+    /// Last edit of the content, title or collection (absent = unedited
+    /// since pinning)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "string")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A user-named group of pinned items, e.g. "SQL snippets"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardCollection {
+    pub id: String,
+    pub name: String,
+    pub position: i32,
+}
+
+/// All collections and pinned items, each sorted by position
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardPinsResponse {
+    pub collections: Vec<ClipboardCollection>,
+    pub items: Vec<PinnedClipboardItem>,
+}
+
+/// Pin a copy of a history item
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct PinClipboardItemRequest {
+    pub item_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub collection_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub title: Option<String>,
+}
+
+/// Edit a pinned item (every field is replaced)
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct UpdatePinnedItemRequest {
+    pub id: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub collection_id: Option<String>,
+}
+
+/// Export history and pins to a passphrase-protected backup file
+#[derive(Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ExportClipboardRequest {
+    pub path: String,
+    pub passphrase: String,
+}
+
+// SECURITY: Custom Debug implementation so the passphrase never reaches logs
+impl std::fmt::Debug for ExportClipboardRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportClipboardRequest")
+            .field("path", &self.path)
+            .field("passphrase", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// What went into a backup file
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardExportSummary {
+    pub items: i32,
+    pub pins: i32,
+    pub collections: i32,
+}
+
+/// Merge a backup file into history and pins
+#[derive(Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ImportClipboardRequest {
+    pub path: String,
+    pub passphrase: String,
+    /// Only report what would change
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// SECURITY: Custom Debug implementation so the passphrase never reaches logs
+impl std::fmt::Debug for ImportClipboardRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportClipboardRequest")
+            .field("path", &self.path)
+            .field("passphrase", &"[REDACTED]")
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// What an import changed, or would change in a dry run
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ClipboardImportSummary {
+    pub dry_run: bool,
+    /// When the backup was made
+    #[ts(type = "string")]
+    pub exported_at: DateTime<Utc>,
+    pub items_added: i32,
+    /// Replaced by a newer copy from the backup
+    pub items_updated: i32,
+    /// Already here and at least as new
+    pub items_skipped: i32,
+    /// Imported, then removed again by the history limits (too old or over
+    /// the count or size budget); only known after a real import
+    pub items_pruned: i32,
+    pub pins_added: i32,
+    pub pins_updated: i32,
+    pub pins_skipped: i32,
+    pub collections_added: i32,
+}
+
+/// Whether copies are being stacked, and what has been collected so far
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct CopyStackState {
+    pub active: bool,
+    /// Previews of the stacked copies, oldest first
+    pub previews: Vec<String>,
+}
+
+/// How to paste the copy stack
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum CopyStackPasteMode {
+    /// Everything at once, joined with the configured separator
+    Joined,
+    /// The oldest entry only, removing it from the stack
+    Next,
+}
+
 #[derive(Debug, Clone, Serialize, TS)]
 #[ts(export, export_to = "../../src/types/bindings.ts")]
 pub struct ExecuteActionResponse {
@@ -473,3 +1194,262 @@ pub struct WindowPosition {
 }
 
 
+
+/// Whether it's daytime where a clock is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum DayPeriod {
+    Day,
+    /// Between civil dawn and sunrise, or sunset and civil dusk
+    Twilight,
+    Night,
+}
+
+/// Whether the sun rises and sets on a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum SolarCondition {
+    Normal,
+    /// Midnight sun: the sun never sets
+    PolarDay,
+    /// The sun never rises
+    PolarNight,
+}
+
+/// Sunrise, sunset and civil twilight for a zone's main city, in that zone's time
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct SolarInfo {
+    pub city: String,
+    /// "Sat 21 Jun"
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sunrise: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sunset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub civil_dawn: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub civil_dusk: Option<String>,
+    /// "16h 38m"
+    pub day_length: String,
+    pub day_length_minutes: i32,
+    pub condition: SolarCondition,
+}
+
+/// One pinned zone on the world clock
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct WorldClockEntry {
+    pub timezone: String,
+    /// Custom label, else the city
+    pub label: String,
+    /// "9:41am"
+    pub time: String,
+    /// "Sat 18 Oct"
+    pub date: String,
+    /// RFC 3339 with the zone's offset
+    pub iso: String,
+    pub abbreviation: String,
+    pub utc_offset: String,
+    /// "+9h" / "-5h 30m" / "Same time" relative to the system zone
+    pub offset_from_local: String,
+    /// Calendar days ahead of (+1) or behind (-1) the system zone
+    pub day_offset: i32,
+    pub day_period: DayPeriod,
+    /// Today's sunrise and sunset there, when the zone's city is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub solar: Option<SolarInfo>,
+}
+
+/// Every pinned zone at one instant
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct WorldClockSnapshot {
+    /// RFC 3339, UTC
+    pub generated_at: String,
+    pub local_timezone: String,
+    pub entries: Vec<WorldClockEntry>,
+}
+
+/// Events pasted as iCalendar text or invite lines with `DTSTART;TZID=...`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertIcsRequest {
+    pub text: String,
+    /// Zones to show each occurrence in (defaults to the system zone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub target_timezones: Option<Vec<String>>,
+    /// Upcoming occurrences listed per recurring event (default 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub occurrences: Option<i32>,
+    /// Also write the calendar back out with every event in this zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub retarget_timezone: Option<String>,
+}
+
+/// An occurrence as wall-clock time in one zone
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct IcsZoneTime {
+    pub iana_id: String,
+    pub label: String,
+    pub zone_abbr: String,
+    /// "Mon 3 Mar, 11:00pm – 11:15pm"
+    pub time: String,
+    /// "Next day" / "Previous day" relative to the event's own zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub date_change_indicator: Option<String>,
+}
+
+/// One occurrence of an event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct IcsOccurrence {
+    /// RFC 3339, or YYYY-MM-DD for all-day events
+    pub start: String,
+    pub end: String,
+    /// As written in the event's zone: "Mon 3 Mar, 9:00am – 9:15am EST"
+    pub original: String,
+    /// Empty for all-day events, which don't move between zones
+    pub zones: Vec<IcsZoneTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct IcsEventInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub uid: Option<String>,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub location: Option<String>,
+    /// IANA ID of the zone the event was written in
+    pub source_timezone: String,
+    pub all_day: bool,
+    /// "Weekly on Mon, Wed, Fri, 10 times"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub recurrence: Option<String>,
+    pub occurrences: Vec<IcsOccurrence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ConvertIcsResponse {
+    pub events: Vec<IcsEventInfo>,
+    /// The calendar with events moved to `retarget_timezone`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub ics: Option<String>,
+    /// Unknown zones, unsupported rules and occurrences that shift when retargeted
+    pub warnings: Vec<String>,
+}
+
+/// Cron syntax, told apart by field count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub enum CronDialect {
+    /// minute hour day-of-month month day-of-week
+    Standard,
+    /// Seconds first (Spring, node-cron)
+    WithSeconds,
+    /// Seconds first and a trailing year, with Quartz weekday numbers (1 = Sunday)
+    Quartz,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ExplainCronRequest {
+    pub expression: String, // e.g., "*/15 9-17 * * MON-FRI", "0 0 12 ? * 2#1 *", "@daily", or a crontab line
+    /// Zone the schedule runs in (defaults to the system zone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub timezone: Option<String>,
+    /// Zones to show each run in, besides the schedule's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub target_timezones: Option<Vec<String>>,
+    /// Runs to list (default 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub count: Option<i32>,
+}
+
+/// Where an expression stops being valid; spans count characters from 0
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct CronSyntaxError {
+    pub message: String,
+    /// "minute", "day of week", ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub field: Option<String>,
+    pub start: i32,
+    pub end: i32,
+}
+
+/// One field as written and what it means
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct CronFieldInfo {
+    pub name: String,        // e.g., "minute"
+    pub value: String,       // e.g., "*/15"
+    pub description: String, // e.g., "every 15 minutes"
+    pub start: i32,
+    pub end: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct CronRun {
+    /// RFC 3339 in the schedule's zone
+    pub iso: String,
+    /// "in 3h 20m"
+    pub relative: String,
+    /// The schedule's zone first, then each target zone
+    pub zones: Vec<TimestampZoneTime>,
+    /// Moved or merged by a clock change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/types/bindings.ts")]
+pub struct ExplainCronResponse {
+    /// The schedule part of the input
+    pub expression: String,
+    /// The command after the schedule on a crontab line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub command: Option<String>,
+    /// Set when the expression is invalid; everything below is then empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<CronSyntaxError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dialect: Option<CronDialect>,
+    /// "Every 15 minutes, between 9:00am and 5:59pm, Monday through Friday"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub description: Option<String>,
+    pub fields: Vec<CronFieldInfo>,
+    /// IANA ID of the zone runs are computed in
+    pub timezone: String,
+    pub runs: Vec<CronRun>,
+}
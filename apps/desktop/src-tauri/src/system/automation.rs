@@ -10,6 +10,7 @@ pub mod macos;
 pub use macos::{
     auto_paste_flow,
     get_active_app,
+    get_active_app_bundle_id,
     restore_focus,
     simulate_cmd_c,
 };
@@ -20,6 +21,11 @@ pub fn get_active_app() -> Result<String, String> {
     Err("Not supported on this platform".to_string())
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn get_active_app_bundle_id() -> Result<String, String> {
+    Err("Not supported on this platform".to_string())
+}
+
 #[cfg(not(target_os = "macos"))]
 pub fn simulate_cmd_c() -> Result<(), String> {
     Err("Not supported on this platform".to_string())
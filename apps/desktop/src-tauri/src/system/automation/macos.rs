@@ -102,6 +102,26 @@ pub fn get_active_app() -> AppResult<String> {
     }
 }
 
+/// Get the bundle identifier of the currently active application (e.g. "com.apple.Terminal")
+pub fn get_active_app_bundle_id() -> AppResult<String> {
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let front_app: id = msg_send![workspace, frontmostApplication];
+
+        if front_app == nil {
+            return Err(AppError::Io("No frontmost application found".to_string()));
+        }
+
+        let bundle_id: id = msg_send![front_app, bundleIdentifier];
+        if bundle_id == nil {
+            return Err(AppError::Io("Frontmost application has no bundle identifier".to_string()));
+        }
+
+        let bundle_cstr = std::ffi::CStr::from_ptr(NSString::UTF8String(bundle_id));
+        Ok(bundle_cstr.to_string_lossy().into_owned())
+    }
+}
+
 /// Restore focus to a specific application by name
 /// Iterates running applications to find match and activates it
 pub fn restore_focus(app_name: &str) -> AppResult<()> {
@@ -1,5 +1,5 @@
 import { useEffect, useState, useRef, useMemo } from "react";
-import { Languages, Settings, CornerDownRight, Coins, BookOpen, WholeWord, Sunrise, Ruler, Clipboard } from "lucide-react";
+import { Languages, Settings, CornerDownRight, Coins, BookOpen, WholeWord, Sunrise, Ruler, Clipboard, Files, Pin, Layers, ShieldCheck } from "lucide-react";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 
 import { listen } from "@tauri-apps/api/event";
@@ -25,6 +25,12 @@ import {
 import { Button } from "./ui/button";
 import { useAppStore } from "../logic/state/store"; // ← Import Zustand store
 
+/** Command ID prefix for pinned clipboard items (the rest is the pin's item ID) */
+const PIN_PREFIX = "clipboard_pin:";
+
+/** Command ID prefix for copy stack commands (the rest names the action) */
+const STACK_PREFIX = "clipboard_stack:";
+
 export function CommandPalette() {
     // ✅ Use Zustand store for query instead of local state
     const query = useAppStore((state) => state.paletteQuery);
@@ -47,6 +53,8 @@ export function CommandPalette() {
     const listRef = useRef<ComponentRef<typeof CommandList>>(null);
     const isPastingRef = useRef<boolean>(false);
     const [clipboardItems, setClipboardItems] = useState<ClipboardItem[]>([]);
+    // Bumped to reload the command index (pins and the copy stack change while the palette is hidden)
+    const [commandsVersion, setCommandsVersion] = useState(0);
 
     // Load clipboard history on mount and when window gains focus
     useEffect(() => {
@@ -65,13 +73,16 @@ export function CommandPalette() {
             const unlisten = await listen<ClipboardItem>('clipboard://updated', (event) => {
                 setClipboardItems(prev => {
                     const newItem = event.payload;
-                    // Deduplicate
-                    const exists = prev.some(p => p.content === newItem.content);
-                    if (exists) return prev;
-                    return [newItem, ...prev].slice(0, 50);
+                    // A repeat copy keeps its ID and moves to the top
+                    return [newItem, ...prev.filter(p => p.id !== newItem.id)].slice(0, 50);
                 });
             });
-            return unlisten;
+            // Sensitive items are deleted once their TTL runs out
+            const unlistenExpired = await listen<string[]>('clipboard://expired', (event) => {
+                const expired = new Set(event.payload);
+                setClipboardItems(prev => prev.filter(p => !expired.has(p.id)));
+            });
+            return () => { unlisten(); unlistenExpired(); };
         };
 
         let unlistenFn: (() => void) | null = null;
@@ -99,8 +110,9 @@ export function CommandPalette() {
             setIsExecuting(false);
             setExecutingActionId(null);
 
-            // Refresh clipboard history when opening
+            // Refresh clipboard history and pinned items when opening
             api.getClipboardHistory().then(setClipboardItems).catch(console.error);
+            setCommandsVersion(v => v + 1);
 
             // Try to focus input
             requestAnimationFrame(() => {
@@ -214,7 +226,7 @@ export function CommandPalette() {
         };
 
         loadCommands();
-    }, [capturedText]);
+    }, [capturedText, commandsVersion]);
 
     // Click-through mode with blur handler
     useEffect(() => {
@@ -302,15 +314,40 @@ export function CommandPalette() {
         }
     };
 
-    // Keyboard shortcuts for clipboard items (1-5)
+    const handleCopyStackCommand = async (action: string) => {
+        if (isPastingRef.current) return;
+        isPastingRef.current = true;
+        try {
+            if (action === "start" || action === "stop") {
+                await api.setCopyStackActive(action === "start");
+            } else {
+                await api.pasteCopyStack(action === "paste_next" ? "next" : "joined");
+                await getCurrentWindow().hide();
+            }
+            setCommandsVersion(v => v + 1);
+        } catch (error) {
+            console.error('Error running copy stack command:', error);
+        } finally {
+            setTimeout(() => { isPastingRef.current = false; }, 500);
+        }
+    };
+
+    // Keyboard shortcuts for clipboard items (1-5 pastes, Alt+1-5 pins)
     useEffect(() => {
         const handleKeyDown = (e: KeyboardEvent) => {
             if (!document.hasFocus() || query.trim().length > 0) return;
-            const num = parseInt(e.key, 10);
+            // e.code keeps the digit when Alt turns e.key into a symbol (macOS)
+            const num = parseInt(e.code.startsWith("Digit") ? e.code.slice(5) : e.key, 10);
             if (num >= 1 && num <= 5 && clipboardItems[num - 1]) {
                 e.preventDefault();
                 e.stopPropagation();
-                handlePasteClipboardItem(clipboardItems[num - 1].id);
+                if (e.altKey) {
+                    api.pinClipboardItem({ item_id: clipboardItems[num - 1].id })
+                        .then(() => setCommandsVersion(v => v + 1))
+                        .catch(console.error);
+                } else {
+                    handlePasteClipboardItem(clipboardItems[num - 1].id);
+                }
             }
         };
         window.addEventListener('keydown', handleKeyDown, true);
@@ -386,6 +423,13 @@ export function CommandPalette() {
     // Actions section: all actions NOT in suggested
     const actionItems = allActions.filter(a => !suggestedIds.has(a.id));
 
+    // Pinned clipboard items: a few when idle, every match while typing
+    const allPins = filteredCommands.filter(c => c.id.startsWith(PIN_PREFIX));
+    const pinItems = query ? allPins : allPins.slice(0, 5);
+
+    // Copy stack controls (start/stop, and paste once something is stacked)
+    const stackItems = filteredCommands.filter(c => c.id.startsWith(STACK_PREFIX));
+
     // Widget execution - Use Tauri backend command to open widgets
     async function handleOpenWidget(widgetId: string) {
         try {
@@ -602,7 +646,15 @@ export function CommandPalette() {
                                     data-item-id={item.id}
                                     title={item.content || item.preview}
                                 >
-                                    <Clipboard className="w-4 h-4" />
+                                    {item.image ? (
+                                        <img src={item.image.thumbnail} alt="" className="w-4 h-4 object-cover rounded-sm" />
+                                    ) : item.item_type === "files" ? (
+                                        <Files className="w-4 h-4" />
+                                    ) : item.redacted ? (
+                                        <ShieldCheck className="w-4 h-4" aria-label="Secrets masked" />
+                                    ) : (
+                                        <Clipboard className="w-4 h-4" />
+                                    )}
                                     <div className="flex flex-col gap-xs py-1 flex-1 min-w-0">
                                         <span className="text-sm truncate">{item.preview}</span>
                                     </div>
@@ -612,7 +664,51 @@ export function CommandPalette() {
                         </CommandGroup>
                     )}
 
-                    {(!query && clipboardItems.length > 0 && (suggestedItems.length > 0 || widgetItems.length > 0 || actionItems.length > 0)) && <CommandSeparator />}
+                    {(!query && clipboardItems.length > 0 && pinItems.length > 0) && <CommandSeparator />}
+
+                    {/* Pinned clipboard items - paste on select */}
+                    {pinItems.length > 0 && (
+                        <CommandGroup>
+                            <div cmdk-group-heading="">pinned</div>
+                            {pinItems.map((cmd) => (
+                                <CommandItemUI
+                                    key={cmd.id}
+                                    value={cmd.id}
+                                    onSelect={() => handlePasteClipboardItem(cmd.id.slice(PIN_PREFIX.length))}
+                                    className="cursor-pointer"
+                                >
+                                    <Pin className="w-4 h-4" />
+                                    <div className="flex flex-col gap-xs py-1 flex-1 min-w-0">
+                                        <span className="text-sm truncate">{cmd.label}</span>
+                                    </div>
+                                    {cmd.description && <CommandShortcut>{cmd.description}</CommandShortcut>}
+                                </CommandItemUI>
+                            ))}
+                        </CommandGroup>
+                    )}
+
+                    {((!query && clipboardItems.length > 0) || pinItems.length > 0) && stackItems.length > 0 && <CommandSeparator />}
+
+                    {/* Copy stack - collect copies, paste them joined or one at a time */}
+                    {stackItems.length > 0 && (
+                        <CommandGroup>
+                            <div cmdk-group-heading="">copy stack</div>
+                            {stackItems.map((cmd) => (
+                                <CommandItemUI
+                                    key={cmd.id}
+                                    value={cmd.id}
+                                    onSelect={() => handleCopyStackCommand(cmd.id.slice(STACK_PREFIX.length))}
+                                    className="cursor-pointer"
+                                >
+                                    <Layers className="w-4 h-4" />
+                                    <span>{cmd.label}</span>
+                                    {cmd.description && <CommandShortcut>{cmd.description}</CommandShortcut>}
+                                </CommandItemUI>
+                            ))}
+                        </CommandGroup>
+                    )}
+
+                    {((!query && clipboardItems.length > 0) || pinItems.length > 0 || stackItems.length > 0) && (suggestedItems.length > 0 || widgetItems.length > 0 || actionItems.length > 0) && <CommandSeparator />}
 
                     {/* Suggested */}
                     {suggestedItems.length > 0 && (
@@ -0,0 +1,141 @@
+import { ArrowDown, ArrowUp, X } from "lucide-react";
+import type { CaptureAction, CaptureRule } from "../../logic/types";
+
+const ACTIONS: { value: CaptureAction; label: string }[] = [
+    { value: "drop", label: "Don't save" },
+    { value: "redact", label: "Save redacted" },
+    { value: "no_sync", label: "Save on this device only" },
+    { value: "allow", label: "Always save" },
+];
+
+const NEW_RULE: CaptureRule = { name: "New rule", enabled: true, apps: [], pattern: null, max_size_kb: null, action: "drop" };
+
+/** Pattern compile error, or null when it is empty or valid (JS and Rust syntax mostly agree) */
+function patternError(pattern: string | null): string | null {
+    if (!pattern) return null;
+    try {
+        new RegExp(pattern);
+        return null;
+    } catch (e) {
+        return e instanceof Error ? e.message : String(e);
+    }
+}
+
+interface CaptureRulesEditorProps {
+    rules: CaptureRule[];
+    onChange: (rules: CaptureRule[]) => void;
+}
+
+/**
+ * Ordered capture rules; edits are kept in the settings draft until it is saved
+ */
+export function CaptureRulesEditor({ rules, onChange }: CaptureRulesEditorProps) {
+    const update = (index: number, patch: Partial<CaptureRule>) =>
+        onChange(rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)));
+
+    const move = (index: number, delta: number) => {
+        const target = index + delta;
+        if (target < 0 || target >= rules.length) return;
+        const next = [...rules];
+        [next[index], next[target]] = [next[target], next[index]];
+        onChange(next);
+    };
+
+    return (
+        <div className="flex flex-col gap-3">
+            {rules.length === 0 && (
+                <div className="text-xs text-gray-500 dark:text-gray-400">No rules: every copy is saved unless it looks like a secret</div>
+            )}
+
+            {rules.map((rule, index) => {
+                const error = patternError(rule.pattern);
+                return (
+                    <div key={index} className="flex flex-col gap-2 border-b border-gray-200 dark:border-gray-700 pb-3">
+                        <div className="flex items-center gap-2">
+                            <input
+                                type="checkbox"
+                                checked={rule.enabled}
+                                onChange={(e) => update(index, { enabled: e.target.checked })}
+                                title="Enabled"
+                            />
+                            <input
+                                type="text"
+                                value={rule.name}
+                                onChange={(e) => update(index, { name: e.target.value })}
+                                className="input-field flex-1 font-medium"
+                                placeholder="Rule name"
+                            />
+                            <select
+                                value={rule.action}
+                                onChange={(e) => update(index, { action: e.target.value as CaptureAction })}
+                                className="input-field w-48"
+                            >
+                                {ACTIONS.map((action) => (
+                                    <option key={action.value} value={action.value}>{action.label}</option>
+                                ))}
+                            </select>
+                            <button
+                                onClick={() => move(index, -1)}
+                                className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                title="Move up"
+                            >
+                                <ArrowUp className="w-4 h-4" />
+                            </button>
+                            <button
+                                onClick={() => move(index, 1)}
+                                className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                title="Move down"
+                            >
+                                <ArrowDown className="w-4 h-4" />
+                            </button>
+                            <button
+                                onClick={() => onChange(rules.filter((_, i) => i !== index))}
+                                className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                title="Delete rule"
+                            >
+                                <X className="w-4 h-4" />
+                            </button>
+                        </div>
+                        <div className="grid grid-cols-3 gap-2">
+                            {/* Parsed on blur so a trailing comma can be typed; the key refreshes it after a move */}
+                            <input
+                                key={rule.apps.join(",")}
+                                type="text"
+                                defaultValue={rule.apps.join(", ")}
+                                onBlur={(e) => update(index, { apps: e.target.value.split(",").map((app) => app.trim()).filter(Boolean) })}
+                                className="input-field"
+                                placeholder="Apps or bundle IDs, * as wildcard (any)"
+                            />
+                            <input
+                                type="text"
+                                value={rule.pattern ?? ""}
+                                onChange={(e) => update(index, { pattern: e.target.value || null })}
+                                className={`input-field font-mono text-xs ${error ? "border-red-500" : ""}`}
+                                placeholder="Text pattern (regex)"
+                                title={error ?? undefined}
+                            />
+                            <input
+                                type="number"
+                                min={1}
+                                value={rule.max_size_kb ?? ""}
+                                onChange={(e) => {
+                                    const kb = parseInt(e.target.value, 10);
+                                    update(index, { max_size_kb: Number.isFinite(kb) && kb > 0 ? kb : null });
+                                }}
+                                className="input-field"
+                                placeholder="Larger than (KB)"
+                            />
+                        </div>
+                    </div>
+                );
+            })}
+
+            <button
+                onClick={() => onChange([...rules, { ...NEW_RULE }])}
+                className="text-sm text-gray-600 dark:text-gray-400 hover:underline self-start"
+            >
+                + Add rule
+            </button>
+        </div>
+    );
+}
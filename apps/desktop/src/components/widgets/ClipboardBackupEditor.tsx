@@ -0,0 +1,101 @@
+import { useState } from "react";
+import { api } from "../../logic/api/tauri";
+import type { ClipboardImportSummary } from "../../logic/types";
+
+/** One line per kind of change, e.g. "12 new, 3 updated, 40 already here" */
+function describe(added: number, updated: number, skipped: number): string {
+    return `${added} new, ${updated} updated, ${skipped} already here`;
+}
+
+/**
+ * Export history and pins to a passphrase-encrypted file, or merge one back in.
+ * Importing always shows a preview (dry run) before anything is written.
+ */
+export function ClipboardBackupEditor({ onImported }: { onImported: () => void }) {
+    const [path, setPath] = useState("");
+    const [passphrase, setPassphrase] = useState("");
+    const [busy, setBusy] = useState(false);
+    const [message, setMessage] = useState<string | null>(null);
+    const [preview, setPreview] = useState<ClipboardImportSummary | null>(null);
+
+    const run = async (action: () => Promise<void>) => {
+        setBusy(true);
+        setMessage(null);
+        try {
+            await action();
+        } catch (e) {
+            setMessage(`${e}`);
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const exportBackup = () => run(async () => {
+        const summary = await api.exportClipboardHistory({ path, passphrase });
+        setPreview(null);
+        setMessage(`Exported ${summary.items} items, ${summary.pins} pins and ${summary.collections} collections`);
+    });
+
+    const previewImport = () => run(async () => {
+        setPreview(await api.importClipboardHistory({ path, passphrase, dry_run: true }));
+    });
+
+    const confirmImport = () => run(async () => {
+        const summary = await api.importClipboardHistory({ path, passphrase, dry_run: false });
+        setPreview(null);
+        const pruned = summary.items_pruned > 0 ? `, ${summary.items_pruned} past the history limits` : "";
+        setMessage(`Imported history (${describe(summary.items_added, summary.items_updated, summary.items_skipped)}${pruned}) and pins (${describe(summary.pins_added, summary.pins_updated, summary.pins_skipped)})`);
+        onImported();
+    });
+
+    const ready = !busy && path.trim() !== "" && passphrase !== "";
+
+    return (
+        <div className="flex flex-col gap-2">
+            <div className="grid grid-cols-2 gap-4">
+                <input
+                    type="text"
+                    value={path}
+                    onChange={(e) => {
+                        setPath(e.target.value);
+                        setPreview(null);
+                    }}
+                    className="input-field"
+                    placeholder="Full path of the backup file"
+                />
+                <input
+                    type="password"
+                    value={passphrase}
+                    onChange={(e) => {
+                        setPassphrase(e.target.value);
+                        setPreview(null);
+                    }}
+                    className="input-field"
+                    placeholder="Passphrase (8+ characters)"
+                />
+            </div>
+            <div className="flex items-center gap-4">
+                <button onClick={exportBackup} disabled={!ready} className="text-sm text-gray-600 dark:text-gray-400 hover:underline disabled:opacity-50">
+                    Export
+                </button>
+                <button onClick={previewImport} disabled={!ready} className="text-sm text-gray-600 dark:text-gray-400 hover:underline disabled:opacity-50">
+                    Import…
+                </button>
+                {busy && <span className="text-xs text-gray-500 dark:text-gray-400">Working…</span>}
+            </div>
+
+            {preview && (
+                <div className="rounded border border-gray-200 dark:border-gray-700 p-2 text-xs text-gray-600 dark:text-gray-300">
+                    <div>Backup from {new Date(preview.exported_at).toLocaleString()}</div>
+                    <div>History: {describe(preview.items_added, preview.items_updated, preview.items_skipped)}</div>
+                    <div>Pins: {describe(preview.pins_added, preview.pins_updated, preview.pins_skipped)}, {preview.collections_added} new collections</div>
+                    <button onClick={confirmImport} disabled={busy} className="mt-1 font-medium hover:underline disabled:opacity-50">
+                        Import these
+                    </button>
+                </div>
+            )}
+
+            {message && <p className="text-xs text-gray-500 dark:text-gray-400">{message}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,190 @@
+import { useEffect, useState } from "react";
+import { ArrowDown, ArrowUp, X } from "lucide-react";
+import { api } from "../../logic/api/tauri";
+import type { ClipboardPinsResponse, PinnedClipboardItem } from "../../logic/types";
+
+const EMPTY: ClipboardPinsResponse = { collections: [], items: [] };
+
+/** Swap an ID with its neighbour; returns null at the edges */
+function moved(ids: string[], index: number, delta: number): string[] | null {
+    const target = index + delta;
+    if (target < 0 || target >= ids.length) return null;
+    const next = [...ids];
+    [next[index], next[target]] = [next[target], next[index]];
+    return next;
+}
+
+/**
+ * Pinned clipboard items and collections; every edit is saved immediately
+ */
+export function ClipboardPinsEditor() {
+    const [pins, setPins] = useState<ClipboardPinsResponse>(EMPTY);
+    const [newCollection, setNewCollection] = useState("");
+
+    const reload = () => api.getClipboardPins().then(setPins).catch((e) => console.error("Failed to load pins:", e));
+
+    useEffect(() => {
+        reload();
+    }, []);
+
+    const run = (change: Promise<unknown>) => change.then(reload).catch((e) => api.log("error", `Pin update failed: ${e}`));
+
+    const savePin = (pin: PinnedClipboardItem, patch: Partial<{ content: string; title: string | undefined; collection_id: string | undefined }>) =>
+        run(api.updatePinnedItem({
+            id: pin.item.id,
+            content: pin.item.content,
+            title: pin.title,
+            collection_id: pin.collection_id,
+            ...patch,
+        }));
+
+    const groups = [
+        { id: undefined as string | undefined, name: "Pinned" },
+        ...pins.collections.map((c) => ({ id: c.id as string | undefined, name: c.name })),
+    ];
+
+    return (
+        <div className="flex flex-col gap-4">
+            {groups.map((group, groupIndex) => {
+                const items = pins.items.filter((pin) => pin.collection_id === group.id);
+                const ids = items.map((pin) => pin.item.id);
+                const collectionIds = pins.collections.map((c) => c.id);
+                return (
+                    <div key={group.id ?? "pinned"} className="flex flex-col gap-2">
+                        <div className="flex items-center gap-2">
+                            {group.id ? (
+                                <>
+                                    <input
+                                        type="text"
+                                        defaultValue={group.name}
+                                        onBlur={(e) => e.target.value !== group.name && run(api.saveClipboardCollection(e.target.value, group.id))}
+                                        className="input-field flex-1 font-medium"
+                                    />
+                                    <button
+                                        onClick={() => {
+                                            const next = moved(collectionIds, groupIndex - 1, -1);
+                                            if (next) run(api.reorderClipboardCollections(next));
+                                        }}
+                                        className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                        title="Move up"
+                                    >
+                                        <ArrowUp className="w-4 h-4" />
+                                    </button>
+                                    <button
+                                        onClick={() => {
+                                            const next = moved(collectionIds, groupIndex - 1, 1);
+                                            if (next) run(api.reorderClipboardCollections(next));
+                                        }}
+                                        className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                        title="Move down"
+                                    >
+                                        <ArrowDown className="w-4 h-4" />
+                                    </button>
+                                    <button
+                                        onClick={() => run(api.deleteClipboardCollection(group.id!))}
+                                        className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                        title="Delete collection (items move to Pinned)"
+                                    >
+                                        <X className="w-4 h-4" />
+                                    </button>
+                                </>
+                            ) : (
+                                <span className="text-sm font-medium text-gray-700 dark:text-gray-300">{group.name}</span>
+                            )}
+                        </div>
+
+                        {items.length === 0 && (
+                            <div className="text-xs text-gray-500 dark:text-gray-400">
+                                {group.id ? "Empty" : "Press Alt+1-5 in the palette to pin a recent copy"}
+                            </div>
+                        )}
+
+                        {items.map((pin, index) => (
+                            <div key={pin.item.id} className="flex items-start gap-2">
+                                <div className="flex flex-col gap-1 flex-1 min-w-0">
+                                    <input
+                                        type="text"
+                                        defaultValue={pin.title ?? ""}
+                                        onBlur={(e) => e.target.value !== (pin.title ?? "") && savePin(pin, { title: e.target.value || undefined })}
+                                        className="input-field"
+                                        placeholder="Title (optional)"
+                                    />
+                                    {pin.item.image ? (
+                                        <div className="flex items-center gap-2 text-xs text-gray-500 dark:text-gray-400">
+                                            <img src={pin.item.image.thumbnail} alt="" className="h-10 rounded" />
+                                            {pin.item.preview}
+                                        </div>
+                                    ) : (
+                                        <textarea
+                                            defaultValue={pin.item.content}
+                                            onBlur={(e) => e.target.value !== pin.item.content && savePin(pin, { content: e.target.value })}
+                                            className="input-field font-mono text-xs"
+                                            rows={2}
+                                        />
+                                    )}
+                                </div>
+                                <select
+                                    value={pin.collection_id ?? ""}
+                                    onChange={(e) => savePin(pin, { collection_id: e.target.value || undefined })}
+                                    className="input-field w-32"
+                                >
+                                    <option value="">Pinned</option>
+                                    {pins.collections.map((c) => (
+                                        <option key={c.id} value={c.id}>{c.name}</option>
+                                    ))}
+                                </select>
+                                <button
+                                    onClick={() => {
+                                        const next = moved(ids, index, -1);
+                                        if (next) run(api.reorderPinnedItems(next));
+                                    }}
+                                    className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                    title="Move up"
+                                >
+                                    <ArrowUp className="w-4 h-4" />
+                                </button>
+                                <button
+                                    onClick={() => {
+                                        const next = moved(ids, index, 1);
+                                        if (next) run(api.reorderPinnedItems(next));
+                                    }}
+                                    className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                    title="Move down"
+                                >
+                                    <ArrowDown className="w-4 h-4" />
+                                </button>
+                                <button
+                                    onClick={() => run(api.unpinClipboardItem(pin.item.id))}
+                                    className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                    title="Unpin"
+                                >
+                                    <X className="w-4 h-4" />
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+                );
+            })}
+
+            <div className="flex items-center gap-2">
+                <input
+                    type="text"
+                    value={newCollection}
+                    onChange={(e) => setNewCollection(e.target.value)}
+                    className="input-field flex-1"
+                    placeholder='New collection, e.g. "SQL snippets"'
+                />
+                <button
+                    onClick={() => {
+                        if (!newCollection.trim()) return;
+                        run(api.saveClipboardCollection(newCollection));
+                        setNewCollection("");
+                    }}
+                    className="text-sm text-gray-600 dark:text-gray-400 hover:underline"
+                >
+                    + Add collection
+                </button>
+            </div>
+        </div>
+    );
+}
@@ -2,7 +2,36 @@ import { useState, useEffect } from "react";
 import { Settings as SettingsIcon, Save, X } from "lucide-react";
 import { useAppStore } from "../../logic/state/store";
 import { api } from "../../logic/api/tauri";
-import type { AppSettings } from "../../logic/types";
+import { ClipboardPinsEditor } from "./ClipboardPinsEditor";
+import { CaptureRulesEditor } from "./CaptureRulesEditor";
+import { ClipboardBackupEditor } from "./ClipboardBackupEditor";
+import type { AppSettings, NumberLocale, HolidayCalendarInfo, BusinessCalendarSettings, ClipboardSettings, PinnedZone, TimezoneInfo } from "../../logic/types";
+
+const WEEKEND_OPTIONS: { value: string; label: string }[] = [
+    { value: "sat,sun", label: "Saturday – Sunday" },
+    { value: "fri,sat", label: "Friday – Saturday" },
+    { value: "fri", label: "Friday only" },
+    { value: "sun", label: "Sunday only" },
+];
+
+const DEFAULT_BUSINESS_CALENDAR: BusinessCalendarSettings = { weekend: ["sat", "sun"], holiday_country: null };
+
+const DEFAULT_CLIPBOARD: ClipboardSettings = { max_items: 500, max_age_days: 30, max_total_mb: 100, stack_separator: "\n", capture_rules: [], redacted_original_secs: null, clear_sensitive_after_secs: null, sensitive_item_ttl_mins: null };
+
+/** Separators offered for pasting the copy stack at once (anything else is "custom") */
+const STACK_SEPARATORS: { label: string; value: string }[] = [
+    { label: "New line", value: "\n" },
+    { label: "Blank line", value: "\n\n" },
+    { label: "Space", value: " " },
+    { label: "Comma", value: ", " },
+    { label: "Tab", value: "\t" },
+];
+
+/** Empty or non-positive input means "no limit" */
+const parseLimit = (value: string): number | null => {
+    const n = parseInt(value, 10);
+    return Number.isFinite(n) && n > 0 ? n : null;
+};
 
 
 export function SettingsWidget() {
@@ -11,6 +40,9 @@ export function SettingsWidget() {
     const [localSettings, setLocalSettings] = useState<AppSettings | null>(null);
     const [saving, setSaving] = useState(false);
     const [saved, setSaved] = useState(false);
+    const [holidayCalendars, setHolidayCalendars] = useState<HolidayCalendarInfo[]>([]);
+    const [timezones, setTimezones] = useState<TimezoneInfo[]>([]);
+    const [pinsVersion, setPinsVersion] = useState(0);
 
     useEffect(() => {
         if (settings) {
@@ -18,6 +50,34 @@ export function SettingsWidget() {
         }
     }, [settings]);
 
+    useEffect(() => {
+        api.listHolidayCalendars()
+            .then(setHolidayCalendars)
+            .catch((e) => console.error("Failed to load holiday calendars:", e));
+        api.getTimezones()
+            .then(setTimezones)
+            .catch((e) => console.error("Failed to load timezones:", e));
+    }, []);
+
+    const setWorldClock = (worldClock: PinnedZone[]) => {
+        if (!localSettings) return;
+        setLocalSettings({
+            ...localSettings,
+            preferences: { ...localSettings.preferences, world_clock: worldClock },
+        });
+    };
+
+    const setClipboard = (patch: Partial<ClipboardSettings>) => {
+        if (!localSettings) return;
+        setLocalSettings({
+            ...localSettings,
+            preferences: {
+                ...localSettings.preferences,
+                clipboard: { ...(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD), ...patch },
+            },
+        });
+    };
+
     const handleSave = async () => {
         if (!localSettings) return;
 
@@ -33,7 +93,7 @@ export function SettingsWidget() {
             setTimeout(() => setSaved(false), 2000);
         } catch (e) {
             console.error("Failed to save settings:", e);
-            alert("Failed to save settings");
+            alert(`Failed to save settings: ${e}`);
         } finally {
             setSaving(false);
         }
@@ -47,6 +107,9 @@ export function SettingsWidget() {
         );
     }
 
+    const stackSeparator = (localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).stack_separator;
+    const stackSeparatorPreset = STACK_SEPARATORS.some((option) => option.value === stackSeparator);
+
     return (
         <div className="min-h-screen p-6">
             <div className="widget-container max-w-3xl mx-auto animate-slide-up">
@@ -307,7 +370,283 @@ export function SettingsWidget() {
                                     <option value="dark">Dark</option>
                                 </select>
                             </div>
+
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Number Format
+                                </label>
+                                <select
+                                    value={localSettings.preferences.number_locale ?? 'auto'}
+                                    onChange={(e) =>
+                                        setLocalSettings({
+                                            ...localSettings,
+                                            preferences: {
+                                                ...localSettings.preferences,
+                                                number_locale: e.target.value as NumberLocale,
+                                            },
+                                        })
+                                    }
+                                    className="input-field"
+                                >
+                                    <option value="auto">Auto-detect (1,234.56)</option>
+                                    <option value="en-US">1,234.56</option>
+                                    <option value="de-DE">1.234,56</option>
+                                    <option value="fr-FR">1 234,56</option>
+                                    <option value="de-CH">1'234.56</option>
+                                </select>
+                            </div>
+
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Weekend
+                                </label>
+                                <select
+                                    value={(localSettings.preferences.business_calendar ?? DEFAULT_BUSINESS_CALENDAR).weekend.join(",")}
+                                    onChange={(e) =>
+                                        setLocalSettings({
+                                            ...localSettings,
+                                            preferences: {
+                                                ...localSettings.preferences,
+                                                business_calendar: {
+                                                    ...(localSettings.preferences.business_calendar ?? DEFAULT_BUSINESS_CALENDAR),
+                                                    weekend: e.target.value.split(","),
+                                                },
+                                            },
+                                        })
+                                    }
+                                    className="input-field"
+                                >
+                                    {WEEKEND_OPTIONS.map((option) => (
+                                        <option key={option.value} value={option.value}>{option.label}</option>
+                                    ))}
+                                </select>
+                            </div>
+
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Public Holidays
+                                </label>
+                                <select
+                                    value={localSettings.preferences.business_calendar?.holiday_country ?? ""}
+                                    onChange={(e) =>
+                                        setLocalSettings({
+                                            ...localSettings,
+                                            preferences: {
+                                                ...localSettings.preferences,
+                                                business_calendar: {
+                                                    ...(localSettings.preferences.business_calendar ?? DEFAULT_BUSINESS_CALENDAR),
+                                                    holiday_country: e.target.value || null,
+                                                },
+                                            },
+                                        })
+                                    }
+                                    className="input-field"
+                                >
+                                    <option value="">None (weekends only)</option>
+                                    {holidayCalendars.map((calendar) => (
+                                        <option key={calendar.country} value={calendar.country}>{calendar.name}</option>
+                                    ))}
+                                </select>
+                            </div>
+
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    World Clock
+                                </label>
+                                <div className="flex flex-col gap-2">
+                                    {(localSettings.preferences.world_clock ?? []).map((pin, index, pins) => (
+                                        <div key={index} className="flex items-center gap-2">
+                                            <select
+                                                value={pin.timezone}
+                                                onChange={(e) =>
+                                                    setWorldClock(pins.map((p, i) => (i === index ? { ...p, timezone: e.target.value } : p)))
+                                                }
+                                                className="input-field flex-1"
+                                            >
+                                                {!timezones.some((tz) => tz.iana_id === pin.timezone) && (
+                                                    <option value={pin.timezone}>{pin.timezone}</option>
+                                                )}
+                                                {timezones.map((tz) => (
+                                                    <option key={tz.iana_id} value={tz.iana_id}>{tz.label}</option>
+                                                ))}
+                                            </select>
+                                            <input
+                                                type="text"
+                                                value={pin.label ?? ""}
+                                                onChange={(e) =>
+                                                    setWorldClock(pins.map((p, i) => (i === index ? { ...p, label: e.target.value || null } : p)))
+                                                }
+                                                className="input-field w-40"
+                                                placeholder="Label (optional)"
+                                            />
+                                            <button
+                                                onClick={() => setWorldClock(pins.filter((_, i) => i !== index))}
+                                                className="p-1 text-gray-500 hover:text-gray-800 dark:hover:text-gray-200"
+                                                title="Remove"
+                                            >
+                                                <X className="w-4 h-4" />
+                                            </button>
+                                        </div>
+                                    ))}
+                                    <button
+                                        onClick={() =>
+                                            setWorldClock([
+                                                ...(localSettings.preferences.world_clock ?? []),
+                                                { timezone: timezones[0]?.iana_id ?? "UTC", label: null },
+                                            ])
+                                        }
+                                        className="self-start text-sm text-gray-600 dark:text-gray-400 hover:underline"
+                                    >
+                                        + Add zone
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    </section>
+
+                    {/* Clipboard History */}
+                    <section>
+                        <h3 className="text-lg font-semibold mb-4">Clipboard History</h3>
+                        <div className="grid grid-cols-3 gap-4">
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Keep Items
+                                </label>
+                                <input
+                                    type="number"
+                                    min={1}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).max_items}
+                                    onChange={(e) => setClipboard({ max_items: parseLimit(e.target.value) ?? 1 })}
+                                    className="input-field"
+                                />
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Max Age (days)
+                                </label>
+                                <input
+                                    type="number"
+                                    min={0}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).max_age_days ?? ""}
+                                    onChange={(e) => setClipboard({ max_age_days: parseLimit(e.target.value) })}
+                                    className="input-field"
+                                    placeholder="Forever"
+                                />
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Max Size (MB)
+                                </label>
+                                <input
+                                    type="number"
+                                    min={0}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).max_total_mb ?? ""}
+                                    onChange={(e) => setClipboard({ max_total_mb: parseLimit(e.target.value) })}
+                                    className="input-field"
+                                    placeholder="No limit"
+                                />
+                            </div>
                         </div>
+                        <p className="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                            Oldest items are removed on the next copy once any limit is reached
+                        </p>
+
+                        <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mt-4 mb-2">
+                            Copy Stack Separator
+                        </label>
+                        <div className="grid grid-cols-2 gap-4">
+                            <select
+                                value={stackSeparatorPreset ? stackSeparator : "custom"}
+                                onChange={(e) => setClipboard({ stack_separator: e.target.value === "custom" ? " | " : e.target.value })}
+                                className="input-field"
+                            >
+                                {STACK_SEPARATORS.map((option) => (
+                                    <option key={option.label} value={option.value}>{option.label}</option>
+                                ))}
+                                <option value="custom">Custom…</option>
+                            </select>
+                            {!stackSeparatorPreset && (
+                                <input
+                                    type="text"
+                                    value={stackSeparator}
+                                    onChange={(e) => setClipboard({ stack_separator: e.target.value })}
+                                    className="input-field"
+                                    placeholder="Separator"
+                                />
+                            )}
+                        </div>
+                        <p className="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                            Placed between copies when the copy stack is pasted all at once
+                        </p>
+
+                        <div className="grid grid-cols-3 gap-4 mt-4">
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Keep Originals of Masked Copies (s)
+                                </label>
+                                <input
+                                    type="number"
+                                    min={0}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).redacted_original_secs ?? ""}
+                                    onChange={(e) => setClipboard({ redacted_original_secs: parseLimit(e.target.value) })}
+                                    className="input-field"
+                                    placeholder="Don't keep"
+                                />
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Clear Clipboard After Secret (s)
+                                </label>
+                                <input
+                                    type="number"
+                                    min={0}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).clear_sensitive_after_secs ?? ""}
+                                    onChange={(e) => setClipboard({ clear_sensitive_after_secs: parseLimit(e.target.value) })}
+                                    className="input-field"
+                                    placeholder="Never"
+                                />
+                            </div>
+                            <div>
+                                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                    Delete Secrets From History (min)
+                                </label>
+                                <input
+                                    type="number"
+                                    min={0}
+                                    value={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).sensitive_item_ttl_mins ?? ""}
+                                    onChange={(e) => setClipboard({ sensitive_item_ttl_mins: parseLimit(e.target.value) })}
+                                    className="input-field"
+                                    placeholder="Like other items"
+                                />
+                            </div>
+                        </div>
+                        <p className="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                            Secrets in copies are masked before they are saved. The unmasked text can stay in memory (never on disk) so pasting the item right away still gives what you copied. The clipboard is only cleared if it still holds the secret
+                        </p>
+
+                        <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mt-4 mb-2">
+                            Capture Rules
+                        </label>
+                        <CaptureRulesEditor
+                            rules={(localSettings.preferences.clipboard ?? DEFAULT_CLIPBOARD).capture_rules}
+                            onChange={(rules) => setClipboard({ capture_rules: rules })}
+                        />
+                        <p className="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                            Checked top to bottom for every copy; the first matching rule decides
+                        </p>
+
+                        <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mt-4 mb-2">
+                            Pinned Items
+                        </label>
+                        <ClipboardPinsEditor key={pinsVersion} />
+
+                        <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mt-4 mb-2">
+                            Backup
+                        </label>
+                        <ClipboardBackupEditor onImported={() => setPinsVersion((version) => version + 1)} />
+                        <p className="mt-2 text-xs text-gray-500 dark:text-gray-400">
+                            Moves history and pins to another machine. Items kept on this device only and secrets on a timer are left out; importing keeps the newer copy of each item
+                        </p>
                     </section>
                 </div>
 
@@ -3,9 +3,22 @@ import { api } from "../../logic/api/tauri";
 import { Card } from "../ui/card";
 import { Combobox } from "../ui/combobox";
 import { useAppStore } from "../../logic/state/store";
-import type { TimezoneInfo } from "../../logic/types";
+import type { TimezoneInfo, TimezoneCandidate, TimeMentionConversion, TimestampRendering, DateCalcResponse, TimeInterpretation, SolarInfo, ConvertIcsResponse, ExplainCronResponse } from "../../logic/types";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useDebounce } from "../../hooks/useDebounce";
+import { WorldClockBoard } from "./WorldClockBoard";
+
+/** iCalendar text or invite lines with DTSTART */
+const ICS_PATTERN = /^\s*(BEGIN:VEVENT|DTSTART[;:])/im;
+
+/** Cron expressions (5-7 fields with a wildcard or step), @daily-style macros and crontab lines */
+const CRON_PATTERN = /^\s*(@(yearly|annually|monthly|weekly|daily|midnight|hourly|reboot)\b|(?=.*([*\/]|(^|\s)\?(\s|$)))[\d*?][\w*?\/,#-]*\s+([\w*?\/,#-]+\s+){3,5}[\w*?\/,#-]+(\s|$))/i;
+
+/** Source city first; one row when both zones share a city and date */
+function solarRows(first?: SolarInfo, second?: SolarInfo): SolarInfo[] {
+    const rows = [first, second].filter((info): info is SolarInfo => !!info);
+    return rows.length === 2 && rows[0].city === rows[1].city && rows[0].date === rows[1].date ? [rows[0]] : rows;
+}
 
 export function TimeConverterWidget() {
     // Global State
@@ -30,6 +43,28 @@ export function TimeConverterWidget() {
     const [isInitialized, setIsInitialized] = useState(false);
     const [offsetDescription, setOffsetDescription] = useState<string>("");
     const [matchedKeyword, setMatchedKeyword] = useState<string | undefined>(undefined);
+    const [alternatives, setAlternatives] = useState<TimezoneCandidate[]>([]);
+    // Daylight saving: gaps/overlaps at the entered time and clock changes nearby
+    const [dstWarning, setDstWarning] = useState<string | null>(null);
+    const [interpretations, setInterpretations] = useState<TimeInterpretation[]>([]);
+    // Sunrise and sunset in both cities on the converted dates
+    const [solar, setSolar] = useState<SolarInfo[]>([]);
+    // Selection with several times or a range, converted line by line
+    const [multiTimeText, setMultiTimeText] = useState<string | null>(null);
+    const [mentionConversions, setMentionConversions] = useState<TimeMentionConversion[]>([]);
+    // Selection that is a Unix timestamp / ISO 8601 / RFC 2822 date, in every format
+    const [timestampRenderings, setTimestampRenderings] = useState<TimestampRendering[]>([]);
+    // Calendar events pasted as .ics or invite text
+    const [icsText, setIcsText] = useState<string | null>(null);
+    const [icsResult, setIcsResult] = useState<ConvertIcsResponse | null>(null);
+    const [icsCopied, setIcsCopied] = useState(false);
+    // Cron schedule: explained, validated and its next runs
+    const [cronText, setCronText] = useState<string | null>(null);
+    const [cronResult, setCronResult] = useState<ExplainCronResponse | null>(null);
+    // Date math ("today + 90 days", "days until Christmas")
+    const [dateExpression, setDateExpression] = useState("");
+    const [dateResult, setDateResult] = useState<DateCalcResponse | null>(null);
+    const [dateError, setDateError] = useState<string | null>(null);
 
     // State to track which field controls the conversion direction
     const [lastActiveField, setLastActiveField] = useState<"from" | "to" | null>(null);
@@ -37,6 +72,7 @@ export function TimeConverterWidget() {
     // Debounced values
     const debouncedFrom = useDebounce(timeFromInput, 500);
     const debouncedTo = useDebounce(timeToInput, 500);
+    const debouncedDateExpression = useDebounce(dateExpression, 400);
 
     // Initialize widget
     const initialize = useCallback(async () => {
@@ -68,6 +104,9 @@ export function TimeConverterWidget() {
                 }
             }
 
+            setTimestampRenderings([]);
+            setIcsText(textToProcess && ICS_PATTERN.test(textToProcess) ? textToProcess : null);
+            setCronText(textToProcess && !textToProcess.trim().includes("\n") && CRON_PATTERN.test(textToProcess) ? textToProcess.trim() : null);
             if (textToProcess) {
                 const parsed = await api.parseTimeFromSelection(textToProcess);
 
@@ -81,6 +120,11 @@ export function TimeConverterWidget() {
                         setMatchedKeyword(parsed.matched_keyword);
                     }
 
+                    setMultiTimeText(parsed.mentions?.length ? textToProcess : null);
+
+                    const timestamp = await api.convertTimestamp({ text: textToProcess }).catch(() => null);
+                    setTimestampRenderings(timestamp?.renderings ?? []);
+
                     setTimeFromInput(parsed.time_input);
                     // Explicitly set "from" as active to trigger initial conversion
                     setLastActiveField("from");
@@ -130,6 +174,10 @@ export function TimeConverterWidget() {
                 setTimeRelativeOffset("");
                 setTimeDateChangeIndicator(null);
                 setOffsetDescription("");
+                setAlternatives([]);
+                setDstWarning(null);
+                setInterpretations([]);
+                setSolar([]);
                 return;
             }
 
@@ -138,7 +186,7 @@ export function TimeConverterWidget() {
                     time_input: debouncedFrom,
                     source_timezone: timeSourceTimezone || null,
                     target_timezone: timeTargetTimezone,
-                    matched_keyword: null
+                    matched_keyword: matchedKeyword ?? null
                 });
 
                 setTimeToInput(response.target_time);
@@ -148,6 +196,10 @@ export function TimeConverterWidget() {
                 setTimeRelativeOffset(response.relative_offset);
                 setTimeDateChangeIndicator(response.date_change_indicator || null);
                 setOffsetDescription(response.offset_description || "");
+                setAlternatives(response.alternatives ?? []);
+                setDstWarning(response.dst_warning ?? null);
+                setInterpretations(response.interpretations ?? []);
+                setSolar(solarRows(response.source_solar, response.target_solar));
                 setErrorMessage(null);
             } catch (error) {
                 setErrorMessage("Conversion failed");
@@ -158,6 +210,92 @@ export function TimeConverterWidget() {
         convert();
     }, [debouncedFrom, timeSourceTimezone, timeTargetTimezone, isInitialized, lastActiveField, matchedKeyword, setTimeToInput, setTimeRelativeOffset, setTimeDateChangeIndicator, setOffsetDescription]);
 
+    // Conversion Effect: every time/range in a multi-time selection
+    useEffect(() => {
+        if (!isInitialized || !multiTimeText) {
+            setMentionConversions([]);
+            return;
+        }
+
+        api.convertTimesInSelection({
+            text: multiTimeText,
+            target_timezone: timeTargetTimezone,
+            source_timezone: timeSourceTimezone || undefined,
+        })
+            .then(response => setMentionConversions(response.conversions))
+            .catch(error => {
+                console.error(error);
+                setMentionConversions([]);
+            });
+    }, [multiTimeText, timeSourceTimezone, timeTargetTimezone, isInitialized]);
+
+    // Calendar events, shown in and rewritten for the target zone
+    useEffect(() => {
+        if (!isInitialized || !icsText || !timeTargetTimezone) {
+            setIcsResult(null);
+            return;
+        }
+
+        api.convertIcs({
+            text: icsText,
+            target_timezones: [timeTargetTimezone],
+            retarget_timezone: timeTargetTimezone,
+        })
+            .then(setIcsResult)
+            .catch(error => {
+                console.error(error);
+                setIcsResult(null);
+            });
+    }, [icsText, timeTargetTimezone, isInitialized]);
+
+    // Cron schedule, with runs shown in the target zone
+    useEffect(() => {
+        if (!isInitialized || !cronText || !timeTargetTimezone) {
+            setCronResult(null);
+            return;
+        }
+
+        api.explainCron({
+            expression: cronText,
+            target_timezones: [timeTargetTimezone],
+        })
+            .then(setCronResult)
+            .catch(error => {
+                console.error(error);
+                setCronResult(null);
+            });
+    }, [cronText, timeTargetTimezone, isInitialized]);
+
+    const copyIcs = async () => {
+        if (!icsResult?.ics) return;
+        try {
+            await navigator.clipboard.writeText(icsResult.ics);
+            setIcsCopied(true);
+            setTimeout(() => setIcsCopied(false), 2000);
+        } catch (error) {
+            console.error(error);
+        }
+    };
+
+    // Date math effect
+    useEffect(() => {
+        if (!debouncedDateExpression.trim()) {
+            setDateResult(null);
+            setDateError(null);
+            return;
+        }
+
+        api.calculateDate({ expression: debouncedDateExpression })
+            .then(response => {
+                setDateResult(response);
+                setDateError(null);
+            })
+            .catch(error => {
+                setDateResult(null);
+                setDateError(typeof error === "string" ? error : "Couldn't calculate that");
+            });
+    }, [debouncedDateExpression]);
+
     // Conversion Effect: TO -> FROM
     useEffect(() => {
         if (!isInitialized || lastActiveField !== "to") return;
@@ -168,6 +306,9 @@ export function TimeConverterWidget() {
                 setTimeRelativeOffset("");
                 setTimeDateChangeIndicator(null);
                 setOffsetDescription("");
+                setDstWarning(null);
+                setInterpretations([]);
+                setSolar([]);
                 return;
             }
 
@@ -184,6 +325,9 @@ export function TimeConverterWidget() {
                 setTimeRelativeOffset(response.relative_offset);
                 setTimeDateChangeIndicator(response.date_change_indicator || null);
                 setOffsetDescription(response.offset_description || "");
+                setDstWarning(response.dst_warning ?? null);
+                setInterpretations(response.interpretations ?? []);
+                setSolar(solarRows(response.target_solar, response.source_solar));
                 setErrorMessage(null);
             } catch (error) {
                 setErrorMessage("Conversion failed");
@@ -304,6 +448,201 @@ export function TimeConverterWidget() {
                 </div>
             )}
 
+            {/* Daylight saving gap/overlap or a clock change within a week */}
+            {dstWarning && (
+                <div className="text-center text-ink-900 text-[11px]">⚠ {dstWarning}</div>
+            )}
+
+            {/* Both readings of a time that happens twice */}
+            {interpretations.length > 0 && (
+                <div className="flex flex-col gap-1 border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                    {interpretations.map((reading, index) => (
+                        <div key={index} className="flex justify-between gap-3">
+                            <span className="text-ink-700" title={reading.source_utc_offset}>
+                                {reading.source_time} {reading.source_zone_abbr}
+                            </span>
+                            <span className="text-ink-1000 text-right" title={reading.target_utc_offset}>
+                                {reading.target_time} {reading.target_zone_abbr}
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            {/* Daylight in each city on the converted dates */}
+            {solar.length > 0 && (
+                <div className="flex flex-col gap-0.5 text-[11px] text-ink-700">
+                    {solar.map(info => (
+                        <div key={`${info.city}-${info.date}`} className="flex justify-between gap-3">
+                            <span>{info.city}, {info.date}</span>
+                            <span className="text-right" title={info.civil_dawn && info.civil_dusk ? `Civil twilight ${info.civil_dawn} – ${info.civil_dusk}` : undefined}>
+                                {info.condition === "polar_day"
+                                    ? "Midnight sun"
+                                    : info.condition === "polar_night"
+                                        ? "Sun doesn't rise"
+                                        : `☀ ${info.sunrise} – ${info.sunset} • ${info.day_length}`}
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            {/* Each time or range in the selection */}
+            {mentionConversions.length > 0 && (
+                <div className="flex flex-col gap-1 border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                    {mentionConversions.map((conversion, index) => (
+                        <div key={index} className="flex justify-between gap-3">
+                            <span className="text-ink-700">{conversion.original}</span>
+                            <span className="text-ink-1000 text-right">
+                                {conversion.converted}
+                                {(conversion.start.date_change_indicator || conversion.end?.date_change_indicator) && (
+                                    <span className="ml-2 text-ink-700">
+                                        • {conversion.start.date_change_indicator ?? conversion.end?.date_change_indicator}
+                                    </span>
+                                )}
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            {/* Calendar events and their next occurrences */}
+            {icsResult && icsResult.events.length > 0 && (
+                <div className="flex flex-col gap-2 border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                    {icsResult.events.map((event, index) => (
+                        <div key={event.uid ?? index} className="flex flex-col gap-0.5">
+                            <div className="flex justify-between gap-3">
+                                <span className="text-ink-1000">{event.summary}</span>
+                                {event.recurrence && <span className="text-ink-700 text-right">{event.recurrence}</span>}
+                            </div>
+                            {event.occurrences.map(occurrence => (
+                                <div key={occurrence.start} className="flex justify-between gap-3">
+                                    <span className="text-ink-700">{occurrence.original}</span>
+                                    <span className="text-ink-1000 text-right">
+                                        {occurrence.zones[0]?.time}
+                                        {occurrence.zones[0]?.date_change_indicator && (
+                                            <span className="ml-2 text-ink-700">• {occurrence.zones[0].date_change_indicator}</span>
+                                        )}
+                                    </span>
+                                </div>
+                            ))}
+                        </div>
+                    ))}
+                    {icsResult.warnings.map((warning, index) => (
+                        <div key={index} className="text-ink-900 text-[11px]">⚠ {warning}</div>
+                    ))}
+                    {icsResult.ics && (
+                        <button
+                            onClick={copyIcs}
+                            className="self-end px-1.5 py-0.5 border border-ink-400 rounded-md hover:bg-ink-100 text-[11px]"
+                        >
+                            {icsCopied ? "Copied" : "Copy .ics in this timezone"}
+                        </button>
+                    )}
+                </div>
+            )}
+
+            {/* Cron schedule in words and its next runs, or where the syntax breaks */}
+            {cronResult && (
+                <div className="flex flex-col gap-1 border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                    {cronResult.error ? (
+                        <>
+                            <div className="font-mono text-ink-700 whitespace-pre">
+                                {Array.from(cronResult.expression).slice(0, cronResult.error.start).join("")}
+                                <span className="text-ink-1000 underline decoration-wavy">
+                                    {Array.from(cronResult.expression).slice(cronResult.error.start, cronResult.error.end).join("") || " "}
+                                </span>
+                                {Array.from(cronResult.expression).slice(cronResult.error.end).join("")}
+                            </div>
+                            <div className="text-ink-900 text-[11px]">⚠ {cronResult.error.message}</div>
+                        </>
+                    ) : (
+                        <>
+                            <div className="text-ink-1000" title={cronResult.fields.map(field => `${field.name}: ${field.value} → ${field.description}`).join("\n")}>
+                                {cronResult.description}
+                            </div>
+                            {cronResult.command && (
+                                <div className="font-mono text-ink-700 text-[11px] truncate">{cronResult.command}</div>
+                            )}
+                            {cronResult.runs.map(run => (
+                                <div key={run.iso} className="flex justify-between gap-3" title={run.note}>
+                                    <span className="text-ink-700">
+                                        {run.zones[0]?.time} {run.zones[0]?.zone_abbr}
+                                        {run.note && " ⚠"}
+                                    </span>
+                                    <span className="text-ink-1000 text-right">
+                                        {run.zones[1]?.time} {run.zones[1]?.zone_abbr}
+                                        <span className="ml-2 text-ink-700">{run.relative}</span>
+                                    </span>
+                                </div>
+                            ))}
+                        </>
+                    )}
+                </div>
+            )}
+
+            {/* The selected timestamp in every format */}
+            {timestampRenderings.length > 0 && (
+                <div className="flex flex-col gap-1 border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                    {timestampRenderings.map(rendering => (
+                        <div key={rendering.format} className="flex justify-between gap-3">
+                            <span className="text-ink-700">{rendering.label}</span>
+                            <span className="text-ink-1000 text-right font-mono select-all">{rendering.value}</span>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            {/* Other zones an ambiguous abbreviation may mean */}
+            {alternatives.length > 0 && (
+                <div className="flex flex-wrap justify-center items-center gap-1 text-[11px] text-ink-700">
+                    <span>{matchedKeyword} could also be:</span>
+                    {alternatives.map(alt => (
+                        <button
+                            key={alt.iana_id}
+                            onClick={() => {
+                                setTimeSourceTimezone(alt.iana_id);
+                                setLastActiveField("from");
+                            }}
+                            className="px-1.5 py-0.5 border border-ink-400 rounded-md hover:bg-ink-100"
+                            title={`${alt.iana_id} (${alt.utc_offset})`}
+                        >
+                            {alt.label}
+                        </button>
+                    ))}
+                </div>
+            )}
+
+            {/* Pinned world clock zones */}
+            <WorldClockBoard />
+
+            {/* Date math */}
+            <div className="flex flex-col gap-1 w-full border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+                <input
+                    type="text"
+                    value={dateExpression}
+                    onChange={(e) => setDateExpression(e.target.value)}
+                    className="bg-transparent border-none outline-none text-[13px] text-ink-1000"
+                    placeholder="today + 90 days, 3h 20m + 45m, days until Christmas"
+                />
+                {dateResult && (
+                    <div className="flex justify-between gap-3">
+                        <span className="text-ink-1000">
+                            {dateResult.result}
+                            {dateResult.detail && <span className="ml-2 text-ink-700">• {dateResult.detail}</span>}
+                        </span>
+                        <span className="text-ink-700 font-mono select-all">{dateResult.iso}</span>
+                    </div>
+                )}
+                {dateResult && dateResult.business_days !== undefined && dateResult.kind === "difference" && (
+                    <div className="text-ink-700">{dateResult.business_days} business days</div>
+                )}
+                {dateResult && dateResult.skipped_holidays.length > 0 && (
+                    <div className="text-ink-700">Skipped: {dateResult.skipped_holidays.join(", ")}</div>
+                )}
+                {dateError && <div className="text-ink-700">{dateError}</div>}
+            </div>
+
             {/* Smart City Detection note */}
             {offsetDescription && offsetDescription.includes('•') && (
                 <div className="text-center text-ink-600 text-[11px] font-normal italic">
@@ -18,6 +18,9 @@ const SMART_DEFAULT_TARGETS: Record<string, string> = {
     "C": "F", "F": "C",
     // Speed (metric ↔ imperial)
     "km/h": "m/h", "m/h": "km/h",
+    // Typography (design units → CSS)
+    "px": "rem", "rem": "px", "em": "px", "pt": "px", "pc": "pt",
+    "vw": "px", "vh": "px", "dp": "px", "sp": "px", "ios-pt": "px",
 };
 
 interface UnitDTO {
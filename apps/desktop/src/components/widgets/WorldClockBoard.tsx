@@ -0,0 +1,66 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { Moon, Sun, Sunrise } from "lucide-react";
+import { api } from "../../logic/api/tauri";
+import type { WorldClockEntry, WorldClockSnapshot } from "../../logic/types";
+
+function solarTitle(entry: WorldClockEntry): string | undefined {
+    const solar = entry.solar;
+    if (!solar) return undefined;
+    if (solar.condition === "polar_day") return `${solar.city}: midnight sun`;
+    if (solar.condition === "polar_night") return `${solar.city}: the sun doesn't rise today`;
+    return `${solar.city}: sunrise ${solar.sunrise}, sunset ${solar.sunset} (${solar.day_length} of daylight)`;
+}
+
+const PERIOD_ICONS = { day: Sun, twilight: Sunrise, night: Moon };
+
+/**
+ * Pinned zones from settings; the backend pushes a new snapshot every minute
+ */
+export function WorldClockBoard() {
+    const [snapshot, setSnapshot] = useState<WorldClockSnapshot | null>(null);
+
+    useEffect(() => {
+        api.getWorldClock()
+            .then(setSnapshot)
+            .catch((e) => console.error("Failed to load world clock:", e));
+
+        // Re-read after pins change in settings, then follow the ticker
+        const unlistenSettings = listen("settings://update", () => {
+            api.getWorldClock().then(setSnapshot).catch(console.error);
+        });
+        const unlistenTick = listen<WorldClockSnapshot>("world-clock://tick", (event) => {
+            setSnapshot(event.payload);
+        });
+
+        return () => {
+            unlistenSettings.then((unlisten) => unlisten());
+            unlistenTick.then((unlisten) => unlisten());
+        };
+    }, []);
+
+    if (!snapshot || snapshot.entries.length === 0) return null;
+
+    return (
+        <div className="flex flex-col gap-1 w-full border border-ink-400 rounded-lg px-3 py-2 text-[12px]">
+            {snapshot.entries.map((entry) => {
+                const PeriodIcon = PERIOD_ICONS[entry.day_period];
+                return (
+                    <div key={`${entry.timezone}-${entry.label}`} className="flex justify-between items-center gap-3">
+                        <span className="flex items-center gap-1.5 text-ink-1000" title={solarTitle(entry)}>
+                            <PeriodIcon className="w-3 h-3 text-ink-700" />
+                            {entry.label}
+                            <span className="text-ink-700" title={entry.utc_offset}>{entry.abbreviation}</span>
+                        </span>
+                        <span className="text-right" title={entry.iso}>
+                            <span className="text-ink-1000">{entry.time}</span>
+                            <span className="ml-2 text-ink-700">
+                                {entry.day_offset !== 0 ? entry.date : entry.offset_from_local}
+                            </span>
+                        </span>
+                    </div>
+                );
+            })}
+        </div>
+    );
+}
@@ -9,6 +9,34 @@ import type {
     ConvertTimeRequest,
     ConvertTimeResponse,
     TimezoneInfo,
+    TimezoneCandidate,
+    PlanMeetingRequest,
+    ConvertTimesInTextRequest,
+    ConvertTimesInTextResponse,
+    ConvertTimestampRequest,
+    ConvertTimestampResponse,
+    ConvertIcsRequest,
+    ConvertIcsResponse,
+    ExplainCronRequest,
+    ExplainCronResponse,
+    SearchClipboardRequest,
+    ClipboardHistoryPage,
+    ClipboardPinsResponse,
+    CopyStackPasteMode,
+    CopyStackState,
+    ClipboardCollection,
+    PinnedClipboardItem,
+    PinClipboardItemRequest,
+    UpdatePinnedItemRequest,
+    ExportClipboardRequest,
+    ClipboardExportSummary,
+    ImportClipboardRequest,
+    ClipboardImportSummary,
+    DateCalcRequest,
+    DateCalcResponse,
+    HolidayCalendarInfo,
+    WorldClockSnapshot,
+    PlanMeetingResponse,
     ParsedTimeInput,
     LogRequest,
     CommandItem,
@@ -23,6 +51,7 @@ import type {
     GetUnitsResponse,
     ConvertUnitsRequest,
     ConvertUnitsResponse,
+    GetMaterialsResponse,
 } from "../types";
 
 
@@ -109,6 +138,69 @@ export const api = {
         return invoke<ClipboardItem[]>("get_clipboard_history");
     },
 
+    /**
+     * Search clipboard history (typo-tolerant), or page through it with an empty query
+     */
+    async searchClipboardHistory(request: SearchClipboardRequest): Promise<ClipboardHistoryPage> {
+        return invoke<ClipboardHistoryPage>("search_clipboard_history", { request });
+    },
+
+    /**
+     * Get pinned clipboard items and collections (in display order)
+     */
+    async getClipboardPins(): Promise<ClipboardPinsResponse> {
+        return invoke<ClipboardPinsResponse>("get_clipboard_pins");
+    },
+
+    /**
+     * Pin a copy of a history item (optionally into a collection)
+     */
+    async pinClipboardItem(request: PinClipboardItemRequest): Promise<PinnedClipboardItem> {
+        return invoke<PinnedClipboardItem>("pin_clipboard_item", { request });
+    },
+
+    /**
+     * Replace a pinned item's content, title and collection
+     */
+    async updatePinnedItem(request: UpdatePinnedItemRequest): Promise<PinnedClipboardItem> {
+        return invoke<PinnedClipboardItem>("update_pinned_item", { request });
+    },
+
+    /**
+     * Remove a pinned item
+     */
+    async unpinClipboardItem(id: string): Promise<void> {
+        return invoke<void>("unpin_clipboard_item", { id });
+    },
+
+    /**
+     * Reorder pinned items (listed IDs first)
+     */
+    async reorderPinnedItems(ids: string[]): Promise<void> {
+        return invoke<void>("reorder_pinned_items", { ids });
+    },
+
+    /**
+     * Create a collection, or rename it when an ID is given
+     */
+    async saveClipboardCollection(name: string, id?: string): Promise<ClipboardCollection> {
+        return invoke<ClipboardCollection>("save_clipboard_collection", { id, name });
+    },
+
+    /**
+     * Delete a collection (its items become top-level pins)
+     */
+    async deleteClipboardCollection(id: string): Promise<void> {
+        return invoke<void>("delete_clipboard_collection", { id });
+    },
+
+    /**
+     * Reorder collections (listed IDs first)
+     */
+    async reorderClipboardCollections(ids: string[]): Promise<void> {
+        return invoke<void>("reorder_clipboard_collections", { ids });
+    },
+
     /**
      * Paste a clipboard history item (auto-paste flow)
      */
@@ -116,6 +208,48 @@ export const api = {
         return invoke<void>("paste_clipboard_item", { itemId });
     },
 
+    /**
+     * Start or stop collecting copies into the copy stack
+     */
+    async setCopyStackActive(active: boolean): Promise<CopyStackState> {
+        return invoke<CopyStackState>("set_copy_stack_active", { active });
+    },
+
+    /**
+     * Get whether the copy stack is collecting, and what it holds
+     */
+    async getCopyStack(): Promise<CopyStackState> {
+        return invoke<CopyStackState>("get_copy_stack");
+    },
+
+    /**
+     * Drop everything in the copy stack
+     */
+    async clearCopyStack(): Promise<CopyStackState> {
+        return invoke<CopyStackState>("clear_copy_stack");
+    },
+
+    /**
+     * Paste the whole copy stack ("joined") or its oldest entry ("next")
+     */
+    async pasteCopyStack(mode: CopyStackPasteMode): Promise<CopyStackState> {
+        return invoke<CopyStackState>("paste_copy_stack", { mode });
+    },
+
+    /**
+     * Write clipboard history and pins to a passphrase-encrypted backup file
+     */
+    async exportClipboardHistory(request: ExportClipboardRequest): Promise<ClipboardExportSummary> {
+        return invoke<ClipboardExportSummary>("export_clipboard_history", { request });
+    },
+
+    /**
+     * Merge a backup file into history and pins (dry_run only reports what would change)
+     */
+    async importClipboardHistory(request: ImportClipboardRequest): Promise<ClipboardImportSummary> {
+        return invoke<ClipboardImportSummary>("import_clipboard_history", { request });
+    },
+
     /**
      * Get the currently active application name
      */
@@ -159,6 +293,69 @@ export const api = {
         return invoke<TimezoneInfo[]>("get_timezones");
     },
 
+    /**
+     * Search every IANA timezone by name, alias, city or abbreviation
+     */
+    async searchTimezones(query: string, limit?: number): Promise<TimezoneCandidate[]> {
+        return invoke<TimezoneCandidate[]>("search_timezones", { query, limit: limit ?? null });
+    },
+
+    /**
+     * Convert every time and range in a selection (e.g., "9:30–11am EST", "2pm and 5pm CET")
+     */
+    async convertTimesInSelection(request: ConvertTimesInTextRequest): Promise<ConvertTimesInTextResponse> {
+        return invoke<ConvertTimesInTextResponse>("convert_times_in_selection", { request });
+    },
+
+    /**
+     * Detect a Unix timestamp / ISO 8601 / RFC 2822 / RFC 3339 date and write it in every format
+     */
+    async convertTimestamp(request: ConvertTimestampRequest): Promise<ConvertTimestampResponse> {
+        return invoke<ConvertTimestampResponse>("convert_timestamp", { request });
+    },
+
+    /**
+     * Read .ics events (with RRULE recurrences) and convert them, optionally rewriting the calendar in another zone
+     */
+    async convertIcs(request: ConvertIcsRequest): Promise<ConvertIcsResponse> {
+        return invoke<ConvertIcsResponse>("convert_ics", { request });
+    },
+
+    /**
+     * Validate and explain a cron expression or crontab line, with its next runs in the schedule's and target zones
+     */
+    async explainCron(request: ExplainCronRequest): Promise<ExplainCronResponse> {
+        return invoke<ExplainCronResponse>("explain_cron", { request });
+    },
+
+    /**
+     * Evaluate date/duration math (e.g., "today + 90 days", "3h 20m + 45m", "days until Christmas")
+     */
+    async calculateDate(request: DateCalcRequest): Promise<DateCalcResponse> {
+        return invoke<DateCalcResponse>("calculate_date", { request });
+    },
+
+    /**
+     * Bundled public holiday calendars for business-day math
+     */
+    async listHolidayCalendars(): Promise<HolidayCalendarInfo[]> {
+        return invoke<HolidayCalendarInfo[]>("list_holiday_calendars");
+    },
+
+    /**
+     * Current time in every pinned world clock zone
+     */
+    async getWorldClock(): Promise<WorldClockSnapshot> {
+        return invoke<WorldClockSnapshot>("get_world_clock");
+    },
+
+    /**
+     * Rank meeting slots by how many participants are inside working hours
+     */
+    async planMeeting(request: PlanMeetingRequest): Promise<PlanMeetingResponse> {
+        return invoke<PlanMeetingResponse>("plan_meeting", { request });
+    },
+
     /**
      * Get the system's IANA timezone (e.g., "Asia/Seoul")
      */
@@ -209,4 +406,11 @@ export const api = {
     async convertUnitsCommand(request: ConvertUnitsRequest): Promise<ConvertUnitsResponse> {
         return invoke<ConvertUnitsResponse>("convert_units_command", { request });
     },
+
+    /**
+     * Get all materials for mass/volume conversions (built-in + user-defined)
+     */
+    async getAllMaterials(): Promise<GetMaterialsResponse> {
+        return invoke<GetMaterialsResponse>("get_all_materials_command");
+    },
 };
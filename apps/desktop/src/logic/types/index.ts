@@ -59,6 +59,71 @@ export interface UserPreferences {
     default_currency_from: string;
     default_currency_to: string;
     theme: string;
+    number_locale: NumberLocale;
+    business_calendar: BusinessCalendarSettings;
+    world_clock: PinnedZone[];
+    clipboard: ClipboardSettings;
+}
+
+/**
+ * Number separator convention used to read and display numbers
+ */
+export type NumberLocale = "auto" | "en-US" | "de-DE" | "fr-FR" | "de-CH";
+
+/**
+ * Weekend days and public holiday calendar for business-day date math
+ */
+export interface BusinessCalendarSettings {
+    weekend: string[];
+    holiday_country: string | null;
+}
+
+/**
+ * Clipboard history retention (null = no limit) and copy stack options
+ */
+export interface ClipboardSettings {
+    max_items: number;
+    max_age_days: number | null;
+    max_total_mb: number | null;
+    /** Placed between copies when the copy stack is pasted at once */
+    stack_separator: string;
+    /** Checked in order before a copy is stored; the first match decides */
+    capture_rules: CaptureRule[];
+    /** Seconds the unmasked text of a redacted copy stays pasteable (null = not kept) */
+    redacted_original_secs: number | null;
+    /** Seconds before a copied secret is cleared from the clipboard, if still there (null = never) */
+    clear_sensitive_after_secs: number | null;
+    /** Minutes items with secrets stay in history (null = like other items) */
+    sensitive_item_ttl_mins: number | null;
+}
+
+/**
+ * What happens to a copy matching a capture rule
+ * (allow skips the built-in secret check; no_sync keeps it on this device)
+ */
+export type CaptureAction = "allow" | "drop" | "redact" | "no_sync";
+
+/**
+ * A user capture rule; every condition that is set must hold
+ */
+export interface CaptureRule {
+    name: string;
+    enabled: boolean;
+    /** App names or bundle IDs (empty = any app) */
+    apps: string[];
+    /** Regex the copied text must contain a match of */
+    pattern: string | null;
+    /** Only copies larger than this many KB */
+    max_size_kb: number | null;
+    action: CaptureAction;
+}
+
+/**
+ * A zone pinned to the world clock, with an optional custom label
+ */
+export interface PinnedZone {
+    timezone: string;
+    label: string | null;
 }
 
 // ============================================================================
@@ -157,6 +157,32 @@ const KEYWORD_MAP: Record<string, string[]> = {
     // Unit Actions - Speed
     "convert_to_kmh": ["km/h", "kmh", "kph", "kilometers per hour", "kilometers/hour", "convert km/h", "to km/h"],
     "convert_to_mph": ["m/h", "mph", "miles per hour", "miles/hour", "convert mph", "to mph"],
+
+    // Unit Actions - Typography
+    "convert_to_px": ["px", "pixels", "pixel", "convert px", "to px"],
+    "convert_to_pt": ["pt", "points", "point", "convert pt", "to pt"],
+    "convert_to_pc": ["pc", "picas", "pica", "convert pc", "to pc"],
+    "convert_to_em": ["em", "convert em", "to em"],
+    "convert_to_rem": ["rem", "convert rem", "to rem"],
+    "convert_to_vw": ["vw", "viewport width", "convert vw", "to vw"],
+    "convert_to_vh": ["vh", "viewport height", "convert vh", "to vh"],
+    "convert_to_dp": ["dp", "dip", "android dp", "convert dp", "to dp"],
+    "convert_to_sp": ["sp", "android sp", "convert sp", "to sp"],
+    "convert_to_ios_pt": ["ios pt", "ios points", "convert ios points", "to ios points"],
+
+    // Timestamp Actions
+    "format_timestamp_local_time": ["timestamp", "local time", "human", "readable", "date"],
+    "format_timestamp_iso8601": ["timestamp", "iso", "iso 8601", "iso8601", "to iso"],
+    "format_timestamp_rfc3339": ["timestamp", "rfc 3339", "rfc3339"],
+    "format_timestamp_rfc2822": ["timestamp", "rfc 2822", "rfc2822", "email date", "http date"],
+    "format_timestamp_epoch_seconds": ["timestamp", "epoch", "unix", "unix time", "seconds", "to epoch"],
+    "format_timestamp_epoch_millis": ["timestamp", "epoch", "unix", "milliseconds", "ms", "epoch ms"],
+    "format_timestamp_epoch_micros": ["timestamp", "epoch", "unix", "microseconds", "us"],
+    "format_timestamp_epoch_nanos": ["timestamp", "epoch", "unix", "nanoseconds", "ns"],
+
+    // Cron Actions
+    "explain_cron": ["cron", "crontab", "schedule", "explain cron", "quartz"],
+    "cron_next_runs": ["cron", "crontab", "schedule", "next run", "next runs", "when"],
 };
 
 /**
@@ -27,6 +27,14 @@ const PATTERNS = {
     // Matches: "12:00", "4pm", "4:30 pm", "UTC", "EST", "GMT", "now"
     time: /(\b\d{1,2}:\d{2}\b)|(\b\d{1,2}\s*(am|pm)\b)|(\b(UTC|GMT|EST|PST|CST|MST|EDT|PDT|CDT|MDT|IST|CET|EET)\b)|(\bnow\b)/i,
 
+    // Timestamps: Unix epochs (seconds to nanoseconds), ISO 8601 / RFC 3339, RFC 2822
+    // Matches: "1741962600", "1741962600123", "2025-03-14T14:30:00Z", "Fri, 14 Mar 2025 14:30:00 +0000"
+    timestamp: /^\s*(\d{9,19}(\.\d+)?|\d{4}-?\d{2}-?\d{2}[T ]\d{2}:?\d{2}\S*|([A-Z][a-z]{2},\s*)?\d{1,2} [A-Z][a-z]{2} \d{4} \d{2}:\d{2}.*)\s*$/i,
+
+    // Cron: 5-7 fields with a wildcard, step or name, a @daily-style macro, or a crontab line
+    // Matches: "*/15 9-17 * * MON-FRI", "0 0 12 ? * 2#1 *", "@weekly", "5 4 * * * /usr/bin/backup"
+    cron: /^\s*(@(yearly|annually|monthly|weekly|daily|midnight|hourly)\b|(?=.*([*\/]|(^|\s)\?(\s|$)))[\d*?][\w*?\/,#-]*\s+([\w*?\/,#-]+\s+){3,5}[\w*?\/,#-]+(\s|$))/i,
+
     // Digits: Simple check for existence of numbers
     hasNumbers: /\d/,
 };
@@ -52,7 +60,7 @@ export function detectContext(text: string): TextContext {
     return {
         isCurrency: PATTERNS.currency.test(trimmed),
         isUnit: PATTERNS.units.test(trimmed),
-        isTime: PATTERNS.time.test(trimmed),
+        isTime: PATTERNS.time.test(trimmed) || PATTERNS.timestamp.test(trimmed) || PATTERNS.cron.test(trimmed),
         isSingleWord: !/\s/.test(trimmed), // No spaces = single word
         hasNumbers: PATTERNS.hasNumbers.test(trimmed),
         isValid: true,
@@ -167,12 +175,13 @@ function isCurrencyCommand(cmd: CommandItem): boolean {
 function isUnitCommand(cmd: CommandItem): boolean {
     if (cmd.widget_type === 'unit_converter') return true;
     if (cmd.action_type && 'type' in cmd.action_type && cmd.action_type.type === 'ConvertUnit') return true;
+    if (cmd.action_type && 'type' in cmd.action_type && cmd.action_type.type === 'ScaleRecipe') return true;
     return cmd.label.toLowerCase().includes('unit') || (cmd.keywords?.includes('unit') ?? false);
 }
 
 function isTimeCommand(cmd: CommandItem): boolean {
     // Assuming we have a widget_type 'time' or similar action
-    if (cmd.action_type && 'type' in cmd.action_type && cmd.action_type.type === 'ConvertTimeAction') return true;
+    if (cmd.action_type && 'type' in cmd.action_type && (cmd.action_type.type === 'ConvertTimeAction' || cmd.action_type.type === 'FormatTimestamp' || cmd.action_type.type === 'ExplainCron')) return true;
     return cmd.label.toLowerCase().includes('time') || (cmd.keywords?.includes('time') ?? false);
 }
 
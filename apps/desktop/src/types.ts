@@ -23,6 +23,52 @@ export interface UserPreferences {
     default_currency_from: string;
     default_currency_to: string;
     theme: string;
+    custom_materials: CustomMaterial[];
+    number_locale: NumberLocale;
+    business_calendar: BusinessCalendarSettings;
+    world_clock: PinnedZone[];
+    clipboard: ClipboardSettings;
+}
+
+export type NumberLocale = "auto" | "en-US" | "de-DE" | "fr-FR" | "de-CH";
+
+export interface BusinessCalendarSettings {
+    weekend: string[];
+    holiday_country: string | null;
+}
+
+export interface ClipboardSettings {
+    max_items: number;
+    max_age_days: number | null;
+    max_total_mb: number | null;
+    stack_separator: string;
+    capture_rules: CaptureRule[];
+    redacted_original_secs: number | null;
+    clear_sensitive_after_secs: number | null;
+    sensitive_item_ttl_mins: number | null;
+}
+
+export type CaptureAction = "allow" | "drop" | "redact" | "no_sync";
+
+export interface CaptureRule {
+    name: string;
+    enabled: boolean;
+    apps: string[];
+    pattern: string | null;
+    max_size_kb: number | null;
+    action: CaptureAction;
+}
+
+export interface PinnedZone {
+    timezone: string;
+    label: string | null;
+}
+
+export interface CustomMaterial {
+    name: string;
+    aliases: string[];
+    density_g_per_ml: number;
+    liquid?: boolean;
 }
 
 export interface CaptureResult {
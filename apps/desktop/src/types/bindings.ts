@@ -3,40 +3,276 @@
 /**
  * Phase 4: Production-ready - All variants use structured payloads
  */
-export type ActionType = { "type": "Translate", "payload": TranslatePayload } | { "type": "ConvertCurrency", "payload": CurrencyPayload } | { "type": "ConvertTimeAction", "payload": TimePayload } | { "type": "AnalyzeText", "payload": TextAnalysisPayload } | { "type": "DefinitionAction", "payload": DefinitionPayload } | { "type": "ConvertUnit", "payload": { target: string, } };
+export type ActionType = { "type": "Translate", "payload": TranslatePayload } | { "type": "ConvertCurrency", "payload": CurrencyPayload } | { "type": "ConvertTimeAction", "payload": TimePayload } | { "type": "AnalyzeText", "payload": TextAnalysisPayload } | { "type": "DefinitionAction", "payload": DefinitionPayload } | { "type": "ConvertUnit", "payload": { target: string, } } | { "type": "ScaleRecipe", "payload": RecipePayload } | { "type": "FormatTimestamp", "payload": TimestampPayload } | { "type": "ExplainCron", "payload": CronPayload };
 
 export type CaptureResult = { text: string, source: string, };
 
+/**
+ * A user-named group of pinned items, e.g. "SQL snippets"
+ */
+export type ClipboardCollection = { id: string, name: string, position: number, };
+
+/**
+ * What went into a backup file
+ */
+export type ClipboardExportSummary = { items: number, pins: number, collections: number, };
+
 /**
  * A single clipboard history item
  */
-export type ClipboardHistoryItem = { id: string, item_type: ClipboardItemType, content: string, preview: string, timestamp: string, source_app: string | null, };
+export type ClipboardHistoryItem = { id: string, item_type: ClipboardItemType, content: string, preview: string, timestamp: string, source_app: string | null, 
+/**
+ * HTML copied alongside the text
+ */
+html?: string, 
+/**
+ * RTF copied alongside the text (macOS)
+ */
+rtf?: string, 
+/**
+ * Copied files as `file://` URLs
+ */
+files: Array<string>, image?: ClipboardImageInfo, 
+/**
+ * Times this content was copied (0 in items saved before counting began)
+ */
+use_count: number, 
+/**
+ * Kept on this device only: never included in sync or exports
+ */
+no_sync: boolean, 
+/**
+ * Secrets in the text were masked before it was stored
+ */
+redacted: boolean, 
+/**
+ * Deleted from history at this time (sensitive items only)
+ */
+expires_at?: string, };
+
+/**
+ * One page of clipboard history, newest first
+ */
+export type ClipboardHistoryPage = { items: Array<ClipboardHistoryItem>, 
+/**
+ * Pass back as `cursor` for the next page (absent = no more results)
+ */
+next_cursor?: string, };
+
+/**
+ * Size and thumbnail of a copied image (the full PNG is stored separately)
+ */
+export type ClipboardImageInfo = { width: number, height: number, 
+/**
+ * Size of the stored PNG
+ */
+byte_size: number, 
+/**
+ * Small PNG as a `data:` URL
+ */
+thumbnail: string, };
+
+/**
+ * What an import changed, or would change in a dry run
+ */
+export type ClipboardImportSummary = { dry_run: boolean, 
+/**
+ * When the backup was made
+ */
+exported_at: string, items_added: number, 
+/**
+ * Replaced by a newer copy from the backup
+ */
+items_updated: number, 
+/**
+ * Already here and at least as new
+ */
+items_skipped: number, 
+/**
+ * Imported, then removed again by the history limits (too old or over
+ * the count or size budget); only known after a real import
+ */
+items_pruned: number, pins_added: number, pins_updated: number, pins_skipped: number, collections_added: number, };
 
 /**
  * Type of clipboard content
  */
-export type ClipboardItemType = "text" | "html" | "rtf" | "image";
+export type ClipboardItemType = "text" | "html" | "rtf" | "image" | "files";
+
+/**
+ * All collections and pinned items, each sorted by position
+ */
+export type ClipboardPinsResponse = { collections: Array<ClipboardCollection>, items: Array<PinnedClipboardItem>, };
 
 export type CommandItem = { id: string, label: string, description: string | null, action_type: ActionType | null, widget_type: string | null, category: ContextCategory | null, };
 
 /**
  * Context categories for content detection and action filtering
  */
-export type ContextCategory = "Length" | "Mass" | "Volume" | "Temperature" | "Speed" | "Currency" | "Text" | "Time" | "General";
+export type ContextCategory = "Length" | "Mass" | "Volume" | "Temperature" | "Speed" | "Typography" | "Currency" | "Text" | "Time" | "Timestamp" | "Cron" | "General";
 
 export type ConvertCurrencyRequest = { amount: string, from: string, to: string, date: string | null, };
 
 export type ConvertCurrencyResponse = { result: string, rate: string, timestamp: string, };
 
+/**
+ * Events pasted as iCalendar text or invite lines with `DTSTART;TZID=...`
+ */
+export type ConvertIcsRequest = { text: string, 
+/**
+ * Zones to show each occurrence in (defaults to the system zone)
+ */
+target_timezones?: Array<string>, 
+/**
+ * Upcoming occurrences listed per recurring event (default 5)
+ */
+occurrences?: number, 
+/**
+ * Also write the calendar back out with every event in this zone
+ */
+retarget_timezone?: string, };
+
+export type ConvertIcsResponse = { events: Array<IcsEventInfo>, 
+/**
+ * The calendar with events moved to `retarget_timezone`
+ */
+ics?: string, 
+/**
+ * Unknown zones, unsupported rules and occurrences that shift when retargeted
+ */
+warnings: Array<string>, };
+
 export type ConvertTimeRequest = { time_input: string, target_timezone: string, source_timezone: string | null, matched_keyword: string | null, };
 
-export type ConvertTimeResponse = { source_time: string, target_time: string, offset_description: string, source_timezone: string, target_timezone: string, target_utc_offset: string, target_zone_abbr: string, relative_offset: string, date_change_indicator: string | null, source_zone_abbr: string, source_utc_offset: string, };
+export type ConvertTimeResponse = { source_time: string, target_time: string, offset_description: string, source_timezone: string, target_timezone: string, target_utc_offset: string, target_zone_abbr: string, relative_offset: string, date_change_indicator: string | null, source_zone_abbr: string, source_utc_offset: string, 
+/**
+ * Other zones an ambiguous abbreviation may mean ("CST" → China, Cuba), best first
+ */
+alternatives?: Array<TimezoneCandidate>, 
+/**
+ * Skipped or repeated wall-clock time, or a clock change within a week in either zone
+ */
+dst_warning?: string, 
+/**
+ * Both readings when the source time happens twice (fall-back overlap), first one first
+ */
+interpretations?: Array<TimeInterpretation>, 
+/**
+ * Sunrise and sunset on the source date, when the zone's city is known
+ */
+source_solar?: SolarInfo, 
+/**
+ * Sunrise and sunset on the target date
+ */
+target_solar?: SolarInfo, };
+
+export type ConvertTimesInTextRequest = { text: string, target_timezone: string, 
+/**
+ * Overrides the timezone detected in the text
+ */
+source_timezone?: string, };
+
+export type ConvertTimesInTextResponse = { conversions: Array<TimeMentionConversion>, source_timezone: string | null, matched_keyword: string | null, };
+
+export type ConvertTimestampRequest = { text: string, 
+/**
+ * Zones to show the instant in, besides local time
+ */
+target_timezones?: Array<string>, };
+
+export type ConvertTimestampResponse = { original: string, detected_format: TimestampFormat, renderings: Array<TimestampRendering>, local: TimestampZoneTime, zones: Array<TimestampZoneTime>, };
 
 export type ConvertUnitPayload = { value: number, from_unit: string, target_unit: string, };
 
-export type ConvertUnitsRequest = { amount: number, from_unit: string, to_unit: string, material: string | null, };
+export type ConvertUnitsRequest = { amount: number, from_unit: string, to_unit: string, material: string | null, 
+/**
+ * Use exact decimal arithmetic instead of f64 (None = false)
+ */
+exact?: boolean, 
+/**
+ * Decimal string form of `amount` for exact mode (avoids JS float precision loss)
+ */
+exact_amount?: string, 
+/**
+ * Round the formatted result to N significant figures instead of 2 decimals
+ */
+significant_figures?: number, 
+/**
+ * Rounding mode for the formatted result (None = half up)
+ */
+rounding_mode?: RoundingMode, 
+/**
+ * Display metrics for typography/screen units (px, em, rem, vw, dp, ...)
+ */
+typography?: TypographyContext, };
+
+export type ConvertUnitsResponse = { result: number, formatted_result: string, from_unit: string, to_unit: string, 
+/**
+ * Unrounded decimal result (only set in exact mode)
+ */
+exact_result: string | null, 
+/**
+ * Material whose density bridged a Mass ↔ Volume conversion
+ */
+material: string | null, };
+
+/**
+ * How to paste the copy stack
+ */
+export type CopyStackPasteMode = "joined" | "next";
+
+/**
+ * Whether copies are being stacked, and what has been collected so far
+ */
+export type CopyStackState = { active: boolean, 
+/**
+ * Previews of the stacked copies, oldest first
+ */
+previews: Array<string>, };
+
+/**
+ * Cron syntax, told apart by field count
+ */
+export type CronDialect = "standard" | "with_seconds" | "quartz";
+
+/**
+ * One field as written and what it means
+ */
+export type CronFieldInfo = { name: string, value: string, description: string, start: number, end: number, };
+
+export type CronOutput = "explain" | "next_runs";
 
-export type ConvertUnitsResponse = { result: number, formatted_result: string, from_unit: string, to_unit: string, };
+/**
+ * Payload for cron actions
+ */
+export type CronPayload = { output: CronOutput, };
+
+export type CronRun = { 
+/**
+ * RFC 3339 in the schedule's zone
+ */
+iso: string, 
+/**
+ * "in 3h 20m"
+ */
+relative: string, 
+/**
+ * The schedule's zone first, then each target zone
+ */
+zones: Array<TimestampZoneTime>, 
+/**
+ * Moved or merged by a clock change
+ */
+note?: string, };
+
+/**
+ * Where an expression stops being valid; spans count characters from 0
+ */
+export type CronSyntaxError = { message: string, 
+/**
+ * "minute", "day of week", ...
+ */
+field?: string, start: number, end: number, };
 
 /**
  * Payload for currency conversion actions (Phase 2)
@@ -50,6 +286,36 @@ target_currency: string, };
 
 export type CursorPosition = { x: number, y: number, };
 
+/**
+ * What a date expression evaluated to
+ */
+export type DateCalcKind = "date" | "duration" | "difference";
+
+export type DateCalcRequest = { expression: string, 
+/**
+ * Weekend days for business-day math (None = from settings)
+ */
+weekend?: Array<string>, 
+/**
+ * Holiday calendar country code, e.g. "US" (None = from settings)
+ */
+holiday_country?: string, };
+
+export type DateCalcResponse = { kind: DateCalcKind, result: string, iso: string, detail?: string, 
+/**
+ * Days from today (dates) or between the two dates (differences)
+ */
+days?: number, business_days?: number, 
+/**
+ * Holidays passed over while counting business days, e.g. "Memorial Day (Mon 27 May 2024)"
+ */
+skipped_holidays: Array<string>, };
+
+/**
+ * Whether it's daytime where a clock is
+ */
+export type DayPeriod = "day" | "twilight" | "night";
+
 /**
  * Definition action types
  */
@@ -70,22 +336,250 @@ export type ExecuteActionRequest = { action_type: ActionType, params: any, };
 
 export type ExecuteActionResponse = { result: string, metadata: any, };
 
+export type ExplainCronRequest = { expression: string, 
+/**
+ * Zone the schedule runs in (defaults to the system zone)
+ */
+timezone?: string, 
+/**
+ * Zones to show each run in, besides the schedule's own
+ */
+target_timezones?: Array<string>, 
+/**
+ * Runs to list (default 5)
+ */
+count?: number, };
+
+export type ExplainCronResponse = { 
+/**
+ * The schedule part of the input
+ */
+expression: string, 
+/**
+ * The command after the schedule on a crontab line
+ */
+command?: string, 
+/**
+ * Set when the expression is invalid; everything below is then empty
+ */
+error?: CronSyntaxError, dialect?: CronDialect, 
+/**
+ * "Every 15 minutes, between 9:00am and 5:59pm, Monday through Friday"
+ */
+description?: string, fields: Array<CronFieldInfo>, 
+/**
+ * IANA ID of the zone runs are computed in
+ */
+timezone: string, runs: Array<CronRun>, };
+
+/**
+ * Export history and pins to a passphrase-protected backup file
+ */
+export type ExportClipboardRequest = { path: string, passphrase: string, };
+
+export type GetMaterialsResponse = { materials: Array<MaterialDTO>, };
+
 export type GetUnitsResponse = { units: Array<UnitDTO>, };
 
+/**
+ * A bundled public holiday calendar
+ */
+export type HolidayCalendarInfo = { country: string, name: string, };
+
+export type IcsEventInfo = { uid?: string, summary: string, location?: string, 
+/**
+ * IANA ID of the zone the event was written in
+ */
+source_timezone: string, all_day: boolean, 
+/**
+ * "Weekly on Mon, Wed, Fri, 10 times"
+ */
+recurrence?: string, occurrences: Array<IcsOccurrence>, };
+
+/**
+ * One occurrence of an event
+ */
+export type IcsOccurrence = { 
+/**
+ * RFC 3339, or YYYY-MM-DD for all-day events
+ */
+start: string, end: string, 
+/**
+ * As written in the event's zone: "Mon 3 Mar, 9:00am – 9:15am EST"
+ */
+original: string, 
+/**
+ * Empty for all-day events, which don't move between zones
+ */
+zones: Array<IcsZoneTime>, };
+
+/**
+ * An occurrence as wall-clock time in one zone
+ */
+export type IcsZoneTime = { iana_id: string, label: string, zone_abbr: string, 
+/**
+ * "Mon 3 Mar, 11:00pm – 11:15pm"
+ */
+time: string, 
+/**
+ * "Next day" / "Previous day" relative to the event's own zone
+ */
+date_change_indicator?: string, };
+
+/**
+ * Merge a backup file into history and pins
+ */
+export type ImportClipboardRequest = { path: string, passphrase: string, 
+/**
+ * Only report what would change
+ */
+dry_run: boolean, };
+
 export type LogRequest = { level: string, message: string, };
 
 export type LookupDefinitionRequest = { word: string, };
 
 export type LookupDefinitionResponse = { word: string, phonetic: string | null, definitions: Array<DefinitionEntry>, synonyms: Array<string>, antonyms: Array<string>, };
 
+export type MaterialDTO = { name: string, density_g_per_ml: number, custom: boolean, };
+
+/**
+ * A meeting attendee and their local working hours
+ */
+export type MeetingParticipant = { name?: string, timezone: string, work_start: string, work_end: string, 
+/**
+ * ISO weekdays worked (1 = Monday … 7 = Sunday); defaults to Monday–Friday
+ */
+work_days?: Array<number>, };
+
+export type MeetingSlot = { start_utc: string, end_utc: string, available_count: number, participant_count: number, participants: Array<ParticipantSlotTime>, summary: string, };
+
 export type OpenWidgetRequest = { widget: string, payload: any, };
 
-export type ParseUnitResponse = { amount: number, unit: string, category: string, };
+export type ParseUnitResponse = { amount: number, unit: string, category: string, material: string | null, 
+/**
+ * How sure the number reading is (below 1.0 when separators were ambiguous, e.g. "1,234")
+ */
+number_confidence?: number, };
+
+export type ParsedTimeInput = { time_input: string, source_timezone: string | null, matched_keyword: string | null, 
+/**
+ * Every time or range in the text when there is more than one, or a range
+ */
+mentions?: Array<TimeMention>, };
 
-export type ParsedTimeInput = { time_input: string, source_timezone: string | null, matched_keyword: string | null, };
+/**
+ * One participant's view of a candidate slot
+ */
+export type ParticipantSlotTime = { name: string, timezone: string, local_start: string, local_end: string, zone_abbr: string, in_working_hours: boolean, };
+
+/**
+ * Pin a copy of a history item
+ */
+export type PinClipboardItemRequest = { item_id: string, collection_id?: string, title?: string, };
+
+/**
+ * A clipboard item kept outside history so it never expires
+ */
+export type PinnedClipboardItem = { item: ClipboardHistoryItem, 
+/**
+ * Name shown in the palette instead of the preview
+ */
+title?: string, 
+/**
+ * Collection holding the item (absent = top-level pins)
+ */
+collection_id?: string, 
+/**
+ * Order within its collection
+ */
+position: number, 
+/**
+ * Last edit of the content, title or collection (absent = unedited
+ * since pinning)
+ */
+updated_at?: string, };
+
+export type PlanMeetingRequest = { participants: Array<MeetingParticipant>, start_date: string, end_date: string, duration_minutes: number, 
+/**
+ * Organizer's IANA zone for the date range and summary; defaults to the system timezone
+ */
+organizer_timezone?: string, 
+/**
+ * Granularity of candidate start times (default 30)
+ */
+step_minutes?: number, max_results?: number, };
+
+export type PlanMeetingResponse = { slots: Array<MeetingSlot>, summary: string, };
+
+/**
+ * Payload for recipe scaling actions
+ * Either `factor` or `servings` may be set; params can override both at execution time
+ */
+export type RecipePayload = { 
+/**
+ * Multiply every quantity by this factor (e.g., 2.0 doubles the recipe)
+ */
+factor: number | null, 
+/**
+ * Scale to this many servings (requires "Serves N" in the text or a `servings_from` param)
+ */
+servings: number | null, 
+/**
+ * Convert all measurements to this system (None = keep original units)
+ */
+unit_system: UnitSystem | null, };
+
+/**
+ * Rounding mode used when formatting numeric results
+ */
+export type RoundingMode = "half_up" | "half_even" | "down" | "up" | "floor" | "ceiling";
 
 export type ScreenBounds = { x: number, y: number, width: number, height: number, };
 
+/**
+ * Search or page through clipboard history (an empty query lists everything)
+ */
+export type SearchClipboardRequest = { 
+/**
+ * Words to find; each may be a prefix or contain a small typo
+ */
+query: string, 
+/**
+ * Only items copied from an app whose name contains this
+ */
+source_app?: string, item_type?: ClipboardItemType, 
+/**
+ * Earliest copy time, RFC 3339 or a local `YYYY-MM-DD`
+ */
+from?: string, 
+/**
+ * Latest copy time, RFC 3339 or a local `YYYY-MM-DD` (whole day included)
+ */
+to?: string, 
+/**
+ * `next_cursor` from the previous page
+ */
+cursor?: string, limit?: number, };
+
+/**
+ * Whether the sun rises and sets on a day
+ */
+export type SolarCondition = "normal" | "polar_day" | "polar_night";
+
+/**
+ * Sunrise, sunset and civil twilight for a zone's main city, in that zone's time
+ */
+export type SolarInfo = { city: string, 
+/**
+ * "Sat 21 Jun"
+ */
+date: string, sunrise?: string, sunset?: string, civil_dawn?: string, civil_dusk?: string, 
+/**
+ * "16h 38m"
+ */
+day_length: string, day_length_minutes: number, condition: SolarCondition, };
+
 /**
  * Text analysis action types
  */
@@ -104,6 +598,21 @@ export type TextAnalysisRequest = { text: string, };
 
 export type TextAnalysisResponse = { word_count: number, char_count: number, char_count_no_spaces: number, grapheme_count: number, line_count: number, reading_time_sec: number, };
 
+/**
+ * One reading of a wall-clock time that happens twice
+ */
+export type TimeInterpretation = { source_time: string, source_zone_abbr: string, source_utc_offset: string, target_time: string, target_zone_abbr: string, target_utc_offset: string, };
+
+/**
+ * A time or time range found in a selection
+ */
+export type TimeMention = { original: string, start: string, end?: string, };
+
+/**
+ * One time or range converted, shown next to the original
+ */
+export type TimeMentionConversion = { original: string, converted: string, start: ConvertTimeResponse, end?: ConvertTimeResponse, };
+
 /**
  * Payload for time conversion actions
  */
@@ -113,6 +622,39 @@ export type TimePayload = {
  */
 target_timezone: string, };
 
+/**
+ * Machine-readable date formats, detected in selections and offered as outputs
+ */
+export type TimestampFormat = "epoch_seconds" | "epoch_millis" | "epoch_micros" | "epoch_nanos" | "iso8601" | "rfc2822" | "rfc3339" | "local_time";
+
+/**
+ * Payload for timestamp formatting actions
+ */
+export type TimestampPayload = { 
+/**
+ * Output format
+ */
+format: TimestampFormat, 
+/**
+ * Zone for ISO 8601 / RFC 2822 / RFC 3339 / local output (None = system timezone)
+ */
+timezone?: string, };
+
+/**
+ * The instant written in one timestamp format
+ */
+export type TimestampRendering = { format: TimestampFormat, label: string, value: string, };
+
+/**
+ * The instant as wall-clock time in one zone
+ */
+export type TimestampZoneTime = { iana_id: string, label: string, time: string, zone_abbr: string, utc_offset: string, };
+
+/**
+ * A timezone suggested by search or abbreviation resolution
+ */
+export type TimezoneCandidate = { iana_id: string, label: string, abbreviation: string, utc_offset: string, matched: string, historical: boolean, };
+
 export type TimezoneInfo = { label: string, iana_id: string, keywords: string, };
 
 /**
@@ -133,6 +675,88 @@ export type TranslateRequest = { text: string, source_lang: string | null, targe
 
 export type TranslateResponse = { translated: string, detected_source_lang: string | null, };
 
+/**
+ * Display metrics used by typography conversions (unset fields use CSS defaults)
+ */
+export type TypographyContext = { 
+/**
+ * Screen density in pixels per inch (default 96, the CSS reference pixel;
+ * 160 = Android mdpi, 163 = iPhone @1x)
+ */
+dpi?: number, 
+/**
+ * Root font size in px, used by `rem` (default 16)
+ */
+root_font_size?: number, 
+/**
+ * Element font size in px, used by `em` (default: root font size)
+ */
+font_size?: number, 
+/**
+ * Viewport width in px, used by `vw` (default 1920)
+ */
+viewport_width?: number, 
+/**
+ * Viewport height in px, used by `vh` (default 1080)
+ */
+viewport_height?: number, 
+/**
+ * Android font scale, used by `sp` (default 1.0)
+ */
+font_scale?: number, };
+
 export type UnitDTO = { id: string, label: string, category: string, };
 
+/**
+ * Measurement system for recipe output
+ */
+export type UnitSystem = "metric" | "imperial";
+
+/**
+ * Edit a pinned item (every field is replaced)
+ */
+export type UpdatePinnedItemRequest = { id: string, content: string, title?: string, collection_id?: string, };
+
 export type WindowPosition = { x: number, y: number, };
+
+/**
+ * One pinned zone on the world clock
+ */
+export type WorldClockEntry = { timezone: string, 
+/**
+ * Custom label, else the city
+ */
+label: string, 
+/**
+ * "9:41am"
+ */
+time: string, 
+/**
+ * "Sat 18 Oct"
+ */
+date: string, 
+/**
+ * RFC 3339 with the zone's offset
+ */
+iso: string, abbreviation: string, utc_offset: string, 
+/**
+ * "+9h" / "-5h 30m" / "Same time" relative to the system zone
+ */
+offset_from_local: string, 
+/**
+ * Calendar days ahead of (+1) or behind (-1) the system zone
+ */
+day_offset: number, day_period: DayPeriod, 
+/**
+ * Today's sunrise and sunset there, when the zone's city is known
+ */
+solar?: SolarInfo, };
+
+/**
+ * Every pinned zone at one instant
+ */
+export type WorldClockSnapshot = { 
+/**
+ * RFC 3339, UTC
+ */
+generated_at: string, local_timezone: string, entries: Array<WorldClockEntry>, };
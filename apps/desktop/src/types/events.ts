@@ -1,8 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
-import type { AppSettings, ClipboardHistoryItem } from "../logic/types";
+import type { AppSettings, ClipboardHistoryItem, WorldClockSnapshot } from "../logic/types";
 
 
 export type AppEvent =
     | { event: "clipboard://updated", payload: ClipboardHistoryItem }
+    | { event: "clipboard://expired", payload: string[] }
     | { event: "settings://updated", payload: AppSettings }
-    | { event: "window://focus-changed", payload: boolean };
+    | { event: "window://focus-changed", payload: boolean }
+    | { event: "world-clock://tick", payload: WorldClockSnapshot };
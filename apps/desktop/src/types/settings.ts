@@ -4,6 +4,124 @@ export type ApiKeys = { translation_provider: string, translation_key: string, g
 
 export type AppSettings = { hotkeys: HotkeySettings, api_keys: ApiKeys, preferences: UserPreferences, };
 
+export type BusinessCalendarSettings = { 
+/**
+ * Weekend day names, e.g. ["sat", "sun"]
+ */
+weekend: Array<string>, 
+/**
+ * Bundled holiday calendar country code, e.g. "US" (None = weekends only)
+ */
+holiday_country: string | null, };
+
+/**
+ * What happens to a copy that matches a capture rule
+ */
+export type CaptureAction = "allow" | "drop" | "redact" | "no_sync";
+
+/**
+ * A user rule deciding whether and how a copy is stored. Every condition
+ * that is set must hold for the rule to match.
+ */
+export type CaptureRule = { 
+/**
+ * Shown in logs when the rule fires
+ */
+name: string, enabled: boolean, 
+/**
+ * App names or bundle IDs, matched case-insensitively; `*` stands for
+ * any run of characters, as in `*password*` (empty = any app)
+ */
+apps: Array<string>, 
+/**
+ * Regex the copied text must contain a match of
+ */
+pattern: string | null, 
+/**
+ * Only copies larger than this many KB
+ */
+max_size_kb: number | null, action: CaptureAction, };
+
+export type ClipboardSettings = { 
+/**
+ * Most items kept in history
+ */
+max_items: number, 
+/**
+ * Items older than this are deleted (None = keep forever)
+ */
+max_age_days: number | null, 
+/**
+ * Oldest items are deleted once stored history exceeds this (None = no limit)
+ */
+max_total_mb: number | null, 
+/**
+ * Text placed between copies when the copy stack is pasted at once
+ */
+stack_separator: string, 
+/**
+ * Checked in order before a copy is stored; the first rule that matches decides
+ */
+capture_rules: Array<CaptureRule>, 
+/**
+ * Seconds the original of a copy stored with its secrets masked stays
+ * available to paste, in memory only (None = discarded at once)
+ */
+redacted_original_secs: number | null, 
+/**
+ * Seconds after a copy with secrets in it before the system clipboard
+ * is cleared, if it still holds that copy (None = never)
+ */
+clear_sensitive_after_secs: number | null, 
+/**
+ * Minutes items with secrets in them stay in history (None = like any other item)
+ */
+sensitive_item_ttl_mins: number | null, };
+
+export type CustomMaterial = { name: string, aliases: Array<string>, density_g_per_ml: number, 
+/**
+ * Measured by volume in recipes, like milk or syrup (kept in ml when converting to metric)
+ */
+liquid: boolean, };
+
 export type HotkeySettings = { command_palette: string, };
 
-export type UserPreferences = { default_source_lang: string, default_target_lang: string, default_currency_from: string, default_currency_to: string, theme: string, };
+/**
+ * Number formatting convention (decimal and grouping separators)
+ */
+export type NumberLocale = "auto" | "en-US" | "de-DE" | "fr-FR" | "de-CH";
+
+/**
+ * A zone on the world clock
+ */
+export type PinnedZone = { 
+/**
+ * IANA ID, e.g. "Asia/Tokyo"
+ */
+timezone: string, 
+/**
+ * Custom name shown instead of the city, e.g. "Tokyo office"
+ */
+label: string | null, };
+
+export type UserPreferences = { default_source_lang: string, default_target_lang: string, default_currency_from: string, default_currency_to: string, theme: string, 
+/**
+ * User-defined material densities for Mass ↔ Volume conversions
+ */
+custom_materials: Array<CustomMaterial>, 
+/**
+ * Separators used to read and display numbers (`auto` detects them per input)
+ */
+number_locale: NumberLocale, 
+/**
+ * Weekend and public holidays used by business-day date math
+ */
+business_calendar: BusinessCalendarSettings, 
+/**
+ * Zones pinned to the world clock, in display order
+ */
+world_clock: Array<PinnedZone>, 
+/**
+ * Clipboard history retention
+ */
+clipboard: ClipboardSettings, };